@@ -0,0 +1,196 @@
+//! `FastCDC` content-defined chunking.
+//!
+//! Boundaries are chosen from a rolling gear hash of the bytes themselves, so an edit only
+//! moves the boundaries next to it and the remaining chunks of two revisions of a file
+//! hash the same. Chunk sizes are normalized around [`AVG_CHUNK_SIZE`]: a stricter mask
+//! is used before the average size and a looser one after it.
+
+use std::io::{self, Read};
+
+/// Chunks are never cut before this many bytes, except at the end of the input.
+pub const MIN_CHUNK_SIZE: usize = 4 * 1024;
+/// Target chunk size.
+pub const AVG_CHUNK_SIZE: usize = 16 * 1024;
+/// Chunks are always cut at this many bytes.
+pub const MAX_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Mask checked before the average size: 16 bits, two more than `log2(AVG_CHUNK_SIZE)`.
+const MASK_SMALL: u64 = 0xFFFF_0000_0000_0000;
+/// Mask checked after the average size: 12 bits, two fewer than `log2(AVG_CHUNK_SIZE)`.
+const MASK_LARGE: u64 = 0xFFF0_0000_0000_0000;
+
+/// Gear values for each byte, generated with splitmix64 so every build agrees on them.
+static GEAR: [u64; 256] = gear_table();
+
+const fn gear_table() -> [u64; 256] {
+    let mut table = [0u64; 256];
+    let mut state = 0x6d76_3263_6463_0001u64;
+    let mut index = 0;
+    while index < table.len() {
+        state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        table[index] = z ^ (z >> 31);
+        index += 1;
+    }
+    table
+}
+
+/// Length of the first chunk of `data`.
+fn cut_point(data: &[u8]) -> usize {
+    if data.len() <= MIN_CHUNK_SIZE {
+        return data.len();
+    }
+    let normal = data.len().min(AVG_CHUNK_SIZE);
+    let max = data.len().min(MAX_CHUNK_SIZE);
+    let mut hash = 0u64;
+    for (index, &byte) in data.iter().enumerate().take(max).skip(MIN_CHUNK_SIZE) {
+        hash = (hash << 1).wrapping_add(GEAR[byte as usize]);
+        let mask = if index < normal {
+            MASK_SMALL
+        } else {
+            MASK_LARGE
+        };
+        if hash & mask == 0 {
+            return index + 1;
+        }
+    }
+    max
+}
+
+/// Split `data` into content-defined chunks.
+///
+/// Every chunk is between [`MIN_CHUNK_SIZE`] and [`MAX_CHUNK_SIZE`] bytes, except the last,
+/// which may be shorter. Empty input yields no chunks.
+#[must_use]
+pub fn chunks(data: &[u8]) -> Chunks<'_> {
+    Chunks { remaining: data }
+}
+
+/// Iterator returned by [`chunks`].
+#[derive(Debug, Clone)]
+pub struct Chunks<'a> {
+    remaining: &'a [u8],
+}
+
+impl<'a> Iterator for Chunks<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining.is_empty() {
+            return None;
+        }
+        let (chunk, rest) = self.remaining.split_at(cut_point(self.remaining));
+        self.remaining = rest;
+        Some(chunk)
+    }
+}
+
+/// Split everything `reader` yields into the chunks [`chunks`] would produce for it,
+/// buffering at most two maximum-size chunks.
+pub fn stream_chunks<R: Read>(reader: R) -> StreamChunks<R> {
+    StreamChunks {
+        reader,
+        buffer: Vec::with_capacity(2 * MAX_CHUNK_SIZE),
+        start: 0,
+        eof: false,
+    }
+}
+
+/// Iterator returned by [`stream_chunks`].
+#[derive(Debug)]
+pub struct StreamChunks<R> {
+    reader: R,
+    buffer: Vec<u8>,
+    start: usize,
+    eof: bool,
+}
+
+impl<R: Read> StreamChunks<R> {
+    /// Buffer at least [`MAX_CHUNK_SIZE`] unconsumed bytes, or everything left.
+    fn fill(&mut self) -> io::Result<()> {
+        self.buffer.drain(..self.start);
+        self.start = 0;
+        let mut block = [0u8; 8192];
+        while !self.eof && self.buffer.len() < MAX_CHUNK_SIZE {
+            match self.reader.read(&mut block) {
+                Ok(0) => self.eof = true,
+                Ok(read) => self.buffer.extend_from_slice(&block[..read]),
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<R: Read> Iterator for StreamChunks<R> {
+    type Item = io::Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buffer.len() - self.start < MAX_CHUNK_SIZE {
+            if let Err(err) = self.fill() {
+                self.eof = true;
+                self.buffer.clear();
+                return Some(Err(err));
+            }
+        }
+        let remaining = &self.buffer[self.start..];
+        if remaining.is_empty() {
+            return None;
+        }
+        let cut = cut_point(remaining);
+        let chunk = remaining[..cut].to_vec();
+        self.start += cut;
+        Some(Ok(chunk))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn boundaries_resynchronize_after_an_edit() {
+        let mut rng = fastrand::Rng::with_seed(7);
+        let original: Vec<u8> = (0..1024 * 1024).map(|_| rng.u8(..)).collect();
+        let mut edited = original.clone();
+        edited.splice(
+            300_000..300_000,
+            b"a page or two of new content".iter().copied(),
+        );
+
+        let before: Vec<&[u8]> = chunks(&original).collect();
+        assert_eq!(before.concat(), original);
+        assert!(
+            before[..before.len() - 1]
+                .iter()
+                .all(|chunk| (MIN_CHUNK_SIZE..=MAX_CHUNK_SIZE).contains(&chunk.len()))
+        );
+
+        let known: HashSet<&[u8]> = before.iter().copied().collect();
+        let after: Vec<&[u8]> = chunks(&edited).collect();
+        assert_eq!(after.concat(), edited);
+        let changed = after.iter().filter(|chunk| !known.contains(*chunk)).count();
+        assert!(changed <= 3, "{changed} of {} chunks changed", after.len());
+
+        let streamed: Vec<Vec<u8>> = stream_chunks(TrickleReader(&edited))
+            .map(|chunk| chunk.expect("chunk"))
+            .collect();
+        assert_eq!(streamed, after);
+    }
+
+    /// Hands out at most 1000 bytes per read.
+    struct TrickleReader<'a>(&'a [u8]);
+
+    impl Read for TrickleReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let len = buf.len().min(self.0.len()).min(1000);
+            buf[..len].copy_from_slice(&self.0[..len]);
+            self.0 = &self.0[len..];
+            Ok(len)
+        }
+    }
+}
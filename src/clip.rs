@@ -26,7 +26,10 @@ use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use std::time::Duration;
 
-use crate::{MemvidError, Result, types::FrameId};
+use crate::{
+    MemvidError, Result,
+    types::{DistanceMetric, FrameId},
+};
 
 // ============================================================================
 // Configuration Constants
@@ -287,6 +290,16 @@ impl ClipIndex {
 
     /// Search for similar embeddings using L2 distance
     pub fn search(&self, query: &[f32], limit: usize) -> Vec<ClipSearchHit> {
+        self.search_with_metric(query, limit, DistanceMetric::L2)
+    }
+
+    /// Search for similar embeddings using the given distance metric
+    pub fn search_with_metric(
+        &self,
+        query: &[f32],
+        limit: usize,
+        metric: DistanceMetric,
+    ) -> Vec<ClipSearchHit> {
         if query.is_empty() {
             return Vec::new();
         }
@@ -295,7 +308,7 @@ impl ClipIndex {
             .documents
             .iter()
             .map(|doc| {
-                let distance = l2_distance(query, &doc.embedding);
+                let distance = metric.distance(query, &doc.embedding);
                 ClipSearchHit {
                     frame_id: doc.frame_id,
                     page: doc.page,
@@ -370,19 +383,10 @@ pub struct ClipSearchHit {
     pub frame_id: FrameId,
     /// Optional page number (for PDFs)
     pub page: Option<u32>,
-    /// L2 distance to query (lower is more similar)
+    /// Distance to query under the index metric (lower is more similar)
     pub distance: f32,
 }
 
-/// L2 (Euclidean) distance between two vectors
-fn l2_distance(a: &[f32], b: &[f32]) -> f32 {
-    a.iter()
-        .zip(b.iter())
-        .map(|(x, y)| (x - y).powi(2))
-        .sum::<f32>()
-        .sqrt()
-}
-
 // ============================================================================
 // Image Filtering (Junk Detection)
 // ============================================================================
@@ -1540,6 +1544,9 @@ pub struct ClipIndexManifest {
     pub checksum: [u8; 32],
     /// Model name used to generate embeddings
     pub model_name: String,
+    /// Distance metric used to rank hits
+    #[serde(default)]
+    pub metric: DistanceMetric,
 }
 
 // ============================================================================
@@ -1589,13 +1596,30 @@ mod tests {
 
     #[test]
     fn l2_distance_calculation() {
-        let d = l2_distance(&[0.0, 0.0], &[3.0, 4.0]);
+        let d = DistanceMetric::L2.distance(&[0.0, 0.0], &[3.0, 4.0]);
         assert!((d - 5.0).abs() < 1e-6);
 
-        let d = l2_distance(&[1.0, 1.0, 1.0], &[1.0, 1.0, 1.0]);
+        let d = DistanceMetric::L2.distance(&[1.0, 1.0, 1.0], &[1.0, 1.0, 1.0]);
         assert!(d.abs() < 1e-6);
     }
 
+    #[test]
+    fn clip_index_search_with_metric() {
+        let mut index = ClipIndex::new();
+        index.add_document(1, None, vec![10.0, 0.0]);
+        index.add_document(2, None, vec![0.9, 0.1]);
+
+        // L2 prefers the nearby vector, cosine and dot prefer the aligned one.
+        let hits = index.search_with_metric(&[1.0, 0.0], 2, DistanceMetric::L2);
+        assert_eq!(hits[0].frame_id, 2);
+        let hits = index.search_with_metric(&[1.0, 0.0], 2, DistanceMetric::Cosine);
+        assert_eq!(hits[0].frame_id, 1);
+        assert!(hits[0].distance.abs() < 1e-6);
+        let hits = index.search_with_metric(&[1.0, 0.0], 2, DistanceMetric::Dot);
+        assert_eq!(hits[0].frame_id, 1);
+        assert!((DistanceMetric::Dot.similarity(hits[0].distance) - 10.0).abs() < 1e-6);
+    }
+
     #[test]
     fn image_info_filtering() {
         // Tiny image - should skip
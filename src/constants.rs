@@ -4,6 +4,14 @@ pub const MAGIC: [u8; 4] = *b"MV2\0";
 pub const HEADER_SIZE: usize = 4096;
 /// Magic bytes for the time index track.
 pub const TIME_INDEX_MAGIC: [u8; 4] = *b"MVTI";
+/// Magic bytes for the paged frame table segment.
+pub const FRAME_TABLE_MAGIC: [u8; 4] = *b"MVFT";
+/// On-disk version for the frame table segment header.
+pub const FRAME_TABLE_VERSION: u16 = 1;
+/// Magic bytes for the blob store chunk index segment.
+pub const BLOB_STORE_MAGIC: [u8; 4] = *b"MVBS";
+/// On-disk version for the blob store chunk index segment.
+pub const BLOB_STORE_VERSION: u16 = 1;
 #[cfg(feature = "temporal_track")]
 /// Magic bytes for the temporal mentions track.
 pub const TEMPORAL_TRACK_MAGIC: [u8; 4] = *b"MVTN";
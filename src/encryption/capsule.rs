@@ -7,90 +7,349 @@ use rand::RngCore;
 use rand::rngs::OsRng;
 use zeroize::Zeroize;
 
-use crate::encryption::constants::{MV2E_MAGIC, MV2E_VERSION, NONCE_SIZE, SALT_SIZE};
-use crate::encryption::crypto::{decrypt, derive_key, encrypt};
+use aes_gcm::Aes256Gcm;
+
+use crate::encryption::constants::{
+    KEY_SIZE, MV2E_MAGIC, MV2E_VERSION, MV2E_VERSION_V1, NONCE_SIZE, SALT_SIZE, STREAM_CHUNK_SIZE,
+    STREAM_NONCE_PREFIX_SIZE, TAG_SIZE,
+};
+use crate::encryption::crypto::{
+    chunk_nonce, decrypt, decrypt_chunk, derive_key, encrypt_chunk, stream_cipher,
+};
 use crate::encryption::error::EncryptionError;
-use crate::encryption::types::{CipherAlgorithm, KdfAlgorithm, Mv2eHeader};
+use crate::encryption::recipients::{
+    Stanza, body_aad, decode_recipient_block, encode_recipient_block, generate_data_key,
+    recipient_block_len, unwrap_data_key,
+};
+use crate::encryption::types::{
+    CipherAlgorithm, Identity, KdfAlgorithm, Mv2eHeader, Recipient, RecipientInfo,
+};
 
-/// Lock (encrypt) an `.mv2` file into a `.mv2e` capsule.
+/// Lock (encrypt) an `.mv2` file into a `.mv2e` capsule unlockable with `password`.
+///
+/// The file is streamed through fixed-size AEAD chunks, so memory use stays constant
+/// regardless of the size of the memory.
 pub fn lock_file(
     input: impl AsRef<Path>,
     output: Option<&Path>,
     password: &[u8],
+) -> Result<PathBuf, EncryptionError> {
+    lock_file_for(input, output, &[Recipient::Password(password.to_vec())])
+}
+
+/// Lock an `.mv2` file into a capsule that any of `recipients` can unlock.
+///
+/// The body is sealed under a random data key, and that key is wrapped once per
+/// recipient, so recipients can later be added or removed without re-encrypting.
+pub fn lock_file_for(
+    input: impl AsRef<Path>,
+    output: Option<&Path>,
+    recipients: &[Recipient],
 ) -> Result<PathBuf, EncryptionError> {
     let input = input.as_ref();
     validate_mv2_file(input)?;
 
-    let plaintext = std::fs::read(input).map_err(|source| EncryptionError::Io {
-        source,
-        path: Some(input.to_path_buf()),
-    })?;
+    let mut reader = File::open(input).map_err(io_error(input))?;
+    let original_size = reader.metadata().map_err(io_error(input))?.len();
 
     let mut salt = [0u8; SALT_SIZE];
-    let mut nonce = [0u8; NONCE_SIZE];
+    let mut prefix = [0u8; STREAM_NONCE_PREFIX_SIZE];
     OsRng.fill_bytes(&mut salt);
-    OsRng.fill_bytes(&mut nonce);
-
-    let mut key = derive_key(password, &salt)?;
-    let ciphertext = encrypt(&plaintext, &key, &nonce)?;
+    OsRng.fill_bytes(&mut prefix);
+    let mut nonce = [0u8; NONCE_SIZE];
+    nonce[..STREAM_NONCE_PREFIX_SIZE].copy_from_slice(&prefix);
 
-    let header = Mv2eHeader {
+    let mut header = Mv2eHeader {
         magic: MV2E_MAGIC,
         version: MV2E_VERSION,
-        kdf_algorithm: KdfAlgorithm::Argon2id,
+        kdf_algorithm: KdfAlgorithm::WrappedKey,
         cipher_algorithm: CipherAlgorithm::Aes256Gcm,
         salt,
         nonce,
-        original_size: plaintext.len() as u64,
+        original_size,
         reserved: [0u8; 4],
     };
 
+    let mut data_key = generate_data_key();
+    let sealed = seal_recipients(&mut header, recipients, &data_key);
+    let cipher = stream_cipher(&data_key);
+    data_key.zeroize();
+    let block = sealed?;
+    let cipher = cipher?;
+    let aad = body_aad(&header);
+
     let output_path = output
         .map(PathBuf::from)
         .unwrap_or_else(|| input.with_extension("mv2e"));
 
     write_atomic(&output_path, |writer| {
-        writer.write_all(&header.encode())?;
-        writer.write_all(&ciphertext)?;
+        writer
+            .write_all(&header.encode())
+            .map_err(io_error(&output_path))?;
+        writer.write_all(&block).map_err(io_error(&output_path))?;
+        let written = encrypt_stream(
+            &cipher,
+            prefix,
+            &aad,
+            (&mut reader, input),
+            (writer, &output_path),
+        )?;
+        // The source changed while it was being locked.
+        if written != original_size {
+            return Err(EncryptionError::SizeMismatch {
+                expected: original_size,
+                actual: written,
+            });
+        }
         Ok(())
     })?;
 
-    key.zeroize();
-
     Ok(output_path)
 }
 
-/// Unlock (decrypt) an `.mv2e` capsule into an `.mv2` file.
+/// Unlock (decrypt) an `.mv2e` capsule into an `.mv2` file using a password.
+///
+/// Multi-recipient (v3), chunked (v2) and single-message (v1) capsules are supported.
+/// Nothing is written to `output` unless every chunk authenticates.
 pub fn unlock_file(
     input: impl AsRef<Path>,
     output: Option<&Path>,
     password: &[u8],
+) -> Result<PathBuf, EncryptionError> {
+    unlock_file_with(input, output, &Identity::Password(password.to_vec()))
+}
+
+/// Unlock an `.mv2e` capsule with any identity it was locked for.
+///
+/// v1 and v2 capsules predate recipients and only accept [`Identity::Password`].
+pub fn unlock_file_with(
+    input: impl AsRef<Path>,
+    output: Option<&Path>,
+    identity: &Identity,
 ) -> Result<PathBuf, EncryptionError> {
     let input = input.as_ref();
 
-    let mut file = File::open(input).map_err(|source| EncryptionError::Io {
-        source,
-        path: Some(input.to_path_buf()),
-    })?;
+    let mut file = File::open(input).map_err(io_error(input))?;
 
     let mut header_bytes = [0u8; Mv2eHeader::SIZE];
     file.read_exact(&mut header_bytes)
-        .map_err(|source| EncryptionError::Io {
-            source,
-            path: Some(input.to_path_buf()),
-        })?;
+        .map_err(io_error(input))?;
     let header = Mv2eHeader::decode(&header_bytes)?;
 
+    let output_path = output
+        .map(PathBuf::from)
+        .unwrap_or_else(|| input.with_extension("mv2"));
+
+    let (cipher, aad) = if header.version == MV2E_VERSION {
+        let (stanzas, block) = read_recipients(&mut file, input, &header)?;
+        let mut data_key = unwrap_data_key(&header, &block, &stanzas, identity)?;
+        let cipher = stream_cipher(&data_key);
+        data_key.zeroize();
+        (cipher?, body_aad(&header))
+    } else {
+        let Identity::Password(password) = identity else {
+            return Err(EncryptionError::Decryption {
+                reason: format!(
+                    "version {} capsules can only be unlocked with a password",
+                    header.version
+                ),
+            });
+        };
+        if header.version == MV2E_VERSION_V1 {
+            let plaintext = unlock_v1(&mut file, input, &header, password)?;
+            write_atomic(&output_path, |writer| {
+                writer.write_all(&plaintext).map_err(io_error(&output_path))
+            })?;
+            return Ok(output_path);
+        }
+        let mut key = derive_key(password, &header.salt)?;
+        let cipher = stream_cipher(&key);
+        key.zeroize();
+        (cipher?, header_bytes)
+    };
+
+    let mut prefix = [0u8; STREAM_NONCE_PREFIX_SIZE];
+    prefix.copy_from_slice(&header.nonce[..STREAM_NONCE_PREFIX_SIZE]);
+
+    write_atomic(&output_path, |writer| {
+        let written = decrypt_stream(
+            &cipher,
+            prefix,
+            &aad,
+            (&mut file, input),
+            (writer, &output_path),
+        )?;
+        if written != header.original_size {
+            return Err(EncryptionError::SizeMismatch {
+                expected: header.original_size,
+                actual: written,
+            });
+        }
+        Ok(())
+    })?;
+
+    Ok(output_path)
+}
+
+/// List the recipients a v3 capsule is wrapped for.
+///
+/// This reads only the recipient block; it does not need any secret and does not
+/// authenticate the list.
+pub fn list_recipients(capsule: impl AsRef<Path>) -> Result<Vec<RecipientInfo>, EncryptionError> {
+    let capsule = capsule.as_ref();
+    let mut file = File::open(capsule).map_err(io_error(capsule))?;
+    let header = read_header(&mut file, capsule)?;
+    let (stanzas, _) = read_recipients(&mut file, capsule, &header)?;
+    Ok(stanzas.iter().map(Stanza::info).collect())
+}
+
+/// Wrap the data key of `capsule` for an additional recipient.
+///
+/// `identity` must already be able to unlock the capsule. Only the header and
+/// recipient block are rewritten; the encrypted body is copied unchanged.
+pub fn add_recipient(
+    capsule: impl AsRef<Path>,
+    identity: &Identity,
+    recipient: &Recipient,
+) -> Result<RecipientInfo, EncryptionError> {
+    let mut added = None;
+    rewrap(capsule.as_ref(), identity, |stanzas, data_key, salt| {
+        let stanza = Stanza::wrap(recipient, data_key, salt)?;
+        added = Some(stanza.info());
+        stanzas.push(stanza);
+        Ok(())
+    })?;
+    added.ok_or_else(|| EncryptionError::InvalidRecipients {
+        reason: "recipient was not added".to_string(),
+    })
+}
+
+/// Remove the recipient with `fingerprint` (see [`list_recipients`]) from `capsule`.
+///
+/// The last remaining recipient cannot be removed.
+pub fn remove_recipient(
+    capsule: impl AsRef<Path>,
+    identity: &Identity,
+    fingerprint: &str,
+) -> Result<(), EncryptionError> {
+    rewrap(capsule.as_ref(), identity, |stanzas, _, _| {
+        let index = stanzas
+            .iter()
+            .position(|stanza| stanza.info().fingerprint == fingerprint)
+            .ok_or_else(|| EncryptionError::RecipientNotFound {
+                fingerprint: fingerprint.to_string(),
+            })?;
+        if stanzas.len() == 1 {
+            return Err(EncryptionError::LastRecipient);
+        }
+        stanzas.remove(index);
+        Ok(())
+    })
+}
+
+/// Replace every password stanza that `old_password` opens with one for `new_password`.
+pub fn rotate_password(
+    capsule: impl AsRef<Path>,
+    old_password: &[u8],
+    new_password: &[u8],
+) -> Result<(), EncryptionError> {
+    let old = Identity::Password(old_password.to_vec());
+    let new = Recipient::Password(new_password.to_vec());
+    rewrap(capsule.as_ref(), &old, |stanzas, data_key, salt| {
+        for stanza in stanzas.iter_mut() {
+            if let Some(mut key) = stanza.unwrap(&old, salt)? {
+                key.zeroize();
+                *stanza = Stanza::wrap(&new, data_key, salt)?;
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Rewrite the recipient block of a v3 capsule in place, copying the body verbatim.
+fn rewrap<F>(capsule: &Path, identity: &Identity, edit: F) -> Result<(), EncryptionError>
+where
+    F: FnOnce(&mut Vec<Stanza>, &[u8; KEY_SIZE], &[u8; SALT_SIZE]) -> Result<(), EncryptionError>,
+{
+    let mut file = File::open(capsule).map_err(io_error(capsule))?;
+    let header = read_header(&mut file, capsule)?;
+    let (mut stanzas, block) = read_recipients(&mut file, capsule, &header)?;
+    let mut data_key = unwrap_data_key(&header, &block, &stanzas, identity)?;
+
+    let mut updated = header.clone();
+    let edited = edit(&mut stanzas, &data_key, &header.salt)
+        .and_then(|()| encode_recipient_block(&mut updated, &stanzas, &data_key));
+    data_key.zeroize();
+    let block = edited?;
+
+    write_atomic(capsule, |writer| {
+        writer
+            .write_all(&updated.encode())
+            .map_err(io_error(capsule))?;
+        writer.write_all(&block).map_err(io_error(capsule))?;
+        std::io::copy(&mut file, writer).map_err(io_error(capsule))?;
+        Ok(())
+    })
+}
+
+fn seal_recipients(
+    header: &mut Mv2eHeader,
+    recipients: &[Recipient],
+    data_key: &[u8; KEY_SIZE],
+) -> Result<Vec<u8>, EncryptionError> {
+    let stanzas = recipients
+        .iter()
+        .map(|recipient| Stanza::wrap(recipient, data_key, &header.salt))
+        .collect::<Result<Vec<_>, _>>()?;
+    encode_recipient_block(header, &stanzas, data_key)
+}
+
+fn read_header(file: &mut File, path: &Path) -> Result<Mv2eHeader, EncryptionError> {
+    let mut header_bytes = [0u8; Mv2eHeader::SIZE];
+    file.read_exact(&mut header_bytes).map_err(io_error(path))?;
+    Mv2eHeader::decode(&header_bytes)
+}
+
+/// Read the recipient block that follows a v3 header.
+fn read_recipients(
+    file: &mut File,
+    path: &Path,
+    header: &Mv2eHeader,
+) -> Result<(Vec<Stanza>, Vec<u8>), EncryptionError> {
+    if header.version != MV2E_VERSION {
+        return Err(EncryptionError::UnsupportedVersion {
+            version: header.version,
+        });
+    }
+    let len = recipient_block_len(header)?;
+    let mut block = vec![0u8; len];
+    file.read_exact(&mut block).map_err(|source| {
+        if source.kind() == std::io::ErrorKind::UnexpectedEof {
+            EncryptionError::InvalidRecipients {
+                reason: "recipient block is truncated".to_string(),
+            }
+        } else {
+            io_error(path)(source)
+        }
+    })?;
+    let stanzas = decode_recipient_block(&block)?;
+    Ok((stanzas, block))
+}
+
+/// Decrypt a v1 capsule, which seals the whole file as one AES-GCM message.
+fn unlock_v1(
+    file: &mut File,
+    input: &Path,
+    header: &Mv2eHeader,
+    password: &[u8],
+) -> Result<Vec<u8>, EncryptionError> {
     let mut ciphertext = Vec::new();
-    file.read_to_end(&mut ciphertext)
-        .map_err(|source| EncryptionError::Io {
-            source,
-            path: Some(input.to_path_buf()),
-        })?;
+    file.read_to_end(&mut ciphertext).map_err(io_error(input))?;
 
     let mut key = derive_key(password, &header.salt)?;
-    let plaintext = decrypt(&ciphertext, &key, &header.nonce)?;
+    let plaintext = decrypt(&ciphertext, &key, &header.nonce);
     key.zeroize();
+    let plaintext = plaintext?;
 
     if plaintext.len() as u64 != header.original_size {
         return Err(EncryptionError::SizeMismatch {
@@ -100,17 +359,120 @@ pub fn unlock_file(
     }
 
     validate_mv2_bytes(&plaintext)?;
+    Ok(plaintext)
+}
 
-    let output_path = output
-        .map(PathBuf::from)
-        .unwrap_or_else(|| input.with_extension("mv2"));
+/// Seal `reader` into `writer` as a sequence of chunks, returning the plaintext length.
+///
+/// Each chunk is read one ahead so the final chunk can be flagged in its nonce.
+fn encrypt_stream(
+    cipher: &Aes256Gcm,
+    prefix: [u8; STREAM_NONCE_PREFIX_SIZE],
+    aad: &[u8],
+    (reader, input): (&mut impl Read, &Path),
+    (writer, output): (&mut impl Write, &Path),
+) -> Result<u64, EncryptionError> {
+    let mut current = vec![0u8; STREAM_CHUNK_SIZE];
+    let mut next = vec![0u8; STREAM_CHUNK_SIZE];
+    let mut current_len = read_chunk(reader, &mut current).map_err(io_error(input))?;
+    let mut index: u32 = 0;
+    let mut total = 0u64;
+    loop {
+        let next_len = if current_len == STREAM_CHUNK_SIZE {
+            read_chunk(reader, &mut next).map_err(io_error(input))?
+        } else {
+            0
+        };
+        let last = next_len == 0;
+        let sealed = encrypt_chunk(
+            cipher,
+            &chunk_nonce(prefix, index, last),
+            &current[..current_len],
+            aad,
+        )?;
+        writer.write_all(&sealed).map_err(io_error(output))?;
+        total += current_len as u64;
+        if last {
+            return Ok(total);
+        }
+        std::mem::swap(&mut current, &mut next);
+        current_len = next_len;
+        index = index
+            .checked_add(1)
+            .ok_or_else(|| EncryptionError::Encryption {
+                reason: "file exceeds the maximum capsule length".to_string(),
+            })?;
+    }
+}
 
-    write_atomic(&output_path, |writer| {
-        writer.write_all(&plaintext)?;
-        Ok(())
-    })?;
+/// Open the chunks of a v2 or v3 capsule body into `writer`, returning the plaintext length.
+fn decrypt_stream(
+    cipher: &Aes256Gcm,
+    prefix: [u8; STREAM_NONCE_PREFIX_SIZE],
+    aad: &[u8],
+    (reader, input): (&mut impl Read, &Path),
+    (writer, output): (&mut impl Write, &Path),
+) -> Result<u64, EncryptionError> {
+    const SEALED_CHUNK_SIZE: usize = STREAM_CHUNK_SIZE + TAG_SIZE;
+    let mut current = vec![0u8; SEALED_CHUNK_SIZE];
+    let mut next = vec![0u8; SEALED_CHUNK_SIZE];
+    let mut current_len = read_chunk(reader, &mut current).map_err(io_error(input))?;
+    let mut index: u32 = 0;
+    let mut total = 0u64;
+    loop {
+        if current_len < TAG_SIZE {
+            return Err(EncryptionError::Truncated { chunk: index });
+        }
+        let next_len = if current_len == SEALED_CHUNK_SIZE {
+            read_chunk(reader, &mut next).map_err(io_error(input))?
+        } else {
+            0
+        };
+        let last = next_len == 0;
+        let plaintext = decrypt_chunk(
+            cipher,
+            &chunk_nonce(prefix, index, last),
+            &current[..current_len],
+            aad,
+        )
+        .map_err(|_| EncryptionError::Decryption {
+            reason: format!("chunk {index} failed authentication"),
+        })?;
+        if index == 0 {
+            validate_mv2_bytes(&plaintext)?;
+        }
+        writer.write_all(&plaintext).map_err(io_error(output))?;
+        total += plaintext.len() as u64;
+        if last {
+            return Ok(total);
+        }
+        std::mem::swap(&mut current, &mut next);
+        current_len = next_len;
+        index = index
+            .checked_add(1)
+            .ok_or(EncryptionError::Truncated { chunk: index })?;
+    }
+}
 
-    Ok(output_path)
+/// Fill `buf` from `reader`, stopping early only at end of input.
+fn read_chunk(reader: &mut impl Read, buf: &mut [u8]) -> std::io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(err) if err.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(filled)
+}
+
+fn io_error(path: &Path) -> impl FnOnce(std::io::Error) -> EncryptionError + '_ {
+    move |source| EncryptionError::Io {
+        source,
+        path: Some(path.to_path_buf()),
+    }
 }
 
 fn validate_mv2_file(path: &Path) -> Result<(), EncryptionError> {
@@ -145,7 +507,7 @@ fn validate_mv2_bytes(bytes: &[u8]) -> Result<(), EncryptionError> {
 
 fn write_atomic<F>(path: &Path, write_fn: F) -> Result<(), EncryptionError>
 where
-    F: FnOnce(&mut File) -> std::io::Result<()>,
+    F: FnOnce(&mut File) -> Result<(), EncryptionError>,
 {
     let mut options = AtomicWriteFile::options();
     options.read(false);
@@ -164,10 +526,7 @@ where
             source,
             path: Some(path.to_path_buf()),
         })?;
-    write_fn(file).map_err(|source| EncryptionError::Io {
-        source,
-        path: Some(path.to_path_buf()),
-    })?;
+    write_fn(file)?;
     file.flush().map_err(|source| EncryptionError::Io {
         source,
         path: Some(path.to_path_buf()),
@@ -182,3 +541,78 @@ where
     })?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encryption::crypto::encrypt;
+
+    #[test]
+    fn unlock_reads_single_message_v1_capsules() {
+        let dir = tempfile::tempdir().expect("tmp");
+        let capsule = dir.path().join("legacy.mv2e");
+        let restored = dir.path().join("legacy.mv2");
+        let plaintext = b"MV2\0legacy capsule body".to_vec();
+
+        let salt = [3u8; SALT_SIZE];
+        let nonce = [5u8; NONCE_SIZE];
+        let key = derive_key(b"legacy", &salt).expect("key");
+        let header = Mv2eHeader {
+            magic: MV2E_MAGIC,
+            version: MV2E_VERSION_V1,
+            kdf_algorithm: KdfAlgorithm::Argon2id,
+            cipher_algorithm: CipherAlgorithm::Aes256Gcm,
+            salt,
+            nonce,
+            original_size: plaintext.len() as u64,
+            reserved: [0u8; 4],
+        };
+        let mut bytes = header.encode().to_vec();
+        bytes.extend(encrypt(&plaintext, &key, &nonce).expect("encrypt"));
+        std::fs::write(&capsule, bytes).expect("write");
+
+        unlock_file(&capsule, Some(restored.as_path()), b"legacy").expect("unlock");
+        assert_eq!(std::fs::read(&restored).expect("read"), plaintext);
+    }
+
+    #[test]
+    fn unlock_reads_password_only_v2_capsules() {
+        let dir = tempfile::tempdir().expect("tmp");
+        let capsule = dir.path().join("v2.mv2e");
+        let restored = dir.path().join("v2.mv2");
+        let plaintext = b"MV2\0password-only streaming capsule".to_vec();
+
+        let salt = [7u8; SALT_SIZE];
+        let prefix = [9u8; STREAM_NONCE_PREFIX_SIZE];
+        let mut nonce = [0u8; NONCE_SIZE];
+        nonce[..STREAM_NONCE_PREFIX_SIZE].copy_from_slice(&prefix);
+        let header = Mv2eHeader {
+            magic: MV2E_MAGIC,
+            version: crate::encryption::constants::MV2E_VERSION_V2,
+            kdf_algorithm: KdfAlgorithm::Argon2id,
+            cipher_algorithm: CipherAlgorithm::Aes256Gcm,
+            salt,
+            nonce,
+            original_size: plaintext.len() as u64,
+            reserved: [0u8; 4],
+        };
+        let header_bytes = header.encode();
+        let cipher = stream_cipher(&derive_key(b"v2", &salt).expect("key")).expect("cipher");
+        let mut bytes = header_bytes.to_vec();
+        encrypt_stream(
+            &cipher,
+            prefix,
+            &header_bytes,
+            (&mut plaintext.as_slice(), &capsule),
+            (&mut bytes, &capsule),
+        )
+        .expect("encrypt");
+        std::fs::write(&capsule, bytes).expect("write");
+
+        let err = unlock_file_with(&capsule, None, &Identity::KeyFile([1u8; KEY_SIZE]))
+            .expect_err("v2 needs a password");
+        assert!(matches!(err, EncryptionError::Decryption { .. }));
+        unlock_file(&capsule, Some(restored.as_path()), b"v2").expect("unlock");
+        assert_eq!(std::fs::read(&restored).expect("read"), plaintext);
+    }
+}
@@ -3,14 +3,23 @@
 /// Magic bytes identifying an encrypted capsule file.
 pub const MV2E_MAGIC: [u8; 4] = *b"MV2E";
 
-/// Current `.mv2e` format version.
-pub const MV2E_VERSION: u16 = 1;
+/// Current `.mv2e` format version: a random data key wrapped for each recipient,
+/// with the body sealed by chunked streaming AEAD.
+pub const MV2E_VERSION: u16 = 3;
+
+/// Password-only capsule with the body sealed by chunked streaming AEAD.
+pub const MV2E_VERSION_V2: u16 = 2;
+
+/// Original `.mv2e` format: the whole file sealed as a single AES-GCM message.
+pub const MV2E_VERSION_V1: u16 = 1;
 
 /// Fixed header size for `.mv2e`.
 pub const MV2E_HEADER_SIZE: usize = 64;
 
 /// KDF algorithm identifiers.
 pub const KDF_ARGON2ID: u8 = 1;
+/// The body key is random and wrapped per recipient in the recipient block.
+pub const KDF_WRAPPED_KEY: u8 = 2;
 
 /// Cipher algorithm identifiers.
 pub const CIPHER_AES_256_GCM: u8 = 1;
@@ -21,7 +30,27 @@ pub const NONCE_SIZE: usize = 12;
 pub const TAG_SIZE: usize = 16;
 pub const KEY_SIZE: usize = 32;
 
+/// X25519 public and secret key size.
+pub const X25519_KEY_SIZE: usize = 32;
+/// Truncated key-file identifier stored in key-file stanzas.
+pub const KEY_ID_SIZE: usize = 8;
+/// A data key sealed with AES-256-GCM.
+pub const WRAPPED_KEY_SIZE: usize = KEY_SIZE + TAG_SIZE;
+/// BLAKE3 MAC over the fixed header and the recipient block.
+pub const HEADER_MAC_SIZE: usize = 32;
+
+/// Plaintext bytes per chunk in v2 capsules; the final chunk may be shorter.
+pub const STREAM_CHUNK_SIZE: usize = 64 * 1024;
+/// Random prefix of each chunk nonce, stored at the start of the header nonce field.
+/// The remaining nonce bytes are the big-endian chunk index and the final-chunk flag.
+pub const STREAM_NONCE_PREFIX_SIZE: usize = 7;
+
 /// Argon2id parameters (OWASP 2024 recommendations).
 pub const ARGON2_MEMORY_KIB: u32 = 64 * 1024; // 64 MiB
 pub const ARGON2_ITERATIONS: u32 = 3;
 pub const ARGON2_PARALLELISM: u32 = 4;
+
+/// Random salt mixed into the region keys of an encrypted-mode `.mv2`.
+pub const REGION_SALT_SIZE: usize = 16;
+/// Smallest stored region: a nonce followed by the authentication tag.
+pub const REGION_OVERHEAD: usize = NONCE_SIZE + TAG_SIZE;
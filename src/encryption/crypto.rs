@@ -1,9 +1,10 @@
-use aes_gcm::aead::Aead;
+use aes_gcm::aead::{Aead, Payload};
 use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
 use argon2::{Algorithm, Argon2, Params, Version};
 
 use crate::encryption::constants::{
     ARGON2_ITERATIONS, ARGON2_MEMORY_KIB, ARGON2_PARALLELISM, KEY_SIZE, NONCE_SIZE, SALT_SIZE,
+    STREAM_NONCE_PREFIX_SIZE,
 };
 use crate::encryption::error::EncryptionError;
 
@@ -62,3 +63,53 @@ pub fn decrypt(
             reason: e.to_string(),
         })
 }
+
+/// Nonce for chunk `index` of a v2 stream: `prefix || index (u32 BE) || final flag`.
+///
+/// Binding the index and final flag into the nonce makes reordered, dropped or
+/// truncated chunks fail authentication.
+#[must_use]
+pub fn chunk_nonce(
+    prefix: [u8; STREAM_NONCE_PREFIX_SIZE],
+    index: u32,
+    last: bool,
+) -> [u8; NONCE_SIZE] {
+    let mut nonce = [0u8; NONCE_SIZE];
+    nonce[..STREAM_NONCE_PREFIX_SIZE].copy_from_slice(&prefix);
+    nonce[STREAM_NONCE_PREFIX_SIZE..NONCE_SIZE - 1].copy_from_slice(&index.to_be_bytes());
+    nonce[NONCE_SIZE - 1] = u8::from(last);
+    nonce
+}
+
+/// AES-256-GCM cipher for sealing the chunks of one v2 stream.
+pub fn stream_cipher(key: &[u8; KEY_SIZE]) -> Result<Aes256Gcm, EncryptionError> {
+    Aes256Gcm::new_from_slice(key).map_err(|e| EncryptionError::CipherInit {
+        reason: e.to_string(),
+    })
+}
+
+pub fn encrypt_chunk(
+    cipher: &Aes256Gcm,
+    nonce: &[u8; NONCE_SIZE],
+    chunk: &[u8],
+    aad: &[u8],
+) -> Result<Vec<u8>, EncryptionError> {
+    cipher
+        .encrypt(Nonce::from_slice(nonce), Payload { msg: chunk, aad })
+        .map_err(|e| EncryptionError::Encryption {
+            reason: e.to_string(),
+        })
+}
+
+pub fn decrypt_chunk(
+    cipher: &Aes256Gcm,
+    nonce: &[u8; NONCE_SIZE],
+    chunk: &[u8],
+    aad: &[u8],
+) -> Result<Vec<u8>, EncryptionError> {
+    cipher
+        .decrypt(Nonce::from_slice(nonce), Payload { msg: chunk, aad })
+        .map_err(|e| EncryptionError::Decryption {
+            reason: e.to_string(),
+        })
+}
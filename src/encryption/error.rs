@@ -42,4 +42,19 @@ pub enum EncryptionError {
 
     #[error("Corrupted decryption - output is not a valid MV2 file")]
     CorruptedDecryption,
+
+    #[error("Capsule is truncated: chunk {chunk} is missing")]
+    Truncated { chunk: u32 },
+
+    #[error("No recipient with fingerprint {fingerprint}")]
+    RecipientNotFound { fingerprint: String },
+
+    #[error("Cannot remove the last recipient of a capsule")]
+    LastRecipient,
+
+    #[error("Invalid recipient block: {reason}")]
+    InvalidRecipients { reason: String },
+
+    #[error("Invalid key file {path}: expected {expected} bytes")]
+    InvalidKeyFile { path: PathBuf, expected: usize },
 }
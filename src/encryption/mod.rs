@@ -1,4 +1,4 @@
-//! Password-based encryption capsules for `.mv2` files (`.mv2e`).
+//! Encryption for `.mv2` files: `.mv2e` capsules and encrypted-mode memories.
 //!
 //! This module is feature-gated (`encryption`) to keep the default memvid-core
 //! binary size small and avoid pulling crypto dependencies into users that don't
@@ -8,9 +8,20 @@ mod capsule;
 mod constants;
 mod crypto;
 mod error;
+mod recipients;
+mod region;
 mod types;
 
-pub use capsule::{lock_file, unlock_file};
+pub use capsule::{
+    add_recipient, list_recipients, lock_file, lock_file_for, remove_recipient, rotate_password,
+    unlock_file, unlock_file_with,
+};
 pub use constants::*;
 pub use error::EncryptionError;
-pub use types::{CipherAlgorithm, KdfAlgorithm, Mv2eHeader};
+pub use recipients::{
+    generate_key_file, generate_x25519_keypair, read_key_file, x25519_public_key,
+};
+pub(crate) use region::RegionCipher;
+pub use types::{
+    CipherAlgorithm, Identity, KdfAlgorithm, Mv2eHeader, Recipient, RecipientInfo, RecipientKind,
+};
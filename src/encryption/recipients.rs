@@ -0,0 +1,490 @@
+//! Recipient stanzas for multi-recipient (v3) capsules.
+//!
+//! A v3 header is followed by a recipient block:
+//! `count: u16`, then per stanza `kind: u8`, `len: u16` and the stanza body, then a
+//! BLAKE3 MAC over the fixed header and the block. The MAC key is derived from the data
+//! key, so the recipient list cannot be edited without being able to unlock the capsule.
+//!
+//! Each stanza holds the data key sealed with AES-256-GCM under a key-encryption key:
+//! - password: Argon2id over the password and a per-stanza salt
+//! - key file: BLAKE3 key derivation over the key file contents and the capsule salt
+//! - X25519: BLAKE3 key derivation over an ephemeral Diffie-Hellman shared secret
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+
+use curve25519_dalek::constants::X25519_BASEPOINT;
+use curve25519_dalek::montgomery::MontgomeryPoint;
+use rand::RngCore;
+use rand::rngs::OsRng;
+use zeroize::Zeroize;
+
+use crate::encryption::constants::{
+    HEADER_MAC_SIZE, KEY_ID_SIZE, KEY_SIZE, NONCE_SIZE, SALT_SIZE, WRAPPED_KEY_SIZE,
+    X25519_KEY_SIZE,
+};
+use crate::encryption::crypto::{decrypt, derive_key, encrypt};
+use crate::encryption::error::EncryptionError;
+use crate::encryption::types::{Identity, Mv2eHeader, Recipient, RecipientInfo, RecipientKind};
+
+const KEY_FILE_CONTEXT: &str = "memvid mv2e key-file wrap key v1";
+const KEY_ID_CONTEXT: &str = "memvid mv2e key-file id v1";
+const X25519_CONTEXT: &str = "memvid mv2e x25519 wrap key v1";
+const HEADER_MAC_CONTEXT: &str = "memvid mv2e header mac v1";
+
+/// Upper bound on the recipient block, to reject absurd lengths before allocating.
+const MAX_RECIPIENT_BLOCK_SIZE: usize = 1024 * 1024;
+
+pub enum Stanza {
+    Password {
+        salt: [u8; SALT_SIZE],
+        nonce: [u8; NONCE_SIZE],
+        wrapped: [u8; WRAPPED_KEY_SIZE],
+    },
+    KeyFile {
+        key_id: [u8; KEY_ID_SIZE],
+        nonce: [u8; NONCE_SIZE],
+        wrapped: [u8; WRAPPED_KEY_SIZE],
+    },
+    X25519 {
+        recipient: [u8; X25519_KEY_SIZE],
+        ephemeral: [u8; X25519_KEY_SIZE],
+        nonce: [u8; NONCE_SIZE],
+        wrapped: [u8; WRAPPED_KEY_SIZE],
+    },
+}
+
+fn random_bytes<const N: usize>() -> [u8; N] {
+    let mut bytes = [0u8; N];
+    OsRng.fill_bytes(&mut bytes);
+    bytes
+}
+
+/// Generate a random data key for a new capsule.
+pub fn generate_data_key() -> [u8; KEY_SIZE] {
+    random_bytes()
+}
+
+/// Generate an X25519 key pair, returned as `(secret, public)`.
+#[must_use]
+pub fn generate_x25519_keypair() -> ([u8; X25519_KEY_SIZE], [u8; X25519_KEY_SIZE]) {
+    let secret = random_bytes();
+    (secret, x25519_public_key(&secret))
+}
+
+/// Public key matching an X25519 secret key.
+#[must_use]
+pub fn x25519_public_key(secret: &[u8; X25519_KEY_SIZE]) -> [u8; X25519_KEY_SIZE] {
+    X25519_BASEPOINT.mul_clamped(*secret).to_bytes()
+}
+
+/// Write a new random key file to `path`, refusing to overwrite an existing file.
+pub fn generate_key_file(path: impl AsRef<Path>) -> Result<[u8; KEY_SIZE], EncryptionError> {
+    let path = path.as_ref();
+    let key: [u8; KEY_SIZE] = random_bytes();
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let io_error = |source| EncryptionError::Io {
+        source,
+        path: Some(path.to_path_buf()),
+    };
+    let mut file = options.open(path).map_err(io_error)?;
+    file.write_all(&key).map_err(io_error)?;
+    file.sync_all().map_err(io_error)?;
+    Ok(key)
+}
+
+/// Read a raw key file written by [`generate_key_file`].
+pub fn read_key_file(path: impl AsRef<Path>) -> Result<[u8; KEY_SIZE], EncryptionError> {
+    let path = path.as_ref();
+    let mut bytes = std::fs::read(path).map_err(|source| EncryptionError::Io {
+        source,
+        path: Some(path.to_path_buf()),
+    })?;
+    let key =
+        <[u8; KEY_SIZE]>::try_from(bytes.as_slice()).map_err(|_| EncryptionError::InvalidKeyFile {
+            path: path.to_path_buf(),
+            expected: KEY_SIZE,
+        });
+    bytes.zeroize();
+    key
+}
+
+fn key_file_id(key: &[u8; KEY_SIZE]) -> [u8; KEY_ID_SIZE] {
+    let mut id = [0u8; KEY_ID_SIZE];
+    id.copy_from_slice(&blake3::derive_key(KEY_ID_CONTEXT, key)[..KEY_ID_SIZE]);
+    id
+}
+
+fn key_file_wrap_key(key: &[u8; KEY_SIZE], capsule_salt: &[u8; SALT_SIZE]) -> [u8; KEY_SIZE] {
+    let mut material = [0u8; KEY_SIZE + SALT_SIZE];
+    material[..KEY_SIZE].copy_from_slice(key);
+    material[KEY_SIZE..].copy_from_slice(capsule_salt);
+    let wrap_key = blake3::derive_key(KEY_FILE_CONTEXT, &material);
+    material.zeroize();
+    wrap_key
+}
+
+fn x25519_wrap_key(
+    secret: &[u8; X25519_KEY_SIZE],
+    peer: &[u8; X25519_KEY_SIZE],
+    ephemeral: &[u8; X25519_KEY_SIZE],
+    recipient: &[u8; X25519_KEY_SIZE],
+    capsule_salt: &[u8; SALT_SIZE],
+) -> Result<[u8; KEY_SIZE], EncryptionError> {
+    let mut shared = MontgomeryPoint(*peer).mul_clamped(*secret).to_bytes();
+    // A low-order peer key yields an all-zero secret that anyone could compute.
+    if shared == [0u8; X25519_KEY_SIZE] {
+        return Err(EncryptionError::InvalidRecipients {
+            reason: "X25519 key is a low-order point".to_string(),
+        });
+    }
+    let mut material = Vec::with_capacity(3 * X25519_KEY_SIZE + SALT_SIZE);
+    material.extend_from_slice(&shared);
+    material.extend_from_slice(ephemeral);
+    material.extend_from_slice(recipient);
+    material.extend_from_slice(capsule_salt);
+    let wrap_key = blake3::derive_key(X25519_CONTEXT, &material);
+    shared.zeroize();
+    material.zeroize();
+    Ok(wrap_key)
+}
+
+fn seal_data_key(
+    wrap_key: &[u8; KEY_SIZE],
+    data_key: &[u8; KEY_SIZE],
+) -> Result<([u8; NONCE_SIZE], [u8; WRAPPED_KEY_SIZE]), EncryptionError> {
+    let nonce = random_bytes();
+    let sealed = encrypt(data_key, wrap_key, &nonce)?;
+    let wrapped = <[u8; WRAPPED_KEY_SIZE]>::try_from(sealed.as_slice()).map_err(|_| {
+        EncryptionError::Encryption {
+            reason: "unexpected wrapped key length".to_string(),
+        }
+    })?;
+    Ok((nonce, wrapped))
+}
+
+fn open_data_key(
+    wrap_key: &[u8; KEY_SIZE],
+    nonce: &[u8; NONCE_SIZE],
+    wrapped: &[u8; WRAPPED_KEY_SIZE],
+) -> Option<[u8; KEY_SIZE]> {
+    let mut opened = decrypt(wrapped, wrap_key, nonce).ok()?;
+    let key = <[u8; KEY_SIZE]>::try_from(opened.as_slice()).ok();
+    opened.zeroize();
+    key
+}
+
+impl Stanza {
+    /// Wrap `data_key` for `recipient`.
+    pub fn wrap(
+        recipient: &Recipient,
+        data_key: &[u8; KEY_SIZE],
+        capsule_salt: &[u8; SALT_SIZE],
+    ) -> Result<Self, EncryptionError> {
+        match recipient {
+            Recipient::Password(password) => {
+                let salt = random_bytes();
+                let mut wrap_key = derive_key(password, &salt)?;
+                let sealed = seal_data_key(&wrap_key, data_key);
+                wrap_key.zeroize();
+                let (nonce, wrapped) = sealed?;
+                Ok(Self::Password {
+                    salt,
+                    nonce,
+                    wrapped,
+                })
+            }
+            Recipient::KeyFile(key) => {
+                let mut wrap_key = key_file_wrap_key(key, capsule_salt);
+                let sealed = seal_data_key(&wrap_key, data_key);
+                wrap_key.zeroize();
+                let (nonce, wrapped) = sealed?;
+                Ok(Self::KeyFile {
+                    key_id: key_file_id(key),
+                    nonce,
+                    wrapped,
+                })
+            }
+            Recipient::X25519(public) => {
+                let mut ephemeral_secret: [u8; X25519_KEY_SIZE] = random_bytes();
+                let ephemeral = x25519_public_key(&ephemeral_secret);
+                let wrap_key =
+                    x25519_wrap_key(&ephemeral_secret, public, &ephemeral, public, capsule_salt);
+                ephemeral_secret.zeroize();
+                let mut wrap_key = wrap_key?;
+                let sealed = seal_data_key(&wrap_key, data_key);
+                wrap_key.zeroize();
+                let (nonce, wrapped) = sealed?;
+                Ok(Self::X25519 {
+                    recipient: *public,
+                    ephemeral,
+                    nonce,
+                    wrapped,
+                })
+            }
+        }
+    }
+
+    /// Recover the data key if `identity` is the one this stanza was wrapped for.
+    pub fn unwrap(
+        &self,
+        identity: &Identity,
+        capsule_salt: &[u8; SALT_SIZE],
+    ) -> Result<Option<[u8; KEY_SIZE]>, EncryptionError> {
+        let mut wrap_key = match (self, identity) {
+            (Self::Password { salt, .. }, Identity::Password(password)) => {
+                derive_key(password, salt)?
+            }
+            (Self::KeyFile { key_id, .. }, Identity::KeyFile(key)) => {
+                if key_file_id(key) != *key_id {
+                    return Ok(None);
+                }
+                key_file_wrap_key(key, capsule_salt)
+            }
+            (
+                Self::X25519 {
+                    recipient,
+                    ephemeral,
+                    ..
+                },
+                Identity::X25519(secret),
+            ) => {
+                if x25519_public_key(secret) != *recipient {
+                    return Ok(None);
+                }
+                x25519_wrap_key(secret, ephemeral, ephemeral, recipient, capsule_salt)?
+            }
+            _ => return Ok(None),
+        };
+        let (nonce, wrapped) = match self {
+            Self::Password { nonce, wrapped, .. }
+            | Self::KeyFile { nonce, wrapped, .. }
+            | Self::X25519 { nonce, wrapped, .. } => (nonce, wrapped),
+        };
+        let key = open_data_key(&wrap_key, nonce, wrapped);
+        wrap_key.zeroize();
+        Ok(key)
+    }
+
+    #[must_use]
+    pub fn info(&self) -> RecipientInfo {
+        match self {
+            Self::Password { salt, .. } => RecipientInfo {
+                kind: RecipientKind::Password,
+                fingerprint: hex::encode(&salt[..KEY_ID_SIZE]),
+            },
+            Self::KeyFile { key_id, .. } => RecipientInfo {
+                kind: RecipientKind::KeyFile,
+                fingerprint: hex::encode(key_id),
+            },
+            Self::X25519 { recipient, .. } => RecipientInfo {
+                kind: RecipientKind::X25519,
+                fingerprint: hex::encode(recipient),
+            },
+        }
+    }
+
+    fn encode_into(&self, out: &mut Vec<u8>) {
+        let start = out.len();
+        out.push(self.info().kind as u8);
+        out.extend_from_slice(&[0, 0]);
+        match self {
+            Self::Password {
+                salt,
+                nonce,
+                wrapped,
+            } => {
+                out.extend_from_slice(salt);
+                out.extend_from_slice(nonce);
+                out.extend_from_slice(wrapped);
+            }
+            Self::KeyFile {
+                key_id,
+                nonce,
+                wrapped,
+            } => {
+                out.extend_from_slice(key_id);
+                out.extend_from_slice(nonce);
+                out.extend_from_slice(wrapped);
+            }
+            Self::X25519 {
+                recipient,
+                ephemeral,
+                nonce,
+                wrapped,
+            } => {
+                out.extend_from_slice(recipient);
+                out.extend_from_slice(ephemeral);
+                out.extend_from_slice(nonce);
+                out.extend_from_slice(wrapped);
+            }
+        }
+        let body_len = (out.len() - start - 3) as u16;
+        out[start + 1..start + 3].copy_from_slice(&body_len.to_le_bytes());
+    }
+
+    fn decode(kind: u8, body: &[u8]) -> Result<Self, EncryptionError> {
+        let invalid = || EncryptionError::InvalidRecipients {
+            reason: format!("stanza of kind {kind} has invalid length {}", body.len()),
+        };
+        let mut cursor = body;
+        let mut take = |len: usize| -> Result<&[u8], EncryptionError> {
+            if cursor.len() < len {
+                return Err(invalid());
+            }
+            let (head, rest) = cursor.split_at(len);
+            cursor = rest;
+            Ok(head)
+        };
+        let stanza = match kind {
+            k if k == RecipientKind::Password as u8 => Self::Password {
+                salt: take(SALT_SIZE)?.try_into().map_err(|_| invalid())?,
+                nonce: take(NONCE_SIZE)?.try_into().map_err(|_| invalid())?,
+                wrapped: take(WRAPPED_KEY_SIZE)?.try_into().map_err(|_| invalid())?,
+            },
+            k if k == RecipientKind::KeyFile as u8 => Self::KeyFile {
+                key_id: take(KEY_ID_SIZE)?.try_into().map_err(|_| invalid())?,
+                nonce: take(NONCE_SIZE)?.try_into().map_err(|_| invalid())?,
+                wrapped: take(WRAPPED_KEY_SIZE)?.try_into().map_err(|_| invalid())?,
+            },
+            k if k == RecipientKind::X25519 as u8 => Self::X25519 {
+                recipient: take(X25519_KEY_SIZE)?.try_into().map_err(|_| invalid())?,
+                ephemeral: take(X25519_KEY_SIZE)?.try_into().map_err(|_| invalid())?,
+                nonce: take(NONCE_SIZE)?.try_into().map_err(|_| invalid())?,
+                wrapped: take(WRAPPED_KEY_SIZE)?.try_into().map_err(|_| invalid())?,
+            },
+            other => {
+                return Err(EncryptionError::InvalidRecipients {
+                    reason: format!("unknown stanza kind {other}"),
+                });
+            }
+        };
+        if !cursor.is_empty() {
+            return Err(invalid());
+        }
+        Ok(stanza)
+    }
+}
+
+fn header_mac(
+    data_key: &[u8; KEY_SIZE],
+    header: &Mv2eHeader,
+    block: &[u8],
+) -> [u8; HEADER_MAC_SIZE] {
+    let mut mac_key = blake3::derive_key(HEADER_MAC_CONTEXT, data_key);
+    let mut hasher = blake3::Hasher::new_keyed(&mac_key);
+    mac_key.zeroize();
+    hasher.update(&header.encode());
+    hasher.update(block);
+    *hasher.finalize().as_bytes()
+}
+
+/// Length of the recipient block following a v3 header.
+pub fn recipient_block_len(header: &Mv2eHeader) -> Result<usize, EncryptionError> {
+    let len = u32::from_le_bytes(header.reserved) as usize;
+    if !(2 + HEADER_MAC_SIZE..=MAX_RECIPIENT_BLOCK_SIZE).contains(&len) {
+        return Err(EncryptionError::InvalidRecipients {
+            reason: format!("recipient block length {len} is out of range"),
+        });
+    }
+    Ok(len)
+}
+
+/// Header bytes authenticated by every body chunk of a v3 capsule.
+///
+/// The recipient block length is zeroed so recipients can be rewrapped without
+/// touching the body.
+#[must_use]
+pub fn body_aad(header: &Mv2eHeader) -> [u8; Mv2eHeader::SIZE] {
+    let mut header = header.clone();
+    header.reserved = [0u8; 4];
+    header.encode()
+}
+
+/// Encode the recipient block for `stanzas`, recording its length in `header.reserved`.
+pub fn encode_recipient_block(
+    header: &mut Mv2eHeader,
+    stanzas: &[Stanza],
+    data_key: &[u8; KEY_SIZE],
+) -> Result<Vec<u8>, EncryptionError> {
+    if stanzas.is_empty() {
+        return Err(EncryptionError::InvalidRecipients {
+            reason: "a capsule needs at least one recipient".to_string(),
+        });
+    }
+    let count = u16::try_from(stanzas.len()).map_err(|_| EncryptionError::InvalidRecipients {
+        reason: format!("too many recipients: {}", stanzas.len()),
+    })?;
+    let mut block = count.to_le_bytes().to_vec();
+    for stanza in stanzas {
+        stanza.encode_into(&mut block);
+    }
+    let total = block.len() + HEADER_MAC_SIZE;
+    header.reserved = u32::try_from(total).unwrap_or(u32::MAX).to_le_bytes();
+    let mac = header_mac(data_key, header, &block);
+    block.extend_from_slice(&mac);
+    Ok(block)
+}
+
+/// Parse the stanzas of a recipient block without authenticating it.
+pub fn decode_recipient_block(block: &[u8]) -> Result<Vec<Stanza>, EncryptionError> {
+    let truncated = || EncryptionError::InvalidRecipients {
+        reason: "recipient block is truncated".to_string(),
+    };
+    let body = block
+        .len()
+        .checked_sub(HEADER_MAC_SIZE)
+        .map(|len| &block[..len])
+        .filter(|body| body.len() >= 2)
+        .ok_or_else(truncated)?;
+    let count = u16::from_le_bytes([body[0], body[1]]) as usize;
+    let mut offset = 2;
+    let mut stanzas = Vec::with_capacity(count);
+    for _ in 0..count {
+        let header = body.get(offset..offset + 3).ok_or_else(truncated)?;
+        let len = u16::from_le_bytes([header[1], header[2]]) as usize;
+        let stanza_body = body
+            .get(offset + 3..offset + 3 + len)
+            .ok_or_else(truncated)?;
+        stanzas.push(Stanza::decode(header[0], stanza_body)?);
+        offset += 3 + len;
+    }
+    if offset != body.len() {
+        return Err(EncryptionError::InvalidRecipients {
+            reason: "trailing bytes after the last stanza".to_string(),
+        });
+    }
+    Ok(stanzas)
+}
+
+/// Recover the data key with `identity` and authenticate the recipient block with it.
+pub fn unwrap_data_key(
+    header: &Mv2eHeader,
+    block: &[u8],
+    stanzas: &[Stanza],
+    identity: &Identity,
+) -> Result<[u8; KEY_SIZE], EncryptionError> {
+    for stanza in stanzas {
+        let Some(mut data_key) = stanza.unwrap(identity, &header.salt)? else {
+            continue;
+        };
+        let (body, mac) = block.split_at(block.len() - HEADER_MAC_SIZE);
+        if header_mac(&data_key, header, body) != mac {
+            data_key.zeroize();
+            return Err(EncryptionError::Decryption {
+                reason: "recipient block failed authentication".to_string(),
+            });
+        }
+        return Ok(data_key);
+    }
+    Err(EncryptionError::Decryption {
+        reason: "no recipient matches the provided identity".to_string(),
+    })
+}
@@ -0,0 +1,142 @@
+//! Region keys for encrypted-mode `.mv2` files.
+//!
+//! Each region kind gets its own AES-256-GCM key, derived with BLAKE3 from the master key
+//! and a random per-file salt. A stored region is `nonce (12) || ciphertext || tag (16)`
+//! with a fresh random nonce, and the region kind is bound in as associated data.
+
+use aes_gcm::{Aes256Gcm, KeyInit};
+use rand::RngCore;
+use rand::rngs::OsRng;
+use zeroize::Zeroize;
+
+use crate::encryption::constants::{KEY_SIZE, NONCE_SIZE, REGION_OVERHEAD, REGION_SALT_SIZE};
+use crate::encryption::crypto::{decrypt_chunk, encrypt_chunk};
+use crate::encryption::error::EncryptionError;
+use crate::io::region::RegionKind;
+
+const PAYLOAD_CONTEXT: &str = "memvid mv2 payload region key v1";
+const INDEX_CONTEXT: &str = "memvid mv2 index region key v1";
+const TOC_CONTEXT: &str = "memvid mv2 toc region key v1";
+const WAL_CONTEXT: &str = "memvid mv2 wal region key v1";
+const KEY_CHECK_CONTEXT: &str = "memvid mv2 region key check v1";
+
+/// Per-kind ciphers of one encrypted memory.
+pub(crate) struct RegionCipher {
+    salt: [u8; REGION_SALT_SIZE],
+    key_check: [u8; 32],
+    payload: Aes256Gcm,
+    index: Aes256Gcm,
+    toc: Aes256Gcm,
+    wal: Aes256Gcm,
+}
+
+impl RegionCipher {
+    /// Derive region keys for a new memory with a random salt.
+    pub(crate) fn generate(master_key: &[u8; KEY_SIZE]) -> Self {
+        let mut salt = [0u8; REGION_SALT_SIZE];
+        OsRng.fill_bytes(&mut salt);
+        Self::new(master_key, &salt)
+    }
+
+    /// Derive region keys for a memory whose header records `salt`.
+    pub(crate) fn new(master_key: &[u8; KEY_SIZE], salt: &[u8; REGION_SALT_SIZE]) -> Self {
+        let mut material = [0u8; KEY_SIZE + REGION_SALT_SIZE];
+        material[..KEY_SIZE].copy_from_slice(master_key);
+        material[KEY_SIZE..].copy_from_slice(salt);
+        let cipher = |context: &str| {
+            let mut key = blake3::derive_key(context, &material);
+            let cipher = Aes256Gcm::new((&key).into());
+            key.zeroize();
+            cipher
+        };
+        let region = Self {
+            salt: *salt,
+            key_check: blake3::derive_key(KEY_CHECK_CONTEXT, &material),
+            payload: cipher(PAYLOAD_CONTEXT),
+            index: cipher(INDEX_CONTEXT),
+            toc: cipher(TOC_CONTEXT),
+            wal: cipher(WAL_CONTEXT),
+        };
+        material.zeroize();
+        region
+    }
+
+    pub(crate) fn salt(&self) -> [u8; REGION_SALT_SIZE] {
+        self.salt
+    }
+
+    /// Value stored in the header to reject a wrong master key before any region is read.
+    pub(crate) fn key_check(&self) -> [u8; 32] {
+        self.key_check
+    }
+
+    /// Constant-time comparison against the key check stored in the header.
+    pub(crate) fn key_check_matches(&self, stored: &[u8; 32]) -> bool {
+        blake3::Hash::from(self.key_check) == blake3::Hash::from(*stored)
+    }
+
+    fn cipher(&self, kind: RegionKind) -> &Aes256Gcm {
+        match kind {
+            RegionKind::Payload => &self.payload,
+            RegionKind::Index => &self.index,
+            RegionKind::Toc => &self.toc,
+            RegionKind::Wal => &self.wal,
+        }
+    }
+
+    pub(crate) fn seal(
+        &self,
+        kind: RegionKind,
+        plaintext: &[u8],
+    ) -> Result<Vec<u8>, EncryptionError> {
+        let mut nonce = [0u8; NONCE_SIZE];
+        OsRng.fill_bytes(&mut nonce);
+        let sealed = encrypt_chunk(
+            self.cipher(kind),
+            &nonce,
+            plaintext,
+            kind.as_str().as_bytes(),
+        )?;
+        let mut stored = Vec::with_capacity(NONCE_SIZE + sealed.len());
+        stored.extend_from_slice(&nonce);
+        stored.extend_from_slice(&sealed);
+        Ok(stored)
+    }
+
+    pub(crate) fn open(&self, kind: RegionKind, stored: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+        let Some((nonce, sealed)) = stored
+            .split_first_chunk::<NONCE_SIZE>()
+            .filter(|_| stored.len() >= REGION_OVERHEAD)
+        else {
+            return Err(EncryptionError::Decryption {
+                reason: format!("{} region is truncated", kind.as_str()),
+            });
+        };
+        decrypt_chunk(self.cipher(kind), nonce, sealed, kind.as_str().as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn regions_roundtrip_and_are_bound_to_their_kind() {
+        let cipher = RegionCipher::generate(&[7u8; KEY_SIZE]);
+        let stored = cipher
+            .seal(RegionKind::Payload, b"frame payload")
+            .expect("seal");
+        assert_eq!(stored.len(), b"frame payload".len() + REGION_OVERHEAD);
+        assert_eq!(
+            cipher.open(RegionKind::Payload, &stored).expect("open"),
+            b"frame payload"
+        );
+        assert!(cipher.open(RegionKind::Index, &stored).is_err());
+
+        let reopened = RegionCipher::new(&[7u8; KEY_SIZE], &cipher.salt());
+        assert!(reopened.key_check_matches(&cipher.key_check()));
+        let other = RegionCipher::new(&[8u8; KEY_SIZE], &cipher.salt());
+        assert!(!other.key_check_matches(&cipher.key_check()));
+        assert!(other.open(RegionKind::Payload, &stored).is_err());
+    }
+}
@@ -1,10 +1,13 @@
 use crate::encryption::constants::{
-    CIPHER_AES_256_GCM, KDF_ARGON2ID, MV2E_HEADER_SIZE, MV2E_MAGIC, MV2E_VERSION, NONCE_SIZE,
-    SALT_SIZE,
+    CIPHER_AES_256_GCM, KDF_ARGON2ID, KDF_WRAPPED_KEY, KEY_SIZE, MV2E_HEADER_SIZE, MV2E_MAGIC,
+    MV2E_VERSION, MV2E_VERSION_V1, MV2E_VERSION_V2, NONCE_SIZE, SALT_SIZE, X25519_KEY_SIZE,
 };
 use crate::encryption::error::EncryptionError;
 
 /// MV2E file header (fixed-size, 64 bytes).
+///
+/// In v3 capsules `reserved` holds the little-endian length of the recipient block
+/// that follows the header, and `salt` identifies the capsule rather than feeding a KDF.
 #[derive(Debug, Clone)]
 pub struct Mv2eHeader {
     pub magic: [u8; 4],
@@ -21,6 +24,7 @@ pub struct Mv2eHeader {
 #[repr(u8)]
 pub enum KdfAlgorithm {
     Argon2id = KDF_ARGON2ID,
+    WrappedKey = KDF_WRAPPED_KEY,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -29,6 +33,63 @@ pub enum CipherAlgorithm {
     Aes256Gcm = CIPHER_AES_256_GCM,
 }
 
+/// Someone a capsule's data key is wrapped for.
+#[derive(Clone)]
+pub enum Recipient {
+    /// Unlockable with this password (Argon2id).
+    Password(Vec<u8>),
+    /// Unlockable with the contents of a raw key file.
+    KeyFile([u8; KEY_SIZE]),
+    /// Unlockable with the secret key matching this X25519 public key.
+    X25519([u8; X25519_KEY_SIZE]),
+}
+
+/// Secret material used to unlock a capsule.
+#[derive(Clone)]
+pub enum Identity {
+    Password(Vec<u8>),
+    KeyFile([u8; KEY_SIZE]),
+    /// X25519 secret key.
+    X25519([u8; X25519_KEY_SIZE]),
+}
+
+impl std::fmt::Debug for Recipient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Password(_) => f.write_str("Recipient::Password(..)"),
+            Self::KeyFile(_) => f.write_str("Recipient::KeyFile(..)"),
+            Self::X25519(public) => write!(f, "Recipient::X25519({})", hex::encode(public)),
+        }
+    }
+}
+
+impl std::fmt::Debug for Identity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Password(_) => f.write_str("Identity::Password(..)"),
+            Self::KeyFile(_) => f.write_str("Identity::KeyFile(..)"),
+            Self::X25519(_) => f.write_str("Identity::X25519(..)"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum RecipientKind {
+    Password = 1,
+    KeyFile = 2,
+    X25519 = 3,
+}
+
+/// A recipient stanza as listed by [`list_recipients`](crate::encryption::list_recipients).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecipientInfo {
+    pub kind: RecipientKind,
+    /// Stable hex identifier: the public key for X25519, a key id for key files and a
+    /// per-stanza salt prefix for passwords. Pass it to `remove_recipient`.
+    pub fingerprint: String,
+}
+
 impl Mv2eHeader {
     pub const SIZE: usize = MV2E_HEADER_SIZE;
 
@@ -56,12 +117,13 @@ impl Mv2eHeader {
         }
 
         let version = u16::from_le_bytes([bytes[4], bytes[5]]);
-        if version != MV2E_VERSION {
+        if !matches!(version, MV2E_VERSION | MV2E_VERSION_V2 | MV2E_VERSION_V1) {
             return Err(EncryptionError::UnsupportedVersion { version });
         }
 
         let kdf_algorithm = match bytes[6] {
             KDF_ARGON2ID => KdfAlgorithm::Argon2id,
+            KDF_WRAPPED_KEY => KdfAlgorithm::WrappedKey,
             other => return Err(EncryptionError::UnsupportedKdf { id: other }),
         };
 
@@ -75,6 +75,15 @@ pub enum MemvidError {
     #[error("This file is encrypted: {path}\n{hint}")]
     EncryptedFile { path: PathBuf, hint: String },
 
+    #[error("Encryption key does not match this memory")]
+    InvalidEncryptionKey,
+
+    #[error("Encrypted {kind} region failed authentication")]
+    RegionDecryption { kind: &'static str },
+
+    #[error("Failed to encrypt {kind} region: {reason}")]
+    RegionEncryption { kind: &'static str, reason: String },
+
     #[error("Table of contents validation failed: {reason}")]
     InvalidToc { reason: Cow<'static, str> },
 
@@ -91,6 +100,12 @@ pub enum MemvidError {
     #[error("Logic-Mesh is invalid: {reason}")]
     InvalidLogicMesh { reason: Cow<'static, str> },
 
+    #[error("Compression dictionary is invalid: {reason}")]
+    InvalidCompressionDictionary { reason: Cow<'static, str> },
+
+    #[error("Blob store is invalid: {reason}")]
+    InvalidBlobStore { reason: Cow<'static, str> },
+
     #[error("Logic-Mesh is not enabled")]
     LogicMeshNotEnabled,
 
@@ -142,6 +157,19 @@ pub enum MemvidError {
         required: u64,
     },
 
+    #[error(
+        "Track '{track}' capacity exceeded. Current: {current} bytes, Limit: {limit} bytes, Required: {required} bytes"
+    )]
+    TrackCapacityExceeded {
+        track: String,
+        current: u64,
+        limit: u64,
+        required: u64,
+    },
+
+    #[error("Frame limit reached. Current: {current} frames, Limit: {limit} frames")]
+    FrameLimitExceeded { current: u64, limit: u64 },
+
     #[error("API key required for files larger than {limit} bytes. File size: {file_size} bytes")]
     ApiKeyRequired { file_size: u64, limit: u64 },
 
@@ -184,12 +212,27 @@ pub enum MemvidError {
     #[error("Frame with uri '{uri}' was not found")]
     FrameNotFoundByUri { uri: String },
 
+    #[error("Frame {frame_id} was changed since it was read; its newest version is {head}")]
+    Conflict {
+        frame_id: crate::types::FrameId,
+        head: crate::types::FrameId,
+    },
+
+    #[error("Commit generation {generation} was not found")]
+    GenerationNotFound { generation: u64 },
+
+    #[error("Archive is invalid: {reason}")]
+    InvalidArchive { reason: Cow<'static, str> },
+
     #[error("Ticket signature verification failed: {reason}")]
     TicketSignatureInvalid { reason: Box<str> },
 
     #[error("Model signature verification failed: {reason}")]
     ModelSignatureInvalid { reason: Box<str> },
 
+    #[error("Commit signature verification failed: {reason}")]
+    CommitSignatureInvalid { reason: Box<str> },
+
     #[error("Model manifest invalid: {reason}")]
     ModelManifestInvalid { reason: Box<str> },
 
@@ -74,47 +74,65 @@ pub struct FooterSlice<'a> {
 /// Scan the provided bytes backwards to locate the most recent valid footer.
 #[must_use]
 pub fn find_last_valid_footer(bytes: &[u8]) -> Option<FooterSlice<'_>> {
-    if bytes.len() < FOOTER_SIZE {
-        return None;
+    scan_valid_footers(bytes).next()
+}
+
+/// Iterate over every valid footer in `bytes`, newest (highest offset) first.
+///
+/// Each footer is validated against the hash of the TOC bytes preceding it, so
+/// magic sequences inside payloads or overwritten generations are skipped.
+#[must_use]
+pub fn scan_valid_footers(bytes: &[u8]) -> FooterScan<'_> {
+    FooterScan {
+        bytes,
+        search_end: bytes.len(),
     }
+}
 
-    let total_len = bytes.len();
-    let mut search_end = bytes.len();
-    while let Some(pos) = memrchr(FOOTER_MAGIC[0], &bytes[..search_end]) {
-        if pos + FOOTER_SIZE > total_len {
-            if pos == 0 {
-                break;
-            }
-            search_end = pos;
-            continue;
+/// Backwards iterator returned by [`scan_valid_footers`].
+#[derive(Debug)]
+pub struct FooterScan<'a> {
+    bytes: &'a [u8],
+    search_end: usize,
+}
+
+impl<'a> Iterator for FooterScan<'a> {
+    type Item = FooterSlice<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let bytes = self.bytes;
+        let total_len = bytes.len();
+        if total_len < FOOTER_SIZE {
+            return None;
         }
-        let candidate = &bytes[pos..pos + FOOTER_SIZE];
-        if let Some(footer) = CommitFooter::decode(candidate) {
-            let toc_end = pos;
-            let toc_len = footer.toc_len as usize;
-            if toc_len == 0 || toc_len > toc_end {
-                search_end = pos;
+        while let Some(pos) = memrchr(FOOTER_MAGIC[0], &bytes[..self.search_end]) {
+            self.search_end = pos;
+            if pos + FOOTER_SIZE > total_len {
                 continue;
             }
-            let toc_offset = toc_end - toc_len;
-            let toc_bytes = &bytes[toc_offset..toc_end];
-            if !footer.hash_matches(toc_bytes) {
-                search_end = pos;
-                continue;
+            let candidate = &bytes[pos..pos + FOOTER_SIZE];
+            if let Some(footer) = CommitFooter::decode(candidate) {
+                let toc_end = pos;
+                let toc_len = footer.toc_len as usize;
+                if toc_len == 0 || toc_len > toc_end {
+                    continue;
+                }
+                let toc_offset = toc_end - toc_len;
+                let toc_bytes = &bytes[toc_offset..toc_end];
+                if !footer.hash_matches(toc_bytes) {
+                    continue;
+                }
+                return Some(FooterSlice {
+                    footer_offset: pos,
+                    toc_offset,
+                    footer,
+                    toc_bytes,
+                });
             }
-            return Some(FooterSlice {
-                footer_offset: pos,
-                toc_offset,
-                footer,
-                toc_bytes,
-            });
-        }
-        if pos == 0 {
-            break;
         }
-        search_end = pos;
+        self.search_end = 0;
+        None
     }
-    None
 }
 
 #[cfg(test)]
@@ -176,4 +194,16 @@ mod tests {
         assert_eq!(slice.footer.generation, 2);
         assert_eq!(slice.toc_bytes, &extra_toc);
     }
+
+    #[test]
+    fn scan_lists_every_footer_newest_first() {
+        let mut bytes = build_sample_bytes(1, &[1u8, 2, 3]);
+        bytes.extend_from_slice(b"payload bytes MV2FOOT! that look like a footer");
+        bytes.extend_from_slice(&build_sample_bytes(2, &[4u8; 40]));
+        bytes.extend_from_slice(&build_sample_bytes(3, &[5u8, 6]));
+        let generations: Vec<u64> = scan_valid_footers(&bytes)
+            .map(|slice| slice.footer.generation)
+            .collect();
+        assert_eq!(generations, vec![3, 2, 1]);
+    }
 }
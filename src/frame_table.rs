@@ -0,0 +1,996 @@
+//! Paged frame table backing [`Toc::frames`](crate::types::Toc)
+//!
+//! Frames used to be serialised inline in the TOC, so every open decoded every
+//! frame and every commit re-encoded all of them. The frame table moves them into
+//! page segments of [`FRAMES_PER_PAGE`] frames and decodes them lazily one page at
+//! a time.
+//!
+//! **Page layout** (little-endian, see `MV2_SPEC` "Frame Table"):
+//! - Header: `[magic:4][version:u16][record_size:u16][frame_count:u64]`
+//! - `frame_count` fixed-size records:
+//!   `[blob_offset:u64][blob_length:u32][reserved:u32][payload_end:u64]`
+//! - Frame blobs (bincode), stored contiguously in frame id order
+//!
+//! `blob_offset` is relative to the start of the blob area. `payload_end` is the
+//! end of the frame's payload, and zero for frames without one.
+//!
+//! A commit only writes the pages whose frames changed, plus the last page when
+//! frames were appended to it; the manifest keeps pointing at every other page
+//! where an earlier generation wrote it.
+
+use std::fs::File;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+
+use serde::{Serialize, Serializer};
+
+#[cfg(not(feature = "mmap"))]
+use crate::io::read_at::read_exact_at;
+use crate::{
+    constants::{FRAME_TABLE_MAGIC, FRAME_TABLE_VERSION},
+    error::{MemvidError, Result},
+    merkle::{frame_leaf, merkle_root},
+    types::{Frame, FrameTableManifest, FrameTablePage},
+};
+
+fn frame_config() -> impl bincode::config::Config {
+    bincode::config::standard()
+        .with_fixed_int_encoding()
+        .with_little_endian()
+        .with_limit::<{ crate::MAX_INDEX_BYTES as usize }>()
+}
+
+/// Number of frames stored together in one page segment.
+pub const FRAMES_PER_PAGE: usize = 256;
+/// Size of the segment header in bytes.
+const SEGMENT_HEADER_SIZE: usize = 16;
+/// Size of a single frame record in bytes.
+const RECORD_SIZE: usize = 24;
+
+/// Fixed-size index entry locating one frame blob.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FrameRecord {
+    blob_offset: u64,
+    blob_length: u32,
+    payload_end: u64,
+}
+
+impl FrameRecord {
+    fn for_frame(frame: &Frame, blob_offset: u64, blob_length: u32) -> Self {
+        let payload_end = if frame.payload_length > 0 {
+            frame.payload_offset.saturating_add(frame.payload_length)
+        } else {
+            0
+        };
+        Self {
+            blob_offset,
+            blob_length,
+            payload_end,
+        }
+    }
+
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.blob_offset.to_le_bytes());
+        out.extend_from_slice(&self.blob_length.to_le_bytes());
+        out.extend_from_slice(&0u32.to_le_bytes());
+        out.extend_from_slice(&self.payload_end.to_le_bytes());
+    }
+
+    fn decode(bytes: &[u8]) -> Self {
+        let u64_at = |start: usize| {
+            let mut buf = [0u8; 8];
+            buf.copy_from_slice(&bytes[start..start + 8]);
+            u64::from_le_bytes(buf)
+        };
+        let mut len_buf = [0u8; 4];
+        len_buf.copy_from_slice(&bytes[8..12]);
+        Self {
+            blob_offset: u64_at(0),
+            blob_length: u32::from_le_bytes(len_buf),
+            payload_end: u64_at(16),
+        }
+    }
+
+    fn blob_end(&self) -> u64 {
+        self.blob_offset + u64::from(self.blob_length)
+    }
+}
+
+fn invalid(reason: &'static str) -> MemvidError {
+    MemvidError::InvalidToc {
+        reason: reason.into(),
+    }
+}
+
+/// Parses a page header, returning its frame count.
+fn page_frame_count(bytes: &[u8]) -> Option<usize> {
+    let header = bytes.get(..SEGMENT_HEADER_SIZE)?;
+    if header[0..4] != FRAME_TABLE_MAGIC
+        || u16::from_le_bytes([header[4], header[5]]) != FRAME_TABLE_VERSION
+        || usize::from(u16::from_le_bytes([header[6], header[7]])) != RECORD_SIZE
+    {
+        return None;
+    }
+    let mut count_buf = [0u8; 8];
+    count_buf.copy_from_slice(&header[8..16]);
+    usize::try_from(u64::from_le_bytes(count_buf)).ok()
+}
+
+/// Returns the length of the page segment at the start of `bytes`.
+///
+/// Used by recovery to step over the pages when only the header hint survived, since
+/// the TOC is written immediately after them. Returns `None` if `bytes` does not start
+/// with a well-formed page.
+pub(crate) fn segment_len(bytes: &[u8]) -> Option<u64> {
+    let frame_count = page_frame_count(bytes)?;
+    let index_len = frame_count
+        .checked_mul(RECORD_SIZE)?
+        .checked_add(SEGMENT_HEADER_SIZE)?;
+    let records = bytes.get(SEGMENT_HEADER_SIZE..index_len)?;
+    let blob_len = records
+        .chunks_exact(RECORD_SIZE)
+        .try_fold(0u64, |end, record| {
+            let record = FrameRecord::decode(record);
+            let blob_end = record
+                .blob_offset
+                .checked_add(u64::from(record.blob_length))?;
+            Some(end.max(blob_end))
+        })?;
+    let total = (index_len as u64).checked_add(blob_len)?;
+    (total <= bytes.len() as u64).then_some(total)
+}
+
+/// Encodes `frames` as one page segment, returning its bytes and furthest
+/// payload end.
+fn encode_page(frames: &[&Frame]) -> Result<(Vec<u8>, u64)> {
+    let mut records = Vec::with_capacity(frames.len());
+    let mut blobs = Vec::new();
+    for frame in frames {
+        let offset = blobs.len() as u64;
+        bincode::serde::encode_into_std_write(frame, &mut blobs, frame_config())?;
+        let length = u32::try_from(blobs.len() as u64 - offset)
+            .map_err(|_| invalid("frame entry too large for frame table"))?;
+        records.push(FrameRecord::for_frame(frame, offset, length));
+    }
+
+    let mut bytes =
+        Vec::with_capacity(SEGMENT_HEADER_SIZE + frames.len() * RECORD_SIZE + blobs.len());
+    bytes.extend_from_slice(&FRAME_TABLE_MAGIC);
+    bytes.extend_from_slice(&FRAME_TABLE_VERSION.to_le_bytes());
+    bytes.extend_from_slice(&(RECORD_SIZE as u16).to_le_bytes());
+    bytes.extend_from_slice(&(frames.len() as u64).to_le_bytes());
+    for record in &records {
+        record.encode(&mut bytes);
+    }
+    bytes.extend_from_slice(&blobs);
+    let payload_end = records.iter().map(|r| r.payload_end).max().unwrap_or(0);
+    Ok((bytes, payload_end))
+}
+
+/// Decodes the page segment holding frames `first_id..first_id + frame_count`,
+/// adding `payload_shift` to every nonzero payload offset.
+fn decode_page(
+    bytes: &[u8],
+    first_id: u64,
+    frame_count: usize,
+    payload_shift: u64,
+) -> Result<Vec<Frame>> {
+    match page_frame_count(bytes) {
+        Some(count) if count == frame_count => {}
+        Some(_) => return Err(invalid("frame table page count does not match manifest")),
+        None => return Err(invalid("frame table page header is invalid")),
+    }
+    let blob_start = SEGMENT_HEADER_SIZE + frame_count * RECORD_SIZE;
+    let records = bytes
+        .get(SEGMENT_HEADER_SIZE..blob_start)
+        .ok_or_else(|| invalid("frame table page shorter than its record index"))?;
+    let blobs = &bytes[blob_start..];
+
+    let mut frames = Vec::with_capacity(frame_count);
+    let mut expected = 0u64;
+    for (idx, record) in records.chunks_exact(RECORD_SIZE).enumerate() {
+        let record = FrameRecord::decode(record);
+        if record.blob_offset != expected {
+            return Err(invalid("frame table blobs are not contiguous"));
+        }
+        expected = record.blob_end();
+        let blob = usize::try_from(record.blob_offset)
+            .ok()
+            .zip(usize::try_from(expected).ok())
+            .and_then(|(from, to)| blobs.get(from..to))
+            .ok_or_else(|| invalid("frame table read out of bounds"))?;
+        let (mut frame, read): (Frame, usize) =
+            bincode::serde::decode_from_slice(blob, frame_config())?;
+        if read != blob.len() {
+            return Err(invalid("unexpected trailing bytes in frame blob"));
+        }
+        if frame.id != first_id + idx as u64 {
+            return Err(invalid("frame table entry does not match its position"));
+        }
+        shift_payload(&mut frame, payload_shift);
+        frames.push(frame);
+    }
+    Ok(frames)
+}
+
+fn shift_payload(frame: &mut Frame, delta: u64) {
+    if frame.payload_offset != 0 {
+        frame.payload_offset += delta;
+    }
+}
+
+/// Read access to the page segments of a memory file.
+struct FrameTableSource {
+    #[cfg(not(feature = "mmap"))]
+    file: File,
+    #[cfg(feature = "mmap")]
+    map: memmap2::Mmap,
+}
+
+impl FrameTableSource {
+    fn open(file: &File) -> Result<Self> {
+        Ok(Self {
+            #[cfg(not(feature = "mmap"))]
+            file: file.try_clone()?,
+            // Safety: the mapping is read-only, pages are never rewritten in place, and
+            // the table maps the file again whenever the WAL growth shifts them.
+            #[cfg(feature = "mmap")]
+            map: unsafe { memmap2::Mmap::map(file)? },
+        })
+    }
+
+    /// Reads the bytes of `page` and checks them against its checksum.
+    fn read(&self, page: &FrameTablePage) -> Result<Vec<u8>> {
+        let len = usize::try_from(page.bytes_length)
+            .map_err(|_| invalid("frame table page too large"))?;
+        #[cfg(feature = "mmap")]
+        let bytes = {
+            let from = usize::try_from(page.bytes_offset)
+                .map_err(|_| invalid("frame table read out of bounds"))?;
+            self.map
+                .get(from..from.saturating_add(len))
+                .filter(|bytes| bytes.len() == len)
+                .map(<[u8]>::to_vec)
+                .ok_or_else(|| invalid("frame table read out of bounds"))?
+        };
+        #[cfg(not(feature = "mmap"))]
+        let bytes = {
+            let mut buf = vec![0u8; len];
+            read_exact_at(&self.file, &mut buf, page.bytes_offset)?;
+            buf
+        };
+        if *blake3::hash(&bytes).as_bytes() != page.checksum {
+            return Err(invalid("frame table page checksum mismatch"));
+        }
+        Ok(bytes)
+    }
+}
+
+/// The pages that must be written for a frame table to be stored.
+pub(crate) struct EncodedFrameTable {
+    /// Changed pages, concatenated; written as one run at `manifest.bytes_offset`.
+    pub bytes: Vec<u8>,
+    pub manifest: FrameTableManifest,
+}
+
+#[derive(Clone, Default)]
+struct Page {
+    /// Decoded frames, shared by clones of the table until one of them changes the page.
+    frames: OnceLock<Arc<Vec<Frame>>>,
+    /// Where the page is stored, until its frames change in memory.
+    stored: Option<FrameTablePage>,
+}
+
+/// Returns a lineage no frame table has had yet.
+fn next_lineage() -> u64 {
+    static NEXT: AtomicU64 = AtomicU64::new(1);
+    NEXT.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Frames of a memory, indexed by [`FrameId`](crate::types::FrameId).
+///
+/// Behaves like a `Vec<Frame>`. When bound to a frame table, frames are decoded a
+/// page at a time the first time they are touched; frames pushed since the last
+/// commit are kept in memory until the table is written.
+#[derive(Clone)]
+pub struct FrameTable {
+    pages: Vec<Page>,
+    persisted: usize,
+    appended: Vec<Frame>,
+    source: Option<Arc<FrameTableSource>>,
+    lineage: u64,
+}
+
+impl Default for FrameTable {
+    fn default() -> Self {
+        Self {
+            pages: Vec::new(),
+            persisted: 0,
+            appended: Vec::new(),
+            source: None,
+            lineage: next_lineage(),
+        }
+    }
+}
+
+impl FrameTable {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stamp of the table the frames were loaded or built into. Pushes and in-place
+    /// changes keep it, clones share it, and only a table built anew gets another.
+    pub(crate) fn lineage(&self) -> u64 {
+        self.lineage
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.persisted + self.appended.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Whether frames live in frame table pages rather than inline in the TOC.
+    #[must_use]
+    pub fn is_paged(&self) -> bool {
+        self.source.is_some()
+    }
+
+    /// Number of pages currently decoded in memory.
+    #[must_use]
+    pub fn loaded_pages(&self) -> usize {
+        self.pages
+            .iter()
+            .filter(|page| page.frames.get().is_some())
+            .count()
+    }
+
+    /// Number of frames held in page `page` when it was last stored.
+    fn persisted_page_len(&self, page: usize) -> usize {
+        (self.persisted - page * FRAMES_PER_PAGE).min(FRAMES_PER_PAGE)
+    }
+
+    fn load_page(&self, page: usize) -> Result<&[Frame]> {
+        let entry = &self.pages[page];
+        if let Some(frames) = entry.frames.get() {
+            return Ok(frames);
+        }
+        let (Some(source), Some(stored)) = (self.source.as_ref(), entry.stored.as_ref()) else {
+            return Err(invalid("frame table page missing"));
+        };
+        let frames = decode_page(
+            &source.read(stored)?,
+            (page * FRAMES_PER_PAGE) as u64,
+            self.persisted_page_len(page),
+            stored.payload_shift,
+        )?;
+        Ok(entry.frames.get_or_init(|| Arc::new(frames)))
+    }
+
+    /// Returns the frame at `index`, surfacing any error from loading its page.
+    pub fn try_get(&self, index: usize) -> Result<Option<&Frame>> {
+        if index >= self.persisted {
+            return Ok(self.appended.get(index - self.persisted));
+        }
+        let page = self.load_page(index / FRAMES_PER_PAGE)?;
+        Ok(page.get(index % FRAMES_PER_PAGE))
+    }
+
+    /// Returns the frame at `index` for modification, surfacing any error from
+    /// loading its page; the page is written again on the next commit.
+    pub fn try_get_mut(&mut self, index: usize) -> Result<Option<&mut Frame>> {
+        if index >= self.persisted {
+            return Ok(self.appended.get_mut(index - self.persisted));
+        }
+        let page = index / FRAMES_PER_PAGE;
+        self.load_page(page)?;
+        let entry = &mut self.pages[page];
+        entry.stored = None;
+        Ok(entry
+            .frames
+            .get_mut()
+            .and_then(|frames| Arc::make_mut(frames).get_mut(index % FRAMES_PER_PAGE)))
+    }
+
+    pub fn push(&mut self, frame: Frame) {
+        self.appended.push(frame);
+    }
+
+    pub fn first(&self) -> Result<Option<&Frame>> {
+        self.try_get(0)
+    }
+
+    pub fn last(&self) -> Result<Option<&Frame>> {
+        match self.len().checked_sub(1) {
+            Some(index) => self.try_get(index),
+            None => Ok(None),
+        }
+    }
+
+    /// Iterates the frames in id order, yielding the error of the first page that
+    /// fails to load and stopping there.
+    #[must_use]
+    pub fn try_iter(&self) -> TryIter<'_> {
+        TryIter {
+            table: self,
+            front: 0,
+            back: self.len(),
+        }
+    }
+
+    /// Mutable iteration decodes every page first, failing if any page cannot be
+    /// loaded, and writes them all again on the next commit.
+    pub fn try_iter_mut(&mut self) -> Result<IterMut<'_>> {
+        self.load_all()?;
+        Ok(self.iter_loaded_mut())
+    }
+
+    fn iter_loaded_mut(&mut self) -> IterMut<'_> {
+        for page in &mut self.pages {
+            page.stored = None;
+        }
+        let loaded: fn(&mut Page) -> Option<&mut Vec<Frame>> =
+            |page| page.frames.get_mut().map(Arc::make_mut);
+        IterMut {
+            inner: self
+                .pages
+                .iter_mut()
+                .filter_map(loaded)
+                .flatten()
+                .chain(self.appended.iter_mut()),
+        }
+    }
+
+    /// Decodes every page, failing on the first page that cannot be read.
+    pub fn load_all(&self) -> Result<()> {
+        for page in 0..self.pages.len() {
+            self.load_page(page)?;
+        }
+        Ok(())
+    }
+
+    pub fn to_vec(&self) -> Result<Vec<Frame>> {
+        self.try_iter().map(|frame| Ok(frame?.clone())).collect()
+    }
+
+    /// End of the furthest page this table still points at.
+    pub(crate) fn stored_end(&self) -> u64 {
+        self.pages
+            .iter()
+            .filter_map(|page| page.stored.as_ref().map(FrameTablePage::end))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Loads every page stored past `offset` and marks it to be written again, so
+    /// the region from `offset` on can be overwritten.
+    pub(crate) fn reclaim_from(&mut self, offset: u64) -> Result<()> {
+        for page in 0..self.pages.len() {
+            if self.pages[page]
+                .stored
+                .is_some_and(|stored| stored.end() > offset)
+            {
+                self.load_page(page)?;
+                self.pages[page].stored = None;
+            }
+        }
+        Ok(())
+    }
+
+    /// Follows the data after the WAL grew by `delta` bytes: stored pages and every
+    /// nonzero payload offset move by `delta`. Frames not written yet stay in memory.
+    pub(crate) fn shift(&mut self, file: &File, delta: u64) -> Result<()> {
+        for page in &mut self.pages {
+            if let Some(stored) = page.stored.as_mut() {
+                stored.shift(delta);
+            }
+            if let Some(frames) = page.frames.get_mut() {
+                for frame in Arc::make_mut(frames) {
+                    shift_payload(frame, delta);
+                }
+            }
+        }
+        for frame in &mut self.appended {
+            shift_payload(frame, delta);
+        }
+        if self.source.is_some() {
+            self.source = Some(Arc::new(FrameTableSource::open(file)?));
+        }
+        Ok(())
+    }
+
+    /// The stored entry of page `page`, if it still holds exactly the frames it was
+    /// stored with.
+    fn reusable_page(&self, page: usize) -> Option<FrameTablePage> {
+        let end = ((page + 1) * FRAMES_PER_PAGE).min(self.len());
+        self.pages
+            .get(page)
+            .and_then(|entry| entry.stored)
+            .filter(|_| self.persisted_page_len(page) == end - page * FRAMES_PER_PAGE)
+    }
+
+    fn page_frames(&self, start: usize, end: usize) -> Result<Vec<&Frame>> {
+        (start..end)
+            .map(|index| {
+                self.try_get(index)?
+                    .ok_or_else(|| invalid("frame table page missing"))
+            })
+            .collect()
+    }
+
+    /// Merkle root over every frame. Pages stored unchanged contribute the root
+    /// recorded for them, so only changed pages are hashed, loading at most the last
+    /// page when frames were appended to it.
+    pub(crate) fn merkle_root(&self) -> Result<[u8; 32]> {
+        let count = self.len();
+        let mut roots = Vec::with_capacity(count.div_ceil(FRAMES_PER_PAGE));
+        for page in 0..count.div_ceil(FRAMES_PER_PAGE) {
+            if let Some(stored) = self.reusable_page(page) {
+                roots.push(stored.merkle_root);
+                continue;
+            }
+            let start = page * FRAMES_PER_PAGE;
+            let end = (start + FRAMES_PER_PAGE).min(count);
+            let leaves: Vec<_> = self
+                .page_frames(start, end)?
+                .into_iter()
+                .map(frame_leaf)
+                .collect();
+            roots.push(merkle_root(&leaves));
+        }
+        Ok(merkle_root(&roots))
+    }
+
+    /// End of the furthest frame payload, read from the stored page entries where
+    /// possible so no page has to be loaded.
+    pub(crate) fn payload_end(&self) -> u64 {
+        let payload_end = |frame: &Frame| {
+            if frame.payload_length > 0 {
+                frame.payload_offset.saturating_add(frame.payload_length)
+            } else {
+                0
+            }
+        };
+        let pages = self.pages.iter().map(|page| match page.stored {
+            // Frames appended to a stored page live in `appended`.
+            Some(stored) => stored.payload_end,
+            None => page.frames.get().map_or(0, |frames| {
+                frames.iter().map(payload_end).max().unwrap_or(0)
+            }),
+        });
+        pages
+            .chain(self.appended.iter().map(payload_end))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Encodes the pages that changed since the table was last stored, as one run to
+    /// be written at `offset`. Unchanged pages keep their earlier location.
+    pub(crate) fn encode_pages(&self, offset: u64) -> Result<EncodedFrameTable> {
+        let count = self.len();
+        let page_count = count.div_ceil(FRAMES_PER_PAGE);
+        let mut pages = Vec::with_capacity(page_count);
+        let mut bytes = Vec::new();
+        for page in 0..page_count {
+            let start = page * FRAMES_PER_PAGE;
+            let end = (start + FRAMES_PER_PAGE).min(count);
+            if let Some(stored) = self.reusable_page(page) {
+                pages.push(stored);
+                continue;
+            }
+            let frames = self.page_frames(start, end)?;
+            let (page_bytes, payload_end) = encode_page(&frames)?;
+            let leaves: Vec<_> = frames.iter().map(|frame| frame_leaf(frame)).collect();
+            pages.push(FrameTablePage {
+                bytes_offset: offset + bytes.len() as u64,
+                bytes_length: page_bytes.len() as u64,
+                payload_shift: 0,
+                payload_end,
+                checksum: *blake3::hash(&page_bytes).as_bytes(),
+                merkle_root: merkle_root(&leaves),
+            });
+            bytes.extend_from_slice(&page_bytes);
+        }
+
+        let manifest = FrameTableManifest {
+            bytes_offset: offset,
+            bytes_length: bytes.len() as u64,
+            frame_count: count as u64,
+            payload_end: pages.iter().map(|page| page.payload_end).max().unwrap_or(0),
+            pages,
+        };
+        Ok(EncodedFrameTable { bytes, manifest })
+    }
+
+    /// Binds the table to the pages described by `manifest` in `file`.
+    ///
+    /// A freshly decoded TOC (no frames yet) becomes fully lazy. Otherwise the
+    /// table must already hold exactly `manifest.frame_count` frames, as after
+    /// writing its pages; pages already in memory stay resident.
+    pub(crate) fn bind(&mut self, file: &File, manifest: &FrameTableManifest) -> Result<()> {
+        let count = usize::try_from(manifest.frame_count)
+            .map_err(|_| invalid("frame table count unreasonable"))?;
+        if manifest.pages.len() != count.div_ceil(FRAMES_PER_PAGE) {
+            return Err(invalid("frame table page count does not match frames"));
+        }
+        if manifest.pages_end() > file.metadata()?.len() {
+            return Err(invalid("frame table exceeds file length"));
+        }
+        let source = Arc::new(FrameTableSource::open(file)?);
+        let stored = |page: usize| Some(manifest.pages[page]);
+
+        if self.source.is_none() && self.is_empty() {
+            self.lineage = next_lineage();
+            self.pages = (0..manifest.pages.len())
+                .map(|page| Page {
+                    frames: OnceLock::new(),
+                    stored: stored(page),
+                })
+                .collect();
+            self.persisted = count;
+            self.source = Some(source);
+            return Ok(());
+        }
+        if self.len() != count {
+            return Err(invalid("frame table count does not match frames"));
+        }
+
+        let old_persisted = self.persisted;
+        let mut old_pages = std::mem::take(&mut self.pages).into_iter();
+        let mut appended = std::mem::take(&mut self.appended).into_iter();
+        let mut pages = Vec::with_capacity(manifest.pages.len());
+        for page in 0..manifest.pages.len() {
+            let start = page * FRAMES_PER_PAGE;
+            let end = (start + FRAMES_PER_PAGE).min(count);
+            let frames = if end <= old_persisted {
+                old_pages.next().map(|old| old.frames).unwrap_or_default()
+            } else if start >= old_persisted {
+                OnceLock::from(Arc::new(
+                    appended.by_ref().take(end - start).collect::<Vec<_>>(),
+                ))
+            } else {
+                let tail: Vec<Frame> = appended.by_ref().take(end - old_persisted).collect();
+                match old_pages.next().and_then(|old| old.frames.into_inner()) {
+                    Some(frames) => {
+                        let mut frames = Arc::unwrap_or_clone(frames);
+                        frames.extend(tail);
+                        OnceLock::from(Arc::new(frames))
+                    }
+                    None => OnceLock::new(),
+                }
+            };
+            pages.push(Page {
+                frames,
+                stored: stored(page),
+            });
+        }
+
+        self.pages = pages;
+        self.persisted = count;
+        self.source = Some(source);
+        Ok(())
+    }
+}
+
+impl From<Vec<Frame>> for FrameTable {
+    fn from(frames: Vec<Frame>) -> Self {
+        Self {
+            appended: frames,
+            ..Self::default()
+        }
+    }
+}
+
+impl FromIterator<Frame> for FrameTable {
+    fn from_iter<I: IntoIterator<Item = Frame>>(iter: I) -> Self {
+        Self::from(iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+impl std::fmt::Debug for FrameTable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.try_iter()).finish()
+    }
+}
+
+/// Paged tables are stored in their own segments, so they serialise as an empty
+/// sequence; inline (legacy) tables serialise every frame.
+impl Serialize for FrameTable {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        if self.is_paged() {
+            serializer.collect_seq(std::iter::empty::<&Frame>())
+        } else {
+            serializer.collect_seq(self.appended.iter())
+        }
+    }
+}
+
+/// Iterator returned by [`FrameTable::try_iter`].
+pub struct TryIter<'a> {
+    table: &'a FrameTable,
+    front: usize,
+    back: usize,
+}
+
+impl<'a> TryIter<'a> {
+    fn load(&mut self, index: usize) -> Option<Result<&'a Frame>> {
+        match self.table.try_get(index) {
+            Ok(frame) => frame.map(Ok),
+            Err(err) => {
+                self.front = self.back;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for TryIter<'a> {
+    type Item = Result<&'a Frame>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.front < self.back {
+            let index = self.front;
+            self.front += 1;
+            if let Some(frame) = self.load(index) {
+                return Some(frame);
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.back - self.front))
+    }
+}
+
+impl DoubleEndedIterator for TryIter<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        while self.front < self.back {
+            self.back -= 1;
+            if let Some(frame) = self.load(self.back) {
+                return Some(frame);
+            }
+        }
+        None
+    }
+}
+
+type PageIterMut<'a> =
+    std::iter::FilterMap<std::slice::IterMut<'a, Page>, fn(&mut Page) -> Option<&mut Vec<Frame>>>;
+
+/// Mutable iterator over the frames of a [`FrameTable`].
+pub struct IterMut<'a> {
+    inner: std::iter::Chain<std::iter::Flatten<PageIterMut<'a>>, std::slice::IterMut<'a, Frame>>,
+}
+
+impl<'a> Iterator for IterMut<'a> {
+    type Item = &'a mut Frame;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{CanonicalEncoding, EnrichmentState, FrameRole, FrameStatus};
+    use std::collections::BTreeMap;
+    use std::io::Write;
+
+    fn frame(id: u64) -> Frame {
+        Frame {
+            id,
+            timestamp: 1_700_000_000 + id as i64,
+            anchor_ts: None,
+            anchor_source: None,
+            kind: None,
+            track: None,
+            payload_offset: 4096 + id * 10,
+            payload_length: 10,
+            checksum: [0u8; 32],
+            uri: Some(format!("mv2://frames/{id}")),
+            title: Some(format!("frame {id}")),
+            canonical_encoding: CanonicalEncoding::Plain,
+            canonical_length: Some(10),
+            metadata: None,
+            search_text: None,
+            tags: Vec::new(),
+            labels: Vec::new(),
+            extra_metadata: BTreeMap::new(),
+            content_dates: Vec::new(),
+            role: FrameRole::Document,
+            parent_id: None,
+            chunk_index: None,
+            chunk_count: None,
+            chunk_manifest: None,
+            status: FrameStatus::Active,
+            supersedes: None,
+            superseded_by: None,
+            source_sha256: None,
+            source_path: None,
+            enrichment_state: EnrichmentState::default(),
+        }
+    }
+
+    /// Appends the pages `table` needs to write to `file`, as a commit would.
+    fn write_pages(file: &mut tempfile::NamedTempFile, table: &FrameTable) -> FrameTableManifest {
+        let offset = file.as_file().metadata().expect("metadata").len();
+        let encoded = table.encode_pages(offset).expect("encode");
+        file.write_all(&encoded.bytes).expect("pages");
+        file.flush().expect("flush");
+        encoded.manifest
+    }
+
+    fn write_table(table: &FrameTable) -> (tempfile::NamedTempFile, FrameTableManifest) {
+        let mut file = tempfile::NamedTempFile::new().expect("temp file");
+        file.write_all(&[0u8; 32]).expect("prefix");
+        let manifest = write_pages(&mut file, table);
+        (file, manifest)
+    }
+
+    #[test]
+    fn pages_load_on_demand() {
+        let count = FRAMES_PER_PAGE * 2 + 7;
+        let table: FrameTable = (0..count as u64).map(frame).collect();
+        let (file, manifest) = write_table(&table);
+        assert_eq!(manifest.frame_count, count as u64);
+        assert_eq!(manifest.pages.len(), 3);
+        assert_eq!(manifest.payload_end, 4096 + (count as u64 - 1) * 10 + 10);
+
+        let mut lazy = FrameTable::new();
+        lazy.bind(file.as_file(), &manifest).expect("bind");
+        assert_eq!(lazy.len(), count);
+        assert_eq!(lazy.loaded_pages(), 0);
+
+        let last = lazy.try_get(count - 1).expect("page").expect("last frame");
+        assert_eq!(last.id, count as u64 - 1);
+        assert_eq!(lazy.loaded_pages(), 1);
+
+        let ids: Vec<u64> = lazy.try_iter().rev().map(|f| f.expect("page").id).collect();
+        assert_eq!(ids.len(), count);
+        assert_eq!(ids[0], count as u64 - 1);
+        assert_eq!(lazy.loaded_pages(), 3);
+    }
+
+    #[test]
+    fn rewrite_writes_only_changed_pages() {
+        let count = FRAMES_PER_PAGE * 2 + 3;
+        let table: FrameTable = (0..count as u64).map(frame).collect();
+        let (mut file, manifest) = write_table(&table);
+
+        let mut lazy = FrameTable::new();
+        lazy.bind(file.as_file(), &manifest).expect("bind");
+        assert!(lazy.encode_pages(0).expect("encode").bytes.is_empty());
+
+        lazy.try_get_mut(FRAMES_PER_PAGE)
+            .expect("page")
+            .expect("frame")
+            .status = FrameStatus::Deleted;
+        lazy.push(frame(count as u64));
+        assert_eq!(lazy.loaded_pages(), 1);
+
+        let rewritten = write_pages(&mut file, &lazy);
+        lazy.bind(file.as_file(), &rewritten).expect("rebind");
+        assert_eq!(lazy.len(), count + 1);
+        assert_eq!(lazy.loaded_pages(), 2);
+        assert_eq!(rewritten.pages[0], manifest.pages[0]);
+        assert_ne!(rewritten.pages[1], manifest.pages[1]);
+        assert_ne!(rewritten.pages[2], manifest.pages[2]);
+        assert_eq!(
+            rewritten.bytes_length,
+            rewritten.pages[1].bytes_length + rewritten.pages[2].bytes_length
+        );
+
+        let mut reopened = FrameTable::new();
+        reopened.bind(file.as_file(), &rewritten).expect("bind");
+        let frames = reopened.to_vec().expect("frames");
+        assert_eq!(frames.len(), count + 1);
+        assert_eq!(frames[0].title.as_deref(), Some("frame 0"));
+        assert_eq!(frames[FRAMES_PER_PAGE].status, FrameStatus::Deleted);
+        assert_eq!(frames[count].id, count as u64);
+    }
+
+    #[test]
+    fn merkle_root_and_payload_end_skip_unchanged_pages() {
+        let count = FRAMES_PER_PAGE * 2 + 3;
+        let table: FrameTable = (0..count as u64).map(frame).collect();
+        let (file, manifest) = write_table(&table);
+
+        let mut lazy = FrameTable::new();
+        lazy.bind(file.as_file(), &manifest).expect("bind");
+        lazy.push(frame(count as u64));
+        lazy.try_get_mut(0).expect("page").expect("frame").status = FrameStatus::Deleted;
+        let root = lazy.merkle_root().expect("root");
+        assert_eq!(lazy.payload_end(), 4096 + count as u64 * 10 + 10);
+        assert_eq!(lazy.loaded_pages(), 2);
+
+        let leaves = crate::merkle::frame_leaves(&lazy).expect("leaves");
+        assert_eq!(root, crate::merkle::merkle_root(&leaves));
+    }
+
+    #[test]
+    fn shift_keeps_pending_frames() {
+        const DELTA: u64 = 4096;
+        let count = FRAMES_PER_PAGE + 3;
+        let table: FrameTable = (0..count as u64).map(frame).collect();
+        let (file, manifest) = write_table(&table);
+
+        let mut lazy = FrameTable::new();
+        lazy.bind(file.as_file(), &manifest).expect("bind");
+        lazy.try_get(0).expect("page").expect("first frame");
+        lazy.push(frame(count as u64));
+
+        // The same pages, moved `DELTA` bytes further into the file.
+        let bytes = std::fs::read(file.path()).expect("read");
+        let mut shifted = tempfile::NamedTempFile::new().expect("temp file");
+        shifted.write_all(&[0u8; DELTA as usize]).expect("gap");
+        shifted.write_all(&bytes).expect("pages");
+        shifted.flush().expect("flush");
+        let mut moved = manifest.clone();
+        moved.shift(DELTA);
+        lazy.shift(shifted.as_file(), DELTA).expect("shift");
+
+        assert_eq!(lazy.len(), count + 1);
+        let offsets: Vec<u64> = lazy
+            .try_iter()
+            .map(|f| f.expect("page").payload_offset)
+            .collect();
+        let expected: Vec<u64> = (0..=count as u64)
+            .map(|id| 4096 + id * 10 + DELTA)
+            .collect();
+        assert_eq!(offsets, expected);
+        assert_eq!(
+            lazy.encode_pages(0).expect("encode").manifest.pages[0],
+            moved.pages[0]
+        );
+    }
+
+    #[test]
+    fn rejects_count_mismatch() {
+        let table: FrameTable = (0..3).map(frame).collect();
+        let (file, mut manifest) = write_table(&table);
+        manifest.frame_count = FRAMES_PER_PAGE as u64 + 1;
+        let mut lazy = FrameTable::new();
+        assert!(lazy.bind(file.as_file(), &manifest).is_err());
+
+        manifest.frame_count = 4;
+        lazy.bind(file.as_file(), &manifest).expect("bind");
+        assert!(lazy.try_get(0).is_err());
+    }
+
+    #[test]
+    fn rejects_corrupted_page() {
+        let table: FrameTable = (0..3).map(frame).collect();
+        let (file, mut manifest) = write_table(&table);
+        manifest.pages[0].checksum[0] ^= 1;
+        let mut lazy = FrameTable::new();
+        lazy.bind(file.as_file(), &manifest).expect("bind");
+        assert!(lazy.try_get(0).is_err());
+        assert!(lazy.try_get_mut(1).is_err());
+        assert!(lazy.try_iter().next().expect("first item").is_err());
+        assert!(lazy.try_iter_mut().is_err());
+        assert!(lazy.first().is_err());
+        assert!(lazy.to_vec().is_err());
+    }
+
+    #[test]
+    fn segment_len_measures_encoded_page() {
+        let table: FrameTable = (0..5).map(frame).collect();
+        let encoded = table.encode_pages(0).expect("encode");
+        let mut bytes = encoded.bytes.clone();
+        bytes.extend_from_slice(b"toc follows");
+        assert_eq!(segment_len(&bytes), Some(encoded.bytes.len() as u64));
+        assert_eq!(segment_len(&encoded.bytes[..encoded.bytes.len() - 1]), None);
+        assert_eq!(segment_len(b"not a frame table"), None);
+    }
+}
@@ -6,7 +6,7 @@ use std::{
 use crate::{
     constants::{HEADER_SIZE, MAGIC, SPEC_MAJOR, SPEC_MINOR, WAL_OFFSET},
     error::{MemvidError, Result},
-    types::Header,
+    types::{Header, HeaderEncryption},
 };
 
 const VERSION_OFFSET: usize = 4;
@@ -21,6 +21,15 @@ const TOC_CHECKSUM_END: usize = 80;
 // Legacy lock metadata occupied bytes 80..140 within the header padding.
 const LEGACY_LOCK_REGION_START: usize = TOC_CHECKSUM_END;
 const LEGACY_LOCK_REGION_END: usize = LEGACY_LOCK_REGION_START + 60;
+// Encrypted-mode descriptor: magic, cipher id, salt and key check, within the header padding.
+const ENCRYPTION_POS: usize = 256;
+const ENCRYPTION_MAGIC: [u8; 4] = *b"MVE1";
+const ENCRYPTION_CIPHER_POS: usize = ENCRYPTION_POS + 4;
+const ENCRYPTION_SALT_POS: usize = ENCRYPTION_POS + 8;
+const ENCRYPTION_KEY_CHECK_POS: usize = ENCRYPTION_SALT_POS + 16;
+const ENCRYPTION_END: usize = ENCRYPTION_KEY_CHECK_POS + 32;
+/// AES-256-GCM with BLAKE3-derived region keys.
+const CIPHER_AES_256_GCM: u8 = 1;
 const EXPECTED_VERSION: u16 = ((SPEC_MAJOR as u16) << 8) | SPEC_MINOR as u16;
 
 /// Deterministic encoder/decoder for the fixed-size header region.
@@ -87,6 +96,12 @@ impl HeaderCodec {
         buf[WAL_SEQUENCE_POS..WAL_SEQUENCE_POS + 8]
             .copy_from_slice(&header.wal_sequence.to_le_bytes());
         buf[TOC_CHECKSUM_POS..TOC_CHECKSUM_END].copy_from_slice(&header.toc_checksum);
+        if let Some(encryption) = &header.encryption {
+            buf[ENCRYPTION_POS..ENCRYPTION_CIPHER_POS].copy_from_slice(&ENCRYPTION_MAGIC);
+            buf[ENCRYPTION_CIPHER_POS] = CIPHER_AES_256_GCM;
+            buf[ENCRYPTION_SALT_POS..ENCRYPTION_KEY_CHECK_POS].copy_from_slice(&encryption.salt);
+            buf[ENCRYPTION_KEY_CHECK_POS..ENCRYPTION_END].copy_from_slice(&encryption.key_check);
+        }
         Ok(buf)
     }
 
@@ -150,6 +165,7 @@ impl HeaderCodec {
         );
         let mut toc_checksum = [0u8; 32];
         toc_checksum.copy_from_slice(&bytes[TOC_CHECKSUM_POS..TOC_CHECKSUM_END]);
+        let encryption = decode_encryption(bytes)?;
 
         Ok(Header {
             magic,
@@ -160,10 +176,33 @@ impl HeaderCodec {
             wal_checkpoint_pos,
             wal_sequence,
             toc_checksum,
+            encryption,
         })
     }
 }
 
+fn decode_encryption(bytes: &[u8; HEADER_SIZE]) -> Result<Option<HeaderEncryption>> {
+    if bytes[ENCRYPTION_POS..ENCRYPTION_CIPHER_POS] != ENCRYPTION_MAGIC {
+        return Ok(None);
+    }
+    if bytes[ENCRYPTION_CIPHER_POS] != CIPHER_AES_256_GCM {
+        return Err(MemvidError::InvalidHeader {
+            reason: "unsupported encryption cipher".into(),
+        });
+    }
+    let mut encryption = HeaderEncryption {
+        salt: [0u8; 16],
+        key_check: [0u8; 32],
+    };
+    encryption
+        .salt
+        .copy_from_slice(&bytes[ENCRYPTION_SALT_POS..ENCRYPTION_KEY_CHECK_POS]);
+    encryption
+        .key_check
+        .copy_from_slice(&bytes[ENCRYPTION_KEY_CHECK_POS..ENCRYPTION_END]);
+    Ok(Some(encryption))
+}
+
 fn clear_legacy_lock_metadata(buf: &mut [u8; HEADER_SIZE]) -> bool {
     let region = &mut buf[LEGACY_LOCK_REGION_START..LEGACY_LOCK_REGION_END];
     if region.iter().any(|byte| *byte != 0) {
@@ -189,6 +228,7 @@ mod tests {
             wal_checkpoint_pos: 0,
             wal_sequence: 42,
             toc_checksum: [0xAB; 32],
+            encryption: None,
         }
     }
 
@@ -204,6 +244,28 @@ mod tests {
         assert_eq!(decoded.toc_checksum, header.toc_checksum);
     }
 
+    #[test]
+    fn roundtrip_encryption_descriptor() {
+        let mut header = sample_header();
+        assert_eq!(
+            HeaderCodec::decode(&HeaderCodec::encode(&header).expect("encode header"))
+                .expect("decode header")
+                .encryption,
+            None
+        );
+        header.encryption = Some(HeaderEncryption {
+            salt: [0x11; 16],
+            key_check: [0x22; 32],
+        });
+        let encoded = HeaderCodec::encode(&header).expect("encode header");
+        assert_eq!(
+            encoded[ENCRYPTION_POS..ENCRYPTION_CIPHER_POS],
+            ENCRYPTION_MAGIC
+        );
+        let decoded = HeaderCodec::decode(&encoded).expect("decode header");
+        assert_eq!(decoded.encryption, header.encryption);
+    }
+
     #[test]
     fn read_write_from_cursor() {
         let header = sample_header();
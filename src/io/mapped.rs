@@ -0,0 +1,133 @@
+//! Read-only memory map of a memory file for zero-copy reads.
+//!
+//! Read-only opens map the file once and hand out payloads and index segments as slices
+//! of the mapping. A writer only ever appends generations, so before every read the map
+//! is compared with the file length and replaced when the file has grown (or, after a
+//! vacuum, shrunk).
+
+use std::fs::File;
+use std::sync::Arc;
+
+use memmap2::Mmap;
+
+use crate::error::Result;
+
+#[derive(Clone)]
+pub(crate) struct MappedFile {
+    map: Arc<Mmap>,
+}
+
+impl MappedFile {
+    pub(crate) fn new(file: &File) -> Result<Self> {
+        // Safety: the mapping is read-only and committed bytes are never rewritten while
+        // a reader holds its shared lock; size changes are picked up by `refresh`.
+        let map = unsafe { Mmap::map(file)? };
+        Ok(Self { map: Arc::new(map) })
+    }
+
+    /// Remap if the file length no longer matches the mapping; returns whether it did.
+    pub(crate) fn refresh(&mut self, file: &File) -> Result<bool> {
+        if file.metadata()?.len() == self.len() {
+            return Ok(false);
+        }
+        *self = Self::new(file)?;
+        Ok(true)
+    }
+
+    pub(crate) fn len(&self) -> u64 {
+        self.map.len() as u64
+    }
+
+    /// `length` bytes at `offset`, or `None` when the range is outside the mapping.
+    pub(crate) fn slice(&self, offset: u64, length: u64) -> Option<&[u8]> {
+        let start = usize::try_from(offset).ok()?;
+        let end = start.checked_add(usize::try_from(length).ok()?)?;
+        self.map.get(start..end)
+    }
+
+    /// The current mapping, kept alive for as long as the caller holds it.
+    pub(crate) fn shared(&self) -> Arc<Mmap> {
+        Arc::clone(&self.map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::CanonicalEncoding;
+    use crate::{Memvid, PutOptions, run_serial_test};
+    use std::fs::OpenOptions;
+    use std::io::{Read, Seek, SeekFrom, Write};
+
+    #[test]
+    fn refresh_follows_the_file_length() {
+        let mut file = tempfile::tempfile().expect("tmp");
+        file.write_all(b"first generation").expect("write");
+        let mut mapped = MappedFile::new(&file).expect("map");
+        assert!(!mapped.refresh(&file).expect("refresh"));
+        assert_eq!(mapped.slice(0, 5), Some(&b"first"[..]));
+
+        file.write_all(b" second").expect("append");
+        assert_eq!(mapped.slice(17, 6), None);
+        let before = mapped.shared();
+        assert!(mapped.refresh(&file).expect("refresh"));
+        assert_eq!(mapped.slice(17, 6), Some(&b"second"[..]));
+        assert_eq!(before.len(), 16);
+    }
+
+    #[test]
+    fn read_only_opens_serve_reads_from_the_mapping() {
+        run_serial_test(|| {
+            let dir = tempfile::tempdir().expect("tmp");
+            let path = dir.path().join("mapped.mv2");
+            let mut rng = fastrand::Rng::with_seed(20);
+            let media: Vec<u8> = (0..64 * 1024).map(|_| rng.u8(..)).collect();
+
+            let mut mem = Memvid::create(&path).expect("create");
+            let options = |uri: &str| {
+                PutOptions::builder()
+                    .uri(uri)
+                    .auto_tag(false)
+                    .extract_dates(false)
+                    .extract_triplets(false)
+            };
+            mem.put_bytes_with_options(
+                &media,
+                options("mv2://media/raw")
+                    .encoding(CanonicalEncoding::Plain)
+                    .build(),
+            )
+            .expect("put media");
+            mem.put_bytes_with_options(b"mapped note", options("mv2://notes/a").build())
+                .expect("put note");
+            mem.commit().expect("commit");
+            drop(mem);
+
+            let mut reader = Memvid::open_read_only(&path).expect("open read only");
+            let mapped_len = reader.mapped.as_ref().expect("mapped").len();
+            let frame = reader.frame_by_uri("mv2://media/raw").expect("frame");
+            let mut blob = reader.blob_reader(frame.id).expect("blob reader");
+            blob.seek(SeekFrom::Start(1000)).expect("seek");
+            let mut window = vec![0u8; 4096];
+            blob.read_exact(&mut window).expect("read");
+            assert!(window[..] == media[1000..5096]);
+
+            // Bytes appended by a writer are picked up on the next read.
+            let mut file = OpenOptions::new().append(true).open(&path).expect("file");
+            file.write_all(&[0u8; 4096]).expect("grow");
+            let note = reader.frame_by_uri("mv2://notes/a").expect("note");
+            assert_eq!(
+                reader.frame_canonical_payload(note.id).expect("payload"),
+                b"mapped note"
+            );
+            assert_eq!(
+                reader.mapped.as_ref().expect("mapped").len(),
+                mapped_len + 4096
+            );
+            let mut bytes = Vec::new();
+            blob.seek(SeekFrom::Start(0)).expect("rewind");
+            blob.read_to_end(&mut bytes).expect("read all");
+            assert!(bytes == media);
+        });
+    }
+}
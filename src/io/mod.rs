@@ -3,6 +3,10 @@
 pub mod header;
 #[cfg(feature = "parallel_segments")]
 pub mod manifest_wal;
+#[cfg(feature = "mmap")]
+pub(crate) mod mapped;
+pub(crate) mod read_at;
+pub(crate) mod region;
 #[cfg(feature = "temporal_track")]
 pub mod temporal_index;
 pub mod time_index;
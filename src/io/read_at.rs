@@ -0,0 +1,36 @@
+//! Reads at an offset that do not depend on the file cursor.
+//!
+//! Handles forked for [`MemvidReader`](crate::MemvidReader) share one descriptor, so a
+//! seek followed by a read could land on the position another handle just set. Reads
+//! on the query path go through these instead.
+
+use std::fs::File;
+use std::io;
+
+/// Read up to `buf.len()` bytes at `offset`, returning how many were read.
+#[cfg(unix)]
+pub(crate) fn read_at(file: &File, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+    std::os::unix::fs::FileExt::read_at(file, buf, offset)
+}
+
+/// Read up to `buf.len()` bytes at `offset`, returning how many were read.
+#[cfg(windows)]
+pub(crate) fn read_at(file: &File, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+    std::os::windows::fs::FileExt::seek_read(file, buf, offset)
+}
+
+/// Fill `buf` with the bytes at `offset`.
+pub(crate) fn read_exact_at(file: &File, mut buf: &mut [u8], mut offset: u64) -> io::Result<()> {
+    while !buf.is_empty() {
+        match read_at(file, buf, offset) {
+            Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+            Ok(read) => {
+                buf = &mut buf[read..];
+                offset += read as u64;
+            }
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(())
+}
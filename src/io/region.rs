@@ -0,0 +1,138 @@
+//! Region-level encryption for encrypted-mode `.mv2` files.
+//!
+//! Frame payloads, WAL entries, index segments and the TOC are sealed one region at a
+//! time with keys derived from the memory's master key. The header and commit footers stay
+//! in the clear so the layout can still be validated without the key. Builds without the
+//! `encryption` feature only ever see plaintext regions.
+
+use std::borrow::Cow;
+#[cfg(feature = "encryption")]
+use std::sync::Arc;
+
+#[cfg(feature = "encryption")]
+use crate::encryption::RegionCipher;
+#[cfg(feature = "encryption")]
+use crate::error::MemvidError;
+use crate::error::Result;
+use crate::types::HeaderEncryption;
+
+/// Kind of region being sealed; each kind is encrypted under its own derived key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RegionKind {
+    Payload,
+    Index,
+    Toc,
+    Wal,
+}
+
+impl RegionKind {
+    #[cfg_attr(not(feature = "encryption"), allow(dead_code))]
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Payload => "payload",
+            Self::Index => "index",
+            Self::Toc => "toc",
+            Self::Wal => "wal",
+        }
+    }
+}
+
+/// Seals and opens regions of a memory; a no-op for plaintext memories.
+#[derive(Clone, Default)]
+pub(crate) struct RegionCrypto {
+    #[cfg(feature = "encryption")]
+    cipher: Option<Arc<RegionCipher>>,
+}
+
+impl RegionCrypto {
+    /// Regions of a plaintext memory.
+    pub(crate) fn plaintext() -> Self {
+        Self::default()
+    }
+
+    /// Fresh region keys for a new encrypted memory.
+    #[cfg(feature = "encryption")]
+    pub(crate) fn generate(master_key: &[u8; 32]) -> Self {
+        Self {
+            cipher: Some(Arc::new(RegionCipher::generate(master_key))),
+        }
+    }
+
+    /// Region keys for an existing encrypted memory, rejecting a master key that does not
+    /// match the key check stored in the header.
+    #[cfg(feature = "encryption")]
+    pub(crate) fn unlock(master_key: &[u8; 32], encryption: &HeaderEncryption) -> Result<Self> {
+        let cipher = RegionCipher::new(master_key, &encryption.salt);
+        if !cipher.key_check_matches(&encryption.key_check) {
+            return Err(MemvidError::InvalidEncryptionKey);
+        }
+        Ok(Self {
+            cipher: Some(Arc::new(cipher)),
+        })
+    }
+
+    pub(crate) fn is_encrypted(&self) -> bool {
+        #[cfg(feature = "encryption")]
+        {
+            self.cipher.is_some()
+        }
+        #[cfg(not(feature = "encryption"))]
+        {
+            false
+        }
+    }
+
+    /// Header descriptor recorded for encrypted memories.
+    pub(crate) fn header_encryption(&self) -> Option<HeaderEncryption> {
+        #[cfg(feature = "encryption")]
+        {
+            self.cipher.as_ref().map(|cipher| HeaderEncryption {
+                salt: cipher.salt(),
+                key_check: cipher.key_check(),
+            })
+        }
+        #[cfg(not(feature = "encryption"))]
+        {
+            None
+        }
+    }
+
+    /// Returns the bytes to store for `plaintext`. Empty regions are stored as nothing, so
+    /// zero-length payloads keep a zero length in the TOC.
+    pub(crate) fn seal<'a>(&self, kind: RegionKind, plaintext: &'a [u8]) -> Result<Cow<'a, [u8]>> {
+        #[cfg(feature = "encryption")]
+        if let Some(cipher) = self.cipher.as_ref().filter(|_| !plaintext.is_empty()) {
+            return cipher.seal(kind, plaintext).map(Cow::Owned).map_err(|err| {
+                MemvidError::RegionEncryption {
+                    kind: kind.as_str(),
+                    reason: err.to_string(),
+                }
+            });
+        }
+        let _ = kind;
+        Ok(Cow::Borrowed(plaintext))
+    }
+
+    /// Returns the plaintext of a stored region.
+    pub(crate) fn open<'a>(&self, kind: RegionKind, stored: &'a [u8]) -> Result<Cow<'a, [u8]>> {
+        #[cfg(feature = "encryption")]
+        if let Some(cipher) = self.cipher.as_ref().filter(|_| !stored.is_empty()) {
+            return cipher.open(kind, stored).map(Cow::Owned).map_err(|_| {
+                MemvidError::RegionDecryption {
+                    kind: kind.as_str(),
+                }
+            });
+        }
+        let _ = kind;
+        Ok(Cow::Borrowed(stored))
+    }
+
+    /// Like [`RegionCrypto::open`], reusing the buffer for plaintext memories.
+    pub(crate) fn open_vec(&self, kind: RegionKind, stored: Vec<u8>) -> Result<Vec<u8>> {
+        if self.is_encrypted() {
+            self.open(kind, &stored).map(Cow::into_owned)
+        } else {
+            Ok(stored)
+        }
+    }
+}
@@ -4,6 +4,7 @@ use std::io::{Read, Seek, SeekFrom, Write};
 use crate::{
     constants::{WAL_CHECKPOINT_PERIOD, WAL_CHECKPOINT_THRESHOLD},
     error::{MemvidError, Result},
+    io::read_at::read_exact_at,
     types::Header,
 };
 
@@ -53,12 +54,12 @@ impl EmbeddedWal {
                 reason: "wal_size must be non-zero".into(),
             });
         }
-        let mut clone = file.try_clone()?;
+        let clone = file.try_clone()?;
         let region_offset = header.wal_offset;
         let region_size = header.wal_size;
         let checkpoint_sequence = header.wal_sequence;
 
-        let (entries, next_head) = Self::scan_records(&mut clone, region_offset, region_size)?;
+        let (entries, next_head) = Self::scan_records(&clone, region_offset, region_size)?;
 
         let pending_bytes = entries
             .iter()
@@ -74,7 +75,7 @@ impl EmbeddedWal {
             file: clone,
             region_offset,
             region_size,
-            write_head: next_head % region_size,
+            write_head: next_head,
             checkpoint_head: header.wal_checkpoint_pos % region_size,
             pending_bytes,
             sequence,
@@ -143,7 +144,7 @@ impl EmbeddedWal {
         );
         self.write_record(self.write_head, next_sequence, payload)?;
 
-        self.write_head = (self.write_head + entry_size) % self.region_size;
+        self.write_head += entry_size;
         self.pending_bytes += entry_size;
         self.sequence = self.sequence.wrapping_add(1);
         self.appends_since_checkpoint = self.appends_since_checkpoint.saturating_add(1);
@@ -164,13 +165,38 @@ impl EmbeddedWal {
 
     pub fn record_checkpoint(&mut self, header: &mut Header) -> Result<()> {
         self.assert_writable()?;
-        self.checkpoint_head = self.write_head;
         self.pending_bytes = 0;
         self.appends_since_checkpoint = 0;
         self.checkpoint_sequence = self.sequence;
+        self.maybe_write_sentinel()?;
+        self.checkpoint_head = self.write_head;
         header.wal_checkpoint_pos = self.checkpoint_head;
         header.wal_sequence = self.checkpoint_sequence;
-        self.maybe_write_sentinel()
+        Ok(())
+    }
+
+    /// Overwrite the whole region with zeros, erasing checkpointed entries, and restart
+    /// the log at the start of the region. Fails while records are pending.
+    pub fn scrub(&mut self, header: &mut Header) -> Result<()> {
+        const CHUNK: u64 = 64 * 1024;
+        self.assert_writable()?;
+        if self.pending_bytes > 0 {
+            return Err(MemvidError::CheckpointFailed {
+                reason: "cannot scrub a WAL with pending records".into(),
+            });
+        }
+        let zeros = vec![0u8; CHUNK.min(self.region_size) as usize];
+        let mut position = 0u64;
+        while position < self.region_size {
+            let len = CHUNK.min(self.region_size - position) as usize;
+            self.seek_and_write(position, &zeros[..len])?;
+            position += len as u64;
+        }
+        self.write_head = 0;
+        self.checkpoint_head = 0;
+        header.wal_checkpoint_pos = 0;
+        self.file.sync_all()?;
+        Ok(())
     }
 
     pub fn pending_records(&mut self) -> Result<Vec<WalRecord>> {
@@ -179,7 +205,7 @@ impl EmbeddedWal {
 
     pub fn records_after(&mut self, sequence: u64) -> Result<Vec<WalRecord>> {
         let (entries, next_head) =
-            Self::scan_records(&mut self.file, self.region_offset, self.region_size)?;
+            Self::scan_records(&self.file, self.region_offset, self.region_size)?;
 
         self.sequence = entries
             .last()
@@ -190,7 +216,7 @@ impl EmbeddedWal {
             .filter(|entry| entry.sequence > self.checkpoint_sequence)
             .map(|entry| entry.total_size)
             .sum();
-        self.write_head = next_head % self.region_size;
+        self.write_head = next_head;
         if !self.read_only {
             self.initialise_sentinel()?;
         }
@@ -259,13 +285,19 @@ impl EmbeddedWal {
         if self.region_size == 0 {
             return Ok(0);
         }
-        let mut pos = position % self.region_size;
+        let mut pos = position.min(self.region_size);
         let remaining = self.region_size - pos;
         if remaining < ENTRY_HEADER_SIZE as u64 {
             if remaining > 0 {
                 let zero_tail = vec![0u8; remaining as usize];
                 self.seek_and_write(pos, &zero_tail)?;
             }
+            // Records are scanned forward from the start of the region, so a sentinel at
+            // offset 0 would hide everything still pending; leave the head at the end and
+            // let the next append grow the region instead.
+            if self.pending_bytes > 0 {
+                return Ok(pos);
+            }
             pos = 0;
         }
         let zero = [0u8; ENTRY_HEADER_SIZE];
@@ -295,13 +327,12 @@ impl EmbeddedWal {
         Ok(())
     }
 
-    fn scan_records(file: &mut File, offset: u64, size: u64) -> Result<(Vec<ScannedRecord>, u64)> {
+    fn scan_records(file: &File, offset: u64, size: u64) -> Result<(Vec<ScannedRecord>, u64)> {
         let mut records = Vec::new();
         let mut cursor = 0u64;
         while cursor + ENTRY_HEADER_SIZE as u64 <= size {
-            file.seek(SeekFrom::Start(offset + cursor))?;
             let mut header = [0u8; ENTRY_HEADER_SIZE];
-            file.read_exact(&mut header)?;
+            read_exact_at(file, &mut header, offset + cursor)?;
 
             let sequence = u64::from_le_bytes(header[..8].try_into().map_err(|_| {
                 MemvidError::WalCorruption {
@@ -335,7 +366,11 @@ impl EmbeddedWal {
             }
 
             let mut payload = vec![0u8; length as usize];
-            file.read_exact(&mut payload)?;
+            read_exact_at(
+                file,
+                &mut payload,
+                offset + cursor + ENTRY_HEADER_SIZE as u64,
+            )?;
             let expected = blake3::hash(&payload);
             if expected.as_bytes() != checksum {
                 return Err(MemvidError::WalCorruption {
@@ -410,6 +445,7 @@ mod tests {
             wal_checkpoint_pos: 0,
             wal_sequence: 0,
             toc_checksum: [0u8; 32],
+            encryption: None,
         }
     }
 
@@ -454,6 +490,56 @@ mod tests {
         assert_eq!(records[0].payload, vec![0xCC; 32]);
     }
 
+    #[test]
+    fn pending_records_survive_filling_the_region_tail() {
+        let (file, header) = prepare_wal(1024);
+        let mut wal = EmbeddedWal::open(&file, &header).expect("open wal");
+
+        // Two 500-byte entries leave less than a header's worth of space at the end.
+        let payload = vec![0x5A; 500 - ENTRY_HEADER_SIZE];
+        wal.append_entry(&payload).expect("append first");
+        wal.append_entry(&payload).expect("append second");
+        assert_eq!(wal.pending_records().expect("pending").len(), 2);
+
+        match wal.append_entry(b"third") {
+            Err(MemvidError::CheckpointFailed { reason }) => {
+                assert_eq!(reason, "embedded WAL region full");
+            }
+            other => panic!("expected a full region, got {other:?}"),
+        }
+
+        let reopened = EmbeddedWal::open(&file, &header)
+            .expect("reopen")
+            .pending_records()
+            .expect("pending after reopen");
+        assert_eq!(reopened.len(), 2);
+        assert_eq!(reopened[1].sequence, 2);
+    }
+
+    #[test]
+    fn scrub_erases_checkpointed_entries() {
+        let (mut file, mut header) = prepare_wal(1024);
+        let mut wal = EmbeddedWal::open(&file, &header).expect("open wal");
+        wal.append_entry(b"forget me").expect("append");
+        assert!(wal.scrub(&mut header).is_err());
+        wal.record_checkpoint(&mut header).expect("checkpoint");
+        wal.scrub(&mut header).expect("scrub");
+
+        let mut region = vec![0u8; 1024];
+        file.seek(SeekFrom::Start(header.wal_offset)).expect("seek");
+        file.read_exact(&mut region).expect("read");
+        assert!(region.iter().all(|byte| *byte == 0));
+
+        wal.append_entry(b"next").expect("append after scrub");
+        let reopened = EmbeddedWal::open(&file, &header)
+            .expect("reopen")
+            .pending_records()
+            .expect("pending");
+        assert_eq!(reopened.len(), 1);
+        assert_eq!(reopened[0].payload, b"next");
+        assert_eq!(reopened[0].sequence, 2);
+    }
+
     #[test]
     fn corrupted_record_reports_offset() {
         let (mut file, header) = prepare_wal(64);
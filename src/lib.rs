@@ -6,6 +6,7 @@
 pub const MEMVID_CORE_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 mod analysis;
+pub mod cdc;
 pub mod constants;
 pub mod enrich;
 pub mod enrichment_worker;
@@ -13,11 +14,13 @@ pub mod error;
 pub mod extract;
 pub mod extract_budgeted;
 pub mod footer;
+pub mod frame_table;
 pub mod io;
 pub mod lex;
 mod lock;
 pub mod lockfile;
 pub mod memvid;
+pub mod merkle;
 pub mod models;
 pub mod pii;
 pub mod reader;
@@ -30,6 +33,7 @@ pub mod text;
 mod toc;
 pub mod types;
 pub mod vec;
+pub mod vec_hnsw;
 pub mod vec_pq;
 
 // Triplet extraction module for automatic SPO extraction during ingestion
@@ -51,7 +55,7 @@ pub mod whisper;
 // Full functionality requires the "replay" feature
 pub mod replay;
 
-// Password-based encryption capsules (.mv2e)
+// Encryption capsules (.mv2e) and encrypted-mode memories
 // Feature-gated to avoid pulling crypto dependencies into default builds.
 #[cfg(feature = "encryption")]
 pub mod encryption;
@@ -79,7 +83,7 @@ pub use constants::*;
 pub use enrichment_worker::{EnrichmentWorkerConfig, EnrichmentWorkerStats};
 pub use error::{MemvidError, Result};
 pub use extract::{DocumentProcessor, ExtractedDocument, ProcessorConfig};
-pub use footer::{CommitFooter, find_last_valid_footer};
+pub use footer::{CommitFooter, find_last_valid_footer, scan_valid_footers};
 #[cfg(feature = "temporal_track")]
 pub use io::temporal_index::{
     append_track as temporal_track_append, calculate_checksum as temporal_track_checksum,
@@ -93,13 +97,14 @@ pub use io::wal::{EmbeddedWal, WalRecord, WalStats};
 pub use lex::{LexIndex, LexIndexArtifact, LexIndexBuilder, LexSearchHit};
 pub use lock::FileLock;
 pub use memvid::{
-    BlobReader, EnrichmentHandle, EnrichmentStats, LockSettings, Memvid, OpenReadOptions,
-    SketchCandidate, SketchSearchOptions, SketchSearchStats,
+    BlobReader, EnrichmentHandle, EnrichmentStats, LockSettings, Memvid, MemvidReader,
+    OpenReadOptions, SketchCandidate, SketchSearchOptions, SketchSearchStats, Transaction,
     mutation::{CommitMode, CommitOptions},
     start_enrichment_worker, start_enrichment_worker_with_embeddings,
 };
 #[cfg(feature = "parallel_segments")]
 pub use memvid::{BuildOpts, ParallelInput, ParallelPayload};
+pub use merkle::verify_frame_proof;
 pub use models::{
     ModelManifest, ModelManifestEntry, ModelVerification, ModelVerificationStatus,
     ModelVerifyOptions, verify_model_dir, verify_models,
@@ -109,7 +114,8 @@ pub use reader::{
     ReaderOutput, ReaderRegistry,
 };
 pub use signature::{
-    parse_ed25519_public_key_base64, verify_model_manifest, verify_ticket_signature,
+    parse_ed25519_public_key_base64, verify_commit_signature, verify_model_manifest,
+    verify_ticket_signature,
 };
 pub use text::{NormalizedText, normalize_text, truncate_at_grapheme_boundary};
 #[cfg(feature = "temporal_track")]
@@ -120,22 +126,30 @@ pub use types::{
     TemporalMentionKind, TemporalTrack, TemporalTrackManifest,
 };
 pub use types::{
-    AskCitation, AskMode, AskRequest, AskResponse, AskRetriever, AskStats, AudioSegmentMetadata,
-    AuditOptions, AuditReport, CanonicalEncoding, DOCTOR_PLAN_VERSION, DocAudioMetadata,
-    DocExifMetadata, DocGpsMetadata, DocMetadata, DoctorActionDetail, DoctorActionKind,
-    DoctorActionPlan, DoctorActionReport, DoctorActionStatus, DoctorFinding, DoctorFindingCode,
-    DoctorMetrics, DoctorOptions, DoctorPhaseDuration, DoctorPhaseKind, DoctorPhasePlan,
-    DoctorPhaseReport, DoctorPhaseStatus, DoctorPlan, DoctorReport, DoctorSeverity, DoctorStatus,
-    EmbeddingIdentity, EmbeddingIdentityCount, EmbeddingIdentitySummary, Frame, FrameId, FrameRole,
-    FrameStatus, Header, IndexManifests, LexIndexManifest, LexSegmentDescriptor,
-    MEMVID_EMBEDDING_DIMENSION_KEY, MEMVID_EMBEDDING_MODEL_KEY, MEMVID_EMBEDDING_NORMALIZED_KEY,
-    MEMVID_EMBEDDING_PROVIDER_KEY, MediaManifest, MemvidHandle, Open, PutOptions,
-    PutOptionsBuilder, Sealed, SearchEngineKind, SearchHit, SearchHitMetadata, SearchParams,
-    SearchRequest, SearchResponse, SegmentCatalog, SegmentCommon, SegmentCompression, SegmentMeta,
-    SegmentSpan, SourceSpan, Stats, TextChunkManifest, TextChunkRange, Ticket, TicketRef, Tier,
-    TimeIndexManifest, TimeSegmentDescriptor, TimelineEntry, TimelineQuery, TimelineQueryBuilder,
-    Toc, VecEmbedder, VecIndexManifest, VecSegmentDescriptor, VectorCompression, VerificationCheck,
-    VerificationReport, VerificationStatus,
+    ArchiveReport, AskCitation, AskMode, AskRequest, AskResponse, AskRetriever, AskStats,
+    AudioSegmentMetadata, AuditOptions, AuditReport, BlobStoreManifest, CanonicalEncoding,
+    ChangeBatch, ChangeEvent, ChangeKind, CommitSignature, CommitSignatureFailure,
+    CommitSignatureIssue, CommitSignatureReport, CompressionDictionaryManifest,
+    CompressionSettings, DEFAULT_CHANGE_LOG_HORIZON, DOCTOR_PLAN_VERSION, DictionaryOptions,
+    DiffFrame, DiffMeshEdge, DiffMeshNode, DistanceMetric, DocAudioMetadata, DocExifMetadata,
+    DocGpsMetadata, DocMetadata, DoctorActionDetail, DoctorActionKind, DoctorActionPlan,
+    DoctorActionReport, DoctorActionStatus, DoctorFinding, DoctorFindingCode, DoctorMetrics,
+    DoctorOptions, DoctorPhaseDuration, DoctorPhaseKind, DoctorPhasePlan, DoctorPhaseReport,
+    DoctorPhaseStatus, DoctorPlan, DoctorReport, DoctorSeverity, DoctorStatus, EmbeddingIdentity,
+    EmbeddingIdentityCount, EmbeddingIdentitySummary, EncodingStats, ErasureReceipt, FieldChange,
+    Frame, FrameId, FrameMetadataChange, FrameProof, FrameRole, FrameStatus, FrameTableManifest,
+    FrameTablePage, FrameVersion, GenerationInfo, Header, HeaderEncryption, HnswGraphManifest,
+    IndexManifests, LexIndexManifest, LexSegmentDescriptor, MEMVID_EMBEDDING_DIMENSION_KEY,
+    MEMVID_EMBEDDING_MODEL_KEY, MEMVID_EMBEDDING_NORMALIZED_KEY, MEMVID_EMBEDDING_PROVIDER_KEY,
+    MEMVID_TOMBSTONE_REASON_KEY, MediaManifest, MemoryDiff, MemvidHandle, MergeOptions,
+    MergeReport, Open, PutOptions, PutOptionsBuilder, PutPreflight, QuotaSettings,
+    RetentionCandidate, RetentionReason, RetentionReport, RetentionRule, RetentionSelector, Sealed,
+    SearchEngineKind, SearchHit, SearchHitMetadata, SearchParams, SearchRequest, SearchResponse,
+    SegmentCatalog, SegmentCommon, SegmentCompression, SegmentMeta, SegmentSpan, SourceSpan, Stats,
+    SupersededFrame, TextChunkManifest, TextChunkRange, Ticket, TicketRef, Tier, TimeIndexManifest,
+    TimeSegmentDescriptor, TimelineEntry, TimelineQuery, TimelineQueryBuilder, Toc, VacuumPhase,
+    VacuumProgress, VacuumReport, VecEmbedder, VecIndexManifest, VecSegmentDescriptor,
+    VectorCompression, VerificationCheck, VerificationReport, VerificationStatus, VerifyOptions,
 };
 // Memory card types for structured memory extraction and storage
 pub use types::{
@@ -182,12 +196,17 @@ pub use types::{
     EmbeddingResult,
 };
 // Reranker types for second-stage ranking in RAG pipelines
+pub use frame_table::{FRAMES_PER_PAGE, FrameTable};
 pub use types::reranker::{
     Reranker, RerankerConfig, RerankerDocument, RerankerKind, RerankerResult,
 };
 #[cfg(feature = "parallel_segments")]
 pub use types::{IndexSegmentRef, SegmentKind, SegmentStats};
 pub use vec::{VecIndex, VecIndexArtifact, VecSearchHit};
+pub use vec_hnsw::{
+    DEFAULT_EF_SEARCH, HNSW_EF_CONSTRUCTION, HNSW_M, HnswGraph, HnswGraphArtifact,
+    MAX_DELETED_ONE_IN, MIN_VECTORS_FOR_HNSW,
+};
 pub use vec_pq::{
     CompressionStats, ProductQuantizer, QuantizedVecIndex, QuantizedVecIndexArtifact,
     QuantizedVecIndexBuilder,
@@ -260,6 +279,17 @@ pub(crate) fn run_serial_test<T>(f: impl FnOnce() -> T) -> T {
     f()
 }
 
+/// Put options that skip enrichment and indexing on put, for tests that only need frames.
+#[cfg(test)]
+pub(crate) fn fast_options() -> PutOptions {
+    PutOptions::builder()
+        .auto_tag(false)
+        .extract_dates(false)
+        .extract_triplets(false)
+        .instant_index(false)
+        .build()
+}
+
 impl Memvid {
     #[cfg(feature = "lex")]
     fn tantivy_index_pending(&self) -> bool {
@@ -346,6 +376,11 @@ impl Drop for Memvid {
         if self.dirty {
             let _ = self.commit();
         }
+        // Readers handed out by this handle share its lock and outlive it; they only need
+        // it shared.
+        if self.published_reader.is_some() && !self.read_only {
+            let _ = self.lock.downgrade_to_shared();
+        }
         // Clean up temporary manifest.wal file (parallel_segments feature)
         #[cfg(feature = "parallel_segments")]
         {
@@ -378,6 +413,22 @@ pub(crate) fn decode_canonical_bytes(
                 reason: "failed to decode canonical payload",
             })
         }
+        CanonicalEncoding::Lz4 => {
+            lz4_flex::decompress_size_prepended(payload).map_err(|_| MemvidError::InvalidFrame {
+                frame_id,
+                reason: "failed to decode canonical payload",
+            })
+        }
+        // Needs the dictionary stored in the memory; see `Memvid::decode_canonical`.
+        CanonicalEncoding::ZstdDict => Err(MemvidError::InvalidFrame {
+            frame_id,
+            reason: "canonical payload requires a compression dictionary",
+        }),
+        // A recipe of chunk hashes; see `Memvid::read_blob_chunks`.
+        CanonicalEncoding::Chunked => Err(MemvidError::InvalidFrame {
+            frame_id,
+            reason: "canonical payload requires the blob store",
+        }),
     }
 }
 
@@ -658,6 +709,612 @@ mod tests {
         });
     }
 
+    #[test]
+    fn vec_hnsw_graph_persists_across_reopen() {
+        run_serial_test(|| {
+            let dir = tempdir().expect("tmp");
+            let path = dir.path().join("hnsw.mv2");
+            let count = MIN_VECTORS_FOR_HNSW + 16;
+
+            let embedding = |idx: usize| {
+                let angle = idx as f32 * 0.01;
+                vec![angle.cos(), angle.sin(), 0.5]
+            };
+
+            let mut mem = Memvid::create(&path).expect("create");
+            mem.enable_vec().expect("enable");
+            for idx in 0..count {
+                let options = PutOptions::builder()
+                    .auto_tag(false)
+                    .extract_dates(false)
+                    .extract_triplets(false)
+                    .instant_index(false)
+                    .build();
+                mem.put_with_embedding_and_options(
+                    format!("doc {idx}").as_bytes(),
+                    embedding(idx),
+                    options,
+                )
+                .expect("put");
+                if idx % 64 == 63 {
+                    mem.commit().expect("commit batch");
+                }
+            }
+            mem.commit().expect("commit");
+
+            let graph = mem
+                .toc
+                .indexes
+                .vec
+                .as_ref()
+                .and_then(|manifest| manifest.hnsw.clone())
+                .expect("hnsw manifest");
+            assert_eq!(graph.node_count, count as u64);
+            assert_eq!(graph.m, HNSW_M);
+            assert_eq!(graph.ef_construction, HNSW_EF_CONSTRUCTION);
+            drop(mem);
+
+            let mut reopened = Memvid::open_read_only(&path).expect("open");
+            let hits = reopened
+                .search_vec_with_ef(&embedding(321), 3, 128)
+                .expect("search");
+            assert_eq!(hits.first().map(|hit| hit.frame_id), Some(321));
+            let loaded = reopened.vec_index.as_ref().and_then(|index| index.graph());
+            assert_eq!(loaded.map(HnswGraph::len), Some(count));
+        });
+    }
+
+    #[test]
+    fn vec_deletes_keep_the_hnsw_graph_until_vacuum() {
+        run_serial_test(|| {
+            let dir = tempdir().expect("tmp");
+            let path = dir.path().join("hnsw-delete.mv2");
+            let count = MIN_VECTORS_FOR_HNSW + 16;
+            let embedding = |idx: usize| {
+                let angle = idx as f32 * 0.01;
+                vec![angle.cos(), angle.sin(), 0.5]
+            };
+
+            let mut mem = Memvid::create(&path).expect("create");
+            mem.enable_vec().expect("enable");
+            for idx in 0..count {
+                let options = PutOptions::builder()
+                    .auto_tag(false)
+                    .extract_dates(false)
+                    .extract_triplets(false)
+                    .instant_index(false)
+                    .build();
+                mem.put_with_embedding_and_options(
+                    format!("doc {idx}").as_bytes(),
+                    embedding(idx),
+                    options,
+                )
+                .expect("put");
+                if idx % 64 == 63 {
+                    mem.commit().expect("commit batch");
+                }
+            }
+            mem.commit().expect("commit");
+            let hnsw = |mem: &Memvid| {
+                mem.toc
+                    .indexes
+                    .vec
+                    .as_ref()
+                    .and_then(|manifest| manifest.hnsw.clone())
+                    .expect("hnsw manifest")
+            };
+            let built = hnsw(&mem);
+
+            mem.delete_frame(321).expect("delete");
+            mem.commit().expect("commit delete");
+            // The graph is carried over with the node marked, not rebuilt.
+            let carried = hnsw(&mem);
+            assert_eq!(carried.node_count, count as u64);
+            assert_ne!(carried.checksum, built.checksum);
+            let graph = mem.vec_index.as_ref().and_then(|index| index.graph());
+            assert_eq!(graph.map(HnswGraph::deleted_count), Some(1));
+            let hits = mem
+                .search_vec_with_ef(&embedding(321), 3, 128)
+                .expect("search");
+            assert_eq!(hits.len(), 3);
+            assert!(hits.iter().all(|hit| hit.frame_id != 321));
+            assert_eq!(mem.frame_embedding(321).expect("embedding"), None);
+
+            mem.vacuum().expect("vacuum");
+            assert_eq!(hnsw(&mem).node_count, count as u64 - 1);
+            let graph = mem.vec_index.as_ref().and_then(|index| index.graph());
+            assert_eq!(graph.map(HnswGraph::deleted_count), Some(0));
+        });
+    }
+
+    #[test]
+    fn vec_metric_persists_and_drives_scores() {
+        run_serial_test(|| {
+            let dir = tempdir().expect("tmp");
+            let path = dir.path().join("metric.mv2");
+
+            let mut mem = Memvid::create(&path).expect("create");
+            mem.enable_vec().expect("enable");
+            assert_eq!(mem.vec_metric(), DistanceMetric::Cosine);
+            mem.enable_vec_with_metric(DistanceMetric::Dot)
+                .expect("enable dot");
+            for embedding in [vec![4.0, 0.0], vec![0.6, 0.8]] {
+                let options = PutOptions::builder()
+                    .auto_tag(false)
+                    .extract_dates(false)
+                    .extract_triplets(false)
+                    .instant_index(false)
+                    .build();
+                mem.put_with_embedding_and_options(b"metric doc", embedding, options)
+                    .expect("put");
+            }
+            mem.commit().expect("commit");
+            drop(mem);
+
+            let mut reopened = Memvid::open_read_only(&path).expect("open");
+            assert_eq!(reopened.vec_metric(), DistanceMetric::Dot);
+            let response = reopened
+                .vec_search_with_embedding("q", &[0.6, 0.8], 2, 80, None)
+                .expect("search");
+            assert_eq!(response.hits[0].frame_id, 0);
+            let score = response.hits[0].score.expect("score");
+            assert!((score - 2.4).abs() < 1e-5, "dot score was {score}");
+        });
+    }
+
+    #[test]
+    fn frame_table_loads_pages_on_demand() {
+        run_serial_test(|| {
+            let dir = tempdir().expect("tmp");
+            let path = dir.path().join("paged.mv2");
+            let count = FRAMES_PER_PAGE * 2 + 10;
+
+            let mut mem = Memvid::create(&path).expect("create");
+            for idx in 0..count {
+                let options = PutOptions::builder()
+                    .uri(format!("mv2://paged/{idx}"))
+                    .auto_tag(false)
+                    .extract_dates(false)
+                    .extract_triplets(false)
+                    .instant_index(false)
+                    .build();
+                mem.put_bytes_with_options(format!("paged doc {idx}").as_bytes(), options)
+                    .expect("put");
+                if idx % 64 == 63 {
+                    mem.commit().expect("commit");
+                }
+            }
+            mem.commit().expect("commit");
+            let table = mem.toc.frame_table.clone().expect("frame table manifest");
+            assert_eq!(table.frame_count, count as u64);
+            drop(mem);
+
+            let reopened = Memvid::open_read_only(&path).expect("open");
+            assert!(reopened.toc.frames.is_paged());
+            assert_eq!(reopened.frame_count(), count);
+            assert_eq!(reopened.toc.frames.loaded_pages(), 0);
+
+            let frame = reopened.frame_by_id((count - 1) as u64).expect("frame");
+            assert_eq!(frame.uri.as_deref(), Some("mv2://paged/521"));
+            assert_eq!(reopened.toc.frames.loaded_pages(), 1);
+
+            let report = Memvid::verify(&path, true).expect("verify");
+            assert_eq!(report.overall_status, VerificationStatus::Passed);
+        });
+    }
+
+    #[test]
+    fn frame_table_commits_write_only_changed_pages() {
+        run_serial_test(|| {
+            let dir = tempdir().expect("tmp");
+            let path = dir.path().join("incremental.mv2");
+            let count = FRAMES_PER_PAGE * 2 + 10;
+            let options = || {
+                PutOptions::builder()
+                    .auto_tag(false)
+                    .extract_dates(false)
+                    .extract_triplets(false)
+                    .instant_index(false)
+                    .build()
+            };
+
+            let mut mem = Memvid::create(&path).expect("create");
+            for idx in 0..count {
+                mem.put_bytes_with_options(format!("page doc {idx}").as_bytes(), options())
+                    .expect("put");
+                if idx % 64 == 63 {
+                    mem.commit().expect("commit");
+                }
+            }
+            mem.commit().expect("commit");
+            let full = mem.toc.frame_table.clone().expect("frame table manifest");
+            assert_eq!(full.pages.len(), 3);
+
+            mem.put_bytes_with_options(b"one more", options())
+                .expect("put");
+            mem.commit().expect("commit append");
+            let appended = mem.toc.frame_table.clone().expect("frame table manifest");
+            assert_eq!(appended.pages[..2], full.pages[..2]);
+            assert!(appended.pages[2].bytes_offset >= full.pages_end());
+
+            mem.delete_frame(3).expect("delete");
+            mem.commit().expect("commit delete");
+            let deleted = mem.toc.frame_table.clone().expect("frame table manifest");
+            assert_eq!(deleted.pages[1..], appended.pages[1..]);
+            assert!(deleted.pages[0].bytes_offset >= appended.pages_end());
+            drop(mem);
+
+            let reopened = Memvid::open_read_only(&path).expect("open");
+            assert_eq!(reopened.frame_count(), count + 1);
+            let frame = reopened.frame_by_id(3).expect("frame");
+            assert_eq!(frame.status, FrameStatus::Deleted);
+            let report = Memvid::verify(&path, true).expect("verify");
+            assert_eq!(report.overall_status, VerificationStatus::Passed);
+        });
+    }
+
+    #[test]
+    fn wal_growth_during_commit_keeps_pending_frames() {
+        run_serial_test(|| {
+            let dir = tempdir().expect("tmp");
+            let path = dir.path().join("grow.mv2");
+            let options = || {
+                PutOptions::builder()
+                    .auto_tag(false)
+                    .extract_dates(false)
+                    .extract_triplets(false)
+                    .instant_index(false)
+                    .build()
+            };
+            // Incompressible, distinct payloads so every put lands in the WAL as is.
+            let mut seed = 0x9E37_79B9_7F4A_7C15u64;
+            let mut payload = |len: usize| -> Vec<u8> {
+                (0..len)
+                    .map(|_| {
+                        seed ^= seed << 13;
+                        seed ^= seed >> 7;
+                        seed ^= seed << 17;
+                        seed as u8
+                    })
+                    .collect()
+            };
+
+            let mut mem = Memvid::create(&path).expect("create");
+            let committed = FRAMES_PER_PAGE + 4;
+            for idx in 0..committed {
+                mem.put_bytes_with_options(format!("stored doc {idx}").as_bytes(), options())
+                    .expect("put");
+                if idx % 64 == 63 {
+                    mem.commit().expect("commit");
+                }
+            }
+            mem.commit().expect("commit");
+            // Restart the log at the start of the region so its free space is known.
+            mem.wal.scrub(&mut mem.header).expect("scrub");
+            let wal_size = mem.header.wal_size;
+
+            // Size the next put to leave less than an entry header free, so the commit
+            // it triggers has to grow the WAL for its lex batch while its frames are
+            // still pending.
+            let before = mem.wal.stats().pending_bytes;
+            let probe = payload(64);
+            mem.put_bytes_with_options(&probe, options())
+                .expect("probe");
+            let overhead = mem.wal.stats().pending_bytes - before - 64;
+            assert_eq!(mem.header.wal_size, wal_size);
+            let free = wal_size - mem.wal.stats().pending_bytes;
+            let filler = payload((free - overhead - 16) as usize);
+            mem.put_bytes_with_options(&filler, options())
+                .expect("put filling the WAL");
+            assert!(mem.header.wal_size > wal_size);
+            mem.commit().expect("commit");
+            drop(mem);
+
+            let mut reopened = Memvid::open(&path).expect("open");
+            assert_eq!(reopened.frame_count(), committed + 2);
+            let last = committed as u64;
+            assert_eq!(
+                reopened.frame_canonical_payload(last).expect("probe"),
+                probe
+            );
+            assert_eq!(
+                reopened.frame_canonical_payload(last + 1).expect("filler"),
+                filler
+            );
+            assert_eq!(
+                reopened.frame_canonical_payload(7).expect("stored"),
+                b"stored doc 7"
+            );
+            drop(reopened);
+            let report = Memvid::verify(&path, true).expect("verify");
+            assert_eq!(report.overall_status, VerificationStatus::Passed);
+        });
+    }
+
+    #[test]
+    fn inline_frames_migrate_to_frame_table() {
+        use std::io::{Seek, SeekFrom, Write};
+
+        run_serial_test(|| {
+            let dir = tempdir().expect("tmp");
+            let path = dir.path().join("inline.mv2");
+
+            let mut mem = Memvid::create(&path).expect("create");
+            mem.put_bytes(b"first").expect("put");
+            mem.commit().expect("commit");
+
+            // Rewrite the TOC the way pre-frame-table releases did: frames inline.
+            let mut toc = mem.toc.clone();
+            toc.frames = FrameTable::from(toc.frames.to_vec().expect("frames"));
+            toc.frame_table = None;
+            let toc_bytes = crate::memvid::lifecycle::prepare_toc_bytes(&mut toc).expect("toc");
+            let footer = crate::footer::CommitFooter {
+                toc_len: toc_bytes.len() as u64,
+                toc_hash: *blake3::hash(&toc_bytes).as_bytes(),
+                generation: mem.generation,
+            };
+            mem.file
+                .seek(SeekFrom::Start(mem.header.footer_offset))
+                .expect("seek");
+            mem.file.write_all(&toc_bytes).expect("toc");
+            mem.file.write_all(&footer.encode()).expect("footer");
+            let len = mem.file.stream_position().expect("position");
+            mem.file.set_len(len).expect("truncate");
+            mem.header.toc_checksum = toc.toc_checksum;
+            persist_header(&mut mem.file, &mem.header).expect("header");
+            drop(mem);
+
+            let mut reopened = Memvid::open(&path).expect("open legacy");
+            assert!(reopened.toc.frame_table.is_none());
+            assert!(!reopened.toc.frames.is_paged());
+            assert_eq!(reopened.frame_count(), 1);
+            reopened.put_bytes(b"second").expect("put");
+            reopened.commit().expect("commit");
+            assert!(reopened.toc.frame_table.is_some());
+            drop(reopened);
+
+            let migrated = Memvid::open_read_only(&path).expect("open migrated");
+            assert!(migrated.toc.frames.is_paged());
+            assert_eq!(migrated.frame_count(), 2);
+            assert_eq!(migrated.frame_by_id(0).expect("frame").id, 0);
+        });
+    }
+
+    #[test]
+    fn signed_commits_form_a_verifiable_chain() {
+        use ed25519_dalek::SigningKey;
+
+        run_serial_test(|| {
+            let dir = tempdir().expect("tmp");
+            let path = dir.path().join("signed.mv2");
+            let key = SigningKey::from_bytes(&[3u8; 32]);
+
+            let mut mem = Memvid::create(&path).expect("create");
+            for payload in [&b"first"[..], b"second"] {
+                mem.put_bytes(payload).expect("put");
+                mem.commit_with_options(
+                    CommitOptions::new(CommitMode::Full).signing_key(key.clone()),
+                )
+                .expect("signed commit");
+            }
+            let signed = mem.generation;
+            drop(mem);
+
+            let report = Memvid::verify(&path, false).expect("verify");
+            assert_eq!(report.overall_status, VerificationStatus::Passed);
+            let chain = report.commit_signatures.expect("signature report");
+            assert_eq!(chain.signed_generations, 2);
+            assert_eq!(chain.signers, vec![key.verifying_key().to_bytes()]);
+            assert!(chain.first_failure.is_none());
+            assert!(!chain.signers_trusted);
+
+            let trusted = VerifyOptions::new(false).trusted_key(key.verifying_key().to_bytes());
+            let report = Memvid::verify_with_options(&path, &trusted).expect("verify");
+            assert_eq!(report.overall_status, VerificationStatus::Passed);
+            assert!(report.commit_signatures.expect("report").signers_trusted);
+
+            let other = SigningKey::from_bytes(&[4u8; 32])
+                .verifying_key()
+                .to_bytes();
+            let report =
+                Memvid::verify_with_options(&path, &VerifyOptions::new(false).trusted_key(other))
+                    .expect("verify");
+            assert_eq!(report.overall_status, VerificationStatus::Failed);
+            let failure = report
+                .commit_signatures
+                .and_then(|chain| chain.first_failure)
+                .expect("failure");
+            assert_eq!(failure.issue, CommitSignatureIssue::UntrustedKey);
+
+            let mut mem = Memvid::open(&path).expect("open");
+            mem.put_bytes(b"third").expect("put");
+            mem.commit().expect("unsigned commit");
+            drop(mem);
+
+            let report = Memvid::verify(&path, false).expect("verify");
+            assert_eq!(report.overall_status, VerificationStatus::Failed);
+            let failure = report
+                .commit_signatures
+                .and_then(|chain| chain.first_failure)
+                .expect("failure");
+            assert_eq!(failure.generation, signed + 1);
+            assert_eq!(failure.issue, CommitSignatureIssue::Unsigned);
+        });
+    }
+
+    #[test]
+    fn signer_change_needs_both_keys_trusted() {
+        use ed25519_dalek::SigningKey;
+
+        run_serial_test(|| {
+            let dir = tempdir().expect("tmp");
+            let path = dir.path().join("rotated.mv2");
+            let old_key = SigningKey::from_bytes(&[6u8; 32]);
+            let new_key = SigningKey::from_bytes(&[7u8; 32]);
+
+            let mut mem = Memvid::create(&path).expect("create");
+            for key in [&old_key, &new_key] {
+                mem.put_bytes(b"entry").expect("put");
+                mem.commit_with_options(
+                    CommitOptions::new(CommitMode::Full).signing_key(key.clone()),
+                )
+                .expect("signed commit");
+            }
+            let rotated = mem.generation;
+            drop(mem);
+
+            let report = Memvid::verify(&path, false).expect("verify");
+            assert_eq!(report.overall_status, VerificationStatus::Failed);
+            let failure = report
+                .commit_signatures
+                .and_then(|chain| chain.first_failure)
+                .expect("failure");
+            assert_eq!(failure.generation, rotated);
+            assert_eq!(failure.issue, CommitSignatureIssue::SignerChanged);
+
+            let options = VerifyOptions::new(false)
+                .trusted_key(old_key.verifying_key().to_bytes())
+                .trusted_key(new_key.verifying_key().to_bytes());
+            let report = Memvid::verify_with_options(&path, &options).expect("verify");
+            assert_eq!(report.overall_status, VerificationStatus::Passed);
+            let chain = report.commit_signatures.expect("report");
+            assert_eq!(chain.signers.len(), 2);
+        });
+    }
+
+    #[test]
+    fn tampered_signed_generation_fails_verification() {
+        use ed25519_dalek::SigningKey;
+        use std::io::{Seek, SeekFrom, Write};
+
+        run_serial_test(|| {
+            let dir = tempdir().expect("tmp");
+            let path = dir.path().join("tampered.mv2");
+
+            let mut mem = Memvid::create(&path).expect("create");
+            mem.put_bytes(b"ledger entry").expect("put");
+            mem.commit_with_options(
+                CommitOptions::new(CommitMode::Full)
+                    .signing_key(SigningKey::from_bytes(&[5u8; 32])),
+            )
+            .expect("signed commit");
+
+            // Edit the signed TOC and re-seal it with valid checksums, but no key.
+            let mut toc = mem.toc.clone();
+            toc.ticket_ref.issuer = "forged".into();
+            let toc_bytes = crate::memvid::lifecycle::prepare_toc_bytes(&mut toc).expect("toc");
+            let footer = crate::footer::CommitFooter {
+                toc_len: toc_bytes.len() as u64,
+                toc_hash: *blake3::hash(&toc_bytes).as_bytes(),
+                generation: mem.generation,
+            };
+            let footer_offset = mem.header.footer_offset
+                + mem
+                    .toc
+                    .frame_table
+                    .as_ref()
+                    .map_or(0, |table| table.bytes_length);
+            mem.file.seek(SeekFrom::Start(footer_offset)).expect("seek");
+            mem.file.write_all(&toc_bytes).expect("toc");
+            mem.file.write_all(&footer.encode()).expect("footer");
+            let len = mem.file.stream_position().expect("position");
+            mem.file.set_len(len).expect("truncate");
+            mem.header.toc_checksum = toc.toc_checksum;
+            persist_header(&mut mem.file, &mem.header).expect("header");
+            let generation = mem.generation;
+            drop(mem);
+
+            let report = Memvid::verify(&path, false).expect("verify");
+            let failure = report
+                .commit_signatures
+                .and_then(|chain| chain.first_failure)
+                .expect("failure");
+            assert_eq!(failure.generation, generation);
+            assert_eq!(failure.issue, CommitSignatureIssue::Tampered);
+        });
+    }
+
+    #[test]
+    fn open_at_generation_restores_deleted_frames() {
+        run_serial_test(|| {
+            let dir = tempdir().expect("tmp");
+            let path = dir.path().join("history.mv2");
+
+            let mut mem = Memvid::create(&path).expect("create");
+            mem.enable_lex().expect("enable lex");
+            mem.put_bytes(b"alpha lighthouse notes").expect("put");
+            mem.put_bytes(b"bravo harbour notes").expect("put");
+            mem.commit().expect("commit");
+            let first = mem.generation;
+
+            mem.delete_frame(1).expect("delete");
+            mem.commit().expect("commit");
+            let second = mem.generation;
+
+            // Larger than the tiny WAL, so the data region moves after both commits.
+            let large = "charlie ".repeat(16 * 1024);
+            mem.put_bytes(large.as_bytes()).expect("put large");
+            mem.commit().expect("commit");
+
+            let generations = mem.list_generations().expect("generations");
+            let listed: Vec<u64> = generations.iter().map(|info| info.generation).collect();
+            assert!(listed.contains(&first) && listed.contains(&second));
+            assert_eq!(listed.last(), Some(&mem.generation));
+            let last = generations.last().expect("latest");
+            assert_eq!(last.frame_count, mem.frame_count() as u64);
+            assert!(last.committed_at.is_some());
+            let tocs = mem.generation_tocs().expect("generation tocs");
+            let (_, _, first_toc) = tocs.iter().find(|(g, _, _)| *g == first).expect("first");
+            assert!(first_toc.wal_size < mem.header.wal_size);
+            drop(mem);
+
+            let mut past = Memvid::open_at_generation(&path, first).expect("open first");
+            assert_eq!(past.frame_count(), 2);
+            assert_eq!(
+                past.frame_by_id(1).expect("frame").status,
+                FrameStatus::Active
+            );
+            assert_eq!(
+                past.frame_canonical_payload(1).expect("payload"),
+                b"bravo harbour notes"
+            );
+            let hits = past
+                .search(SearchRequest {
+                    query: "harbour".into(),
+                    top_k: 5,
+                    snippet_chars: 80,
+                    uri: None,
+                    scope: None,
+                    cursor: None,
+                    #[cfg(feature = "temporal_track")]
+                    temporal: None,
+                    as_of_frame: None,
+                    as_of_ts: None,
+                    no_sketch: false,
+                })
+                .expect("search");
+            assert_eq!(hits.hits.len(), 1);
+            assert!(past.put_bytes(b"nope").is_err());
+            drop(past);
+
+            let past = Memvid::open_at_generation(&path, second).expect("open second");
+            assert_eq!(
+                past.frame_by_id(1).expect("frame").status,
+                FrameStatus::Deleted
+            );
+            drop(past);
+
+            assert!(matches!(
+                Memvid::open_at_generation(&path, 9_999),
+                Err(MemvidError::GenerationNotFound { generation: 9_999 })
+            ));
+
+            let mut mem = Memvid::open(&path).expect("open");
+            mem.vacuum().expect("vacuum");
+            assert_eq!(mem.list_generations().expect("generations").len(), 1);
+        });
+    }
+
     #[test]
     fn search_snippet_ranges_match_bytes() {
         run_serial_test(|| {
@@ -697,7 +1354,8 @@ mod tests {
             let frame = mem
                 .toc
                 .frames
-                .get(hit.frame_id as usize)
+                .try_get(hit.frame_id as usize)
+                .expect("page")
                 .cloned()
                 .expect("frame");
             let canonical = mem.frame_content(&frame).expect("content");
@@ -782,7 +1440,12 @@ mod tests {
                 .expect("put bytes");
             mem.commit().expect("commit");
 
-            let frame = mem.toc.frames.first().expect("frame present");
+            let frame = mem
+                .toc
+                .frames
+                .first()
+                .expect("page")
+                .expect("frame present");
             assert!(!frame.tags.is_empty());
             assert!(frame.content_dates.iter().any(|date| date.contains("2024")));
         });
@@ -1,5 +1,6 @@
 use std::fs::{File, OpenOptions};
 use std::path::Path;
+use std::sync::Arc;
 use std::thread;
 use std::time::Duration;
 
@@ -15,11 +16,29 @@ pub enum LockMode {
 }
 
 /// File lock guard that can hold either a shared or exclusive OS lock.
+///
+/// Guards made with [`FileLock::share`] hold the same OS lock, which is released when the
+/// last of them is dropped.
 pub struct FileLock {
-    file: File,
+    file: Arc<LockedFile>,
     mode: LockMode,
 }
 
+/// Descriptor the OS lock is taken on.
+struct LockedFile {
+    file: File,
+    locked: bool,
+}
+
+impl LockedFile {
+    fn new(file: File, mode: LockMode) -> Arc<Self> {
+        Arc::new(Self {
+            file,
+            locked: mode != LockMode::None,
+        })
+    }
+}
+
 impl FileLock {
     /// Opens a file at `path` with read/write permissions and acquires an exclusive lock.
     pub fn open_and_lock(path: &Path) -> Result<(File, Self)> {
@@ -38,7 +57,7 @@ impl FileLock {
     /// Returns a non-locking guard for callers that only require a stable clone handle.
     pub fn unlocked(file: &File) -> Result<Self> {
         Ok(Self {
-            file: file.try_clone()?,
+            file: LockedFile::new(file.try_clone()?, LockMode::None),
             mode: LockMode::None,
         })
     }
@@ -55,7 +74,7 @@ impl FileLock {
             match clone.try_lock_exclusive() {
                 Ok(()) => {
                     return Ok(Some(Self {
-                        file: clone,
+                        file: LockedFile::new(clone, LockMode::Exclusive),
                         mode: LockMode::Exclusive,
                     }));
                 }
@@ -72,13 +91,23 @@ impl FileLock {
             return Ok(());
         }
         self.file
+            .file
             .unlock()
             .map_err(|err| MemvidError::Lock(err.to_string()))
     }
 
     /// Exposes a clone of the locked handle for buffered operations.
     pub fn clone_handle(&self) -> Result<File> {
-        Ok(self.file.try_clone()?)
+        Ok(self.file.file.try_clone()?)
+    }
+
+    /// Another guard on the same lock, for handles that read through the same descriptor.
+    /// Upgrading or downgrading either guard changes the lock both hold.
+    pub(crate) fn share(&self) -> Self {
+        Self {
+            file: Arc::clone(&self.file),
+            mode: self.mode,
+        }
     }
 
     pub fn mode(&self) -> LockMode {
@@ -95,9 +124,10 @@ impl FileLock {
             return Ok(());
         }
         self.file
+            .file
             .unlock()
             .map_err(|err| MemvidError::Lock(err.to_string()))?;
-        Self::lock_with_retry(&self.file, LockMode::Shared)?;
+        Self::lock_with_retry(&self.file.file, LockMode::Shared)?;
         self.mode = LockMode::Shared;
         Ok(())
     }
@@ -112,9 +142,10 @@ impl FileLock {
             return Ok(());
         }
         self.file
+            .file
             .unlock()
             .map_err(|err| MemvidError::Lock(err.to_string()))?;
-        Self::lock_with_retry(&self.file, LockMode::Exclusive)?;
+        Self::lock_with_retry(&self.file.file, LockMode::Exclusive)?;
         self.mode = LockMode::Exclusive;
         Ok(())
     }
@@ -122,7 +153,10 @@ impl FileLock {
     pub(crate) fn acquire_with_mode(file: &File, mode: LockMode) -> Result<Self> {
         let clone = file.try_clone()?;
         Self::lock_with_retry(&clone, mode)?;
-        Ok(Self { file: clone, mode })
+        Ok(Self {
+            file: LockedFile::new(clone, mode),
+            mode,
+        })
     }
 
     fn lock_with_retry(file: &File, mode: LockMode) -> Result<()> {
@@ -155,9 +189,9 @@ impl FileLock {
     }
 }
 
-impl Drop for FileLock {
+impl Drop for LockedFile {
     fn drop(&mut self) {
-        if self.mode != LockMode::None {
+        if self.locked {
             let _ = self.file.unlock();
         }
     }
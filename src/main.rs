@@ -1,10 +1,13 @@
 //! Memvid CLI - Command-line interface for memvid-core
 //!
-//! Provides create, put, search, stats, and timeline operations.
+//! Provides create, put, search, stats, timeline, verify, and diff operations.
 
 use clap::{Parser, Subcommand};
-use memvid_core::{Memvid, PutOptions, Result, SearchRequest, TimelineQuery};
-use std::fs;
+use memvid_core::{
+    Memvid, PutOptions, Result, SearchRequest, TimelineQuery, VerifyOptions,
+    parse_ed25519_public_key_base64,
+};
+use std::fs::File;
 use std::path::PathBuf;
 
 #[derive(Parser)]
@@ -81,9 +84,36 @@ enum Commands {
         /// Perform deep verification
         #[arg(long)]
         deep: bool,
+        /// Base64-encoded Ed25519 public key allowed to sign commits (repeatable)
+        #[arg(long = "trusted-key", value_name = "KEY", value_parser = parse_public_key)]
+        trusted_keys: Vec<[u8; 32]>,
+    },
+
+    /// Compare two memory files, or two generations of one file
+    Diff {
+        /// Path to the old .mv2 file
+        path: PathBuf,
+        /// Path to the new .mv2 file
+        #[arg(required_unless_present = "from_generation")]
+        other: Option<PathBuf>,
+        /// Generation to use as the old side
+        #[arg(long, requires = "to_generation", conflicts_with = "other")]
+        from_generation: Option<u64>,
+        /// Generation to use as the new side
+        #[arg(long, requires = "from_generation")]
+        to_generation: Option<u64>,
+        /// Print a human-readable summary instead of JSON
+        #[arg(long)]
+        summary: bool,
     },
 }
 
+fn parse_public_key(value: &str) -> std::result::Result<[u8; 32], String> {
+    parse_ed25519_public_key_base64(value)
+        .map(|key| key.to_bytes())
+        .map_err(|err| err.to_string())
+}
+
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
@@ -115,7 +145,8 @@ fn main() -> Result<()> {
 
         Commands::Ingest { path, file, title } => {
             let mut mem = Memvid::open(&path)?;
-            let content = fs::read(&file)?;
+            let content = File::open(&file)?;
+            let len = content.metadata()?.len();
             let file_title = title.unwrap_or_else(|| {
                 file.file_name()
                     .map(|s| s.to_string_lossy().to_string())
@@ -125,7 +156,7 @@ fn main() -> Result<()> {
                 .title(&file_title)
                 .uri(&format!("file://{}", file.display()))
                 .build();
-            let seq = mem.put_bytes_with_options(&content, options)?;
+            let seq = mem.put_reader(content, Some(len), options)?;
             mem.commit()?;
             println!(
                 "{{\"status\": \"ok\", \"sequence\": {}, \"title\": \"{}\"}}",
@@ -205,8 +236,13 @@ fn main() -> Result<()> {
             println!("{}", serde_json::json!({ "entries": items }));
         }
 
-        Commands::Verify { path, deep } => {
-            let report = Memvid::verify(&path, deep)?;
+        Commands::Verify {
+            path,
+            deep,
+            trusted_keys,
+        } => {
+            let options = VerifyOptions { deep, trusted_keys };
+            let report = Memvid::verify_with_options(&path, &options)?;
             println!(
                 "{}",
                 serde_json::json!({
@@ -215,6 +251,29 @@ fn main() -> Result<()> {
                 })
             );
         }
+
+        Commands::Diff {
+            path,
+            other,
+            from_generation,
+            to_generation,
+            summary,
+        } => {
+            let diff = match (from_generation, to_generation) {
+                (Some(from), Some(to)) => Memvid::diff_generations(&path, from, to)?,
+                _ => Memvid::diff_files(&path, other.as_deref().unwrap_or(&path))?,
+            };
+            if summary {
+                println!("{}", diff.summary());
+            } else {
+                let mut json = serde_json::to_value(&diff).map_err(std::io::Error::from)?;
+                json["summary"] = serde_json::Value::String(diff.summary());
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&json).map_err(std::io::Error::from)?
+                );
+            }
+        }
     }
 
     Ok(())
@@ -0,0 +1,770 @@
+//! Portable export and import of whole memories.
+//!
+//! An archive is a plain directory (or a tar of one) that standard tools can inspect:
+//!
+//! - `manifest.json`: archive version and index settings
+//! - `frames.jsonl`: one [`Frame`] per line, in frame id order, with the name of its blob
+//! - `blobs/<blake3>`: decoded canonical payloads, named by the BLAKE3 hash of their bytes
+//! - `dictionaries/<id>.zdict`: trained zstd dictionaries, named by dictionary id
+//! - `embeddings.jsonl`, `clip.jsonl`: vectors keyed by frame id
+//! - `sketches.jsonl`: sketch entries keyed by frame id
+//! - `cards.jsonl`, `enrichment.json`: the memories track
+//! - `mesh.json`: the Logic-Mesh
+//! - `replay.jsonl`: recorded replay sessions
+//!
+//! Frame offsets are file-specific and are reassigned on import; everything else, including
+//! frame ids, statuses and supersede links, is restored as exported. Payloads are re-encoded
+//! on import with the encoding (and dictionary) they were exported from. Derived indexes (time,
+//! lexical, vector graph) are rebuilt rather than archived.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, BufWriter, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::clip::ClipIndex;
+use crate::error::{MemvidError, Result};
+use crate::io::region::RegionKind;
+use crate::memvid::compression::{encode_canonical_bytes, payload_dictionary_id};
+use crate::memvid::lifecycle::Memvid;
+use crate::types::{
+    ArchiveReport, CanonicalEncoding, DistanceMetric, EmbeddingIdentity, EnrichmentManifest, Frame,
+    FrameId, FrameStatus, LogicMesh, MemoriesTrack, MemoryCard, SketchEntry, SketchTrack,
+    SketchVariant,
+};
+
+/// Identifies a memvid archive in `manifest.json`.
+const ARCHIVE_FORMAT: &str = "memvid-archive";
+/// Newest archive layout this build can read.
+const ARCHIVE_VERSION: u32 = 2;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ArchiveManifest {
+    format: String,
+    version: u32,
+    memvid_version: String,
+    frame_count: u64,
+    vec_enabled: bool,
+    vec_metric: DistanceMetric,
+    clip_enabled: bool,
+    clip_metric: DistanceMetric,
+    #[serde(default)]
+    sketch_variant: Option<SketchVariant>,
+    #[serde(default)]
+    track_encodings: BTreeMap<String, CanonicalEncoding>,
+    #[serde(default)]
+    dictionaries: Vec<ArchivedDictionary>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ArchivedDictionary {
+    dictionary_id: u32,
+    track: Option<String>,
+    sample_count: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ArchivedFrame {
+    frame: Frame,
+    /// Name of the payload blob under `blobs/`, if the frame has a payload.
+    blob: Option<String>,
+    /// Dictionary a `ZstdDict` payload was compressed with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    dictionary_id: Option<u32>,
+}
+
+fn dictionary_file(root: &Path, dictionary_id: u32) -> PathBuf {
+    root.join("dictionaries")
+        .join(format!("{dictionary_id}.zdict"))
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ArchivedEmbedding {
+    frame_id: FrameId,
+    embedding: Vec<f32>,
+    identity: Option<EmbeddingIdentity>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ArchivedClipEntry {
+    frame_id: FrameId,
+    page: Option<u32>,
+    embedding: Vec<f32>,
+}
+
+fn invalid(reason: impl Into<String>) -> MemvidError {
+    MemvidError::InvalidArchive {
+        reason: reason.into().into(),
+    }
+}
+
+fn is_tar(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("tar"))
+}
+
+/// Line-oriented JSON writer for one archive file.
+struct JsonLines {
+    out: BufWriter<File>,
+    lines: u64,
+}
+
+impl JsonLines {
+    fn create(path: &Path) -> Result<Self> {
+        Ok(Self {
+            out: BufWriter::new(File::create(path)?),
+            lines: 0,
+        })
+    }
+
+    fn push<T: Serialize>(&mut self, value: &T) -> Result<()> {
+        serde_json::to_writer(&mut self.out, value)
+            .map_err(|err| invalid(format!("failed to encode archive entry: {err}")))?;
+        self.out.write_all(b"\n")?;
+        self.lines += 1;
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<u64> {
+        self.out.flush()?;
+        Ok(self.lines)
+    }
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    serde_json::to_writer_pretty(&mut out, value)
+        .map_err(|err| invalid(format!("failed to encode {}: {err}", path.display())))?;
+    out.flush()?;
+    Ok(())
+}
+
+fn read_json<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    let file = File::open(path)
+        .map_err(|err| invalid(format!("cannot open {}: {err}", path.display())))?;
+    serde_json::from_reader(BufReader::new(file))
+        .map_err(|err| invalid(format!("failed to decode {}: {err}", path.display())))
+}
+
+/// Visit every line of an optional JSONL file; a missing file has no entries.
+fn read_json_lines<T, F>(path: &Path, mut visit: F) -> Result<()>
+where
+    T: DeserializeOwned,
+    F: FnMut(T) -> Result<()>,
+{
+    if !path.exists() {
+        return Ok(());
+    }
+    let reader = BufReader::new(File::open(path)?);
+    for (line_no, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let value = serde_json::from_str(&line).map_err(|err| {
+            invalid(format!(
+                "{} line {}: {err}",
+                path.file_name().unwrap_or_default().to_string_lossy(),
+                line_no + 1
+            ))
+        })?;
+        visit(value)?;
+    }
+    Ok(())
+}
+
+impl Memvid {
+    /// Export the committed state of this memory as a portable archive.
+    ///
+    /// `dest` must not exist yet. A `.tar` extension produces a single tar file;
+    /// anything else is created as a directory. Pending WAL records are not included,
+    /// so commit first to export them.
+    pub fn export_archive(&mut self, dest: &Path) -> Result<ArchiveReport> {
+        if dest.exists() {
+            return Err(invalid(format!("{} already exists", dest.display())));
+        }
+        if !is_tar(dest) {
+            fs::create_dir_all(dest)?;
+            return self.write_archive_dir(dest);
+        }
+
+        let parent = dest
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .unwrap_or_else(|| Path::new("."));
+        let staging = tempfile::tempdir_in(parent)?;
+        let report = self.write_archive_dir(staging.path())?;
+        let mut tar_file = tempfile::NamedTempFile::new_in(parent)?;
+        {
+            let mut builder = tar::Builder::new(tar_file.as_file_mut());
+            builder.append_dir_all(".", staging.path())?;
+            builder.finish()?;
+        }
+        tar_file.as_file().sync_all()?;
+        tar_file
+            .persist(dest)
+            .map_err(|err| MemvidError::from(err.error))?;
+        Ok(report)
+    }
+
+    fn write_archive_dir(&mut self, root: &Path) -> Result<ArchiveReport> {
+        let mut report = ArchiveReport::default();
+        self.toc.frames.load_all()?;
+        self.ensure_vec_index()?;
+        self.ensure_clip_index()?;
+        #[cfg(feature = "replay")]
+        if self.completed_sessions.is_empty() {
+            self.load_replay_sessions()?;
+        }
+
+        write_json(
+            &root.join("manifest.json"),
+            &ArchiveManifest {
+                format: ARCHIVE_FORMAT.to_string(),
+                version: ARCHIVE_VERSION,
+                memvid_version: env!("CARGO_PKG_VERSION").to_string(),
+                frame_count: self.toc.frames.len() as u64,
+                vec_enabled: self.vec_enabled,
+                vec_metric: self.vec_metric(),
+                clip_enabled: self.clip_enabled,
+                clip_metric: self.clip_metric(),
+                sketch_variant: (!self.sketch_track.is_empty())
+                    .then_some(self.sketch_track.variant),
+                track_encodings: self.toc.compression.track_encodings.clone(),
+                dictionaries: self
+                    .toc
+                    .compression
+                    .dictionaries
+                    .iter()
+                    .map(|dictionary| ArchivedDictionary {
+                        dictionary_id: dictionary.dictionary_id,
+                        track: dictionary.track.clone(),
+                        sample_count: dictionary.sample_count,
+                    })
+                    .collect(),
+            },
+        )?;
+
+        let dictionaries: Vec<u32> = self
+            .toc
+            .compression
+            .dictionaries
+            .iter()
+            .map(|dictionary| dictionary.dictionary_id)
+            .collect();
+        if !dictionaries.is_empty() {
+            fs::create_dir_all(root.join("dictionaries"))?;
+        }
+        for dictionary_id in dictionaries {
+            let bytes = self.compression_dictionary(dictionary_id)?;
+            fs::write(dictionary_file(root, dictionary_id), bytes.as_slice())?;
+        }
+
+        let blobs = root.join("blobs");
+        fs::create_dir_all(&blobs)?;
+        let mut frames = JsonLines::create(&root.join("frames.jsonl"))?;
+        for index in 0..self.toc.frames.len() {
+            let Some(frame) = self.toc.frames.try_get(index)?.cloned() else {
+                continue;
+            };
+            let mut dictionary_id = None;
+            let blob = if frame.payload_length > 0 {
+                let raw = self.read_frame_payload_bytes(&frame)?;
+                if frame.canonical_encoding == CanonicalEncoding::ZstdDict {
+                    dictionary_id = payload_dictionary_id(&raw);
+                }
+                let bytes = self.decode_canonical(&raw, frame.canonical_encoding, frame.id)?;
+                let name = blake3::hash(&bytes).to_hex().to_string();
+                let path = blobs.join(&name);
+                if !path.exists() {
+                    fs::write(&path, &bytes)?;
+                    report.blobs += 1;
+                    report.blob_bytes += bytes.len() as u64;
+                }
+                Some(name)
+            } else {
+                None
+            };
+            frames.push(&ArchivedFrame {
+                frame,
+                blob,
+                dictionary_id,
+            })?;
+        }
+        report.frames = frames.finish()?;
+
+        let mut embeddings = JsonLines::create(&root.join("embeddings.jsonl"))?;
+        if let Some(index) = self.vec_index.as_ref() {
+            for (frame_id, embedding) in index.entries() {
+                let identity = self
+                    .toc
+                    .frames
+                    .try_get(frame_id as usize)?
+                    .and_then(|frame| {
+                        EmbeddingIdentity::from_extra_metadata(&frame.extra_metadata)
+                    });
+                embeddings.push(&ArchivedEmbedding {
+                    frame_id,
+                    embedding: embedding.to_vec(),
+                    identity,
+                })?;
+            }
+        }
+        report.embeddings = embeddings.finish()?;
+
+        let mut clip = JsonLines::create(&root.join("clip.jsonl"))?;
+        if let Some(index) = self.clip_index.as_ref() {
+            for (frame_id, page, embedding) in index.entries() {
+                clip.push(&ArchivedClipEntry {
+                    frame_id,
+                    page,
+                    embedding: embedding.to_vec(),
+                })?;
+            }
+        }
+        report.clip_entries = clip.finish()?;
+
+        let mut sketches = JsonLines::create(&root.join("sketches.jsonl"))?;
+        for entry in self.sketch_track.iter() {
+            sketches.push(entry)?;
+        }
+        sketches.finish()?;
+
+        let mut cards = JsonLines::create(&root.join("cards.jsonl"))?;
+        for card in self.memories_track.cards() {
+            cards.push(card)?;
+        }
+        report.cards = cards.finish()?;
+        write_json(
+            &root.join("enrichment.json"),
+            self.memories_track.enrichment_manifest(),
+        )?;
+
+        write_json(&root.join("mesh.json"), self.logic_mesh.as_ref())?;
+        report.mesh_nodes = self.logic_mesh.nodes.len() as u64;
+
+        #[cfg(feature = "replay")]
+        {
+            let mut replay = JsonLines::create(&root.join("replay.jsonl"))?;
+            for session in &self.completed_sessions {
+                replay.push(session)?;
+            }
+            report.replay_sessions = replay.finish()?;
+        }
+
+        tracing::info!(
+            frames = report.frames,
+            blobs = report.blobs,
+            cards = report.cards,
+            "archive exported"
+        );
+        Ok(report)
+    }
+
+    /// Create a new memory at `path` from an archive written by [`Memvid::export_archive`].
+    ///
+    /// `archive` may be an archive directory or a tar file. Frames keep their ids, and
+    /// every blob is checked against its content address before it is stored.
+    pub fn import_archive(archive: &Path, path: &Path) -> Result<(Self, ArchiveReport)> {
+        let unpacked;
+        let root: PathBuf = if archive.is_dir() {
+            archive.to_path_buf()
+        } else {
+            unpacked = tempfile::tempdir()?;
+            tar::Archive::new(File::open(archive)?).unpack(unpacked.path())?;
+            unpacked.path().to_path_buf()
+        };
+
+        let manifest: ArchiveManifest = read_json(&root.join("manifest.json"))?;
+        if manifest.format != ARCHIVE_FORMAT {
+            return Err(invalid(format!("unknown format '{}'", manifest.format)));
+        }
+        if manifest.version > ARCHIVE_VERSION {
+            return Err(invalid(format!(
+                "archive version {} is newer than supported version {ARCHIVE_VERSION}",
+                manifest.version
+            )));
+        }
+
+        let mut mem = Memvid::create(path)?;
+        mem.toc.compression.track_encodings = manifest.track_encodings.clone();
+        if manifest.vec_enabled {
+            mem.enable_vec_with_metric(manifest.vec_metric)?;
+        }
+        if manifest.clip_enabled {
+            mem.enable_clip_with_metric(manifest.clip_metric)?;
+        }
+
+        let mut report = ArchiveReport::default();
+        mem.with_staging(None, |mem| {
+            mem.begin_generation()?;
+            mem.import_archive_frames(&root, &manifest.dictionaries, &mut report)?;
+            if report.frames != manifest.frame_count {
+                return Err(invalid(format!(
+                    "manifest lists {} frames but frames.jsonl has {}",
+                    manifest.frame_count, report.frames
+                )));
+            }
+
+            let frame_count = report.frames;
+            let check_frame = |frame_id: FrameId, file: &str| {
+                if frame_id < frame_count {
+                    Ok(())
+                } else {
+                    Err(invalid(format!(
+                        "{file} references unknown frame {frame_id}"
+                    )))
+                }
+            };
+
+            let mut vec_docs = Vec::new();
+            read_json_lines(
+                &root.join("embeddings.jsonl"),
+                |entry: ArchivedEmbedding| {
+                    check_frame(entry.frame_id, "embeddings.jsonl")?;
+                    vec_docs.push((entry.frame_id, entry.embedding));
+                    Ok(())
+                },
+            )?;
+            if !vec_docs.is_empty() && !mem.vec_enabled {
+                mem.enable_vec()?;
+            }
+            report.embeddings = vec_docs.len() as u64;
+
+            let mut clip_index = ClipIndex::new();
+            read_json_lines(&root.join("clip.jsonl"), |entry: ArchivedClipEntry| {
+                check_frame(entry.frame_id, "clip.jsonl")?;
+                clip_index.add_document(entry.frame_id, entry.page, entry.embedding);
+                Ok(())
+            })?;
+            report.clip_entries = clip_index.len() as u64;
+            if !clip_index.is_empty() {
+                mem.clip_enabled = true;
+                mem.clip_index = Some(Arc::new(clip_index));
+            }
+
+            let mut cards: Vec<MemoryCard> = Vec::new();
+            read_json_lines(&root.join("cards.jsonl"), |card: MemoryCard| {
+                check_frame(card.source_frame_id, "cards.jsonl")?;
+                cards.push(card);
+                Ok(())
+            })?;
+            report.cards = cards.len() as u64;
+            let enrichment_path = root.join("enrichment.json");
+            let enrichment = if enrichment_path.exists() {
+                read_json(&enrichment_path)?
+            } else {
+                EnrichmentManifest::default()
+            };
+            mem.memories_track = Arc::new(MemoriesTrack::from_cards(cards, enrichment));
+
+            let mesh_path = root.join("mesh.json");
+            if mesh_path.exists() {
+                let mut mesh: LogicMesh = read_json(&mesh_path)?;
+                mesh.finalize();
+                report.mesh_nodes = mesh.nodes.len() as u64;
+                mem.logic_mesh = Arc::new(mesh);
+            }
+
+            mem.rebuild_indexes(&vec_docs)?;
+
+            if let Some(variant) = manifest.sketch_variant {
+                let mut track = SketchTrack::new(variant);
+                read_json_lines(&root.join("sketches.jsonl"), |entry: SketchEntry| {
+                    check_frame(entry.frame_id, "sketches.jsonl")?;
+                    track.insert(entry);
+                    Ok(())
+                })?;
+                mem.sketch_track = Arc::new(track);
+                mem.persist_sketch_track()?;
+            }
+            mem.import_replay_sessions(&root, &mut report)?;
+
+            mem.rewrite_toc_footer()?;
+            mem.header.toc_checksum = mem.toc.toc_checksum;
+            crate::persist_header(&mut mem.file, &mem.header)?;
+            mem.file.sync_all()?;
+            Ok(())
+        })?;
+        mem.dirty = false;
+
+        tracing::info!(
+            frames = report.frames,
+            blobs = report.blobs,
+            cards = report.cards,
+            "archive imported"
+        );
+        Ok((mem, report))
+    }
+
+    /// Append the archived dictionaries, then the frames of `frames.jsonl` and their
+    /// payloads, after `data_end`.
+    fn import_archive_frames(
+        &mut self,
+        root: &Path,
+        dictionaries: &[ArchivedDictionary],
+        report: &mut ArchiveReport,
+    ) -> Result<()> {
+        let blobs = root.join("blobs");
+        let mut cursor = self.data_end;
+        for dictionary in dictionaries {
+            let path = dictionary_file(root, dictionary.dictionary_id);
+            let bytes =
+                fs::read(&path).map_err(|err| invalid(format!("{}: {err}", path.display())))?;
+            let stored = self.append_dictionary(
+                cursor,
+                bytes,
+                dictionary.track.clone(),
+                dictionary.sample_count,
+            )?;
+            if stored.dictionary_id != dictionary.dictionary_id {
+                return Err(invalid(format!(
+                    "dictionary {} has id {}",
+                    dictionary.dictionary_id, stored.dictionary_id
+                )));
+            }
+            cursor += stored.bytes_length;
+        }
+        // Blobs shared between frames are stored once per encoding and dictionary.
+        let mut stored: HashMap<(String, u8, Option<u32>), (u64, u64)> = HashMap::new();
+
+        self.file.seek(SeekFrom::Start(cursor))?;
+        read_json_lines(&root.join("frames.jsonl"), |entry: ArchivedFrame| {
+            let ArchivedFrame {
+                mut frame,
+                blob,
+                dictionary_id,
+            } = entry;
+            if frame.id != self.toc.frames.len() as FrameId {
+                return Err(invalid(format!(
+                    "frame {} is out of order in frames.jsonl",
+                    frame.id
+                )));
+            }
+
+            match blob {
+                None => {
+                    frame.payload_offset = 0;
+                    frame.payload_length = 0;
+                }
+                Some(name) => {
+                    let key = (name, frame.canonical_encoding.as_byte(), dictionary_id);
+                    if let Some(&(offset, length)) = stored.get(&key) {
+                        frame.payload_offset = offset;
+                        frame.payload_length = length;
+                    } else {
+                        let bytes = fs::read(blobs.join(&key.0))
+                            .map_err(|err| invalid(format!("blob {}: {err}", key.0)))?;
+                        if blake3::hash(&bytes).to_hex().as_str() != key.0 {
+                            return Err(invalid(format!("blob {} does not match its hash", key.0)));
+                        }
+                        report.blobs += 1;
+                        report.blob_bytes += bytes.len() as u64;
+                        let encoded = if frame.canonical_encoding == CanonicalEncoding::Chunked {
+                            let (recipe, written) = self.append_blob_chunks(cursor, &bytes)?;
+                            cursor += written;
+                            recipe
+                        } else {
+                            let dictionary = match dictionary_id {
+                                Some(dictionary_id) => {
+                                    Some(self.compression_dictionary(dictionary_id)?)
+                                }
+                                None => None,
+                            };
+                            encode_canonical_bytes(
+                                &bytes,
+                                frame.canonical_encoding,
+                                dictionary.as_deref().map(Vec::as_slice),
+                            )?
+                        };
+                        let length = self.write_region(cursor, RegionKind::Payload, &encoded)?;
+                        stored.insert(key, (cursor, length));
+                        frame.payload_offset = cursor;
+                        frame.payload_length = length;
+                        cursor += length;
+                    }
+                }
+            }
+            if frame.status == FrameStatus::Active {
+                self.log_frame_insert(&frame);
+                self.note_quota_frame(frame.track.as_deref(), frame.payload_length, true);
+            }
+            self.toc.frames.push(frame);
+            report.frames += 1;
+            Ok(())
+        })?;
+
+        self.data_end = cursor;
+        self.header.footer_offset = self.header.footer_offset.max(cursor);
+        Ok(())
+    }
+
+    #[cfg(feature = "replay")]
+    fn import_replay_sessions(&mut self, root: &Path, report: &mut ArchiveReport) -> Result<()> {
+        read_json_lines(
+            &root.join("replay.jsonl"),
+            |session: crate::replay::ReplaySession| {
+                self.completed_sessions.push(session);
+                Ok(())
+            },
+        )?;
+        report.replay_sessions = self.completed_sessions.len() as u64;
+        self.save_replay_sessions()
+    }
+
+    #[cfg(not(feature = "replay"))]
+    fn import_replay_sessions(&mut self, root: &Path, _report: &mut ArchiveReport) -> Result<()> {
+        let path = root.join("replay.jsonl");
+        if fs::metadata(&path).is_ok_and(|meta| meta.len() > 0) {
+            tracing::warn!(
+                "archive contains replay sessions; enable the `replay` feature to import them"
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{MemoryCardBuilder, SearchRequest};
+    use crate::{PutOptions, fast_options, run_serial_test};
+    use tempfile::tempdir;
+
+    /// Frame contents that must survive a round trip; offsets are file-specific.
+    fn portable_frames(mem: &Memvid) -> Vec<serde_json::Value> {
+        (0..mem.frame_count() as FrameId)
+            .map(|id| {
+                let mut frame = mem.frame_by_id(id).expect("frame");
+                frame.payload_offset = 0;
+                serde_json::to_value(frame).expect("json")
+            })
+            .collect()
+    }
+
+    fn search_hits(mem: &mut Memvid, query: &str) -> Vec<(FrameId, String)> {
+        mem.search(SearchRequest::test_query(query))
+            .expect("search")
+            .hits
+            .into_iter()
+            .map(|hit| (hit.frame_id, hit.text))
+            .collect()
+    }
+
+    #[test]
+    fn archive_round_trip_preserves_frames_search_and_cards() {
+        run_serial_test(|| {
+            let dir = tempdir().expect("tmp");
+            let source_path = dir.path().join("source.mv2");
+            let mut source = Memvid::create(&source_path).expect("create");
+            source
+                .put_bytes_with_options(
+                    b"harbour crane inspection report",
+                    PutOptions::builder()
+                        .auto_tag(false)
+                        .extract_dates(false)
+                        .extract_triplets(false)
+                        .instant_index(false)
+                        .uri("mv2://docs/crane")
+                        .title("Crane inspection")
+                        .tag("team", "ops")
+                        .push_tag("inspection")
+                        .build(),
+                )
+                .expect("put");
+            source
+                .put_bytes_with_options(b"lighthouse maintenance schedule", fast_options())
+                .expect("put");
+            source
+                .put_bytes_with_options(b"obsolete ferry timetable", fast_options())
+                .expect("put");
+            source.commit().expect("commit");
+            source
+                .update_frame(
+                    1,
+                    Some(b"lighthouse maintenance schedule, revised weekly".to_vec()),
+                    fast_options(),
+                    None,
+                )
+                .expect("update");
+            source.delete_frame(2).expect("delete");
+            source
+                .put_memory_card(
+                    MemoryCardBuilder::new()
+                        .fact()
+                        .entity("lighthouse")
+                        .slot("schedule")
+                        .value("weekly")
+                        .source(3, None)
+                        .engine("rules-v1", "1.0.0")
+                        .build(0)
+                        .expect("card"),
+                )
+                .expect("card");
+            source.commit().expect("commit");
+
+            let expected_frames = portable_frames(&source);
+            let expected_hits = search_hits(&mut source, "lighthouse");
+            assert!(!expected_hits.is_empty());
+
+            for name in ["export", "export.tar"] {
+                let archive = dir.path().join(name);
+                let exported = source.export_archive(&archive).expect("export");
+                assert_eq!(exported.frames, 4);
+                assert_eq!(exported.cards, 1);
+                assert!(source.export_archive(&archive).is_err());
+
+                let restored_path = dir.path().join(format!("{name}.mv2"));
+                let (restored, imported) =
+                    Memvid::import_archive(&archive, &restored_path).expect("import");
+                assert_eq!(imported.frames, exported.frames);
+                assert_eq!(imported.blobs, exported.blobs);
+                drop(restored);
+
+                let mut restored = Memvid::open(&restored_path).expect("reopen");
+                assert_eq!(portable_frames(&restored), expected_frames);
+                assert_eq!(search_hits(&mut restored, "lighthouse"), expected_hits);
+                assert_eq!(
+                    restored.frame_canonical_payload(0).expect("payload"),
+                    b"harbour crane inspection report"
+                );
+                let card = restored
+                    .get_current_memory("lighthouse", "schedule")
+                    .expect("card");
+                assert_eq!(card.value, "weekly");
+                assert_eq!(card.source_frame_id, 3);
+            }
+        });
+    }
+
+    #[test]
+    fn import_rejects_tampered_blob() {
+        run_serial_test(|| {
+            let dir = tempdir().expect("tmp");
+            let mut mem = Memvid::create(dir.path().join("source.mv2")).expect("create");
+            mem.put_bytes_with_options(b"tamper evident payload", fast_options())
+                .expect("put");
+            mem.commit().expect("commit");
+
+            let archive = dir.path().join("export");
+            mem.export_archive(&archive).expect("export");
+            let blob = fs::read_dir(archive.join("blobs"))
+                .expect("blobs")
+                .next()
+                .expect("blob")
+                .expect("entry")
+                .path();
+            fs::write(&blob, b"tampered").expect("write");
+
+            let Err(err) = Memvid::import_archive(&archive, &dir.path().join("restored.mv2"))
+            else {
+                panic!("tampered blob was accepted");
+            };
+            assert!(matches!(err, MemvidError::InvalidArchive { .. }));
+        });
+    }
+}
@@ -22,6 +22,7 @@ impl Memvid {
     where
         E: VecEmbedder + ?Sized,
     {
+        self.auto_refresh()?;
         if !self.lex_enabled {
             return Err(MemvidError::LexNotEnabled);
         }
@@ -114,7 +115,7 @@ impl Memvid {
         }
 
         tracing::debug!("ask search query: {}", search_request.query);
-        let mut retrieval = self.search(search_request.clone())?;
+        let mut retrieval = self.search_without_refresh(search_request.clone())?;
         self.filter_hits_in_time_range(
             &mut retrieval.hits,
             request.start,
@@ -130,7 +131,7 @@ impl Memvid {
                     if or_query != search_request.query {
                         let mut or_request = search_request.clone();
                         or_request.query = or_query.clone();
-                        let mut or_response = self.search(or_request)?;
+                        let mut or_response = self.search_without_refresh(or_request)?;
                         self.filter_hits_in_time_range(
                             &mut or_response.hits,
                             request.start,
@@ -150,7 +151,8 @@ impl Memvid {
                     if fallback_query != search_request.query {
                         let mut fallback_request = search_request.clone();
                         fallback_request.query = fallback_query.clone();
-                        let mut fallback_response = self.search(fallback_request)?;
+                        let mut fallback_response =
+                            self.search_without_refresh(fallback_request)?;
                         self.filter_hits_in_time_range(
                             &mut fallback_response.hits,
                             request.start,
@@ -172,7 +174,8 @@ impl Memvid {
                     if expanded_query != search_request.query {
                         let mut expanded_request = search_request.clone();
                         expanded_request.query = expanded_query.clone();
-                        let mut expanded_response = self.search(expanded_request)?;
+                        let mut expanded_response =
+                            self.search_without_refresh(expanded_request)?;
                         self.filter_hits_in_time_range(
                             &mut expanded_response.hits,
                             request.start,
@@ -243,7 +246,7 @@ impl Memvid {
                 if or_query != search_request.query {
                     let mut or_request = search_request.clone();
                     or_request.query = or_query.clone();
-                    let mut or_response = self.search(or_request)?;
+                    let mut or_response = self.search_without_refresh(or_request)?;
                     self.filter_hits_in_time_range(
                         &mut or_response.hits,
                         request.start,
@@ -281,7 +284,7 @@ impl Memvid {
             let mut correction_request = search_request.clone();
             correction_request.query = correction_query;
             correction_request.top_k = 10; // Limit to 10 corrections
-            if let Ok(correction_response) = self.search(correction_request) {
+            if let Ok(correction_response) = self.search_without_refresh(correction_request) {
                 if !correction_response.hits.is_empty() {
                     tracing::debug!(
                         "found {} potential corrections for question",
@@ -512,11 +515,13 @@ impl Memvid {
             });
         }
 
+        // Score with the metric the index was built for, as vector search does.
+        let metric = self.vec_metric();
         let mut semantic_scores: HashMap<u64, f32> = HashMap::new();
         for hit in hits.iter() {
             if let Some(embedding) = self.frame_embedding(hit.frame_id)? {
                 if expected_dimension == 0 || embedding.len() == expected_dimension {
-                    let score = cosine_similarity(&query_embedding, &embedding);
+                    let score = metric.similarity(metric.distance(query_embedding, &embedding));
                     semantic_scores.insert(hit.frame_id, score);
                 }
             }
@@ -793,23 +798,6 @@ fn synthesize_answer(
     Some(segments.join(" "))
 }
 
-fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
-    let mut dot = 0.0f32;
-    let mut sum_a = 0.0f32;
-    let mut sum_b = 0.0f32;
-    for (x, y) in a.iter().zip(b.iter()) {
-        dot += x * y;
-        sum_a += x * x;
-        sum_b += y * y;
-    }
-
-    if sum_a <= f32::EPSILON || sum_b <= f32::EPSILON {
-        0.0
-    } else {
-        dot / (sum_a.sqrt() * sum_b.sqrt())
-    }
-}
-
 fn lexical_fallback_query(question: &str) -> Option<String> {
     let sanitized_full = sanitize_question_for_lexical(question);
     if sanitized_full.is_empty() {
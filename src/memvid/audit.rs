@@ -79,6 +79,18 @@ impl Memvid {
         let mut sources: Vec<SourceSpan> = Vec::new();
         let mut notes: Vec<String> = Vec::new();
 
+        let leaves = if opts.include_proofs {
+            match self.merkle_leaves() {
+                Ok(leaves) => Some(leaves),
+                Err(err) => {
+                    notes.push(format!("Merkle proofs unavailable: {err}"));
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
         for (idx, citation) in response.citations.iter().enumerate() {
             // Get the corresponding hit for additional context
             let hit = response
@@ -117,6 +129,9 @@ impl Memvid {
                     .map(|f| f.content_dates.clone())
                     .unwrap_or_default(),
                 snippet,
+                proof: leaves.as_ref().and_then(|leaves| {
+                    self.frame_proof_from_leaves(leaves, citation.frame_id).ok()
+                }),
             };
 
             sources.push(source);
@@ -143,6 +158,7 @@ impl Memvid {
             mode: response.mode,
             retriever: response.retriever,
             sources,
+            merkle_root: leaves.map(|_| self.toc.merkle_root),
             total_hits: response.retrieval.total_hits,
             stats: response.stats,
             notes,
@@ -196,6 +212,7 @@ impl Memvid {
                     .map(|f| f.content_dates.clone())
                     .unwrap_or_default(),
                 snippet,
+                proof: None,
             };
 
             sources.push(source);
@@ -323,4 +340,48 @@ mod tests {
             }
         });
     }
+
+    #[test]
+    #[cfg(feature = "lex")]
+    fn test_audit_with_proofs() {
+        run_serial_test(|| {
+            let dir = tempdir().expect("tmp");
+            let path = dir.path().join("audit_proofs.mv2");
+
+            let mut mem = Memvid::create(&path).expect("create");
+            mem.enable_lex().expect("enable lex");
+            let passage = "Memvid stores every frame inside a single portable file.";
+            mem.put_bytes(b"An unrelated note about harbour tides.")
+                .expect("put");
+            mem.put_bytes(passage.as_bytes()).expect("put");
+            mem.put_bytes(b"Another note about lighthouse keepers.")
+                .expect("put");
+            mem.commit().expect("commit");
+
+            let opts = AuditOptions {
+                include_proofs: true,
+                ..Default::default()
+            };
+            let report = mem
+                .audit::<dyn VecEmbedder>("portable file", Some(opts), None)
+                .expect("audit");
+
+            let root = report.merkle_root.expect("merkle root");
+            let source = report
+                .sources
+                .iter()
+                .find(|source| source.frame_id == 1)
+                .expect("cited frame");
+            let proof = source.proof.as_ref().expect("proof");
+            assert!(crate::verify_frame_proof(&root, proof, passage.as_bytes()));
+            assert!(!crate::verify_frame_proof(
+                &root,
+                proof,
+                b"Memvid stores every frame inside a single forged file."
+            ));
+
+            let standalone = mem.frame_proof(1).expect("frame proof");
+            assert_eq!(&standalone, proof);
+        });
+    }
 }
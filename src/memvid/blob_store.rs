@@ -0,0 +1,266 @@
+//! Content-addressed blob store for chunked raw payloads.
+//!
+//! `Chunked` puts carry their raw bytes through the WAL; commit splits them with `FastCDC`,
+//! appends every chunk the store does not hold yet to the data region and stores the
+//! frame's recipe as its payload. The chunk index is written as an index segment listed in
+//! the TOC whenever it changes. Vacuum drops chunks no active frame references.
+
+use std::collections::HashSet;
+
+use blake3::hash;
+
+use crate::cdc;
+use crate::error::{MemvidError, Result};
+use crate::io::read_at::read_exact_at;
+use crate::io::region::RegionKind;
+use crate::memvid::compression::encode_canonical_bytes;
+use crate::memvid::lifecycle::Memvid;
+use crate::types::blob_store::{decode_chunk_recipe, encode_chunk_recipe};
+use crate::types::{
+    BlobChunk, BlobStore, BlobStoreManifest, CanonicalEncoding, ChunkHash, FrameId, FrameStatus,
+};
+
+impl Memvid {
+    /// Split `payload` into chunks, write the ones not stored yet from `offset` on and
+    /// return the recipe with the number of bytes written.
+    pub(crate) fn append_blob_chunks(
+        &mut self,
+        offset: u64,
+        payload: &[u8],
+    ) -> Result<(Vec<u8>, u64)> {
+        let mut hashes = Vec::new();
+        let mut cursor = offset;
+        for chunk in cdc::chunks(payload) {
+            let (chunk_hash, written) = self.append_blob_chunk(cursor, chunk)?;
+            hashes.push(chunk_hash);
+            cursor += written;
+        }
+        Ok((encode_chunk_recipe(&hashes), cursor - offset))
+    }
+
+    /// Write `chunk` at `offset` unless the store already holds it, returning its hash and
+    /// the number of bytes written.
+    pub(crate) fn append_blob_chunk(
+        &mut self,
+        offset: u64,
+        chunk: &[u8],
+    ) -> Result<(ChunkHash, u64)> {
+        let chunk_hash = *hash(chunk).as_bytes();
+        if self.blob_store.contains(&chunk_hash) {
+            return Ok((chunk_hash, 0));
+        }
+        let compressed = encode_canonical_bytes(chunk, CanonicalEncoding::Zstd, None)?;
+        let (stored, encoding) = if compressed.len() < chunk.len() {
+            (compressed, CanonicalEncoding::Zstd)
+        } else {
+            (chunk.to_vec(), CanonicalEncoding::Plain)
+        };
+        let stored_length = self.write_region(offset, RegionKind::Payload, &stored)?;
+        self.blob_store.insert(BlobChunk {
+            hash: chunk_hash,
+            offset,
+            stored_length,
+            raw_length: chunk.len() as u64,
+            encoding,
+        });
+        Ok((chunk_hash, stored_length))
+    }
+
+    /// Reassemble the raw bytes described by `recipe`, verifying every chunk's hash.
+    pub(crate) fn read_blob_chunks(&mut self, recipe: &[u8], frame_id: FrameId) -> Result<Vec<u8>> {
+        let mut out = Vec::new();
+        for chunk_hash in decode_chunk_recipe(recipe)? {
+            let chunk = *self
+                .blob_store
+                .get(&chunk_hash)
+                .ok_or(MemvidError::InvalidFrame {
+                    frame_id,
+                    reason: "chunk missing from the blob store",
+                })?;
+            let stored = self.read_blob_chunk(&chunk)?;
+            let raw = crate::decode_canonical_bytes(&stored, chunk.encoding, frame_id)?;
+            if *hash(&raw).as_bytes() != chunk_hash {
+                return Err(MemvidError::InvalidFrame {
+                    frame_id,
+                    reason: "chunk does not match its hash",
+                });
+            }
+            out.extend_from_slice(&raw);
+        }
+        Ok(out)
+    }
+
+    /// Read the stored bytes of `chunk`.
+    pub(crate) fn read_blob_chunk(&mut self, chunk: &BlobChunk) -> Result<Vec<u8>> {
+        let file_len = self.file.metadata()?.len();
+        let end = chunk.offset.checked_add(chunk.stored_length);
+        if chunk.offset < self.header.wal_offset + self.header.wal_size
+            || end.is_none_or(|end| end > file_len)
+        {
+            return Err(MemvidError::InvalidBlobStore {
+                reason: "chunk outside data region".into(),
+            });
+        }
+        let mut buf = vec![0u8; chunk.stored_length as usize];
+        read_exact_at(&self.file, &mut buf, chunk.offset)?;
+        self.regions.open_vec(RegionKind::Payload, buf)
+    }
+
+    /// Hashes of every chunk referenced by an active `Chunked` frame.
+    pub(crate) fn referenced_blob_chunks(&mut self) -> Result<HashSet<ChunkHash>> {
+        let mut referenced = HashSet::new();
+        if self.blob_store.is_empty() {
+            return Ok(referenced);
+        }
+        for index in 0..self.toc.frames.len() {
+            let Some(frame) = self.toc.frames.try_get(index)?.cloned() else {
+                continue;
+            };
+            if frame.status == FrameStatus::Active
+                && frame.canonical_encoding == CanonicalEncoding::Chunked
+                && frame.payload_length != 0
+            {
+                let recipe = self.read_frame_payload_bytes(&frame)?;
+                referenced.extend(decode_chunk_recipe(&recipe)?);
+            }
+        }
+        Ok(referenced)
+    }
+
+    /// Load the chunk index listed in the TOC, verifying it against its checksum.
+    pub(crate) fn load_blob_store(&mut self) -> Result<()> {
+        let Some(manifest) = self.toc.blob_store.clone() else {
+            self.blob_store = BlobStore::default();
+            return Ok(());
+        };
+        let bytes = self.read_range(manifest.bytes_offset, manifest.bytes_length)?;
+        if *hash(&bytes).as_bytes() != manifest.checksum {
+            return Err(MemvidError::InvalidBlobStore {
+                reason: "chunk index checksum mismatch".into(),
+            });
+        }
+        self.blob_store = BlobStore::decode(&bytes)?;
+        Ok(())
+    }
+
+    /// Write the chunk index after `footer_offset` if it changed since it was last written.
+    pub(crate) fn persist_blob_store(&mut self) -> Result<()> {
+        if !self.blob_store.is_dirty() {
+            return Ok(());
+        }
+        if self.blob_store.is_empty() {
+            self.toc.blob_store = None;
+            self.blob_store.mark_clean();
+            return Ok(());
+        }
+
+        let encoded = self.blob_store.encode();
+        let offset = self.header.footer_offset;
+        let bytes_length = self.write_region(offset, RegionKind::Index, &encoded)?;
+        self.toc.blob_store = Some(BlobStoreManifest {
+            bytes_offset: offset,
+            bytes_length,
+            chunk_count: self.blob_store.len() as u64,
+            stored_bytes: self.blob_store.stored_bytes(),
+            raw_bytes: self.blob_store.raw_bytes(),
+            checksum: *hash(&encoded).as_bytes(),
+        });
+        self.header.footer_offset = offset + bytes_length;
+        self.blob_store.mark_clean();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::MergeOptions;
+    use crate::{PutOptions, run_serial_test};
+    use std::io::Read;
+    use tempfile::tempdir;
+
+    fn read_blob(mem: &mut Memvid, frame_id: FrameId) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        mem.blob_reader(frame_id)
+            .expect("blob reader")
+            .read_to_end(&mut bytes)
+            .expect("read blob");
+        bytes
+    }
+
+    fn put(mem: &mut Memvid, uri: &str, payload: &[u8]) -> FrameId {
+        let options = PutOptions::builder()
+            .uri(uri)
+            .auto_tag(false)
+            .extract_dates(false)
+            .extract_triplets(false)
+            .build();
+        mem.put_bytes_with_options(payload, options).expect("put");
+        mem.commit().expect("commit");
+        mem.frame_by_uri(uri).expect("frame").id
+    }
+
+    #[test]
+    fn revisions_share_chunks_and_vacuum_collects_unreferenced_ones() {
+        run_serial_test(|| {
+            let dir = tempdir().expect("tmp");
+            let path = dir.path().join("blobs.mv2");
+            let mut rng = fastrand::Rng::with_seed(18);
+            let mut first: Vec<u8> = (0..512 * 1024).map(|_| rng.u8(..)).collect();
+            first[0] = 0xFF; // never valid UTF-8
+            let mut second = first.clone();
+            second.splice(200_000..200_000, b"one more slide".iter().copied());
+
+            let mut mem = Memvid::create(&path).expect("create");
+            let first_id = put(&mut mem, "mv2://deck/v1", &first);
+            let chunks_after_first = mem.stats().expect("stats").blob_chunk_count;
+            let second_id = put(&mut mem, "mv2://deck/v2", &second);
+            let frame = mem.frame_by_id(second_id).expect("frame");
+            assert_eq!(frame.canonical_encoding, CanonicalEncoding::Chunked);
+            assert_eq!(frame.checksum, *hash(&second).as_bytes());
+
+            let stats = mem.stats().expect("stats");
+            assert!(stats.blob_chunk_count - chunks_after_first <= 3);
+            assert!(stats.dedup_ratio > 1.8, "ratio {}", stats.dedup_ratio);
+            drop(mem);
+
+            let mut mem = Memvid::open(&path).expect("reopen");
+            assert_eq!(read_blob(&mut mem, first_id), first);
+            assert_eq!(read_blob(&mut mem, second_id), second);
+
+            mem.delete_frame(first_id).expect("delete");
+            mem.commit().expect("commit");
+            let report = mem.vacuum().expect("vacuum");
+            assert!(report.blob_chunks_purged >= 1 && report.blob_chunks_purged <= 3);
+            assert_eq!(
+                report.blob_chunks_retained,
+                mem.stats().expect("stats").blob_chunk_count
+            );
+            drop(mem);
+
+            let mut mem = Memvid::open(&path).expect("reopen after vacuum");
+            assert_eq!(read_blob(&mut mem, second_id), second);
+            let stats = mem.stats().expect("stats");
+            assert!((stats.dedup_ratio - 1.0).abs() < f64::EPSILON);
+
+            // Merging the first revision back in only adds the chunks it does not share.
+            let other_path = dir.path().join("other.mv2");
+            let mut other = Memvid::create(&other_path).expect("create other");
+            put(&mut other, "mv2://deck/v1", &first);
+            drop(other);
+            let report = mem
+                .merge_from(&other_path, MergeOptions::default())
+                .expect("merge");
+            let merged_id = report.frame_ids[&0];
+            assert_eq!(read_blob(&mut mem, merged_id), first);
+            assert!(mem.stats().expect("stats").dedup_ratio > 1.8);
+
+            let archive = dir.path().join("archive");
+            mem.export_archive(&archive).expect("export");
+            let (mut imported, _) =
+                Memvid::import_archive(&archive, &dir.path().join("imported.mv2")).expect("import");
+            assert_eq!(read_blob(&mut imported, merged_id), first);
+            assert_eq!(read_blob(&mut imported, second_id), second);
+        });
+    }
+}
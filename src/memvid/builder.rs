@@ -280,6 +280,8 @@ impl Memvid {
                 bytes_length: 0,
                 checksum: empty_checksum,
                 compression_mode: self.vec_compression.clone(),
+                metric: self.vec_metric(),
+                hnsw: None,
             });
         }
         if let Some(manifest) = self.toc.indexes.vec.as_mut() {
@@ -0,0 +1,392 @@
+//! Change feed for mirroring a memory into downstream systems.
+//!
+//! The change log lives in a segment of its own, rewritten only by commits that change it.
+//! Applying the WAL records a put or update event per inserted frame, deleting a frame
+//! records a delete event, and removing a frame's memory cards records a removal event per
+//! card. Every commit then records the memory cards added since the previous one and the
+//! Logic-Mesh nodes and edges added or removed. Consumers page through
+//! [`Memvid::changes_since`] with the returned resume token. Every written log keeps the
+//! newest [`Memvid::change_log_horizon`] events and drops the rest.
+
+use std::collections::BTreeSet;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use blake3::hash;
+
+use crate::error::{MemvidError, Result};
+use crate::io::region::RegionKind;
+use crate::memvid::lifecycle::Memvid;
+use crate::types::{
+    ChangeBatch, ChangeKind, ChangeLog, ChangeLogManifest, DEFAULT_CHANGE_LOG_HORIZON, Frame,
+    MemoryCardId, MeshEdgeId,
+};
+
+impl Memvid {
+    /// Up to `limit` committed changes after `sequence`, oldest first; `0` starts from the
+    /// first event. Fails with [`MemvidError::InvalidCursor`] once the log has dropped
+    /// events after `sequence`, in which case the consumer has to resynchronise.
+    pub fn changes_since(&self, sequence: u64, limit: usize) -> Result<ChangeBatch> {
+        self.change_log
+            .since(sequence, limit)
+            .ok_or(MemvidError::InvalidCursor {
+                reason: "change log was trimmed past the resume token",
+            })
+    }
+
+    /// Number of events the change log keeps.
+    #[must_use]
+    pub fn change_log_horizon(&self) -> u64 {
+        self.change_log
+            .horizon
+            .unwrap_or(DEFAULT_CHANGE_LOG_HORIZON)
+    }
+
+    /// Set the number of events the change log keeps; applied by the next commit.
+    pub fn set_change_log_horizon(&mut self, events: u64) -> Result<()> {
+        self.ensure_mutation_allowed()?;
+        self.change_log.horizon = Some(events);
+        self.change_log.mark_dirty();
+        self.dirty = true;
+        Ok(())
+    }
+
+    /// Record a put, or an update when `frame` supersedes another.
+    pub(crate) fn log_frame_insert(&mut self, frame: &Frame) {
+        let kind = match frame.supersedes {
+            Some(supersedes) => ChangeKind::Update {
+                frame_id: frame.id,
+                supersedes,
+                uri: frame.uri.clone(),
+            },
+            None => ChangeKind::Put {
+                frame_id: frame.id,
+                uri: frame.uri.clone(),
+            },
+        };
+        self.log_change(kind);
+    }
+
+    /// Record memory cards removed along with the frames they came from.
+    pub(crate) fn log_card_removals(&mut self, card_ids: &[MemoryCardId]) {
+        for &card_id in card_ids {
+            self.log_change(ChangeKind::MemoryCardRemoved { card_id });
+        }
+    }
+
+    /// Record the memory cards added and the Logic-Mesh changes since the last call.
+    pub(crate) fn log_track_changes(&mut self) {
+        let next_card_id = self.change_log.next_card_id;
+        let cards: Vec<_> = self
+            .memories_track
+            .cards()
+            .iter()
+            .filter(|card| card.id >= next_card_id)
+            .map(|card| (card.id, card.entity.clone(), card.slot.clone()))
+            .collect();
+        for (card_id, entity, slot) in cards {
+            self.log_change(ChangeKind::MemoryCard {
+                card_id,
+                entity,
+                slot,
+            });
+            self.change_log.next_card_id = card_id + 1;
+        }
+
+        let nodes: BTreeSet<u64> = self.logic_mesh.nodes.iter().map(|node| node.id).collect();
+        let edges: BTreeSet<MeshEdgeId> = self
+            .logic_mesh
+            .edges
+            .iter()
+            .map(|edge| MeshEdgeId {
+                from_node: edge.from_node,
+                to_node: edge.to_node,
+                link: edge.link.as_str().to_string(),
+            })
+            .collect();
+        let log = &self.change_log;
+        if nodes != log.mesh_nodes || edges != log.mesh_edges {
+            let kind = ChangeKind::Mesh {
+                added_nodes: nodes.difference(&log.mesh_nodes).copied().collect(),
+                removed_nodes: log.mesh_nodes.difference(&nodes).copied().collect(),
+                added_edges: edges.difference(&log.mesh_edges).cloned().collect(),
+                removed_edges: log.mesh_edges.difference(&edges).cloned().collect(),
+            };
+            self.log_change(kind);
+            self.change_log.mesh_nodes = nodes;
+            self.change_log.mesh_edges = edges;
+        }
+    }
+
+    pub(crate) fn log_change(&mut self, kind: ChangeKind) {
+        let timestamp = self.toc.committed_at.unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs() as i64)
+                .unwrap_or(0)
+        });
+        self.change_log.record(timestamp, kind);
+    }
+
+    /// Load the change log listed in the TOC, verifying it against its checksum.
+    pub(crate) fn load_change_log(&mut self) -> Result<()> {
+        let Some(manifest) = self.toc.change_log.clone() else {
+            self.change_log = ChangeLog::default();
+            return Ok(());
+        };
+        let bytes = self.read_range(manifest.bytes_offset, manifest.bytes_length)?;
+        if *hash(&bytes).as_bytes() != manifest.checksum {
+            return Err(MemvidError::InvalidToc {
+                reason: "change log checksum mismatch".into(),
+            });
+        }
+        self.change_log = ChangeLog::decode(&bytes)?;
+        Ok(())
+    }
+
+    /// Trim the change log to its horizon and write it after `footer_offset` if it
+    /// changed since it was last written.
+    pub(crate) fn persist_change_log(&mut self) -> Result<()> {
+        self.change_log.trim();
+        if !self.change_log.is_dirty() {
+            return Ok(());
+        }
+        let encoded = self.change_log.encode()?;
+        let offset = self.header.footer_offset;
+        let bytes_length = self.write_region(offset, RegionKind::Index, &encoded)?;
+        self.toc.change_log = Some(ChangeLogManifest {
+            bytes_offset: offset,
+            bytes_length,
+            event_count: self.change_log.events.len() as u64,
+            last_sequence: self.change_log.last_sequence,
+            checksum: *hash(&encoded).as_bytes(),
+        });
+        self.header.footer_offset = offset + bytes_length;
+        self.change_log.mark_clean();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::run_serial_test;
+    use crate::types::{
+        ChangeEvent, EntityKind, LinkType, LogicMesh, MemoryCardBuilder, MeshEdge, MeshEdgeId,
+        MeshNode, PutOptions, QuotaSettings,
+    };
+    use tempfile::tempdir;
+
+    fn options(uri: &str) -> PutOptions {
+        PutOptions::builder()
+            .uri(uri)
+            .auto_tag(false)
+            .extract_dates(false)
+            .extract_triplets(false)
+            .build()
+    }
+
+    fn kinds(events: &[ChangeEvent]) -> Vec<ChangeKind> {
+        events.iter().map(|event| event.kind.clone()).collect()
+    }
+
+    #[test]
+    fn changes_page_through_commits_and_survive_vacuum() {
+        run_serial_test(|| {
+            let dir = tempdir().expect("tmp");
+            let path = dir.path().join("changes.mv2");
+            let mut mem = Memvid::create(&path).expect("create");
+            mem.put_bytes_with_options(b"first note", options("mv2://notes/a"))
+                .expect("put a");
+            mem.put_bytes_with_options(b"second note", options("mv2://notes/b"))
+                .expect("put b");
+            let card = MemoryCardBuilder::new()
+                .fact()
+                .entity("user")
+                .slot("city")
+                .value("Lisbon")
+                .source(0, None)
+                .engine("test", "1")
+                .build(0)
+                .expect("card");
+            mem.put_memory_card(card).expect("card");
+            mem.commit().expect("commit");
+
+            let first = mem.changes_since(0, 2).expect("changes");
+            assert_eq!(
+                kinds(&first.events),
+                [
+                    ChangeKind::Put {
+                        frame_id: 0,
+                        uri: Some("mv2://notes/a".into()),
+                    },
+                    ChangeKind::Put {
+                        frame_id: 1,
+                        uri: Some("mv2://notes/b".into()),
+                    },
+                ]
+            );
+            assert_eq!(first.resume_token, 2);
+            let rest = mem.changes_since(first.resume_token, 10).expect("changes");
+            assert_eq!(
+                kinds(&rest.events),
+                [ChangeKind::MemoryCard {
+                    card_id: 0,
+                    entity: "user".into(),
+                    slot: "city".into(),
+                }]
+            );
+
+            mem.update_frame(
+                0,
+                Some(b"first note, edited".to_vec()),
+                options("mv2://notes/a"),
+                None,
+            )
+            .expect("update");
+            mem.delete_frame(1).expect("delete");
+            mem.commit().expect("commit");
+            let later = mem.changes_since(rest.resume_token, 10).expect("changes");
+            assert_eq!(
+                kinds(&later.events),
+                [
+                    ChangeKind::Update {
+                        frame_id: 2,
+                        supersedes: 0,
+                        uri: Some("mv2://notes/a".into()),
+                    },
+                    ChangeKind::Delete {
+                        frame_id: 1,
+                        uri: Some("mv2://notes/b".into()),
+                    },
+                ]
+            );
+            assert_eq!(later.resume_token, 5);
+            assert!(
+                mem.changes_since(later.resume_token, 10)
+                    .expect("changes")
+                    .events
+                    .is_empty()
+            );
+
+            // Commits keep the newest events up to the horizon, across vacuums and reopens.
+            mem.set_change_log_horizon(2).expect("horizon");
+            mem.commit().expect("commit");
+            assert_eq!(mem.change_log.events.len(), 2);
+            assert_eq!(mem.change_log.first_retained(), 4);
+            mem.vacuum().expect("vacuum");
+            drop(mem);
+            let mem = Memvid::open_read_only(&path).expect("reopen");
+            assert_eq!(mem.change_log_horizon(), 2);
+            let kept = mem.changes_since(3, 10).expect("changes");
+            assert_eq!(kept.events.len(), 2);
+            assert_eq!(kept.events[0].sequence, 4);
+            assert!(matches!(
+                mem.changes_since(0, 10),
+                Err(MemvidError::InvalidCursor { .. })
+            ));
+        });
+    }
+
+    #[test]
+    fn change_log_names_mesh_ids_and_removed_cards() {
+        run_serial_test(|| {
+            let dir = tempdir().expect("tmp");
+            let path = dir.path().join("changes.mv2");
+            let mut mem = Memvid::create(&path).expect("create");
+            mem.put_bytes_with_options(b"crane idle at harbour", options("mv2://notes/port"))
+                .expect("put");
+            let card = MemoryCardBuilder::new()
+                .fact()
+                .entity("crane")
+                .slot("status")
+                .value("idle")
+                .source(0, None)
+                .engine("test", "1")
+                .build(0)
+                .expect("card");
+            mem.put_memory_card(card).expect("card");
+            let harbour = MeshNode::new(
+                "harbour".into(),
+                "Harbour".into(),
+                EntityKind::Location,
+                0.9,
+                0,
+                14,
+                7,
+            );
+            let crane = MeshNode::new(
+                "crane".into(),
+                "Crane".into(),
+                EntityKind::Product,
+                0.9,
+                0,
+                0,
+                5,
+            );
+            let edge = MeshEdgeId {
+                from_node: crane.id,
+                to_node: harbour.id,
+                link: LinkType::Location.as_str().into(),
+            };
+            let mut nodes = vec![harbour.id, crane.id];
+            nodes.sort_unstable();
+            let mut mesh = LogicMesh::new();
+            mesh.merge_edge(MeshEdge::new(
+                crane.id,
+                harbour.id,
+                LinkType::Location,
+                0.8,
+                0,
+            ));
+            mesh.merge_node(harbour);
+            mesh.merge_node(crane);
+            mem.set_logic_mesh(mesh);
+            mem.commit().expect("commit");
+
+            let first = mem.changes_since(0, 10).expect("changes");
+            assert_eq!(first.events.len(), 3);
+            assert_eq!(
+                first.events[2].kind,
+                ChangeKind::Mesh {
+                    added_nodes: nodes.clone(),
+                    removed_nodes: Vec::new(),
+                    added_edges: vec![edge.clone()],
+                    removed_edges: Vec::new(),
+                }
+            );
+
+            // A commit that logs nothing leaves the segment where it was.
+            let segment = mem.toc.change_log.clone().expect("segment");
+            let footer = mem.header.footer_offset;
+            mem.set_quotas(QuotaSettings::new().max_frames(10))
+                .expect("quotas");
+            mem.commit().expect("commit");
+            assert!(mem.header.footer_offset > footer);
+            assert_eq!(mem.toc.change_log.as_ref(), Some(&segment));
+
+            mem.erase_frame(0).expect("erase");
+            let later = mem.changes_since(first.resume_token, 10).expect("changes");
+            assert_eq!(
+                kinds(&later.events),
+                [
+                    ChangeKind::Delete {
+                        frame_id: 0,
+                        uri: Some("mv2://notes/port".into()),
+                    },
+                    ChangeKind::MemoryCardRemoved { card_id: 0 },
+                    ChangeKind::Mesh {
+                        added_nodes: Vec::new(),
+                        removed_nodes: nodes,
+                        added_edges: Vec::new(),
+                        removed_edges: vec![edge],
+                    },
+                ]
+            );
+            assert_ne!(mem.toc.change_log.as_ref(), Some(&segment));
+
+            drop(mem);
+            let mem = Memvid::open_read_only(&path).expect("reopen");
+            assert_eq!(mem.changes_since(0, 10).expect("changes").events.len(), 6);
+        });
+    }
+}
@@ -0,0 +1,526 @@
+//! Canonical encoder selection and trained zstd dictionaries.
+//!
+//! Puts use the encoding requested in their options, then the encoding configured for
+//! their track, and otherwise zstd for UTF-8, `Chunked` for binary payloads of at least
+//! [`CHUNKED_MIN_BYTES`] and plain bytes for everything else.
+//! `ZstdDict` payloads are zstd frames whose header names the dictionary they were
+//! compressed with; dictionaries are stored as index segments listed in the TOC and are
+//! never rewritten, so every frame encoded with one stays decodable.
+
+use std::io::{Cursor, Read};
+use std::sync::Arc;
+
+use blake3::hash;
+
+use crate::error::{MemvidError, Result};
+use crate::io::region::RegionKind;
+use crate::memvid::lifecycle::Memvid;
+use crate::memvid::mutation::prepare_canonical_payload;
+use crate::types::{
+    CanonicalEncoding, CompressionDictionaryManifest, DictionaryOptions, Frame, FrameId,
+    FrameStatus,
+};
+
+/// Compression level used for every zstd canonical payload.
+const ZSTD_LEVEL: i32 = 3;
+
+/// Binary payloads at least this large are stored in the blob store by default.
+pub const CHUNKED_MIN_BYTES: usize = 4 * crate::cdc::MAX_CHUNK_SIZE;
+
+/// Encoder chosen for one put, with its dictionary already loaded.
+pub(crate) struct CanonicalEncoder {
+    encoding: Option<CanonicalEncoding>,
+    dictionary: Option<Arc<Vec<u8>>>,
+}
+
+impl CanonicalEncoder {
+    /// Encode `payload`, returning the stored bytes, their encoding and the decoded length.
+    pub(crate) fn encode(
+        &self,
+        payload: &[u8],
+    ) -> Result<(Vec<u8>, CanonicalEncoding, Option<u64>)> {
+        let Some(encoding) = self.encoding else {
+            if payload.len() >= CHUNKED_MIN_BYTES && std::str::from_utf8(payload).is_err() {
+                return Ok((
+                    payload.to_vec(),
+                    CanonicalEncoding::Chunked,
+                    Some(payload.len() as u64),
+                ));
+            }
+            return prepare_canonical_payload(payload);
+        };
+        // Chunking happens at commit, so the WAL carries the raw bytes.
+        if encoding == CanonicalEncoding::Chunked {
+            return Ok((payload.to_vec(), encoding, Some(payload.len() as u64)));
+        }
+        let encoded = encode_canonical_bytes(
+            payload,
+            encoding,
+            self.dictionary.as_deref().map(Vec::as_slice),
+        )?;
+        Ok((encoded, encoding, Some(payload.len() as u64)))
+    }
+}
+
+/// Encode `payload` with `encoding`; `ZstdDict` requires the dictionary bytes and
+/// `Chunked` payloads are written by [`Memvid::append_blob_chunks`] instead.
+pub(crate) fn encode_canonical_bytes(
+    payload: &[u8],
+    encoding: CanonicalEncoding,
+    dictionary: Option<&[u8]>,
+) -> Result<Vec<u8>> {
+    match encoding {
+        CanonicalEncoding::Plain => Ok(payload.to_vec()),
+        CanonicalEncoding::Zstd => Ok(zstd::encode_all(Cursor::new(payload), ZSTD_LEVEL)?),
+        CanonicalEncoding::Lz4 => Ok(lz4_flex::compress_prepend_size(payload)),
+        CanonicalEncoding::ZstdDict => {
+            let dictionary =
+                dictionary.ok_or_else(|| MemvidError::InvalidCompressionDictionary {
+                    reason: "no dictionary available for zstd dictionary encoding".into(),
+                })?;
+            let mut compressor = zstd::bulk::Compressor::with_dictionary(ZSTD_LEVEL, dictionary)?;
+            Ok(compressor.compress(payload)?)
+        }
+        CanonicalEncoding::Chunked => Err(MemvidError::InvalidBlobStore {
+            reason: "chunked payloads are stored through the blob store".into(),
+        }),
+    }
+}
+
+/// Dictionary id recorded in the header of a `ZstdDict` payload.
+pub(crate) fn payload_dictionary_id(payload: &[u8]) -> Option<u32> {
+    zstd::zstd_safe::get_dict_id_from_frame(payload).map(u32::from)
+}
+
+impl Memvid {
+    /// Use `encoding` for puts to `track` that don't request one, or restore the default
+    /// with `None`. Persisted by the next commit.
+    pub fn set_track_encoding<T: Into<String>>(
+        &mut self,
+        track: T,
+        encoding: Option<CanonicalEncoding>,
+    ) -> Result<()> {
+        self.ensure_mutation_allowed()?;
+        let track = track.into();
+        match encoding {
+            Some(encoding) => {
+                self.toc.compression.track_encodings.insert(track, encoding);
+            }
+            None => {
+                self.toc.compression.track_encodings.remove(&track);
+            }
+        }
+        self.dirty = true;
+        Ok(())
+    }
+
+    /// Encodings configured per track.
+    #[must_use]
+    pub fn track_encodings(&self) -> &std::collections::BTreeMap<String, CanonicalEncoding> {
+        &self.toc.compression.track_encodings
+    }
+
+    /// Trained dictionaries stored in the memory, oldest first.
+    #[must_use]
+    pub fn compression_dictionaries(&self) -> &[CompressionDictionaryManifest] {
+        &self.toc.compression.dictionaries
+    }
+
+    /// Train a zstd dictionary over the canonical bytes of recent active frames and store
+    /// it in a new generation.
+    ///
+    /// Pending WAL records are committed first. Later `ZstdDict` puts to `options.track`
+    /// use the new dictionary; a dictionary trained without a track serves every track
+    /// that has none of its own. Frames already stored keep their encoding.
+    pub fn train_dictionary(
+        &mut self,
+        options: DictionaryOptions,
+    ) -> Result<CompressionDictionaryManifest> {
+        self.ensure_mutation_allowed()?;
+        self.commit()?;
+
+        let mut frames: Vec<Frame> = Vec::new();
+        for index in (0..self.toc.frames.len()).rev() {
+            if frames.len() >= options.max_samples {
+                break;
+            }
+            let Some(frame) = self.toc.frames.try_get(index)? else {
+                continue;
+            };
+            if frame.status == FrameStatus::Active
+                && frame.payload_length != 0
+                && (options.track.is_none() || frame.track == options.track)
+            {
+                frames.push(frame.clone());
+            }
+        }
+        if frames.is_empty() {
+            return Err(MemvidError::InvalidCompressionDictionary {
+                reason: "no frames to sample".into(),
+            });
+        }
+        let mut samples = Vec::with_capacity(frames.len());
+        for frame in &frames {
+            samples.push(self.frame_canonical_bytes(frame)?);
+        }
+
+        let dictionary = zstd::dict::from_samples(&samples, options.max_size).map_err(|err| {
+            MemvidError::InvalidCompressionDictionary {
+                reason: format!("training failed: {err}").into(),
+            }
+        })?;
+        let dictionary_id = zstd::zstd_safe::get_dict_id_from_dict(&dictionary)
+            .map(u32::from)
+            .ok_or_else(|| MemvidError::InvalidCompressionDictionary {
+                reason: "trained dictionary has no id".into(),
+            })?;
+        let checksum = *hash(&dictionary).as_bytes();
+        if let Some(existing) = self
+            .toc
+            .compression
+            .dictionaries
+            .iter()
+            .find(|existing| existing.dictionary_id == dictionary_id)
+        {
+            if existing.checksum == checksum && existing.track == options.track {
+                return Ok(existing.clone());
+            }
+            return Err(MemvidError::InvalidCompressionDictionary {
+                reason: format!("dictionary id {dictionary_id} is already in use").into(),
+            });
+        }
+
+        let mut manifest = None;
+        self.with_staging(None, |mem| {
+            mem.begin_generation()?;
+            let offset = mem.header.footer_offset.max(mem.data_end);
+            let stored = mem.append_dictionary(
+                offset,
+                dictionary,
+                options.track.clone(),
+                samples.len() as u64,
+            )?;
+            let end = offset + stored.bytes_length;
+            mem.data_end = mem.data_end.max(end);
+            mem.header.footer_offset = end;
+            mem.rewrite_toc_footer()?;
+            mem.header.toc_checksum = mem.toc.toc_checksum;
+            crate::persist_header(&mut mem.file, &mem.header)?;
+            mem.file.sync_all()?;
+            manifest = Some(stored);
+            Ok(())
+        })?;
+        self.dirty = false;
+
+        let manifest = manifest.ok_or_else(|| MemvidError::InvalidCompressionDictionary {
+            reason: "dictionary was not stored".into(),
+        })?;
+        tracing::info!(
+            dictionary_id,
+            samples = manifest.sample_count,
+            bytes = manifest.bytes_length,
+            "compression dictionary trained"
+        );
+        Ok(manifest)
+    }
+
+    /// Write `dictionary` as an index region at `offset` and list it in the TOC.
+    pub(crate) fn append_dictionary(
+        &mut self,
+        offset: u64,
+        dictionary: Vec<u8>,
+        track: Option<String>,
+        sample_count: u64,
+    ) -> Result<CompressionDictionaryManifest> {
+        let dictionary_id = zstd::zstd_safe::get_dict_id_from_dict(&dictionary)
+            .map(u32::from)
+            .ok_or_else(|| MemvidError::InvalidCompressionDictionary {
+                reason: "dictionary has no id".into(),
+            })?;
+        let bytes_length = self.write_region(offset, RegionKind::Index, &dictionary)?;
+        let manifest = CompressionDictionaryManifest {
+            dictionary_id,
+            track,
+            sample_count,
+            bytes_offset: offset,
+            bytes_length,
+            checksum: *hash(&dictionary).as_bytes(),
+        };
+        self.toc.compression.dictionaries.push(manifest.clone());
+        self.compression_dictionaries
+            .insert(dictionary_id, Arc::new(dictionary));
+        Ok(manifest)
+    }
+
+    /// Load a stored dictionary, verifying it against its checksum.
+    pub(crate) fn compression_dictionary(&mut self, dictionary_id: u32) -> Result<Arc<Vec<u8>>> {
+        if let Some(dictionary) = self.compression_dictionaries.get(&dictionary_id) {
+            return Ok(Arc::clone(dictionary));
+        }
+        let manifest = self
+            .toc
+            .compression
+            .dictionaries
+            .iter()
+            .find(|manifest| manifest.dictionary_id == dictionary_id)
+            .cloned()
+            .ok_or_else(|| MemvidError::InvalidCompressionDictionary {
+                reason: format!("dictionary {dictionary_id} is not stored in this memory").into(),
+            })?;
+        let bytes = self.read_range(manifest.bytes_offset, manifest.bytes_length)?;
+        if *hash(&bytes).as_bytes() != manifest.checksum {
+            return Err(MemvidError::InvalidCompressionDictionary {
+                reason: format!("dictionary {dictionary_id} checksum mismatch").into(),
+            });
+        }
+        let dictionary = Arc::new(bytes);
+        self.compression_dictionaries
+            .insert(dictionary_id, Arc::clone(&dictionary));
+        Ok(dictionary)
+    }
+
+    /// Choose the encoder for a put requesting `requested` on `track`.
+    ///
+    /// `ZstdDict` uses the newest dictionary trained for the track, then the newest one
+    /// trained without a track, and falls back to plain zstd when neither exists.
+    pub(crate) fn canonical_encoder(
+        &mut self,
+        requested: Option<CanonicalEncoding>,
+        track: Option<&str>,
+    ) -> Result<CanonicalEncoder> {
+        let encoding = requested.or_else(|| {
+            track.and_then(|track| self.toc.compression.track_encodings.get(track).copied())
+        });
+        if encoding != Some(CanonicalEncoding::ZstdDict) {
+            return Ok(CanonicalEncoder {
+                encoding,
+                dictionary: None,
+            });
+        }
+
+        let dictionaries = &self.toc.compression.dictionaries;
+        let chosen = dictionaries
+            .iter()
+            .rev()
+            .find(|manifest| track.is_some() && manifest.track.as_deref() == track)
+            .or_else(|| {
+                dictionaries
+                    .iter()
+                    .rev()
+                    .find(|manifest| manifest.track.is_none())
+            })
+            .map(|manifest| manifest.dictionary_id);
+        match chosen {
+            Some(dictionary_id) => Ok(CanonicalEncoder {
+                encoding,
+                dictionary: Some(self.compression_dictionary(dictionary_id)?),
+            }),
+            None => Ok(CanonicalEncoder {
+                encoding: Some(CanonicalEncoding::Zstd),
+                dictionary: None,
+            }),
+        }
+    }
+
+    /// Decode a stored canonical payload, loading its dictionary or chunks when it needs them.
+    pub(crate) fn decode_canonical(
+        &mut self,
+        payload: &[u8],
+        encoding: CanonicalEncoding,
+        frame_id: FrameId,
+    ) -> Result<Vec<u8>> {
+        match encoding {
+            CanonicalEncoding::ZstdDict => {}
+            CanonicalEncoding::Chunked => return self.read_blob_chunks(payload, frame_id),
+            _ => return crate::decode_canonical_bytes(payload, encoding, frame_id),
+        }
+        let dictionary_id = payload_dictionary_id(payload).ok_or(MemvidError::InvalidFrame {
+            frame_id,
+            reason: "dictionary-encoded payload has no dictionary id",
+        })?;
+        let dictionary = self.compression_dictionary(dictionary_id)?;
+        let mut decoded = Vec::new();
+        zstd::stream::read::Decoder::with_dictionary(Cursor::new(payload), &dictionary)
+            .and_then(|mut decoder| decoder.read_to_end(&mut decoded))
+            .map_err(|_| MemvidError::InvalidFrame {
+                frame_id,
+                reason: "failed to decode canonical payload",
+            })?;
+        Ok(decoded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PutOptions, run_serial_test};
+    use tempfile::tempdir;
+
+    fn put(
+        mem: &mut Memvid,
+        uri: &str,
+        text: &str,
+        track: Option<&str>,
+        encoding: Option<CanonicalEncoding>,
+    ) -> FrameId {
+        let mut builder = PutOptions::builder()
+            .uri(uri)
+            .auto_tag(false)
+            .extract_dates(false)
+            .extract_triplets(false);
+        if let Some(track) = track {
+            builder = builder.track(track);
+        }
+        if let Some(encoding) = encoding {
+            builder = builder.encoding(encoding);
+        }
+        mem.put_bytes_with_options(text.as_bytes(), builder.build())
+            .expect("put");
+        mem.commit().expect("commit");
+        mem.frame_by_uri(uri).expect("frame").id
+    }
+
+    fn log_line(index: usize) -> String {
+        format!(
+            "{{\"level\":\"info\",\"service\":\"checkout\",\"request\":{index},\"latency_ms\":{},\"status\":\"ok\",\"region\":\"eu-west-{}\"}}",
+            index * 7 % 300,
+            index % 3
+        )
+    }
+
+    #[test]
+    fn encodings_are_selectable_per_put_and_track() {
+        run_serial_test(|| {
+            let dir = tempdir().expect("tmp");
+            let path = dir.path().join("encodings.mv2");
+            let mut mem = Memvid::create(&path).expect("create");
+            mem.set_track_encoding("chat", Some(CanonicalEncoding::Lz4))
+                .expect("track encoding");
+
+            let text = "latency sensitive message ".repeat(8);
+            let default_id = put(&mut mem, "mv2://default", &text, None, None);
+            let track_id = put(&mut mem, "mv2://track", &text, Some("chat"), None);
+            let explicit_id = put(
+                &mut mem,
+                "mv2://explicit",
+                &text,
+                Some("chat"),
+                Some(CanonicalEncoding::Plain),
+            );
+            // Without a dictionary, dictionary encoding falls back to plain zstd.
+            let fallback_id = put(
+                &mut mem,
+                "mv2://fallback",
+                &text,
+                None,
+                Some(CanonicalEncoding::ZstdDict),
+            );
+            drop(mem);
+
+            let mut mem = Memvid::open(&path).expect("reopen");
+            assert_eq!(
+                mem.track_encodings().get("chat"),
+                Some(&CanonicalEncoding::Lz4)
+            );
+            for (id, encoding) in [
+                (default_id, CanonicalEncoding::Zstd),
+                (track_id, CanonicalEncoding::Lz4),
+                (explicit_id, CanonicalEncoding::Plain),
+                (fallback_id, CanonicalEncoding::Zstd),
+            ] {
+                assert_eq!(
+                    mem.frame_by_id(id).expect("frame").canonical_encoding,
+                    encoding
+                );
+                assert_eq!(
+                    mem.frame_canonical_payload(id).expect("payload"),
+                    text.as_bytes()
+                );
+            }
+
+            let stats = mem.stats().expect("stats");
+            let lz4 = stats
+                .encodings
+                .iter()
+                .find(|entry| entry.encoding == CanonicalEncoding::Lz4)
+                .expect("lz4 stats");
+            assert_eq!(lz4.frames, 1);
+            assert_eq!(lz4.logical_bytes, text.len() as u64);
+            assert!(lz4.payload_bytes < lz4.logical_bytes);
+        });
+    }
+
+    #[test]
+    fn trained_dictionary_encodes_and_survives_reopen_and_vacuum() {
+        run_serial_test(|| {
+            let dir = tempdir().expect("tmp");
+            let path = dir.path().join("dictionary.mv2");
+            let mut mem = Memvid::create(&path).expect("create");
+            for index in 0..200 {
+                let options = PutOptions::builder()
+                    .track("logs")
+                    .auto_tag(false)
+                    .extract_dates(false)
+                    .extract_triplets(false)
+                    .instant_index(false)
+                    .build();
+                mem.put_bytes_with_options(log_line(index).as_bytes(), options)
+                    .expect("put");
+            }
+            mem.commit().expect("commit");
+
+            let manifest = mem
+                .train_dictionary(DictionaryOptions::new().track("logs").max_size(4096))
+                .expect("train");
+            assert_eq!(manifest.track.as_deref(), Some("logs"));
+            assert_eq!(manifest.sample_count, 200);
+            assert_eq!(mem.compression_dictionaries(), &[manifest.clone()]);
+
+            let line = log_line(1000);
+            let dict_id = put(
+                &mut mem,
+                "mv2://dict",
+                &line,
+                Some("logs"),
+                Some(CanonicalEncoding::ZstdDict),
+            );
+            let plain_zstd = zstd::encode_all(Cursor::new(line.as_bytes()), ZSTD_LEVEL)
+                .expect("zstd")
+                .len() as u64;
+            let frame = mem.frame_by_id(dict_id).expect("frame");
+            assert_eq!(frame.canonical_encoding, CanonicalEncoding::ZstdDict);
+            assert!(frame.payload_length < plain_zstd);
+            mem.delete_frame(0).expect("delete");
+            mem.commit().expect("commit");
+            drop(mem);
+
+            let mut mem = Memvid::open(&path).expect("reopen");
+            assert_eq!(
+                mem.frame_canonical_payload(dict_id).expect("payload"),
+                line.as_bytes()
+            );
+            let stats = mem.stats().expect("stats");
+            assert_eq!(stats.dictionary_bytes, manifest.bytes_length);
+
+            mem.vacuum().expect("vacuum");
+            drop(mem);
+            let mut mem = Memvid::open(&path).expect("reopen after vacuum");
+            assert_eq!(mem.compression_dictionaries().len(), 1);
+            assert_eq!(
+                mem.frame_canonical_payload(dict_id).expect("payload"),
+                line.as_bytes()
+            );
+
+            let archive = dir.path().join("archive");
+            mem.export_archive(&archive).expect("export");
+            let (mut imported, _) =
+                Memvid::import_archive(&archive, &dir.path().join("imported.mv2")).expect("import");
+            let frame = imported.frame_by_id(dict_id).expect("frame");
+            assert_eq!(frame.canonical_encoding, CanonicalEncoding::ZstdDict);
+            assert_eq!(
+                imported.frame_canonical_payload(dict_id).expect("payload"),
+                line.as_bytes()
+            );
+        });
+    }
+}
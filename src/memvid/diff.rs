@@ -0,0 +1,425 @@
+//! Structural comparison of two memories.
+//!
+//! Everything is computed from the TOC, the memories track and the Logic-Mesh; payload
+//! changes are detected through the frame checksums, so no payload is ever read.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::Path;
+
+use crate::error::Result;
+use crate::memvid::lifecycle::Memvid;
+use crate::types::{
+    DiffFrame, DiffMeshEdge, DiffMeshNode, EntityKind, FieldChange, Frame, FrameId,
+    FrameMetadataChange, FrameStatus, LinkType, LogicMesh, MemoryCard, MemoryDiff, SupersededFrame,
+    VersionRelation,
+};
+
+impl Memvid {
+    /// Compare this memory (the old side) against `other` (the new side).
+    ///
+    /// Frames are matched by id, which is stable across generations of one file and
+    /// across copies produced by export, import or vacuum.
+    pub fn diff(&self, other: &Memvid) -> Result<MemoryDiff> {
+        let mut diff = MemoryDiff::default();
+        diff_frames(self, other, &mut diff)?;
+        diff_cards(
+            self.memories_track.cards(),
+            other.memories_track.cards(),
+            &mut diff,
+        );
+        diff_mesh(&self.logic_mesh, &other.logic_mesh, &mut diff);
+        Ok(diff)
+    }
+
+    /// Compare the committed state of two memory files.
+    pub fn diff_files(old: &Path, new: &Path) -> Result<MemoryDiff> {
+        let old = Memvid::open_read_only(old)?;
+        let new = Memvid::open_read_only(new)?;
+        old.diff(&new)
+    }
+
+    /// Compare two commit generations of the same memory file.
+    pub fn diff_generations(path: &Path, from: u64, to: u64) -> Result<MemoryDiff> {
+        let old = Memvid::open_at_generation(path, from)?;
+        let new = Memvid::open_at_generation(path, to)?;
+        old.diff(&new)
+    }
+}
+
+fn diff_frame(frame: &Frame) -> DiffFrame {
+    DiffFrame {
+        frame_id: frame.id,
+        uri: frame.uri.clone(),
+        title: frame.title.clone(),
+        checksum: hex::encode(frame.checksum),
+    }
+}
+
+fn diff_frames(old: &Memvid, new: &Memvid, diff: &mut MemoryDiff) -> Result<()> {
+    let old_frames = &old.toc.frames;
+    let new_frames = &new.toc.frames;
+
+    for (index, after) in new_frames.try_iter().enumerate() {
+        let after = after?;
+        let Some(before) = old_frames.try_get(index)? else {
+            if after.status != FrameStatus::Deleted {
+                diff.frames_added.push(diff_frame(after));
+            }
+            continue;
+        };
+        if before.status == FrameStatus::Deleted {
+            continue;
+        }
+        match (before.status, after.status) {
+            (_, FrameStatus::Deleted) => {
+                diff.frames_deleted.push(diff_frame(before));
+                continue;
+            }
+            (FrameStatus::Active, FrameStatus::Superseded) => {
+                diff.frames_superseded.push(SupersededFrame {
+                    frame: diff_frame(after),
+                    superseded_by: after.superseded_by,
+                });
+            }
+            _ => {}
+        }
+        if before.checksum != after.checksum {
+            diff.frames_modified.push(diff_frame(after));
+        }
+        if let Some(change) = frame_metadata_change(before, after) {
+            diff.metadata_changed.push(change);
+        }
+    }
+
+    // Frames the new side does not know about at all were dropped, e.g. by a rewrite.
+    for index in new_frames.len()..old_frames.len() {
+        if let Some(before) = old_frames.try_get(index)? {
+            if before.status != FrameStatus::Deleted {
+                diff.frames_deleted.push(diff_frame(before));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Elements of `after` missing from `before`, and of `before` missing from `after`.
+fn set_delta(before: &[String], after: &[String]) -> (Vec<String>, Vec<String>) {
+    let before: BTreeSet<&String> = before.iter().collect();
+    let after: BTreeSet<&String> = after.iter().collect();
+    (
+        after.difference(&before).map(|s| (*s).clone()).collect(),
+        before.difference(&after).map(|s| (*s).clone()).collect(),
+    )
+}
+
+fn frame_metadata_change(before: &Frame, after: &Frame) -> Option<FrameMetadataChange> {
+    let (tags_added, tags_removed) = set_delta(&before.tags, &after.tags);
+    let (labels_added, labels_removed) = set_delta(&before.labels, &after.labels);
+
+    let mut fields = Vec::new();
+    let mut field = |name: &str, old: Option<String>, new: Option<String>| {
+        if old != new {
+            fields.push(FieldChange {
+                field: name.to_string(),
+                before: old,
+                after: new,
+            });
+        }
+    };
+    field("uri", before.uri.clone(), after.uri.clone());
+    field("title", before.title.clone(), after.title.clone());
+    field("kind", before.kind.clone(), after.kind.clone());
+    field("track", before.track.clone(), after.track.clone());
+    let doc_metadata = |frame: &Frame| {
+        frame
+            .metadata
+            .as_ref()
+            .and_then(|metadata| serde_json::to_string(metadata).ok())
+    };
+    field("metadata", doc_metadata(before), doc_metadata(after));
+    let keys: BTreeSet<&String> = before
+        .extra_metadata
+        .keys()
+        .chain(after.extra_metadata.keys())
+        .collect();
+    for key in keys {
+        field(
+            &format!("extra_metadata.{key}"),
+            before.extra_metadata.get(key).cloned(),
+            after.extra_metadata.get(key).cloned(),
+        );
+    }
+
+    if tags_added.is_empty()
+        && tags_removed.is_empty()
+        && labels_added.is_empty()
+        && labels_removed.is_empty()
+        && fields.is_empty()
+    {
+        return None;
+    }
+    Some(FrameMetadataChange {
+        frame_id: after.id,
+        tags_added,
+        tags_removed,
+        labels_added,
+        labels_removed,
+        fields,
+    })
+}
+
+/// Cards are compared by content rather than id, since ids are local to one file.
+type CardKey<'a> = (&'a str, &'a str, &'a str, u8, FrameId, i64);
+
+fn card_key(card: &MemoryCard) -> CardKey<'_> {
+    (
+        card.entity.as_str(),
+        card.slot.as_str(),
+        card.value.as_str(),
+        card.version_relation as u8,
+        card.source_frame_id,
+        card.created_at,
+    )
+}
+
+fn diff_cards(old: &[MemoryCard], new: &[MemoryCard], diff: &mut MemoryDiff) {
+    let old_keys: HashSet<CardKey<'_>> = old.iter().map(card_key).collect();
+    let new_keys: HashSet<CardKey<'_>> = new.iter().map(card_key).collect();
+    for card in new
+        .iter()
+        .filter(|card| !old_keys.contains(&card_key(card)))
+    {
+        if card.version_relation == VersionRelation::Retracts {
+            diff.cards_retracted.push(card.clone());
+        } else {
+            diff.cards_added.push(card.clone());
+        }
+    }
+    diff.cards_removed = old
+        .iter()
+        .filter(|card| !new_keys.contains(&card_key(card)))
+        .cloned()
+        .collect();
+}
+
+fn mesh_nodes(mesh: &LogicMesh) -> HashMap<(&str, EntityKind), &str> {
+    mesh.nodes
+        .iter()
+        .map(|node| {
+            (
+                (node.canonical_name.as_str(), node.kind),
+                node.display_name.as_str(),
+            )
+        })
+        .collect()
+}
+
+fn mesh_edges(mesh: &LogicMesh) -> HashSet<(&str, &str, LinkType)> {
+    let names: HashMap<u64, &str> = mesh
+        .nodes
+        .iter()
+        .map(|node| (node.id, node.canonical_name.as_str()))
+        .collect();
+    mesh.edges
+        .iter()
+        .filter_map(|edge| {
+            let from = names.get(&edge.from_node)?;
+            let to = names.get(&edge.to_node)?;
+            Some((*from, *to, edge.link.clone()))
+        })
+        .collect()
+}
+
+fn node_delta(
+    nodes: &HashMap<(&str, EntityKind), &str>,
+    other: &HashMap<(&str, EntityKind), &str>,
+) -> Vec<DiffMeshNode> {
+    let mut delta: Vec<DiffMeshNode> = nodes
+        .iter()
+        .filter(|(key, _)| !other.contains_key(*key))
+        .map(|((canonical_name, kind), display_name)| DiffMeshNode {
+            canonical_name: (*canonical_name).to_string(),
+            display_name: (*display_name).to_string(),
+            kind: *kind,
+        })
+        .collect();
+    delta.sort_by(|a, b| a.canonical_name.cmp(&b.canonical_name));
+    delta
+}
+
+fn edge_delta(
+    edges: &HashSet<(&str, &str, LinkType)>,
+    other: &HashSet<(&str, &str, LinkType)>,
+) -> Vec<DiffMeshEdge> {
+    let mut delta: Vec<DiffMeshEdge> = edges
+        .difference(other)
+        .map(|(from, to, link)| DiffMeshEdge {
+            from: (*from).to_string(),
+            to: (*to).to_string(),
+            link: link.clone(),
+        })
+        .collect();
+    delta.sort_by(|a, b| (&a.from, &a.to).cmp(&(&b.from, &b.to)));
+    delta
+}
+
+fn diff_mesh(old: &LogicMesh, new: &LogicMesh, diff: &mut MemoryDiff) {
+    let old_nodes = mesh_nodes(old);
+    let new_nodes = mesh_nodes(new);
+    diff.mesh_nodes_added = node_delta(&new_nodes, &old_nodes);
+    diff.mesh_nodes_removed = node_delta(&old_nodes, &new_nodes);
+
+    let old_edges = mesh_edges(old);
+    let new_edges = mesh_edges(new);
+    diff.mesh_edges_added = edge_delta(&new_edges, &old_edges);
+    diff.mesh_edges_removed = edge_delta(&old_edges, &new_edges);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{MemoryCardBuilder, MeshEdge, MeshNode};
+    use crate::{PutOptions, fast_options, run_serial_test};
+    use tempfile::tempdir;
+
+    fn card(value: &str, frame_id: FrameId, retracts: bool) -> MemoryCard {
+        let builder = MemoryCardBuilder::new()
+            .fact()
+            .entity("lighthouse")
+            .slot("schedule")
+            .value(value)
+            .source(frame_id, None)
+            .engine("rules-v1", "1.0.0");
+        let builder = if retracts {
+            builder.retracts()
+        } else {
+            builder
+        };
+        builder.build(0).expect("card")
+    }
+
+    fn node(name: &str, frame_id: FrameId) -> MeshNode {
+        MeshNode::new(
+            name.to_lowercase(),
+            name.into(),
+            EntityKind::Location,
+            0.9,
+            frame_id,
+            0,
+            10,
+        )
+    }
+
+    #[test]
+    fn diff_generations_reports_frame_card_and_mesh_changes() {
+        run_serial_test(|| {
+            let dir = tempdir().expect("tmp");
+            let path = dir.path().join("diff.mv2");
+            let mut mem = Memvid::create(&path).expect("create");
+            mem.put_bytes_with_options(b"harbour crane report", fast_options())
+                .expect("put");
+            mem.put_bytes_with_options(b"lighthouse schedule", fast_options())
+                .expect("put");
+            mem.put_memory_card(card("weekly", 1, false)).expect("card");
+            let mut mesh = LogicMesh::new();
+            mesh.merge_node(node("Harbour", 0));
+            mem.set_logic_mesh(mesh);
+            mem.commit().expect("commit");
+            let first = mem.generation;
+
+            mem.delete_frame(0).expect("delete");
+            mem.update_frame(
+                1,
+                Some(b"lighthouse schedule, revised".to_vec()),
+                fast_options(),
+                None,
+            )
+            .expect("update");
+            mem.put_memory_card(card("weekly", 2, true)).expect("card");
+            let mut mesh = mem.logic_mesh().clone();
+            mesh.merge_node(node("Lighthouse", 2));
+            let harbour = mesh.nodes[0].id;
+            let lighthouse = mesh.nodes[1].id;
+            mesh.merge_edge(MeshEdge::new(
+                lighthouse,
+                harbour,
+                LinkType::Location,
+                0.8,
+                2,
+            ));
+            mem.set_logic_mesh(mesh);
+            mem.commit().expect("commit");
+            let second = mem.generation;
+            drop(mem);
+
+            let diff = Memvid::diff_generations(&path, first, second).expect("diff");
+            let ids = |frames: &[DiffFrame]| frames.iter().map(|f| f.frame_id).collect::<Vec<_>>();
+            assert_eq!(ids(&diff.frames_added), vec![2]);
+            assert_eq!(ids(&diff.frames_deleted), vec![0]);
+            assert_eq!(diff.frames_superseded.len(), 1);
+            assert_eq!(diff.frames_superseded[0].frame.frame_id, 1);
+            assert_eq!(diff.frames_superseded[0].superseded_by, Some(2));
+            assert!(diff.frames_modified.is_empty());
+            assert!(diff.cards_added.is_empty());
+            assert_eq!(diff.cards_retracted.len(), 1);
+            assert!(diff.cards_removed.is_empty());
+            assert_eq!(diff.mesh_nodes_added.len(), 1);
+            assert_eq!(diff.mesh_nodes_added[0].canonical_name, "lighthouse");
+            assert!(diff.mesh_nodes_removed.is_empty());
+            assert_eq!(diff.mesh_edges_added.len(), 1);
+            assert_eq!(diff.mesh_edges_added[0].from, "lighthouse");
+            assert!(diff.summary().contains("frames superseded: 1"));
+
+            let unchanged = Memvid::diff_generations(&path, second, second).expect("diff");
+            assert!(unchanged.is_empty());
+            assert_eq!(unchanged.summary(), "no changes");
+        });
+    }
+
+    #[test]
+    fn diff_files_reports_tag_and_payload_changes() {
+        run_serial_test(|| {
+            let dir = tempdir().expect("tmp");
+            let old_path = dir.path().join("old.mv2");
+            let new_path = dir.path().join("new.mv2");
+            for (path, tag, second) in [
+                (&old_path, "draft", b"ferry timetable".as_slice()),
+                (&new_path, "final", b"ferry timetable v2".as_slice()),
+            ] {
+                let mut mem = Memvid::create(path).expect("create");
+                mem.put_bytes_with_options(
+                    b"harbour crane report",
+                    PutOptions::builder()
+                        .auto_tag(false)
+                        .extract_dates(false)
+                        .extract_triplets(false)
+                        .instant_index(false)
+                        .push_tag(tag)
+                        .tag("owner", tag)
+                        .build(),
+                )
+                .expect("put");
+                mem.put_bytes_with_options(second, fast_options())
+                    .expect("put");
+                mem.commit().expect("commit");
+            }
+
+            let diff = Memvid::diff_files(&old_path, &new_path).expect("diff");
+            assert!(diff.frames_added.is_empty() && diff.frames_deleted.is_empty());
+            assert_eq!(diff.frames_modified.len(), 1);
+            assert_eq!(diff.frames_modified[0].frame_id, 1);
+            let change = diff
+                .metadata_changed
+                .iter()
+                .find(|change| change.frame_id == 0)
+                .expect("frame 0 metadata change");
+            assert!(change.tags_added.contains(&"final".to_string()));
+            assert!(change.tags_removed.contains(&"draft".to_string()));
+            assert!(change.fields.iter().any(|field| {
+                field.field == "extra_metadata.owner"
+                    && field.before.as_deref() == Some("draft")
+                    && field.after.as_deref() == Some("final")
+            }));
+        });
+    }
+}
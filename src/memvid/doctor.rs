@@ -1,11 +1,13 @@
 use std::cmp::min;
-use std::fs::OpenOptions;
+use std::fs::{File, OpenOptions};
 use std::io::{Read, Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
 use std::time::Instant;
 
+use crate::constants::HEADER_SIZE;
 use crate::error::{MemvidError, Result};
 use crate::io::header::HeaderCodec;
+use crate::io::region::RegionCrypto;
 use crate::io::time_index::{calculate_checksum as time_index_checksum, read_track};
 use crate::io::wal::EmbeddedWal;
 use crate::memvid::lifecycle::{Memvid, ensure_single_file, read_toc, recover_toc};
@@ -52,10 +54,29 @@ pub(crate) fn doctor_plan(path: &Path, options: DoctorOptions) -> Result<DoctorP
         path, options.rebuild_time_index, options.rebuild_lex_index, options.rebuild_vec_index,
     );
     ensure_single_file(path)?;
+    ensure_plaintext(path)?;
     let planner = DoctorPlanner::new(path.to_path_buf(), options);
     planner.compute()
 }
 
+/// Doctor rewrites regions in place without a key, so encrypted memories are refused.
+fn ensure_plaintext(path: &Path) -> Result<()> {
+    let mut bytes = [0u8; HEADER_SIZE];
+    if File::open(path)
+        .and_then(|mut file| file.read_exact(&mut bytes))
+        .is_err()
+    {
+        return Ok(());
+    }
+    match HeaderCodec::decode(&bytes) {
+        Ok(header) if header.encryption.is_some() => Err(MemvidError::EncryptedFile {
+            path: path.to_path_buf(),
+            hint: "doctor cannot repair encrypted memories".to_string(),
+        }),
+        _ => Ok(()),
+    }
+}
+
 /// Attempt to recover from WAL corruption by rebuilding a clean WAL
 fn try_recover_from_wal_corruption(path: &Path) -> Result<Memvid> {
     use fs2::FileExt;
@@ -106,6 +127,7 @@ fn try_recover_from_wal_corruption(path: &Path) -> Result<Memvid> {
 }
 
 pub(crate) fn doctor_apply(path: &Path, plan: DoctorPlan) -> Result<DoctorReport> {
+    ensure_plaintext(path)?;
     if plan.options.dry_run {
         let findings = plan.findings.clone();
         let status = if plan.is_noop() {
@@ -366,27 +388,32 @@ impl DoctorPlanner {
         );
 
         println!("doctor: attempting read_toc");
-        let (toc, toc_offset, recovered) = match read_toc(&mut file, header) {
-            Ok(toc) => (toc, header.footer_offset, false),
-            Err(_) => match recover_toc(&mut file, Some(header.footer_offset)) {
-                Ok((toc, offset)) => {
-                    println!("doctor: recover_toc succeeded at offset {}", offset);
-                    probe.findings.push(DoctorFinding::warning(
-                        DoctorFindingCode::TocDecodeFailure,
-                        "recovered toc from trailer",
-                    ));
-                    (toc, offset, true)
-                }
-                Err(err) => {
-                    println!("doctor: recover_toc failed: {}", err);
-                    probe.findings.push(DoctorFinding::error(
-                        DoctorFindingCode::TocDecodeFailure,
-                        err.to_string(),
-                    ));
-                    return Ok(probe);
-                }
-            },
-        };
+        let (toc, toc_offset, recovered) =
+            match read_toc(&mut file, header, &RegionCrypto::plaintext()) {
+                Ok(toc) => (toc, header.footer_offset, false),
+                Err(_) => match recover_toc(
+                    &mut file,
+                    Some(header.footer_offset),
+                    &RegionCrypto::plaintext(),
+                ) {
+                    Ok((toc, offset)) => {
+                        println!("doctor: recover_toc succeeded at offset {}", offset);
+                        probe.findings.push(DoctorFinding::warning(
+                            DoctorFindingCode::TocDecodeFailure,
+                            "recovered toc from trailer",
+                        ));
+                        (toc, offset, true)
+                    }
+                    Err(err) => {
+                        println!("doctor: recover_toc failed: {}", err);
+                        probe.findings.push(DoctorFinding::error(
+                            DoctorFindingCode::TocDecodeFailure,
+                            err.to_string(),
+                        ));
+                        return Ok(probe);
+                    }
+                },
+            };
         probe.toc_recovered = recovered;
         probe.toc = Some(toc.clone());
         probe.toc_offset = Some(toc_offset);
@@ -423,6 +450,13 @@ impl DoctorPlanner {
             ));
         }
 
+        if let Err(err) = toc.frames.load_all() {
+            probe.findings.push(DoctorFinding::error(
+                DoctorFindingCode::TocDecodeFailure,
+                format!("frame table unreadable: {err}"),
+            ));
+        }
+
         match EmbeddedWal::open(&file, header) {
             Ok(mut wal) => {
                 println!("doctor: embedded wal open success");
@@ -1326,11 +1360,14 @@ impl DoctorExecutor {
                 })
             }
             DoctorActionKind::VacuumCompaction => {
-                mem.vacuum()?;
+                let report = mem.vacuum()?;
                 Ok(DoctorActionReport {
                     action: action.action,
                     status: DoctorActionStatus::Executed,
-                    detail: Some("vacuum completed".into()),
+                    detail: Some(format!(
+                        "vacuum completed: {} frames retained, {} bytes reclaimed",
+                        report.frames_retained, report.bytes_reclaimed
+                    )),
                 })
             }
             DoctorActionKind::RecomputeToc => {
@@ -15,7 +15,7 @@ use crate::enrichment_worker::{
 };
 use crate::error::Result;
 use crate::extract_budgeted::ExtractionBudget;
-use crate::types::{EnrichmentState, EnrichmentTask, FrameId, FrameStatus, VecEmbedder};
+use crate::types::{EnrichmentState, EnrichmentTask, Frame, FrameId, FrameStatus, VecEmbedder};
 use crate::vec::VecIndexBuilder;
 
 use super::Memvid;
@@ -236,12 +236,13 @@ impl Memvid {
     /// Read frame data needed for enrichment.
     ///
     /// Returns (search_text, is_skim, needs_embedding) if frame exists.
-    pub fn read_frame_for_enrichment(&self, frame_id: FrameId) -> Option<(String, bool, bool)> {
-        let frame = self
-            .toc
-            .frames
-            .iter()
-            .find(|f| f.id == frame_id && f.status == FrameStatus::Active)?;
+    pub fn read_frame_for_enrichment(
+        &self,
+        frame_id: FrameId,
+    ) -> Result<Option<(String, bool, bool)>> {
+        let Some(frame) = self.active_frame(frame_id)? else {
+            return Ok(None);
+        };
 
         let search_text = frame.search_text.clone().unwrap_or_default();
 
@@ -255,7 +256,15 @@ impl Memvid {
         // Check if embeddings are needed
         let needs_embedding = frame.enrichment_state == EnrichmentState::Searchable;
 
-        Some((search_text, is_skim, needs_embedding))
+        Ok(Some((search_text, is_skim, needs_embedding)))
+    }
+
+    fn active_frame(&self, frame_id: FrameId) -> Result<Option<&Frame>> {
+        Ok(self
+            .toc
+            .frames
+            .try_get(frame_id as usize)?
+            .filter(|f| f.id == frame_id && f.status == FrameStatus::Active))
     }
 
     /// Perform full text extraction for a frame.
@@ -264,15 +273,12 @@ impl Memvid {
     pub fn extract_full_text(&mut self, frame_id: FrameId) -> Result<String> {
         // Clone the frame to avoid borrow conflicts
         let frame = self
-            .toc
-            .frames
-            .iter()
-            .find(|f| f.id == frame_id && f.status == FrameStatus::Active)
+            .active_frame(frame_id)?
             .cloned()
             .ok_or_else(|| crate::MemvidError::FrameNotFound { frame_id })?;
 
         // Read the payload
-        let payload = self.read_frame_payload_bytes(&frame)?;
+        let payload = self.frame_canonical_bytes(&frame)?;
 
         // Extract with no time budget
         let mime_hint = frame.metadata.as_ref().and_then(|m| m.mime.as_deref());
@@ -292,19 +298,18 @@ impl Memvid {
     /// Update the Tantivy index with enriched content.
     #[cfg(feature = "lex")]
     pub fn update_tantivy_for_enrichment(&mut self, frame_id: FrameId, text: &str) -> Result<()> {
-        let tantivy = match self.tantivy.as_mut() {
-            Some(t) => t,
-            None => return Ok(()), // No Tantivy engine, nothing to update
-        };
+        if self.tantivy.is_none() {
+            return Ok(()); // No Tantivy engine, nothing to update
+        }
 
         // Find the frame
         let frame = self
-            .toc
-            .frames
-            .iter()
-            .find(|f| f.id == frame_id && f.status == FrameStatus::Active)
+            .active_frame(frame_id)?
             .ok_or_else(|| crate::MemvidError::FrameNotFound { frame_id })?
             .clone();
+        let Some(tantivy) = self.tantivy.as_mut() else {
+            return Ok(());
+        };
 
         // Delete old document
         tantivy.delete_frame(frame_id)?;
@@ -325,16 +330,15 @@ impl Memvid {
     }
 
     /// Update frame's enrichment state.
-    pub fn mark_frame_enriched(&mut self, frame_id: FrameId) {
-        if let Some(frame) = self
-            .toc
-            .frames
-            .iter_mut()
-            .find(|f| f.id == frame_id && f.status == FrameStatus::Active)
-        {
+    pub fn mark_frame_enriched(&mut self, frame_id: FrameId) -> Result<()> {
+        if self.active_frame(frame_id)?.is_none() {
+            return Ok(());
+        }
+        if let Some(frame) = self.toc.frames.try_get_mut(frame_id as usize)? {
             frame.enrichment_state = EnrichmentState::Enriched;
             self.dirty = true;
         }
+        Ok(())
     }
 
     /// Process a single enrichment task synchronously.
@@ -346,8 +350,8 @@ impl Memvid {
 
         // Process with closures that capture self
         let (search_text, is_skim, _needs_embedding) = match frame_data {
-            Some(data) => data,
-            None => {
+            Ok(Some(data)) => data,
+            Ok(None) => {
                 return TaskResult {
                     frame_id: task.frame_id,
                     re_extracted: false,
@@ -356,6 +360,15 @@ impl Memvid {
                     error: Some("Frame not found".to_string()),
                 };
             }
+            Err(err) => {
+                return TaskResult {
+                    frame_id: task.frame_id,
+                    re_extracted: false,
+                    embeddings_generated: 0,
+                    elapsed_ms: 0,
+                    error: Some(format!("Frame read failed: {err}")),
+                };
+            }
         };
 
         let start = std::time::Instant::now();
@@ -393,7 +406,9 @@ impl Memvid {
         }
 
         // Mark frame as enriched
-        self.mark_frame_enriched(task.frame_id);
+        if let Err(err) = self.mark_frame_enriched(task.frame_id) {
+            result.error = Some(format!("Marking frame enriched failed: {err}"));
+        }
 
         result.elapsed_ms = start.elapsed().as_millis() as u64;
         result
@@ -431,29 +446,26 @@ impl Memvid {
     }
 
     /// Get enrichment statistics.
-    pub fn enrichment_stats(&self) -> EnrichmentStats {
-        let total_frames = self
-            .toc
-            .frames
-            .iter()
-            .filter(|f| f.status == FrameStatus::Active)
-            .count();
-        let enriched_frames = self
-            .toc
-            .frames
-            .iter()
-            .filter(|f| {
-                f.status == FrameStatus::Active && f.enrichment_state == EnrichmentState::Enriched
-            })
-            .count();
+    pub fn enrichment_stats(&self) -> Result<EnrichmentStats> {
+        let mut total_frames = 0;
+        let mut enriched_frames = 0;
+        for frame in self.toc.frames.try_iter() {
+            let frame = frame?;
+            if frame.status == FrameStatus::Active {
+                total_frames += 1;
+                if frame.enrichment_state == EnrichmentState::Enriched {
+                    enriched_frames += 1;
+                }
+            }
+        }
         let pending_frames = self.enrichment_queue_len();
 
-        EnrichmentStats {
+        Ok(EnrichmentStats {
             total_frames,
             enriched_frames,
             pending_frames,
             searchable_only: total_frames.saturating_sub(enriched_frames),
-        }
+        })
     }
 
     /// Add embeddings to the vector index.
@@ -493,7 +505,7 @@ impl Memvid {
 
         // Decode and store the new index
         let new_index = crate::vec::VecIndex::decode(&artifact.bytes)?;
-        self.vec_index = Some(new_index);
+        self.vec_index = Some(Arc::new(new_index));
 
         // Update TOC with new manifest
         self.toc.indexes.vec = Some(crate::types::VecIndexManifest {
@@ -503,6 +515,8 @@ impl Memvid {
             bytes_length: artifact.bytes.len() as u64,
             checksum: artifact.checksum,
             compression_mode: crate::types::VectorCompression::None,
+            metric: self.vec_metric(),
+            hnsw: None,
         });
 
         self.dirty = true;
@@ -541,7 +555,7 @@ impl Memvid {
 
         for task in tasks {
             // Read frame data
-            let frame_data = self.read_frame_for_enrichment(task.frame_id);
+            let frame_data = self.read_frame_for_enrichment(task.frame_id)?;
             let (search_text, is_skim, needs_embedding) = match frame_data {
                 Some(data) => data,
                 None => continue, // Frame not found, skip
@@ -590,7 +604,7 @@ impl Memvid {
             }
 
             // Mark frame as enriched
-            self.mark_frame_enriched(task.frame_id);
+            self.mark_frame_enriched(task.frame_id)?;
             self.complete_enrichment_task(task.frame_id);
             frames_processed += 1;
 
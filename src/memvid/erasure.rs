@@ -0,0 +1,380 @@
+//! Right-to-erasure: removing a frame's content from every copy the file still holds.
+//!
+//! Deleting a frame only tombstones it, and vacuum only drops payloads from the latest
+//! generation. Erasure strips the frame from every index and writes a generation that no
+//! longer mentions its content. Only once that generation is on disk does it overwrite the
+//! payload bytes in place and zero the WAL and every earlier generation's TOC, frame table
+//! and index segments that could, so a crash part-way leaves a readable file.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::{Seek, SeekFrom, Write};
+use std::sync::Arc;
+
+use memmap2::Mmap;
+
+use crate::error::{MemvidError, Result};
+use crate::footer::{FOOTER_SIZE, scan_valid_footers};
+use crate::memvid::lifecycle::{Memvid, decode_toc};
+use crate::types::blob_store::decode_chunk_recipe;
+use crate::types::{
+    CanonicalEncoding, ErasureReceipt, FrameId, FrameRole, MEMVID_TOMBSTONE_REASON_KEY,
+};
+
+/// Tombstone reason recorded on erased frames.
+const ERASED_REASON: &str = "erased";
+
+impl Memvid {
+    /// Irreversibly erase a frame, its chunks and any frame sharing its stored payload.
+    ///
+    /// Pending WAL records are committed first. The erased frames stay in the frame table
+    /// as tombstones that keep their id, URI and checksum, so Merkle proofs for the other
+    /// frames still verify; everything else about them is cleared. Generations written
+    /// before the frame existed are left intact and can still be opened, later ones are
+    /// overwritten. Replay sessions and compression dictionaries are kept as recorded, and
+    /// blob store chunks are only erased once no remaining active frame references them.
+    pub fn erase_frame(&mut self, frame_id: FrameId) -> Result<ErasureReceipt> {
+        self.ensure_mutation_allowed()?;
+        self.commit()?;
+
+        // The erasure generation is rebuilt from memory, so load everything it carries.
+        self.toc.frames.load_all()?;
+        self.ensure_vec_index()?;
+        if self.clip_enabled {
+            self.ensure_clip_index()?;
+        }
+
+        let target = self.frame_by_id(frame_id)?;
+        let mut erased = BTreeSet::from([frame_id]);
+        for frame in self.toc.frames.try_iter() {
+            let frame = frame?;
+            let chunk_of_target =
+                frame.role == FrameRole::DocumentChunk && frame.parent_id == Some(frame_id);
+            let shares_payload = target.payload_length != 0
+                && frame.payload_offset == target.payload_offset
+                && frame.payload_length == target.payload_length;
+            if chunk_of_target || shares_payload {
+                erased.insert(frame.id);
+            }
+        }
+        let oldest = erased.first().copied().unwrap_or(frame_id);
+        let (wipe_from, generations_overwritten) = self.erasure_history(oldest)?;
+
+        // Recipes are zeroed with the payloads below; note their chunks first.
+        let mut erased_chunks = BTreeSet::new();
+        for &id in &erased {
+            let frame = self.frame_by_id(id)?;
+            if frame.canonical_encoding == CanonicalEncoding::Chunked && frame.payload_length != 0 {
+                let recipe = self.read_frame_payload_bytes(&frame)?;
+                erased_chunks.extend(decode_chunk_recipe(&recipe)?);
+            }
+        }
+
+        // Ranges to zero once the new generation no longer references them.
+        let mut scrubbed = BTreeSet::new();
+        for &id in &erased {
+            let frame = self.frame_by_id(id)?;
+            if frame.payload_length != 0 {
+                scrubbed.insert((frame.payload_offset, frame.payload_length));
+            }
+            self.mark_frame_deleted(id)?;
+            if let Some(clip_index) = self.clip_index.as_mut() {
+                Arc::make_mut(clip_index).remove(id);
+            }
+            Arc::make_mut(&mut self.sketch_track).clear(id);
+            Arc::make_mut(&mut self.logic_mesh).remove_frame(id);
+            self.toc.enrichment_queue.remove(id);
+            let frame =
+                self.toc
+                    .frames
+                    .try_get_mut(id as usize)?
+                    .ok_or(MemvidError::InvalidFrame {
+                        frame_id: id,
+                        reason: "erase target missing",
+                    })?;
+            frame.payload_length = 0;
+            frame.canonical_length = None;
+            frame.title = None;
+            frame.metadata = None;
+            frame.search_text = None;
+            frame.tags.clear();
+            frame.labels.clear();
+            frame.content_dates.clear();
+            frame.chunk_manifest = None;
+            frame.source_sha256 = None;
+            frame.source_path = None;
+            frame.extra_metadata = BTreeMap::from([(
+                MEMVID_TOMBSTONE_REASON_KEY.to_string(),
+                ERASED_REASON.to_string(),
+            )]);
+        }
+        let memory_cards_removed =
+            Arc::make_mut(&mut self.memories_track).remove_cards_from_frames(&erased);
+        self.log_card_removals(&memory_cards_removed);
+
+        let referenced = self.referenced_blob_chunks()?;
+        for chunk_hash in erased_chunks {
+            if referenced.contains(&chunk_hash) {
+                continue;
+            }
+            if let Some(chunk) = self.blob_store.remove(&chunk_hash) {
+                scrubbed.insert((chunk.offset, chunk.stored_length));
+            }
+        }
+        // The previous chunk index lists the erased chunks, so always write a new one.
+        self.blob_store.mark_dirty();
+
+        // Write a generation without the frame, with every index rebuilt after the old ones.
+        let erase_start = self.file.metadata()?.len();
+        self.begin_generation()?;
+        self.log_track_changes();
+        self.discard_index_segments()?;
+        self.rebuild_indexes(&[])?;
+        self.persist_sketch_track()?;
+        self.rewrite_toc_footer()?;
+        self.header.toc_checksum = self.toc.toc_checksum;
+        // The footer carries the index batches the rebuild logged.
+        self.wal.record_checkpoint(&mut self.header)?;
+        crate::persist_header(&mut self.file, &self.header)?;
+        self.file.sync_all()?;
+        self.dirty = false;
+
+        let mut payload_bytes_scrubbed = 0;
+        for &(offset, length) in &scrubbed {
+            zero_range(&mut self.file, offset, length)?;
+            payload_bytes_scrubbed += length;
+        }
+        // Checkpointed WAL entries still carry the payload; the commit above applied them.
+        self.wal.scrub(&mut self.header)?;
+        crate::persist_header(&mut self.file, &self.header)?;
+        let mut history_bytes_scrubbed = self.header.wal_size;
+
+        // Zero everything earlier generations wrote that the new one no longer references.
+        let mut live: Vec<(u64, u64)> = Vec::new();
+        for frame in self.toc.frames.try_iter() {
+            let frame = frame?;
+            if frame.payload_length != 0 {
+                live.push((
+                    frame.payload_offset,
+                    frame.payload_offset + frame.payload_length,
+                ));
+            }
+        }
+        // Unchanged frame table pages stay where earlier generations wrote them.
+        if let Some(table) = self.toc.frame_table.as_ref() {
+            live.extend(
+                table
+                    .pages
+                    .iter()
+                    .map(|page| (page.bytes_offset, page.end())),
+            );
+        }
+        if let Some(manifest) = self.toc.replay_manifest.as_ref() {
+            live.push((
+                manifest.segment_offset,
+                manifest.segment_offset + manifest.segment_size,
+            ));
+        }
+        for dictionary in &self.toc.compression.dictionaries {
+            live.push((
+                dictionary.bytes_offset,
+                dictionary.bytes_offset + dictionary.bytes_length,
+            ));
+        }
+        live.extend(
+            self.blob_store
+                .iter()
+                .map(|chunk| (chunk.offset, chunk.offset + chunk.stored_length)),
+        );
+        live.sort_unstable();
+        let mut cursor = wipe_from;
+        for (start, end) in live.into_iter().chain([(erase_start, erase_start)]) {
+            let start = start.clamp(cursor, erase_start);
+            if start > cursor {
+                zero_range(&mut self.file, cursor, start - cursor)?;
+                history_bytes_scrubbed += start - cursor;
+            }
+            cursor = cursor.max(end.min(erase_start));
+        }
+        self.file.sync_all()?;
+
+        tracing::info!(
+            frame_id,
+            frames = erased.len(),
+            payload_bytes_scrubbed,
+            history_bytes_scrubbed,
+            "erased frame"
+        );
+        Ok(ErasureReceipt {
+            frame_id,
+            erased_frames: erased.into_iter().collect(),
+            checksum: target.checksum,
+            erased_at: self.toc.committed_at.unwrap_or_default(),
+            generation: self.generation,
+            generations_overwritten,
+            payload_bytes_scrubbed,
+            history_bytes_scrubbed,
+            memory_cards_removed,
+            merkle_root: self.toc.merkle_root,
+        })
+    }
+
+    /// Find where the history that may mention `oldest` begins.
+    ///
+    /// Frame ids are assigned in order, so a generation whose frame table ends before
+    /// `oldest` predates it. Returns the end of the last such generation's footer and the
+    /// generations written after it.
+    fn erasure_history(&self, oldest: FrameId) -> Result<(u64, Vec<u64>)> {
+        // Safety: read-only mapping over committed bytes; nothing writes while it is alive.
+        let mmap = unsafe { Mmap::map(&self.file)? };
+        let mut wipe_from = self.header.wal_offset + self.header.wal_size;
+        let mut overwritten = BTreeSet::new();
+        for slice in scan_valid_footers(&mmap) {
+            let Ok(toc) = decode_toc(slice.toc_bytes, &self.regions) else {
+                continue;
+            };
+            let frame_count = toc
+                .frame_table
+                .map_or(toc.frames.len() as u64, |table| table.frame_count);
+            if frame_count > oldest {
+                overwritten.insert(slice.footer.generation);
+            } else {
+                wipe_from = wipe_from.max((slice.footer_offset + FOOTER_SIZE) as u64);
+            }
+        }
+        Ok((wipe_from, overwritten.into_iter().collect()))
+    }
+}
+
+/// Overwrite `length` bytes at `offset` with zeros.
+fn zero_range(file: &mut File, offset: u64, length: u64) -> Result<()> {
+    const CHUNK: u64 = 64 * 1024;
+    let zeros = vec![0u8; CHUNK.min(length) as usize];
+    file.seek(SeekFrom::Start(offset))?;
+    let mut remaining = length;
+    while remaining > 0 {
+        let step = remaining.min(CHUNK);
+        file.write_all(&zeros[..step as usize])?;
+        remaining -= step;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{FrameStatus, MemoryCardBuilder, SearchRequest};
+    use crate::{PutOptions, run_serial_test};
+    use tempfile::tempdir;
+
+    const SECRET: &[u8] = b"zebracorn-passport-4471";
+
+    fn put(mem: &mut Memvid, uri: &str, text: &[u8]) -> FrameId {
+        let options = PutOptions::builder()
+            .uri(uri)
+            .auto_tag(false)
+            .extract_dates(false)
+            .extract_triplets(false)
+            .instant_index(false)
+            .build();
+        mem.put_bytes_with_options(text, options).expect("put");
+        mem.commit().expect("commit");
+        mem.frame_by_uri(uri).expect("frame").id
+    }
+
+    fn hits(mem: &mut Memvid, query: &str) -> Vec<FrameId> {
+        mem.search(SearchRequest::test_query(query))
+            .expect("search")
+            .hits
+            .into_iter()
+            .map(|hit| hit.frame_id)
+            .collect()
+    }
+
+    fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+        haystack
+            .windows(needle.len())
+            .any(|window| window == needle)
+    }
+
+    #[test]
+    fn erase_frame_removes_content_from_the_file() {
+        run_serial_test(|| {
+            let dir = tempdir().expect("tmp");
+            let path = dir.path().join("erase.mv2");
+            let mut mem = Memvid::create(&path).expect("create");
+            let before = put(
+                &mut mem,
+                "mv2://docs/before",
+                b"lighthouse maintenance schedule",
+            );
+            let mut secret = b"customer record ".to_vec();
+            secret.extend_from_slice(SECRET);
+            let erased = put(&mut mem, "mv2://docs/secret", &secret);
+            let after = put(
+                &mut mem,
+                "mv2://docs/after",
+                b"harbour crane inspection report",
+            );
+            let card = MemoryCardBuilder::new()
+                .fact()
+                .entity("customer")
+                .slot("passport")
+                .value("zebracorn")
+                .source(erased, None)
+                .engine("rules-v1", "1.0.0")
+                .build(0)
+                .expect("card");
+            mem.put_memory_card(card).expect("card");
+            mem.commit().expect("commit");
+            let checksum = mem.frame_by_id(erased).expect("frame").checksum;
+            assert!(contains(&std::fs::read(&path).expect("read"), SECRET));
+            assert_eq!(hits(&mut mem, "zebracorn"), [erased]);
+
+            let receipt = mem.erase_frame(erased).expect("erase");
+            assert_eq!(receipt.erased_frames, [erased]);
+            assert_eq!(receipt.checksum, checksum);
+            assert_eq!(receipt.memory_cards_removed.len(), 1);
+            assert!(receipt.payload_bytes_scrubbed > 0);
+            assert!(receipt.history_bytes_scrubbed > 0);
+            assert!(!receipt.generations_overwritten.is_empty());
+            assert_eq!(receipt.merkle_root, mem.toc.merkle_root);
+            let bytes = std::fs::read(&path).expect("read");
+            assert!(!contains(&bytes, SECRET));
+            assert!(!contains(&bytes, b"zebracorn"));
+            assert!(hits(&mut mem, "zebracorn").is_empty());
+            assert_eq!(hits(&mut mem, "crane"), [after]);
+            drop(mem);
+
+            let mut mem = Memvid::open(&path).expect("reopen");
+            let frame = mem.frame_by_id(erased).expect("frame");
+            assert_eq!(frame.status, FrameStatus::Deleted);
+            assert_eq!(frame.payload_length, 0);
+            assert_eq!(
+                frame
+                    .extra_metadata
+                    .get(MEMVID_TOMBSTONE_REASON_KEY)
+                    .map(String::as_str),
+                Some(ERASED_REASON)
+            );
+            assert_eq!(mem.memory_card_count(), 0);
+            assert_eq!(
+                mem.frame_canonical_payload(before).expect("payload"),
+                b"lighthouse maintenance schedule"
+            );
+            assert_eq!(hits(&mut mem, "crane"), [after]);
+            // Generations written before the frame existed survive; later ones are gone.
+            let generations = mem.list_generations().expect("generations");
+            assert!(generations.iter().any(|info| info.frame_count == 1));
+            assert!(generations.iter().all(|info| info.frame_count <= erased
+                || info.generation >= receipt.generation));
+            assert_eq!(
+                generations.last().map(|info| info.generation),
+                Some(receipt.generation)
+            );
+
+            put(&mut mem, "mv2://docs/later", b"ferry timetable");
+            assert_eq!(hits(&mut mem, "ferry").len(), 1);
+        });
+    }
+}
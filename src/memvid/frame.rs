@@ -1,11 +1,22 @@
 //! Frame payload and preview helpers for `Memvid`.
 
+use std::borrow::Cow;
 use std::fs::File;
 use std::io::{self, Cursor, Read, Seek, SeekFrom};
+#[cfg(feature = "mmap")]
+use std::sync::Arc;
+
+use blake3::hash;
 
 use crate::error::{MemvidError, Result};
+use crate::io::read_at::{read_at, read_exact_at};
+use crate::io::region::{RegionCrypto, RegionKind};
 use crate::memvid::lifecycle::Memvid;
-use crate::types::{CanonicalEncoding, Frame, FrameId, FrameRole, FrameStatus, MediaManifest};
+use crate::merkle::{frame_leaves, merkle_root, sibling_path};
+use crate::types::blob_store::decode_chunk_recipe;
+use crate::types::{
+    BlobChunk, CanonicalEncoding, Frame, FrameId, FrameProof, FrameRole, FrameStatus, MediaManifest,
+};
 
 #[derive(Debug, Clone)]
 pub(crate) struct ChunkInfo {
@@ -15,7 +26,7 @@ pub(crate) struct ChunkInfo {
 }
 
 /// Streaming reader over canonical frame bytes. For binary payloads (e.g., video) the reader
-/// clones the underlying file handle to avoid disturbing `Memvid`'s primary cursor.
+/// clones the underlying file handle and reads at offsets, leaving every cursor alone.
 pub struct BlobReader {
     inner: BlobReaderInner,
 }
@@ -28,6 +39,26 @@ enum BlobReaderInner {
         pos: u64,
     },
     Memory(Cursor<Vec<u8>>),
+    /// Slice of the file mapping of a read-only open.
+    #[cfg(feature = "mmap")]
+    Mapped {
+        map: Arc<memmap2::Mmap>,
+        start: u64,
+        len: u64,
+        pos: u64,
+    },
+    /// Chunked payloads decode one blob store chunk at a time.
+    Chunks {
+        file: File,
+        regions: RegionCrypto,
+        frame_id: FrameId,
+        chunks: Vec<BlobChunk>,
+        /// Offset of each chunk within the payload.
+        starts: Vec<u64>,
+        len: u64,
+        pos: u64,
+        current: Option<(usize, Vec<u8>)>,
+    },
 }
 
 impl BlobReader {
@@ -48,15 +79,106 @@ impl BlobReader {
         }
     }
 
+    #[cfg(feature = "mmap")]
+    fn from_map(map: Arc<memmap2::Mmap>, start: u64, len: u64) -> Self {
+        Self {
+            inner: BlobReaderInner::Mapped {
+                map,
+                start,
+                len,
+                pos: 0,
+            },
+        }
+    }
+
+    fn from_chunks(
+        file: File,
+        regions: RegionCrypto,
+        frame_id: FrameId,
+        chunks: Vec<BlobChunk>,
+    ) -> Self {
+        let mut starts = Vec::with_capacity(chunks.len());
+        let mut len = 0u64;
+        for chunk in &chunks {
+            starts.push(len);
+            len += chunk.raw_length;
+        }
+        Self {
+            inner: BlobReaderInner::Chunks {
+                file,
+                regions,
+                frame_id,
+                chunks,
+                starts,
+                len,
+                pos: 0,
+                current: None,
+            },
+        }
+    }
+
     #[must_use]
     pub fn len(&self) -> u64 {
         match &self.inner {
-            BlobReaderInner::File { len, .. } => *len,
+            BlobReaderInner::File { len, .. } | BlobReaderInner::Chunks { len, .. } => *len,
+            #[cfg(feature = "mmap")]
+            BlobReaderInner::Mapped { len, .. } => *len,
             BlobReaderInner::Memory(cursor) => cursor.get_ref().len() as u64,
         }
     }
 }
 
+/// Read, decode and verify one blob store chunk.
+fn load_chunk(
+    file: &File,
+    regions: &RegionCrypto,
+    frame_id: FrameId,
+    chunk: &BlobChunk,
+) -> io::Result<Vec<u8>> {
+    let invalid = |err: MemvidError| io::Error::new(io::ErrorKind::InvalidData, err.to_string());
+    let mut stored = vec![0u8; chunk.stored_length as usize];
+    read_exact_at(file, &mut stored, chunk.offset)?;
+    let stored = regions
+        .open_vec(RegionKind::Payload, stored)
+        .map_err(invalid)?;
+    let raw = crate::decode_canonical_bytes(&stored, chunk.encoding, frame_id).map_err(invalid)?;
+    if *hash(&raw).as_bytes() != chunk.hash {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "chunk does not match its hash",
+        ));
+    }
+    Ok(raw)
+}
+
+/// Resolve `position` against a reader at `pos` over `len` bytes.
+fn seek_target(position: SeekFrom, pos: u64, len: u64) -> io::Result<u64> {
+    let relative = |base: u64, delta: i64| {
+        let result = (base as i64)
+            .checked_add(delta)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "seek overflow"))?;
+        if result < 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "seek before start",
+            ));
+        }
+        Ok(result as u64)
+    };
+    let absolute = match position {
+        SeekFrom::Start(offset) => offset,
+        SeekFrom::End(delta) => relative(len, delta)?,
+        SeekFrom::Current(delta) => relative(pos, delta)?,
+    };
+    if absolute > len {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "seek beyond end",
+        ));
+    }
+    Ok(absolute)
+}
+
 impl Read for BlobReader {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
         match &mut self.inner {
@@ -71,12 +193,51 @@ impl Read for BlobReader {
                     return Ok(0);
                 }
                 let to_read = remaining.min(buf.len() as u64) as usize;
-                file.seek(SeekFrom::Start(*start + *pos))?;
-                let read = file.read(&mut buf[..to_read])?;
+                let read = read_at(file, &mut buf[..to_read], *start + *pos)?;
                 *pos += read as u64;
                 Ok(read)
             }
             BlobReaderInner::Memory(cursor) => cursor.read(buf),
+            #[cfg(feature = "mmap")]
+            BlobReaderInner::Mapped {
+                map,
+                start,
+                len,
+                pos,
+            } => {
+                let read = len.saturating_sub(*pos).min(buf.len() as u64) as usize;
+                let from = (*start + *pos) as usize;
+                buf[..read].copy_from_slice(&map[from..from + read]);
+                *pos += read as u64;
+                Ok(read)
+            }
+            BlobReaderInner::Chunks {
+                file,
+                regions,
+                frame_id,
+                chunks,
+                starts,
+                len,
+                pos,
+                current,
+            } => {
+                if *pos >= *len || buf.is_empty() {
+                    return Ok(0);
+                }
+                let index = starts.partition_point(|start| *start <= *pos) - 1;
+                if current.as_ref().is_none_or(|(loaded, _)| *loaded != index) {
+                    let raw = load_chunk(file, regions, *frame_id, &chunks[index])?;
+                    *current = Some((index, raw));
+                }
+                let Some((_, raw)) = current.as_ref() else {
+                    return Ok(0);
+                };
+                let within = (*pos - starts[index]) as usize;
+                let read = (raw.len() - within).min(buf.len());
+                buf[..read].copy_from_slice(&raw[within..within + read]);
+                *pos += read as u64;
+                Ok(read)
+            }
         }
     }
 }
@@ -84,54 +245,20 @@ impl Read for BlobReader {
 impl Seek for BlobReader {
     fn seek(&mut self, position: SeekFrom) -> io::Result<u64> {
         match &mut self.inner {
-            BlobReaderInner::File {
-                file,
-                start,
-                len,
-                pos,
-            } => {
-                let absolute = match position {
-                    SeekFrom::Start(offset) => offset,
-                    SeekFrom::End(delta) => {
-                        let end = *len as i64;
-                        let result = end.checked_add(delta).ok_or_else(|| {
-                            io::Error::new(io::ErrorKind::InvalidInput, "seek overflow")
-                        })?;
-                        if result < 0 {
-                            return Err(io::Error::new(
-                                io::ErrorKind::InvalidInput,
-                                "seek before start",
-                            ));
-                        }
-                        result as u64
-                    }
-                    SeekFrom::Current(delta) => {
-                        let current = *pos as i64;
-                        let result = current.checked_add(delta).ok_or_else(|| {
-                            io::Error::new(io::ErrorKind::InvalidInput, "seek overflow")
-                        })?;
-                        if result < 0 {
-                            return Err(io::Error::new(
-                                io::ErrorKind::InvalidInput,
-                                "seek before start",
-                            ));
-                        }
-                        result as u64
-                    }
-                };
-
-                if absolute > *len {
-                    return Err(io::Error::new(
-                        io::ErrorKind::InvalidInput,
-                        "seek beyond end",
-                    ));
-                }
-
-                *pos = absolute;
-                file.seek(SeekFrom::Start(*start + *pos))?;
+            BlobReaderInner::File { len, pos, .. } => {
+                *pos = seek_target(position, *pos, *len)?;
                 Ok(*pos)
             }
             BlobReaderInner::Memory(cursor) => cursor.seek(position),
+            #[cfg(feature = "mmap")]
+            BlobReaderInner::Mapped { len, pos, .. } => {
+                *pos = seek_target(position, *pos, *len)?;
+                Ok(*pos)
+            }
+            BlobReaderInner::Chunks { len, pos, .. } => {
+                *pos = seek_target(position, *pos, *len)?;
+                Ok(*pos)
+            }
         }
     }
 }
@@ -163,51 +290,80 @@ impl Memvid {
     pub fn frame_by_id(&self, frame_id: FrameId) -> Result<Frame> {
         self.toc
             .frames
-            .get(frame_id as usize)
+            .try_get(frame_id as usize)?
             .cloned()
             .ok_or(MemvidError::FrameNotFound { frame_id })
     }
 
     pub fn frame_by_uri(&self, uri: &str) -> Result<Frame> {
-        let candidate = self
-            .toc
-            .frames
-            .iter()
-            .rev()
-            .find(|frame| {
-                frame
-                    .uri
-                    .as_deref()
-                    .map(|candidate_uri| candidate_uri == uri)
-                    .unwrap_or(false)
-                    && frame.status == FrameStatus::Active
-            })
-            .or_else(|| {
-                self.toc
-                    .frames
-                    .iter()
-                    .rev()
-                    .find(|frame| frame.uri.as_deref() == Some(uri))
-            })
-            .cloned();
+        // Prefer the newest active frame, then the newest frame of any status.
+        let mut candidate = None;
+        for frame in self.toc.frames.try_iter().rev() {
+            let frame = frame?;
+            if frame.uri.as_deref() != Some(uri) {
+                continue;
+            }
+            if frame.status == FrameStatus::Active {
+                candidate = Some(frame);
+                break;
+            }
+            candidate = candidate.or(Some(frame));
+        }
+        let candidate = candidate.cloned();
 
         candidate.ok_or_else(|| MemvidError::FrameNotFoundByUri {
             uri: uri.to_string(),
         })
     }
 
+    /// Merkle inclusion proof that `frame_id` is covered by the TOC's `merkle_root`.
+    ///
+    /// The proof can be checked without the file using [`crate::verify_frame_proof`].
+    pub fn frame_proof(&self, frame_id: FrameId) -> Result<FrameProof> {
+        let leaves = self.merkle_leaves()?;
+        self.frame_proof_from_leaves(&leaves, frame_id)
+    }
+
+    /// Leaf hashes of the frame table, checked against the committed `merkle_root`.
+    pub(crate) fn merkle_leaves(&self) -> Result<Vec<[u8; 32]>> {
+        let leaves = frame_leaves(&self.toc.frames)?;
+        if merkle_root(&leaves) != self.toc.merkle_root {
+            return Err(MemvidError::InvalidToc {
+                reason: "merkle root does not cover the frame table; commit to refresh it".into(),
+            });
+        }
+        Ok(leaves)
+    }
+
+    pub(crate) fn frame_proof_from_leaves(
+        &self,
+        leaves: &[[u8; 32]],
+        frame_id: FrameId,
+    ) -> Result<FrameProof> {
+        let frame = self.frame_by_id(frame_id)?;
+        Ok(FrameProof {
+            frame_id,
+            leaf_count: leaves.len() as u64,
+            checksum: frame.checksum,
+            encoding: frame.canonical_encoding,
+            siblings: sibling_path(leaves, frame_id as usize),
+        })
+    }
+
     /// Find an active frame by its content BLAKE3 hash.
     ///
     /// This is used for deduplication - if a frame with the same content hash already exists,
     /// we can skip re-ingestion. The hash is computed from the original file bytes.
     ///
     /// Returns `None` if no matching frame is found.
-    pub fn find_frame_by_hash(&self, hash: &[u8; 32]) -> Option<&Frame> {
-        self.toc
-            .frames
-            .iter()
-            .rev()
-            .find(|frame| frame.status == FrameStatus::Active && frame.checksum == *hash)
+    pub fn find_frame_by_hash(&self, hash: &[u8; 32]) -> Result<Option<&Frame>> {
+        for frame in self.toc.frames.try_iter().rev() {
+            let frame = frame?;
+            if frame.status == FrameStatus::Active && frame.checksum == *hash {
+                return Ok(Some(frame));
+            }
+        }
+        Ok(None)
     }
 
     pub fn blob_reader(&mut self, frame_id: FrameId) -> Result<BlobReader> {
@@ -232,16 +388,51 @@ impl Memvid {
 
     fn blob_reader_from_frame(&mut self, frame: Frame) -> Result<BlobReader> {
         match frame.canonical_encoding {
+            // Encrypted payloads are authenticated as a whole, so they cannot be streamed.
+            CanonicalEncoding::Plain if self.regions.is_encrypted() => Ok(BlobReader::from_memory(
+                self.read_frame_payload_bytes(&frame)?,
+            )),
             CanonicalEncoding::Plain => {
-                let mut file = self.file.try_clone()?;
-                file.seek(SeekFrom::Start(frame.payload_offset))?;
+                #[cfg(feature = "mmap")]
+                if let Some(mapped) = self.mapped.as_mut() {
+                    mapped.refresh(&self.file)?;
+                    let map = mapped.shared();
+                    self.validate_frame_bounds(&frame)?;
+                    return Ok(BlobReader::from_map(
+                        map,
+                        frame.payload_offset,
+                        frame.payload_length,
+                    ));
+                }
                 Ok(BlobReader::from_file(
-                    file,
+                    self.file.try_clone()?,
                     frame.payload_offset,
                     frame.payload_length,
                 ))
             }
-            CanonicalEncoding::Zstd => {
+            // Read from the blob store chunk by chunk, bypassing any extracted text chunks.
+            CanonicalEncoding::Chunked => {
+                let recipe = self.read_frame_payload_bytes(&frame)?;
+                let chunks = decode_chunk_recipe(&recipe)?
+                    .iter()
+                    .map(|chunk_hash| {
+                        self.blob_store
+                            .get(chunk_hash)
+                            .copied()
+                            .ok_or(MemvidError::InvalidFrame {
+                                frame_id: frame.id,
+                                reason: "chunk missing from the blob store",
+                            })
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(BlobReader::from_chunks(
+                    self.file.try_clone()?,
+                    self.regions.clone(),
+                    frame.id,
+                    chunks,
+                ))
+            }
+            CanonicalEncoding::Zstd | CanonicalEncoding::Lz4 | CanonicalEncoding::ZstdDict => {
                 let bytes = self.frame_canonical_bytes(&frame)?;
                 Ok(BlobReader::from_memory(bytes))
             }
@@ -254,7 +445,7 @@ impl Memvid {
     }
 
     pub fn frame_preview_by_id(&mut self, frame_id: FrameId) -> Result<String> {
-        let frame = self.toc.frames.get(frame_id as usize).cloned().ok_or(
+        let frame = self.toc.frames.try_get(frame_id as usize)?.cloned().ok_or(
             MemvidError::InvalidTimeIndex {
                 reason: "frame id out of range".into(),
             },
@@ -267,7 +458,7 @@ impl Memvid {
     /// Unlike `frame_preview_by_id` which truncates for display purposes,
     /// this returns the complete text content suitable for LLM processing.
     pub fn frame_text_by_id(&mut self, frame_id: FrameId) -> Result<String> {
-        let frame = self.toc.frames.get(frame_id as usize).cloned().ok_or(
+        let frame = self.toc.frames.try_get(frame_id as usize)?.cloned().ok_or(
             MemvidError::InvalidTimeIndex {
                 reason: "frame id out of range".into(),
             },
@@ -345,7 +536,7 @@ impl Memvid {
     }
 
     pub fn frame_context(&mut self, frame_id: FrameId, query: &str) -> Result<(String, usize)> {
-        let frame = self.toc.frames.get(frame_id as usize).cloned().ok_or(
+        let frame = self.toc.frames.try_get(frame_id as usize)?.cloned().ok_or(
             MemvidError::InvalidTimeIndex {
                 reason: "frame id out of range".into(),
             },
@@ -372,8 +563,16 @@ impl Memvid {
                 return Ok(buffer);
             }
         }
-        let raw = self.read_frame_payload_bytes(frame)?;
-        let decoded = crate::decode_canonical_bytes(&raw, frame.canonical_encoding, frame.id)?;
+        let decoded = match frame.canonical_encoding {
+            CanonicalEncoding::ZstdDict | CanonicalEncoding::Chunked => {
+                let raw = self.read_frame_payload_bytes(frame)?;
+                self.decode_canonical(&raw, frame.canonical_encoding, frame.id)?
+            }
+            encoding => {
+                let raw = self.frame_payload(frame)?;
+                crate::decode_canonical_bytes(&raw, encoding, frame.id)?
+            }
+        };
         if let Some(expected) = frame.canonical_length {
             if decoded.len() as u64 != expected {
                 return Err(MemvidError::InvalidFrame {
@@ -435,7 +634,7 @@ impl Memvid {
                 reason: "document missing chunk manifest",
             });
         };
-        let mut children = self.document_chunk_frames(frame.id);
+        let mut children = self.document_chunk_frames(frame.id)?;
         if children.is_empty() {
             return Err(MemvidError::InvalidFrame {
                 frame_id: frame.id,
@@ -452,7 +651,7 @@ impl Memvid {
         let mut payloads = Vec::with_capacity(children.len());
         for child in children {
             let raw = self.read_frame_payload_bytes(&child)?;
-            let decoded = crate::decode_canonical_bytes(&raw, child.canonical_encoding, child.id)?;
+            let decoded = self.decode_canonical(&raw, child.canonical_encoding, child.id)?;
             if let Some(expected) = child.canonical_length {
                 if decoded.len() as u64 != expected {
                     return Err(MemvidError::InvalidFrame {
@@ -466,20 +665,19 @@ impl Memvid {
         Ok(payloads)
     }
 
-    fn document_chunk_frames(&self, parent_id: FrameId) -> Vec<Frame> {
-        let mut frames: Vec<Frame> = self
-            .toc
-            .frames
-            .iter()
-            .filter(|candidate| {
-                candidate.status == FrameStatus::Active
-                    && candidate.role == FrameRole::DocumentChunk
-                    && candidate.parent_id == Some(parent_id)
-            })
-            .cloned()
-            .collect();
+    fn document_chunk_frames(&self, parent_id: FrameId) -> Result<Vec<Frame>> {
+        let mut frames = Vec::new();
+        for candidate in self.toc.frames.try_iter() {
+            let candidate = candidate?;
+            if candidate.status == FrameStatus::Active
+                && candidate.role == FrameRole::DocumentChunk
+                && candidate.parent_id == Some(parent_id)
+            {
+                frames.push(candidate.clone());
+            }
+        }
         frames.sort_by_key(|frame| (frame.chunk_index.unwrap_or(u32::MAX), frame.id));
-        frames
+        Ok(frames)
     }
 
     pub(crate) fn resolve_chunk_context(&mut self, frame: &Frame) -> Result<ChunkInfo> {
@@ -530,7 +728,7 @@ impl Memvid {
             FrameRole::DocumentChunk => {
                 // Try to resolve via parent's chunk manifest (new format)
                 if let Some(parent_id) = frame.parent_id {
-                    if let Some(parent) = self.toc.frames.get(parent_id as usize).cloned() {
+                    if let Some(parent) = self.toc.frames.try_get(parent_id as usize)?.cloned() {
                         if parent.chunk_manifest.is_some() {
                             if let Ok(payloads) = self.document_chunk_payloads(&parent) {
                                 if let Some(idx) = frame.chunk_index {
@@ -587,11 +785,17 @@ impl Memvid {
     }
 
     pub(crate) fn read_frame_payload_bytes(&mut self, frame: &Frame) -> Result<Vec<u8>> {
+        self.frame_payload(frame).map(Cow::into_owned)
+    }
+
+    /// Stored payload of `frame`, borrowed from the file mapping when there is one.
+    pub(crate) fn frame_payload(&mut self, frame: &Frame) -> Result<Cow<'_, [u8]>> {
         self.validate_frame_bounds(frame)?;
-        self.file.seek(SeekFrom::Start(frame.payload_offset))?;
-        let mut buf = vec![0u8; frame.payload_length as usize];
-        self.file.read_exact(&mut buf)?;
-        Ok(buf)
+        self.region_bytes(
+            RegionKind::Payload,
+            frame.payload_offset,
+            frame.payload_length,
+        )
     }
 
     pub(crate) fn validate_frame_bounds(&mut self, frame: &Frame) -> Result<()> {
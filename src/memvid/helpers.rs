@@ -86,12 +86,15 @@ impl Memvid {
     /// - `Unknown` if no identity metadata is present
     /// - `Single` if exactly one identity is observed
     /// - `Mixed` if multiple identities are observed (counts included, descending)
-    #[must_use]
-    pub fn embedding_identity_summary(&self, max_frames: usize) -> EmbeddingIdentitySummary {
+    pub fn embedding_identity_summary(
+        &self,
+        max_frames: usize,
+    ) -> Result<EmbeddingIdentitySummary> {
         let mut counts: HashMap<EmbeddingIdentity, u64> = HashMap::new();
         let mut scanned = 0usize;
 
-        for frame in &self.toc.frames {
+        for frame in self.toc.frames.try_iter() {
+            let frame = frame?;
             if frame.status != FrameStatus::Active {
                 continue;
             }
@@ -106,16 +109,16 @@ impl Memvid {
         }
 
         if counts.is_empty() {
-            return EmbeddingIdentitySummary::Unknown;
+            return Ok(EmbeddingIdentitySummary::Unknown);
         }
         if counts.len() == 1 {
-            return EmbeddingIdentitySummary::Single(
+            return Ok(EmbeddingIdentitySummary::Single(
                 counts
                     .into_iter()
                     .next()
                     .map(|(identity, _)| identity)
                     .expect("counts.len()==1 implies one entry"),
-            );
+            ));
         }
 
         let mut identities: Vec<EmbeddingIdentityCount> = counts
@@ -123,7 +126,7 @@ impl Memvid {
             .map(|(identity, count)| EmbeddingIdentityCount { identity, count })
             .collect();
         identities.sort_by(|a, b| b.count.cmp(&a.count));
-        EmbeddingIdentitySummary::Mixed(identities)
+        Ok(EmbeddingIdentitySummary::Mixed(identities))
     }
 
     pub(crate) fn render_binary_summary(len: usize) -> String {
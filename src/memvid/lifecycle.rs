@@ -6,30 +6,42 @@
 //! - Validate TOC/footer layout, recover the latest valid footer when needed.
 //! - Wire up index state (lex/vector/time) without mutating payload bytes.
 
+use std::collections::{BTreeMap, HashMap};
 use std::convert::TryInto;
 use std::fs::{File, OpenOptions};
-use std::io::{Read, Seek, SeekFrom};
+use std::io::{Cursor, Read, Seek, SeekFrom};
 use std::panic;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, RwLock};
 
 use crate::constants::{MAGIC, SPEC_VERSION, WAL_OFFSET, WAL_SIZE_TINY};
 use crate::error::{MemvidError, Result};
-use crate::footer::{FooterSlice, find_last_valid_footer};
+use crate::footer::{
+    FOOTER_MAGIC, FOOTER_SIZE, FooterSlice, find_last_valid_footer, scan_valid_footers,
+};
+use crate::frame_table::FrameTable;
 use crate::io::header::HeaderCodec;
 #[cfg(feature = "parallel_segments")]
 use crate::io::manifest_wal::ManifestWal;
+#[cfg(feature = "mmap")]
+use crate::io::mapped::MappedFile;
+use crate::io::region::{RegionCrypto, RegionKind};
+use crate::io::time_index::{TimeIndexEntry, read_track as time_index_read};
 use crate::io::wal::EmbeddedWal;
 use crate::lock::{FileLock, LockMode};
+use crate::memvid::mutation::CommitSigner;
+use crate::memvid::quota::QuotaUsage;
+use crate::memvid::reader::MemvidReader;
+use crate::memvid::refresh::RefreshState;
+use crate::memvid::retention::RetentionCheck;
 #[cfg(feature = "lex")]
 use crate::search::{EmbeddedLexStorage, TantivyEngine};
-#[cfg(feature = "temporal_track")]
-use crate::types::FrameId;
 #[cfg(feature = "parallel_segments")]
 use crate::types::IndexSegmentRef;
 use crate::types::{
-    FrameStatus, Header, IndexManifests, LogicMesh, MemoriesTrack, SchemaRegistry, SegmentCatalog,
-    SketchTrack, TicketRef, Tier, Toc, VectorCompression,
+    BlobStore, ChangeLog, FrameId, FrameStatus, GenerationInfo, Header, IndexManifests, LogicMesh,
+    MemoriesTrack, RetentionReport, SchemaRegistry, SegmentCatalog, SketchTrack, TicketRef, Tier,
+    Toc, VectorCompression,
 };
 #[cfg(feature = "temporal_track")]
 use crate::{TemporalTrack, temporal_track_read};
@@ -53,24 +65,51 @@ pub struct Memvid {
     pub(crate) header: Header,
     pub(crate) toc: Toc,
     pub(crate) wal: EmbeddedWal,
+    /// Seals payloads, WAL entries, indexes and the TOC of encrypted memories.
+    pub(crate) regions: RegionCrypto,
+    /// Mapping of the file that read-only opens serve payloads and segments from.
+    #[cfg(feature = "mmap")]
+    pub(crate) mapped: Option<MappedFile>,
+    /// Signs the current generation; set by the last `commit_with_options`.
+    pub(crate) commit_signer: Option<CommitSigner>,
+    /// Frames and cards removed by retention during the last commit.
+    pub(crate) retention_report: Option<RetentionReport>,
+    /// Last retention evaluation that removed nothing, and how long it holds.
+    pub(crate) retention_check: Option<RetentionCheck>,
+    /// Compression dictionaries loaded so far, keyed by zstd dictionary id.
+    pub(crate) compression_dictionaries: HashMap<u32, Arc<Vec<u8>>>,
+    /// Chunk index of the blob store holding `Chunked` payloads.
+    pub(crate) blob_store: BlobStore,
+    /// Committed mutations, loaded from the segment `toc.change_log` points at.
+    pub(crate) change_log: ChangeLog,
     /// Number of frame inserts appended to WAL but not yet materialized into `toc.frames`.
     ///
     /// This lets frontends predict stable frame IDs before an explicit commit.
     pub(crate) pending_frame_inserts: u64,
+    /// Stored bytes of puts still pending in the WAL, by track.
+    pub(crate) pending_track_bytes: BTreeMap<String, u64>,
+    /// Counters the frame and track quotas are checked against, once a put needs them.
+    pub(crate) quota_usage: Option<QuotaUsage>,
+    /// Frames updated (`Some(successor)`) or deleted (`None`) since the last commit.
+    pub(crate) pending_versions: HashMap<FrameId, Option<FrameId>>,
     pub(crate) data_end: u64,
     pub(crate) generation: u64,
+    /// Committed length of the file while an operation appends after it in place.
+    pub(crate) staged_tail: Option<u64>,
     pub(crate) lock_settings: LockSettings,
     pub(crate) lex_enabled: bool,
-    pub(crate) lex_index: Option<LexIndex>,
+    /// Shared with the handles of [`MemvidReader`](crate::MemvidReader) snapshots.
+    pub(crate) lex_index: Option<Arc<LexIndex>>,
     #[cfg(feature = "lex")]
     #[allow(dead_code)]
     pub(crate) lex_storage: Arc<RwLock<EmbeddedLexStorage>>,
     pub(crate) vec_enabled: bool,
     pub(crate) vec_compression: VectorCompression,
-    pub(crate) vec_index: Option<VecIndex>,
+    /// Shared with the handles of [`MemvidReader`](crate::MemvidReader) snapshots.
+    pub(crate) vec_index: Option<Arc<VecIndex>>,
     /// CLIP visual embeddings index (separate from vec due to different dimensions)
     pub(crate) clip_enabled: bool,
-    pub(crate) clip_index: Option<crate::clip::ClipIndex>,
+    pub(crate) clip_index: Option<Arc<crate::clip::ClipIndex>>,
     pub(crate) dirty: bool,
     #[cfg(feature = "lex")]
     pub(crate) tantivy: Option<TantivyEngine>,
@@ -81,15 +120,21 @@ pub struct Memvid {
     #[cfg(feature = "parallel_segments")]
     pub(crate) manifest_wal: Option<ManifestWal>,
     /// In-memory track for structured memory cards.
-    pub(crate) memories_track: MemoriesTrack,
+    pub(crate) memories_track: Arc<MemoriesTrack>,
     /// In-memory Logic-Mesh graph for entity-relationship traversal.
-    pub(crate) logic_mesh: LogicMesh,
+    pub(crate) logic_mesh: Arc<LogicMesh>,
     /// In-memory sketch track for fast candidate generation.
-    pub(crate) sketch_track: SketchTrack,
+    pub(crate) sketch_track: Arc<SketchTrack>,
     /// Schema registry for predicate validation.
     pub(crate) schema_registry: SchemaRegistry,
     /// Whether to enforce strict schema validation on card insert.
     pub(crate) schema_strict: bool,
+    /// Readers handed out by [`Memvid::reader`]; every commit publishes to them.
+    pub(crate) published_reader: Option<MemvidReader>,
+    /// Header last seen at `path`; `None` for writers and views pinned to a generation.
+    pub(crate) refresh: Option<RefreshState>,
+    /// A [`Transaction`](crate::Transaction) is open; automatic checkpoints wait for it.
+    pub(crate) in_transaction: bool,
     /// Active replay session being recorded (if any).
     #[cfg(feature = "replay")]
     pub(crate) active_session: Option<crate::replay::ActiveSession>,
@@ -102,6 +147,8 @@ pub struct Memvid {
 #[derive(Debug, Clone, Copy)]
 pub struct OpenReadOptions {
     pub allow_repair: bool,
+    /// Call [`Memvid::refresh`] before every search, ask and timeline query.
+    pub auto_refresh: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -129,6 +176,7 @@ impl Default for OpenReadOptions {
     fn default() -> Self {
         Self {
             allow_repair: false,
+            auto_refresh: false,
         }
     }
 }
@@ -137,7 +185,19 @@ impl Memvid {
     /// Create a new, empty `.mv2` file with an embedded WAL and empty TOC.
     /// The file is locked exclusively for the lifetime of the handle.
     pub fn create<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let path_ref = path.as_ref();
+        Self::create_with_regions(path.as_ref(), RegionCrypto::plaintext())
+    }
+
+    /// Create a new encrypted-mode `.mv2` whose payloads, WAL, indexes and TOC are
+    /// encrypted with keys derived from `key`.
+    ///
+    /// The header stays readable; reopen the memory with [`Memvid::open_encrypted`].
+    #[cfg(feature = "encryption")]
+    pub fn create_encrypted<P: AsRef<Path>>(path: P, key: &[u8; 32]) -> Result<Self> {
+        Self::create_with_regions(path.as_ref(), RegionCrypto::generate(key))
+    }
+
+    fn create_with_regions(path_ref: &Path, regions: RegionCrypto) -> Result<Self> {
         ensure_single_file(path_ref)?;
 
         OpenOptions::new()
@@ -157,6 +217,7 @@ impl Memvid {
             wal_checkpoint_pos: 0,
             wal_sequence: 0,
             toc_checksum: [0u8; 32],
+            encryption: regions.header_encryption(),
         };
 
         let mut toc = empty_toc();
@@ -185,9 +246,22 @@ impl Memvid {
             header,
             toc,
             wal,
+            regions,
+            #[cfg(feature = "mmap")]
+            mapped: None,
+            commit_signer: None,
+            retention_report: None,
+            retention_check: None,
+            compression_dictionaries: HashMap::new(),
+            blob_store: BlobStore::default(),
+            change_log: ChangeLog::default(),
             pending_frame_inserts: 0,
+            pending_track_bytes: BTreeMap::new(),
+            quota_usage: None,
+            pending_versions: HashMap::new(),
             data_end,
             generation: 0,
+            staged_tail: None,
             lock_settings: LockSettings::default(),
             lex_enabled: cfg!(feature = "lex"), // Enable by default if feature is enabled
             lex_index: None,
@@ -207,11 +281,14 @@ impl Memvid {
             temporal_track: None,
             #[cfg(feature = "parallel_segments")]
             manifest_wal: Some(manifest_wal),
-            memories_track: MemoriesTrack::new(),
-            logic_mesh: LogicMesh::new(),
-            sketch_track: SketchTrack::default(),
+            memories_track: Arc::new(MemoriesTrack::new()),
+            logic_mesh: Arc::new(LogicMesh::new()),
+            sketch_track: Arc::new(SketchTrack::default()),
             schema_registry: SchemaRegistry::new(),
             schema_strict: false,
+            published_reader: None,
+            refresh: None,
+            in_transaction: false,
             #[cfg(feature = "replay")]
             active_session: None,
             #[cfg(feature = "replay")]
@@ -251,6 +328,8 @@ impl Memvid {
                 bytes_length: 0,
                 checksum: empty_checksum,
                 compression_mode: memvid.vec_compression.clone(),
+                metric: crate::types::DistanceMetric::default(),
+                hnsw: None,
             });
         }
 
@@ -297,7 +376,12 @@ impl Memvid {
         self.toc.frames.len()
     }
 
-    fn open_locked(mut file: File, lock: FileLock, path_ref: &Path) -> Result<Self> {
+    fn open_locked(
+        mut file: File,
+        lock: FileLock,
+        path_ref: &Path,
+        key: Option<&[u8; 32]>,
+    ) -> Result<Self> {
         // Fast-path detection for encrypted capsules (.mv2e).
         // This avoids confusing "invalid header" errors and provides an actionable hint.
         let mut magic = [0u8; 4];
@@ -311,11 +395,13 @@ impl Memvid {
         }
 
         let mut header = HeaderCodec::read(&mut file)?;
-        let toc = match read_toc(&mut file, &header) {
+        let regions = unlock_regions(&header, key, path_ref)?;
+        let toc = match read_toc(&mut file, &header, &regions) {
             Ok(toc) => toc,
             Err(err @ MemvidError::Decode(_)) | Err(err @ MemvidError::InvalidToc { .. }) => {
                 tracing::info!("toc decode failed ({}); attempting recovery", err);
-                let (toc, recovered_offset) = recover_toc(&mut file, Some(header.footer_offset))?;
+                let (toc, recovered_offset) =
+                    recover_toc(&mut file, Some(header.footer_offset), &regions)?;
                 if recovered_offset != header.footer_offset
                     || header.toc_checksum != toc.toc_checksum
                 {
@@ -360,9 +446,22 @@ impl Memvid {
             header,
             toc,
             wal,
+            regions,
+            #[cfg(feature = "mmap")]
+            mapped: None,
+            commit_signer: None,
+            retention_report: None,
+            retention_check: None,
+            compression_dictionaries: HashMap::new(),
+            blob_store: BlobStore::default(),
+            change_log: ChangeLog::default(),
             pending_frame_inserts: 0,
+            pending_track_bytes: BTreeMap::new(),
+            quota_usage: None,
+            pending_versions: HashMap::new(),
             data_end: 0,
             generation,
+            staged_tail: None,
             lock_settings: LockSettings::default(),
             lex_enabled: false,
             lex_index: None,
@@ -382,17 +481,21 @@ impl Memvid {
             temporal_track: None,
             #[cfg(feature = "parallel_segments")]
             manifest_wal: Some(manifest_wal),
-            memories_track: MemoriesTrack::new(),
-            logic_mesh: LogicMesh::new(),
-            sketch_track: SketchTrack::default(),
+            memories_track: Arc::new(MemoriesTrack::new()),
+            logic_mesh: Arc::new(LogicMesh::new()),
+            sketch_track: Arc::new(SketchTrack::default()),
             schema_registry: SchemaRegistry::new(),
             schema_strict: false,
+            published_reader: None,
+            refresh: None,
+            in_transaction: false,
             #[cfg(feature = "replay")]
             active_session: None,
             #[cfg(feature = "replay")]
             completed_sessions: Vec::new(),
         };
-        memvid.data_end = compute_data_end(&memvid.toc, &memvid.header);
+        // Appends (including WAL recovery below) must not overwrite committed generations.
+        memvid.data_end = compute_data_end(&memvid.toc, &memvid.header)?.max(file_len);
         // Use consolidated helper for lex_enabled check
         memvid.lex_enabled = has_lex_index(&memvid.toc);
         if memvid.lex_enabled {
@@ -411,14 +514,18 @@ impl Memvid {
         if memvid.clip_enabled {
             memvid.load_clip_index_from_manifest()?;
         }
+        // WAL recovery may commit chunked payloads, which need the chunk index, and
+        // rewrites the TOC with whatever memories track and mesh are loaded.
+        memvid.load_blob_store()?;
+        memvid.load_change_log()?;
+        memvid.load_memories_track()?;
+        memvid.load_logic_mesh()?;
         memvid.recover_wal()?;
         #[cfg(feature = "parallel_segments")]
         memvid.load_manifest_segments(manifest_wal_entries);
         memvid.bootstrap_segment_catalog();
         #[cfg(feature = "temporal_track")]
         memvid.ensure_temporal_track_loaded()?;
-        memvid.load_memories_track()?;
-        memvid.load_logic_mesh()?;
         memvid.load_sketch_track()?;
         if checksum_result.is_err() {
             memvid.toc.verify_checksum()?;
@@ -437,7 +544,21 @@ impl Memvid {
         ensure_single_file(path_ref)?;
 
         let (file, lock) = FileLock::open_and_lock(path_ref)?;
-        Self::open_locked(file, lock, path_ref)
+        Self::open_locked(file, lock, path_ref, None)
+    }
+
+    /// Open an encrypted-mode `.mv2` created by [`Memvid::create_encrypted`].
+    ///
+    /// Payloads and index segments are decrypted on the fly as they are read, so no
+    /// plaintext copy of the memory is written to disk. A key that does not match the
+    /// memory fails with [`MemvidError::InvalidEncryptionKey`].
+    #[cfg(feature = "encryption")]
+    pub fn open_encrypted<P: AsRef<Path>>(path: P, key: &[u8; 32]) -> Result<Self> {
+        let path_ref = path.as_ref();
+        ensure_single_file(path_ref)?;
+
+        let (file, lock) = FileLock::open_and_lock(path_ref)?;
+        Self::open_locked(file, lock, path_ref, Some(key))
     }
 
     pub fn open_read_only<P: AsRef<Path>>(path: P) -> Result<Self> {
@@ -455,17 +576,111 @@ impl Memvid {
             return Self::open(path_ref);
         }
 
-        Self::open_read_only_snapshot(path_ref)
+        let mut memvid = Self::open_read_only_snapshot(path_ref, None, None)?;
+        memvid.set_auto_refresh(options.auto_refresh);
+        Ok(memvid)
+    }
+
+    /// Open a read-only view of the memory exactly as commit `generation` left it.
+    ///
+    /// Frames, indexes, memory cards and the Logic-Mesh are loaded from that generation's
+    /// TOC, so frames deleted by later commits are visible again. Generations survive
+    /// until the next vacuum; see [`Memvid::list_generations`].
+    pub fn open_at_generation<P: AsRef<Path>>(path: P, generation: u64) -> Result<Self> {
+        let path_ref = path.as_ref();
+        ensure_single_file(path_ref)?;
+        Self::open_read_only_snapshot(path_ref, Some(generation), None)
+    }
+
+    /// Open a read-only view of an encrypted-mode `.mv2`, decrypting with `key`.
+    ///
+    /// See [`Memvid::open_read_only`] and [`Memvid::open_encrypted`].
+    #[cfg(feature = "encryption")]
+    pub fn open_read_only_encrypted<P: AsRef<Path>>(path: P, key: &[u8; 32]) -> Result<Self> {
+        let path_ref = path.as_ref();
+        ensure_single_file(path_ref)?;
+        Self::open_read_only_snapshot(path_ref, None, Some(key))
+    }
+
+    /// Open a read-only view of an encrypted-mode `.mv2` as commit `generation` left it.
+    ///
+    /// See [`Memvid::open_at_generation`] and [`Memvid::open_encrypted`].
+    #[cfg(feature = "encryption")]
+    pub fn open_at_generation_encrypted<P: AsRef<Path>>(
+        path: P,
+        generation: u64,
+        key: &[u8; 32],
+    ) -> Result<Self> {
+        let path_ref = path.as_ref();
+        ensure_single_file(path_ref)?;
+        Self::open_read_only_snapshot(path_ref, Some(generation), Some(key))
+    }
+
+    /// List every committed generation still present in the file, oldest first.
+    pub fn list_generations(&self) -> Result<Vec<GenerationInfo>> {
+        Ok(self
+            .generation_tocs()?
+            .into_iter()
+            .map(|(generation, toc_offset, toc)| GenerationInfo {
+                generation,
+                committed_at: toc.committed_at,
+                frame_count: toc
+                    .frame_table
+                    .as_ref()
+                    .map_or(toc.frames.len() as u64, |table| table.frame_count),
+                toc_offset,
+                toc_checksum: toc.toc_checksum,
+            })
+            .collect())
+    }
+
+    /// Decode the TOC of every committed generation still present in the file, oldest
+    /// first, as `(generation, toc_offset, toc)`.
+    pub(crate) fn generation_tocs(&self) -> Result<Vec<(u64, u64, Toc)>> {
+        // Safety: read-only mapping over committed bytes; writers only append.
+        let mmap = unsafe { Mmap::map(&self.file)? };
+        let mut generations: Vec<(u64, u64, Toc)> = Vec::new();
+        for slice in scan_valid_footers(&mmap) {
+            // A generation may rewrite its TOC (e.g. after WAL growth); keep the newest.
+            if generations
+                .iter()
+                .any(|(generation, _, _)| *generation == slice.footer.generation)
+            {
+                continue;
+            }
+            let Ok(toc) = decode_toc(slice.toc_bytes, &self.regions) else {
+                continue;
+            };
+            generations.push((slice.footer.generation, slice.toc_offset as u64, toc));
+        }
+        generations.sort_by_key(|(generation, _, _)| *generation);
+        Ok(generations)
     }
 
-    fn open_read_only_snapshot(path_ref: &Path) -> Result<Self> {
+    fn open_read_only_snapshot(
+        path_ref: &Path,
+        generation: Option<u64>,
+        key: Option<&[u8; 32]>,
+    ) -> Result<Self> {
         let mut file = OpenOptions::new().read(true).write(true).open(path_ref)?;
+        let disk_header = HeaderCodec::read(&mut file)?;
+        let regions = unlock_regions(&disk_header, key, path_ref)?;
+        // Read before the tail so a commit landing in between is seen by the next refresh.
+        let refresh = generation
+            .is_none()
+            .then(|| RefreshState::new(&disk_header));
         let TailSnapshot {
             toc,
             footer_offset,
             data_end,
             generation,
-        } = load_tail_snapshot(&file)?;
+            relocation,
+        } = match generation {
+            Some(generation) => {
+                load_generation_snapshot(&file, generation, disk_header.wal_size, &regions)?
+            }
+            None => load_tail_snapshot(&file, &regions)?,
+        };
 
         let mut header = HeaderCodec::read(&mut file)?;
         header.footer_offset = footer_offset;
@@ -473,6 +688,8 @@ impl Memvid {
 
         let lock = FileLock::acquire_with_mode(&file, LockMode::Shared)?;
         let wal = EmbeddedWal::open_read_only(&file, &header)?;
+        #[cfg(feature = "mmap")]
+        let mapped = MappedFile::new(&file)?;
 
         #[cfg(feature = "lex")]
         let lex_storage = Arc::new(RwLock::new(EmbeddedLexStorage::from_manifest(
@@ -488,9 +705,22 @@ impl Memvid {
             header,
             toc,
             wal,
+            regions,
+            #[cfg(feature = "mmap")]
+            mapped: Some(mapped),
+            commit_signer: None,
+            retention_report: None,
+            retention_check: None,
+            compression_dictionaries: HashMap::new(),
+            blob_store: BlobStore::default(),
+            change_log: ChangeLog::default(),
             pending_frame_inserts: 0,
+            pending_track_bytes: BTreeMap::new(),
+            quota_usage: None,
+            pending_versions: HashMap::new(),
             data_end,
             generation,
+            staged_tail: None,
             lock_settings: LockSettings::default(),
             lex_enabled: false,
             lex_index: None,
@@ -510,16 +740,23 @@ impl Memvid {
             temporal_track: None,
             #[cfg(feature = "parallel_segments")]
             manifest_wal: None,
-            memories_track: MemoriesTrack::new(),
-            logic_mesh: LogicMesh::new(),
-            sketch_track: SketchTrack::default(),
+            memories_track: Arc::new(MemoriesTrack::new()),
+            logic_mesh: Arc::new(LogicMesh::new()),
+            sketch_track: Arc::new(SketchTrack::default()),
             schema_registry: SchemaRegistry::new(),
             schema_strict: false,
+            published_reader: None,
+            refresh,
+            in_transaction: false,
             #[cfg(feature = "replay")]
             active_session: None,
             #[cfg(feature = "replay")]
             completed_sessions: Vec::new(),
         };
+        if relocation != 0 {
+            memvid.adjust_offsets_after_wal_growth(relocation)?;
+            memvid.toc.bind_frame_table(&memvid.file)?;
+        }
 
         // Use consolidated helper for lex_enabled check
         memvid.lex_enabled = has_lex_index(&memvid.toc);
@@ -542,6 +779,10 @@ impl Memvid {
         memvid.load_memories_track()?;
         memvid.load_logic_mesh()?;
         memvid.load_sketch_track()?;
+        // The chunk index was written before the relocation, so its offsets are stale.
+        memvid.load_blob_store()?;
+        memvid.blob_store.shift(relocation);
+        memvid.load_change_log()?;
 
         memvid.bootstrap_segment_catalog();
         #[cfg(feature = "temporal_track")]
@@ -563,10 +804,10 @@ impl Memvid {
                 ));
             }
         };
-        Self::open_locked(file, lock, path_ref)
+        Self::open_locked(file, lock, path_ref, None)
     }
 
-    fn bootstrap_segment_catalog(&mut self) {
+    pub(super) fn bootstrap_segment_catalog(&mut self) {
         let catalog = &mut self.toc.segment_catalog;
         if catalog.version == 0 {
             catalog.version = 1;
@@ -615,17 +856,14 @@ impl Memvid {
     }
 
     /// Load the memories track from the manifest if present.
-    fn load_memories_track(&mut self) -> Result<()> {
+    pub(super) fn load_memories_track(&mut self) -> Result<()> {
         let manifest = match &self.toc.memories_track {
-            Some(m) => m,
+            Some(m) => m.clone(),
             None => return Ok(()),
         };
 
         // Read the compressed data from the file
-        let mut buf = vec![0u8; manifest.bytes_length as usize];
-        self.file
-            .seek(std::io::SeekFrom::Start(manifest.bytes_offset))?;
-        self.file.read_exact(&mut buf)?;
+        let buf = self.read_range(manifest.bytes_offset, manifest.bytes_length)?;
 
         // Verify checksum
         let actual_checksum: [u8; 32] = blake3::hash(&buf).into();
@@ -636,23 +874,20 @@ impl Memvid {
         }
 
         // Deserialize the memories track
-        self.memories_track = MemoriesTrack::deserialize(&buf)?;
+        self.memories_track = Arc::new(MemoriesTrack::deserialize(&buf)?);
 
         Ok(())
     }
 
     /// Load the Logic-Mesh from the manifest if present.
-    fn load_logic_mesh(&mut self) -> Result<()> {
+    pub(super) fn load_logic_mesh(&mut self) -> Result<()> {
         let manifest = match &self.toc.logic_mesh {
-            Some(m) => m,
+            Some(m) => m.clone(),
             None => return Ok(()),
         };
 
         // Read the serialized data from the file
-        let mut buf = vec![0u8; manifest.bytes_length as usize];
-        self.file
-            .seek(std::io::SeekFrom::Start(manifest.bytes_offset))?;
-        self.file.read_exact(&mut buf)?;
+        let buf = self.read_range(manifest.bytes_offset, manifest.bytes_length)?;
 
         // Verify checksum
         let actual_checksum: [u8; 32] = blake3::hash(&buf).into();
@@ -663,28 +898,38 @@ impl Memvid {
         }
 
         // Deserialize the logic mesh
-        self.logic_mesh = LogicMesh::deserialize(&buf)?;
+        self.logic_mesh = Arc::new(LogicMesh::deserialize(&buf)?);
 
         Ok(())
     }
 
     /// Load the sketch track from the manifest if present.
-    fn load_sketch_track(&mut self) -> Result<()> {
+    pub(super) fn load_sketch_track(&mut self) -> Result<()> {
         let manifest = match &self.toc.sketch_track {
             Some(m) => m.clone(),
             None => return Ok(()),
         };
 
-        // Read and deserialize the sketch track (read_sketch_track handles seeking and checksum)
-        self.sketch_track = crate::types::read_sketch_track(
-            &mut self.file,
-            manifest.bytes_offset,
-            manifest.bytes_length,
-        )?;
+        // Read and deserialize the sketch track (read_sketch_track handles the checksum)
+        let bytes = self.index_bytes(manifest.bytes_offset, manifest.bytes_length)?;
+        let length = bytes.len() as u64;
+        let track = crate::types::read_sketch_track(&mut Cursor::new(&*bytes), 0, length)?;
+        self.sketch_track = Arc::new(track);
 
         Ok(())
     }
 
+    /// Read the time index track stored at `offset`.
+    pub(crate) fn read_time_index(
+        &mut self,
+        offset: u64,
+        length: u64,
+    ) -> Result<Vec<TimeIndexEntry>> {
+        let bytes = self.index_bytes(offset, length)?;
+        let length = bytes.len() as u64;
+        time_index_read(&mut Cursor::new(&*bytes), 0, length)
+    }
+
     #[cfg(feature = "temporal_track")]
     pub(crate) fn ensure_temporal_track_loaded(&mut self) -> Result<()> {
         if self.temporal_track.is_some() {
@@ -704,7 +949,10 @@ impl Memvid {
         if end > file_len {
             return Ok(());
         }
-        match temporal_track_read(&mut self.file, manifest.bytes_offset, manifest.bytes_length) {
+        let bytes = self.index_bytes(manifest.bytes_offset, manifest.bytes_length)?;
+        let length = bytes.len() as u64;
+        let track = temporal_track_read(&mut Cursor::new(&*bytes), 0, length);
+        match track {
             Ok(track) => self.temporal_track = Some(track),
             Err(MemvidError::InvalidTemporalTrack { .. }) => {
                 return Ok(());
@@ -808,7 +1056,7 @@ impl Memvid {
         self.toc.memory_binding = None;
         // Revert to free tier
         self.toc.ticket_ref = crate::types::TicketRef {
-            issuer: "free-tier".into(),
+            issuer: crate::types::ticket::FREE_TIER_ISSUER.into(),
             seq_no: 1,
             expires_in_secs: 0,
             capacity_bytes: crate::types::Tier::Free.capacity_bytes(),
@@ -818,7 +1066,29 @@ impl Memvid {
     }
 }
 
-pub(crate) fn read_toc(file: &mut File, header: &Header) -> Result<Toc> {
+/// Resolve the region keys for `header`, requiring `key` exactly when the memory is encrypted.
+fn unlock_regions(header: &Header, key: Option<&[u8; 32]>, path: &Path) -> Result<RegionCrypto> {
+    match (&header.encryption, key) {
+        (None, None) => Ok(RegionCrypto::plaintext()),
+        (None, Some(_)) => Err(MemvidError::InvalidHeader {
+            reason: "memory is not encrypted".into(),
+        }),
+        #[cfg(feature = "encryption")]
+        (Some(encryption), Some(key)) => RegionCrypto::unlock(key, encryption),
+        (Some(_), _) => Err(MemvidError::EncryptedFile {
+            path: path.to_path_buf(),
+            hint: "Open it with Memvid::open_encrypted (requires the `encryption` feature)"
+                .to_string(),
+        }),
+    }
+}
+
+/// Decode TOC bytes as stored in the file, decrypting them first for encrypted memories.
+pub(crate) fn decode_toc(stored: &[u8], regions: &RegionCrypto) -> Result<Toc> {
+    Toc::decode(&regions.open(RegionKind::Toc, stored)?)
+}
+
+pub(crate) fn read_toc(file: &mut File, header: &Header, regions: &RegionCrypto) -> Result<Toc> {
     use crate::footer::{CommitFooter, FOOTER_SIZE};
 
     let len = file.metadata()?.len();
@@ -828,41 +1098,44 @@ pub(crate) fn read_toc(file: &mut File, header: &Header) -> Result<Toc> {
         });
     }
 
-    // Read the entire region from footer_offset to EOF (includes TOC + footer)
-    file.seek(SeekFrom::Start(header.footer_offset))?;
-    let total_size = (len - header.footer_offset) as usize;
-
-    if total_size < FOOTER_SIZE {
+    // The region from footer_offset to EOF holds [TOC][commit footer]; frame table
+    // pages are written before it.
+    let total_size = len - header.footer_offset;
+    if total_size < FOOTER_SIZE as u64 {
         return Err(MemvidError::InvalidToc {
             reason: "region too small to contain footer".into(),
         });
     }
 
-    let mut buf = Vec::with_capacity(total_size);
-    file.read_to_end(&mut buf)?;
-
     // Parse the footer (last FOOTER_SIZE bytes)
-    let footer_start = buf.len() - FOOTER_SIZE;
-    let footer_bytes = &buf[footer_start..];
-    let footer = CommitFooter::decode(footer_bytes).ok_or(MemvidError::InvalidToc {
+    let footer_start = len - FOOTER_SIZE as u64;
+    let mut footer_bytes = [0u8; FOOTER_SIZE];
+    file.seek(SeekFrom::Start(footer_start))?;
+    file.read_exact(&mut footer_bytes)?;
+    let footer = CommitFooter::decode(&footer_bytes).ok_or(MemvidError::InvalidToc {
         reason: "failed to decode commit footer".into(),
     })?;
 
-    // Extract only the TOC bytes (excluding the footer)
-    let toc_bytes = &buf[..footer_start];
-    if toc_bytes.len() != footer.toc_len as usize {
-        return Err(MemvidError::InvalidToc {
+    // Frame table pages are loaded on demand.
+    let toc_offset = footer_start
+        .checked_sub(footer.toc_len)
+        .filter(|offset| *offset == header.footer_offset)
+        .ok_or(MemvidError::InvalidToc {
             reason: "toc length mismatch".into(),
-        });
-    }
-    if !footer.hash_matches(toc_bytes) {
+        })?;
+    let mut toc_bytes = vec![0u8; footer.toc_len as usize];
+    file.seek(SeekFrom::Start(toc_offset))?;
+    file.read_exact(&mut toc_bytes)?;
+    if !footer.hash_matches(&toc_bytes) {
         return Err(MemvidError::InvalidToc {
             reason: "commit footer toc hash mismatch".into(),
         });
     }
 
-    verify_toc_prefix(toc_bytes)?;
-    let toc = Toc::decode(toc_bytes)?;
+    let toc_bytes = regions.open(RegionKind::Toc, &toc_bytes)?;
+    verify_toc_prefix(&toc_bytes)?;
+    let mut toc = Toc::decode(&toc_bytes)?;
+    toc.bind_frame_table(file)?;
     Ok(toc)
 }
 
@@ -924,13 +1197,37 @@ fn verify_toc_prefix(bytes: &[u8]) -> Result<()> {
 ///
 /// Note: Frames with payload_length == 0 are "virtual" frames (e.g., document
 /// frames that reference chunks) and are skipped from this check.
+///
+/// Table-backed TOCs only check the frame table bounds here; loading every page
+/// would defeat lazy loading, and each page is validated when it is decoded.
 fn ensure_non_overlapping_frames(toc: &Toc, file_len: u64) -> Result<()> {
+    if let Some(table) = toc.frame_table.as_ref() {
+        let mut table_end = 0u64;
+        for page in &table.pages {
+            let page_end = page
+                .bytes_offset
+                .checked_add(page.bytes_length)
+                .ok_or_else(|| MemvidError::InvalidToc {
+                    reason: "frame table offsets overflow".into(),
+                })?;
+            table_end = table_end.max(page_end);
+        }
+        if table_end > file_len || table.payload_end > file_len {
+            return Err(MemvidError::InvalidToc {
+                reason: "frame payload exceeds file length".into(),
+            });
+        }
+        return Ok(());
+    }
+
     // Collect active frames with actual payloads and sort by payload_offset
-    let mut frames_by_offset: Vec<_> = toc
-        .frames
-        .iter()
-        .filter(|f| f.status == FrameStatus::Active && f.payload_length > 0)
-        .collect();
+    let mut frames_by_offset = Vec::new();
+    for frame in toc.frames.try_iter() {
+        let frame = frame?;
+        if frame.status == FrameStatus::Active && frame.payload_length > 0 {
+            frames_by_offset.push(frame);
+        }
+    }
     frames_by_offset.sort_by_key(|f| f.payload_offset);
 
     let mut previous_end = 0u64;
@@ -960,54 +1257,105 @@ fn ensure_non_overlapping_frames(toc: &Toc, file_len: u64) -> Result<()> {
     Ok(())
 }
 
-pub(crate) fn recover_toc(file: &mut File, hint: Option<u64>) -> Result<(Toc, u64)> {
+/// Decode the TOC closed by a validated footer, returning it with its footer offset.
+fn decode_footer_toc(
+    file: &File,
+    footer_slice: &FooterSlice<'_>,
+    regions: &RegionCrypto,
+) -> Option<(Toc, u64)> {
+    tracing::debug!(
+        footer_offset = footer_slice.footer_offset,
+        toc_offset = footer_slice.toc_offset,
+        toc_len = footer_slice.toc_bytes.len(),
+        "found valid footer during recovery"
+    );
+    // The footer has already validated the TOC hash, so we can directly decode it
+    match decode_toc(footer_slice.toc_bytes, regions) {
+        Ok(mut toc) => {
+            toc.bind_frame_table(file).ok()?;
+            Some((toc, footer_slice.toc_offset as u64))
+        }
+        Err(err) => {
+            tracing::warn!(
+                error = %err,
+                "footer-validated TOC failed to decode, falling back to scan"
+            );
+            None
+        }
+    }
+}
+
+/// Whether a footer magic follows the valid footer at `footer_offset`, meaning a newer
+/// commit footer exists but is damaged.
+fn newer_footer_damaged(bytes: &[u8], footer_offset: usize) -> bool {
+    let tail = bytes.get(footer_offset + FOOTER_SIZE..).unwrap_or_default();
+    memchr::memmem::find(tail, FOOTER_MAGIC).is_some()
+}
+
+pub(crate) fn recover_toc(
+    file: &mut File,
+    hint: Option<u64>,
+    regions: &RegionCrypto,
+) -> Result<(Toc, u64)> {
     let len = file.metadata()?.len();
     // Safety: we only create a read-only mapping over stable file bytes.
     let mmap = unsafe { Mmap::map(&*file)? };
     tracing::debug!(file_len = len, "attempting toc recovery");
 
-    // First, try to find a valid footer which includes validated TOC bytes
-    if let Some(footer_slice) = find_last_valid_footer(&mmap) {
-        tracing::debug!(
-            footer_offset = footer_slice.footer_offset,
-            toc_offset = footer_slice.toc_offset,
-            toc_len = footer_slice.toc_bytes.len(),
-            "found valid footer during recovery"
-        );
-        // The footer has already validated the TOC hash, so we can directly decode it
-        match Toc::decode(footer_slice.toc_bytes) {
-            Ok(toc) => {
-                return Ok((toc, footer_slice.toc_offset as u64));
-            }
-            Err(err) => {
-                tracing::warn!(
-                    error = %err,
-                    "footer-validated TOC failed to decode, falling back to scan"
-                );
-            }
+    // First, try to find a valid footer which includes validated TOC bytes. Older
+    // generations keep valid footers, so if the newest footer is torn, prefer the
+    // TOC bytes found below and only fall back to the previous generation after them.
+    let last_valid = find_last_valid_footer(&mmap);
+    let torn_tail = last_valid
+        .as_ref()
+        .is_some_and(|slice| newer_footer_damaged(&mmap, slice.footer_offset));
+    if !torn_tail {
+        if let Some(recovered) = last_valid
+            .as_ref()
+            .and_then(|slice| decode_footer_toc(file, slice, regions))
+        {
+            return Ok(recovered);
         }
     }
 
+    // Sealed TOCs cannot be told apart from other bytes without their footer, so the
+    // best-effort scans below only apply to plaintext memories.
+    if regions.is_encrypted() {
+        return last_valid
+            .as_ref()
+            .filter(|_| torn_tail)
+            .and_then(|slice| decode_footer_toc(file, slice, regions))
+            .ok_or(MemvidError::InvalidToc {
+                reason: "unable to recover table of contents from file trailer".into(),
+            });
+    }
+
     // If we have a header-provided hint (`footer_offset`) but the commit footer itself is corrupted,
     // we can often still recover because the TOC bytes are intact. In that case, assume the TOC
     // spans from `hint` up to the final fixed-size commit footer and decode it best-effort.
+    // When the hint points at frame table pages, the TOC starts right after them.
     if let Some(hint_offset) = hint {
         use crate::footer::FOOTER_SIZE;
 
-        let start = (hint_offset.min(len)) as usize;
+        let mut start = (hint_offset.min(len)) as usize;
+        while let Some(page_len) = crate::frame_table::segment_len(&mmap[start..]) {
+            start = start.saturating_add(page_len as usize);
+        }
         if mmap.len().saturating_sub(start) >= FOOTER_SIZE {
             let toc_end = mmap.len().saturating_sub(FOOTER_SIZE);
             if toc_end > start {
                 let toc_bytes = &mmap[start..toc_end];
                 if verify_toc_prefix(toc_bytes).is_ok() {
                     let attempt = panic::catch_unwind(|| Toc::decode(toc_bytes));
-                    if let Ok(Ok(toc)) = attempt {
-                        tracing::debug!(
-                            recovered_offset = hint_offset,
-                            recovered_frames = toc.frames.len(),
-                            "recovered toc from hinted offset without validated footer"
-                        );
-                        return Ok((toc, hint_offset));
+                    if let Ok(Ok(mut toc)) = attempt {
+                        if toc.bind_frame_table(file).is_ok() {
+                            tracing::debug!(
+                                recovered_offset = hint_offset,
+                                recovered_frames = toc.frames.len(),
+                                "recovered toc from hinted offset without validated footer"
+                            );
+                            return Ok((toc, hint_offset));
+                        }
                     }
                 }
             }
@@ -1027,8 +1375,19 @@ pub(crate) fn recover_toc(file: &mut File, hint: Option<u64>) -> Result<(Toc, u6
     }
 
     for (start, end) in ranges {
-        if let Some(found) = scan_range_for_toc(&mmap, start, end) {
-            return Ok(found);
+        if let Some((mut toc, offset)) = scan_range_for_toc(&mmap, start, end) {
+            if toc.bind_frame_table(file).is_ok() {
+                return Ok((toc, offset));
+            }
+        }
+    }
+
+    if torn_tail {
+        if let Some(recovered) = last_valid
+            .as_ref()
+            .and_then(|slice| decode_footer_toc(file, slice, regions))
+        {
+            return Ok(recovered);
         }
     }
 
@@ -1096,7 +1455,7 @@ pub(crate) fn empty_toc() -> Toc {
     Toc {
         toc_version: 0,
         segments: Vec::new(),
-        frames: Vec::new(),
+        frames: FrameTable::new(),
         indexes: IndexManifests::default(),
         time_index: None,
         temporal_track: None,
@@ -1105,7 +1464,7 @@ pub(crate) fn empty_toc() -> Toc {
         sketch_track: None,
         segment_catalog: SegmentCatalog::default(),
         ticket_ref: TicketRef {
-            issuer: "free-tier".into(),
+            issuer: crate::types::ticket::FREE_TIER_ISSUER.into(),
             seq_no: 1,
             expires_in_secs: 0,
             capacity_bytes: Tier::Free.capacity_bytes(),
@@ -1113,12 +1472,21 @@ pub(crate) fn empty_toc() -> Toc {
         memory_binding: None,
         replay_manifest: None,
         enrichment_queue: crate::types::EnrichmentQueueManifest::default(),
+        frame_table: None,
+        committed_at: None,
+        commit_signature: None,
+        retention_rules: Vec::new(),
+        quotas: Default::default(),
+        compression: Default::default(),
+        blob_store: None,
+        change_log: None,
+        wal_size: 0,
         merkle_root: [0u8; 32],
         toc_checksum: [0u8; 32],
     }
 }
 
-pub(crate) fn compute_data_end(toc: &Toc, header: &Header) -> u64 {
+pub(crate) fn compute_data_end(toc: &Toc, header: &Header) -> Result<u64> {
     // `data_end` tracks the end of all data bytes that should not be overwritten by appends:
     // - frame payloads
     // - embedded indexes / metadata segments referenced by the TOC
@@ -1128,15 +1496,23 @@ pub(crate) fn compute_data_end(toc: &Toc, header: &Header) -> u64 {
     let wal_region_end = header.wal_offset.saturating_add(header.wal_size);
     let mut max_end = wal_region_end.max(header.footer_offset);
 
-    // Frame payloads (active only).
-    for frame in toc
-        .frames
-        .iter()
-        .filter(|f| f.status == FrameStatus::Active && f.payload_length > 0)
-    {
-        if let Some(end) = frame.payload_offset.checked_add(frame.payload_length) {
+    // Frame payloads. The frame table records the furthest payload
+    // so its pages do not have to be loaded.
+    if let Some(table) = toc.frame_table.as_ref() {
+        max_end = max_end.max(table.payload_end).max(table.pages_end());
+        if let Some(end) = table.bytes_offset.checked_add(table.bytes_length) {
             max_end = max_end.max(end);
         }
+    } else {
+        for frame in toc.frames.try_iter() {
+            let frame = frame?;
+            if frame.status != FrameStatus::Active || frame.payload_length == 0 {
+                continue;
+            }
+            if let Some(end) = frame.payload_offset.checked_add(frame.payload_length) {
+                max_end = max_end.max(end);
+            }
+        }
     }
 
     // Segment catalog entries.
@@ -1182,9 +1558,7 @@ pub(crate) fn compute_data_end(toc: &Toc, header: &Header) -> u64 {
         }
     }
     if let Some(manifest) = toc.indexes.vec.as_ref() {
-        if let Some(end) = manifest.bytes_offset.checked_add(manifest.bytes_length) {
-            max_end = max_end.max(end);
-        }
+        max_end = max_end.max(manifest.end_offset());
     }
     if let Some(manifest) = toc.indexes.clip.as_ref() {
         if let Some(end) = manifest.bytes_offset.checked_add(manifest.bytes_length) {
@@ -1217,6 +1591,22 @@ pub(crate) fn compute_data_end(toc: &Toc, header: &Header) -> u64 {
             max_end = max_end.max(end);
         }
     }
+    for dictionary in &toc.compression.dictionaries {
+        if let Some(end) = dictionary.bytes_offset.checked_add(dictionary.bytes_length) {
+            max_end = max_end.max(end);
+        }
+    }
+    // The chunk index is always written after the chunks it lists.
+    if let Some(manifest) = toc.blob_store.as_ref() {
+        if let Some(end) = manifest.bytes_offset.checked_add(manifest.bytes_length) {
+            max_end = max_end.max(end);
+        }
+    }
+    if let Some(manifest) = toc.change_log.as_ref() {
+        if let Some(end) = manifest.bytes_offset.checked_add(manifest.bytes_length) {
+            max_end = max_end.max(end);
+        }
+    }
     #[cfg(feature = "replay")]
     if let Some(manifest) = toc.replay_manifest.as_ref() {
         if let Some(end) = manifest.segment_offset.checked_add(manifest.segment_size) {
@@ -1231,14 +1621,17 @@ pub(crate) fn compute_data_end(toc: &Toc, header: &Header) -> u64 {
         "compute_data_end"
     );
 
-    max_end
+    Ok(max_end)
 }
 
-struct TailSnapshot {
-    toc: Toc,
-    footer_offset: u64,
-    data_end: u64,
-    generation: u64,
+pub(super) struct TailSnapshot {
+    pub(super) toc: Toc,
+    pub(super) footer_offset: u64,
+    pub(super) data_end: u64,
+    pub(super) generation: u64,
+    /// Bytes the data region moved since the TOC was written; its frame table is left
+    /// unbound until the offsets are adjusted.
+    pub(super) relocation: u64,
 }
 
 fn locate_footer_window(mmap: &[u8]) -> Option<(FooterSlice<'_>, usize)> {
@@ -1260,7 +1653,7 @@ fn locate_footer_window(mmap: &[u8]) -> Option<(FooterSlice<'_>, usize)> {
     None
 }
 
-fn load_tail_snapshot(file: &File) -> Result<TailSnapshot> {
+pub(super) fn load_tail_snapshot(file: &File, regions: &RegionCrypto) -> Result<TailSnapshot> {
     // Safety: we only create a read-only mapping over the stable file bytes.
     let mmap = unsafe { Mmap::map(file)? };
 
@@ -1268,8 +1661,16 @@ fn load_tail_snapshot(file: &File) -> Result<TailSnapshot> {
         locate_footer_window(&mmap).ok_or_else(|| MemvidError::InvalidToc {
             reason: "no valid commit footer found".into(),
         })?;
-    let toc = Toc::decode(slice.toc_bytes)?;
+    // Earlier generations keep valid footers, so a torn newest footer would otherwise
+    // silently open an older commit.
+    if newer_footer_damaged(&mmap, offset_adjustment + slice.footer_offset) {
+        return Err(MemvidError::InvalidToc {
+            reason: "latest commit footer is damaged".into(),
+        });
+    }
+    let mut toc = decode_toc(slice.toc_bytes, regions)?;
     toc.verify_checksum()?;
+    toc.bind_frame_table(file)?;
 
     Ok(TailSnapshot {
         toc,
@@ -1277,6 +1678,49 @@ fn load_tail_snapshot(file: &File) -> Result<TailSnapshot> {
         // Using toc_offset causes stale data_end that moves footer backwards on next commit
         data_end: slice.footer_offset as u64 + offset_adjustment as u64,
         generation: slice.footer.generation,
+        relocation: 0,
+    })
+}
+
+/// Loads the TOC of `generation`, relocating its offsets if the WAL region has grown
+/// to `wal_size` since it was written.
+fn load_generation_snapshot(
+    file: &File,
+    generation: u64,
+    wal_size: u64,
+    regions: &RegionCrypto,
+) -> Result<TailSnapshot> {
+    // Safety: we only create a read-only mapping over the stable file bytes.
+    let mmap = unsafe { Mmap::map(file)? };
+
+    let slice = scan_valid_footers(&mmap)
+        .find(|slice| slice.footer.generation == generation)
+        .ok_or(MemvidError::GenerationNotFound { generation })?;
+    let mut toc = decode_toc(slice.toc_bytes, regions)?;
+    toc.verify_checksum()?;
+
+    // Growing the WAL shifts every segment after it by the size difference, and only
+    // the TOC being written at the time is rewritten with the new offsets.
+    if toc.wal_size == 0 {
+        return Err(MemvidError::InvalidToc {
+            reason: format!("generation {generation} does not record its WAL size").into(),
+        });
+    }
+    let relocation = wal_size
+        .checked_sub(toc.wal_size)
+        .ok_or_else(|| MemvidError::InvalidToc {
+            reason: format!("generation {generation} has a larger WAL than the file").into(),
+        })?;
+    if relocation == 0 {
+        toc.bind_frame_table(file)?;
+    }
+
+    Ok(TailSnapshot {
+        toc,
+        footer_offset: slice.footer_offset as u64,
+        data_end: slice.footer_offset as u64,
+        generation,
+        relocation,
     })
 }
 
@@ -1,15 +1,27 @@
+use std::io::{Read, Seek, SeekFrom};
 use std::path::Path;
 
+use ed25519_dalek::VerifyingKey;
+
 use crate::Result;
-use crate::io::time_index::read_track as time_index_read;
 use crate::memvid::lifecycle::Memvid;
+use crate::signature::verify_commit_signature;
 use crate::types::{
-    DoctorOptions, DoctorPlan, DoctorReport, VerificationCheck, VerificationReport,
-    VerificationStatus,
+    CommitSignatureFailure, CommitSignatureIssue, CommitSignatureReport, DoctorOptions, DoctorPlan,
+    DoctorReport, VerificationCheck, VerificationReport, VerificationStatus, VerifyOptions,
 };
 
 impl Memvid {
     pub fn verify<P: AsRef<Path>>(path: P, deep: bool) -> Result<VerificationReport> {
+        Self::verify_with_options(path, &VerifyOptions::new(deep))
+    }
+
+    /// Verify the file, checking commit signatures against `options.trusted_keys`.
+    pub fn verify_with_options<P: AsRef<Path>>(
+        path: P,
+        options: &VerifyOptions,
+    ) -> Result<VerificationReport> {
+        let deep = options.deep;
         let path_buf = path.as_ref().to_path_buf();
         let mut mem = Self::open_read_only(&path_buf)?;
 
@@ -28,7 +40,7 @@ impl Memvid {
 
         // Time index integrity
         if let Some(manifest) = mem.toc.time_index.clone() {
-            match time_index_read(&mut mem.file, manifest.bytes_offset, manifest.bytes_length) {
+            match mem.read_time_index(manifest.bytes_offset, manifest.bytes_length) {
                 Ok(entries) => {
                     if manifest.entry_count == entries.len() as u64 {
                         push_check("TimeIndexEntryCount", VerificationStatus::Passed, None);
@@ -75,6 +87,61 @@ impl Memvid {
             );
         }
 
+        // Frame table decode and checksum
+        if let Some(manifest) = mem.toc.frame_table.clone() {
+            match mem.toc.frames.load_all() {
+                Ok(()) => push_check("FrameTableDecode", VerificationStatus::Passed, None),
+                Err(err) => push_check(
+                    "FrameTableDecode",
+                    VerificationStatus::Failed,
+                    Some(err.to_string()),
+                ),
+            }
+
+            if deep {
+                let mut verified = Ok(true);
+                for page in &manifest.pages {
+                    let mut bytes = vec![0u8; page.bytes_length as usize];
+                    let read = mem
+                        .file
+                        .seek(SeekFrom::Start(page.bytes_offset))
+                        .and_then(|_| mem.file.read_exact(&mut bytes));
+                    match read {
+                        Ok(()) if *blake3::hash(&bytes).as_bytes() == page.checksum => {}
+                        Ok(()) => {
+                            verified = Ok(false);
+                            break;
+                        }
+                        Err(err) => {
+                            verified = Err(err);
+                            break;
+                        }
+                    }
+                }
+                match verified {
+                    Ok(true) => {
+                        push_check("FrameTableChecksum", VerificationStatus::Passed, None);
+                    }
+                    Ok(false) => push_check(
+                        "FrameTableChecksum",
+                        VerificationStatus::Failed,
+                        Some("frame table checksum mismatch".into()),
+                    ),
+                    Err(err) => push_check(
+                        "FrameTableChecksum",
+                        VerificationStatus::Failed,
+                        Some(err.to_string()),
+                    ),
+                }
+            }
+        } else {
+            push_check(
+                "FrameTableDecode",
+                VerificationStatus::Skipped,
+                Some("frames stored inline".into()),
+            );
+        }
+
         // Lexical index decode
         if mem.lex_enabled {
             match mem.ensure_lex_index() {
@@ -155,13 +222,126 @@ impl Memvid {
             ),
         }
 
+        // Commit signature chain
+        let commit_signatures = match mem.verify_commit_signatures(&options.trusted_keys) {
+            Ok(report) => {
+                match (
+                    report.first_failure.as_ref(),
+                    report.first_signed_generation,
+                ) {
+                    (Some(failure), _) => push_check(
+                        "CommitSignatures",
+                        VerificationStatus::Failed,
+                        Some(format!(
+                            "generation {} breaks the signature chain: {:?}",
+                            failure.generation, failure.issue
+                        )),
+                    ),
+                    (None, Some(first)) => push_check(
+                        "CommitSignatures",
+                        VerificationStatus::Passed,
+                        Some(format!(
+                            "{} signed generations since generation {first}{}",
+                            report.signed_generations,
+                            if report.signers_trusted {
+                                ""
+                            } else {
+                                ", signer not checked against trusted keys"
+                            }
+                        )),
+                    ),
+                    (None, None) => push_check(
+                        "CommitSignatures",
+                        VerificationStatus::Skipped,
+                        Some("commits are not signed".into()),
+                    ),
+                }
+                Some(report)
+            }
+            Err(err) => {
+                push_check(
+                    "CommitSignatures",
+                    VerificationStatus::Failed,
+                    Some(err.to_string()),
+                );
+                None
+            }
+        };
+
         Ok(VerificationReport {
             file_path: path_buf,
             checks,
             overall_status: overall,
+            commit_signatures,
         })
     }
 
+    /// Walk the generations still present in the file and validate their commit
+    /// signatures, stopping at the first generation that breaks the chain.
+    ///
+    /// With `trusted_keys` every signature must use one of them; without, the signer
+    /// may not change along the chain.
+    fn verify_commit_signatures(&self, trusted_keys: &[[u8; 32]]) -> Result<CommitSignatureReport> {
+        let generations = self.generation_tocs()?;
+        let mut report = CommitSignatureReport {
+            generations: generations.len() as u64,
+            signers_trusted: !trusted_keys.is_empty(),
+            ..CommitSignatureReport::default()
+        };
+        let mut previous: Option<Vec<u8>> = None;
+        for (generation, _, mut toc) in generations {
+            let Some(signature) = toc.commit_signature.clone() else {
+                if report.first_signed_generation.is_some() {
+                    report.first_failure = Some(CommitSignatureFailure {
+                        generation,
+                        issue: CommitSignatureIssue::Unsigned,
+                    });
+                    break;
+                }
+                continue;
+            };
+            let toc_checksum = toc.unsigned_checksum()?;
+            let authentic = VerifyingKey::from_bytes(&signature.public_key).is_ok_and(|key| {
+                verify_commit_signature(
+                    &key,
+                    signature.previous_signature.as_deref(),
+                    &toc.merkle_root,
+                    &toc_checksum,
+                    toc.committed_at,
+                    &signature.signature,
+                )
+                .is_ok()
+            });
+            let issue = if !authentic {
+                Some(CommitSignatureIssue::Tampered)
+            } else if !trusted_keys.is_empty() && !trusted_keys.contains(&signature.public_key) {
+                Some(CommitSignatureIssue::UntrustedKey)
+            } else if previous.is_some() && signature.previous_signature != previous {
+                Some(CommitSignatureIssue::BrokenChain)
+            } else if trusted_keys.is_empty()
+                && report
+                    .signers
+                    .last()
+                    .is_some_and(|signer| *signer != signature.public_key)
+            {
+                Some(CommitSignatureIssue::SignerChanged)
+            } else {
+                None
+            };
+            if let Some(issue) = issue {
+                report.first_failure = Some(CommitSignatureFailure { generation, issue });
+                break;
+            }
+            report.first_signed_generation.get_or_insert(generation);
+            report.signed_generations += 1;
+            if !report.signers.contains(&signature.public_key) {
+                report.signers.push(signature.public_key);
+            }
+            previous = Some(signature.signature);
+        }
+        Ok(report)
+    }
+
     pub fn doctor<P: AsRef<Path>>(path: P, options: DoctorOptions) -> Result<DoctorReport> {
         crate::memvid::doctor::doctor_run(path.as_ref(), options)
     }
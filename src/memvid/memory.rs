@@ -4,6 +4,8 @@
 //! an MV2 file, including adding cards, querying by entity/slot, temporal
 //! lookups, and enrichment tracking.
 
+use std::sync::Arc;
+
 use crate::error::Result;
 use crate::memvid::lifecycle::Memvid;
 use crate::types::{
@@ -55,7 +57,7 @@ impl Memvid {
     /// Changes are persisted when the file is sealed.
     pub fn memories_mut(&mut self) -> &mut MemoriesTrack {
         self.dirty = true;
-        &mut self.memories_track
+        Arc::make_mut(&mut self.memories_track)
     }
 
     /// Add a memory card to the memories track.
@@ -76,8 +78,17 @@ impl Memvid {
     /// # Errors
     /// Returns an error if strict schema validation is enabled and the card is invalid.
     pub fn put_memory_card(&mut self, card: MemoryCard) -> Result<MemoryCardId> {
+        self.check_memory_card(&card)?;
+        self.dirty = true;
+        let id = Arc::make_mut(&mut self.memories_track).add_card(card);
+        Ok(id)
+    }
+
+    /// Validate a card before it is added, rejecting it in strict mode and logging a
+    /// warning otherwise.
+    pub(crate) fn check_memory_card(&self, card: &MemoryCard) -> Result<()> {
         // Validate against schema
-        if let Err(e) = self.validate_card(&card) {
+        if let Err(e) = self.validate_card(card) {
             if self.schema_strict {
                 return Err(crate::error::MemvidError::SchemaValidation {
                     reason: e.to_string(),
@@ -92,10 +103,7 @@ impl Memvid {
                 "Schema validation warning"
             );
         }
-
-        self.dirty = true;
-        let id = self.memories_track.add_card(card);
-        Ok(id)
+        Ok(())
     }
 
     /// Add multiple memory cards at once.
@@ -150,7 +158,7 @@ impl Memvid {
         }
 
         self.dirty = true;
-        let ids = self.memories_track.add_cards(cards);
+        let ids = Arc::make_mut(&mut self.memories_track).add_cards(cards);
         Ok(ids)
     }
 
@@ -172,8 +180,12 @@ impl Memvid {
         card_ids: Vec<MemoryCardId>,
     ) -> Result<()> {
         self.dirty = true;
-        self.memories_track
-            .record_enrichment(frame_id, engine_kind, engine_version, card_ids);
+        Arc::make_mut(&mut self.memories_track).record_enrichment(
+            frame_id,
+            engine_kind,
+            engine_version,
+            card_ids,
+        );
         Ok(())
     }
 
@@ -335,7 +347,7 @@ impl Memvid {
     /// This is destructive and cannot be undone.
     pub fn clear_memories(&mut self) {
         self.dirty = true;
-        self.memories_track.clear();
+        Arc::make_mut(&mut self.memories_track).clear();
     }
 
     // ========================================================================
@@ -591,7 +603,7 @@ impl Memvid {
 
         for frame_id in unenriched {
             // Get frame data
-            let Some(frame) = self.toc.frames.get(frame_id as usize) else {
+            let Some(frame) = self.toc.frames.try_get(frame_id as usize)? else {
                 continue;
             };
             let frame = frame.clone();
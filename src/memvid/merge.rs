@@ -0,0 +1,715 @@
+//! Merging another `.mv2` file into an open memory.
+//!
+//! Active frames of the source are appended under fresh frame ids, and every reference to
+//! them (frame links, memory cards, Logic-Mesh nodes and edges, vector and CLIP entries)
+//! is rewritten through the resulting id map. Payloads are copied in their stored
+//! encoding, together with any compression dictionary and blob store chunk this memory
+//! lacks, and the indexes are rebuilt once after every frame has been appended.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::clip::ClipIndex;
+use crate::error::{MemvidError, Result};
+use crate::io::region::RegionKind;
+use crate::memvid::lifecycle::Memvid;
+use crate::types::blob_store::decode_chunk_recipe;
+use crate::types::{
+    BlobChunk, CanonicalEncoding, Frame, FrameId, FrameStatus, MergeOptions, MergeReport,
+};
+
+impl Memvid {
+    /// Merge the active frames of the memory at `other` into this one.
+    ///
+    /// Pending WAL records are committed first, and the merge is published as a single
+    /// new commit appended after the committed end of the file, which is truncated back
+    /// if the merge fails. `other` is only read. Embeddings stored with product quantization
+    /// cannot be decoded back to vectors and are not carried over.
+    pub fn merge_from(&mut self, other: &Path, options: MergeOptions) -> Result<MergeReport> {
+        self.ensure_mutation_allowed()?;
+        self.commit()?;
+
+        let mut source = Memvid::open_read_only(other)?;
+        source.toc.frames.load_all()?;
+        source.ensure_vec_index()?;
+        source.ensure_clip_index()?;
+
+        // Everything the rebuild carries over must be resident before it starts.
+        self.toc.frames.load_all()?;
+        self.ensure_vec_index()?;
+        self.ensure_clip_index()?;
+
+        let first_id = self.toc.frames.len() as FrameId;
+        let mut report = MergeReport::default();
+        let incoming = self.plan_merged_frames(&source, &options, &mut report)?;
+        let merged = |frame_id: FrameId| {
+            report
+                .frame_ids
+                .get(&frame_id)
+                .copied()
+                .filter(|id| *id >= first_id)
+        };
+
+        let mut incoming_bytes = 0u64;
+        let mut seen = HashSet::new();
+        for frame in &incoming {
+            if frame.payload_length > 0 && seen.insert((frame.payload_offset, frame.payload_length))
+            {
+                incoming_bytes = incoming_bytes.saturating_add(frame.payload_length);
+            }
+        }
+        // Chunks the incoming recipes need that this memory does not hold yet.
+        let mut chunks = Vec::new();
+        let mut wanted = HashSet::new();
+        for frame in &incoming {
+            if frame.canonical_encoding != CanonicalEncoding::Chunked || frame.payload_length == 0 {
+                continue;
+            }
+            let recipe = source.read_frame_payload_bytes(frame)?;
+            for chunk_hash in decode_chunk_recipe(&recipe)? {
+                if self.blob_store.contains(&chunk_hash) || !wanted.insert(chunk_hash) {
+                    continue;
+                }
+                let chunk =
+                    *source
+                        .blob_store
+                        .get(&chunk_hash)
+                        .ok_or(MemvidError::InvalidFrame {
+                            frame_id: frame.id,
+                            reason: "chunk missing from the blob store",
+                        })?;
+                incoming_bytes = incoming_bytes.saturating_add(chunk.stored_length);
+                chunks.push((chunk, source.read_blob_chunk(&chunk)?));
+            }
+        }
+        let payload_tail = self.payload_region_end();
+        let capacity_limit = self.capacity_limit();
+        if payload_tail.saturating_add(incoming_bytes) > capacity_limit {
+            return Err(MemvidError::CapacityExceeded {
+                current: payload_tail,
+                limit: capacity_limit,
+                required: incoming_bytes,
+            });
+        }
+
+        let mut vec_docs = Vec::new();
+        if let Some(index) = source.vec_index.as_ref() {
+            for (frame_id, embedding) in index.entries() {
+                if let Some(new_id) = merged(frame_id) {
+                    vec_docs.push((new_id, embedding.to_vec()));
+                }
+            }
+        }
+        if let Some(first) = vec_docs.first() {
+            let incoming_dimension = first.1.len() as u32;
+            let expected = self
+                .effective_vec_index_dimension()?
+                .unwrap_or(incoming_dimension);
+            if let Some((_, embedding)) = vec_docs
+                .iter()
+                .find(|(_, embedding)| embedding.len() as u32 != expected)
+            {
+                return Err(MemvidError::VecDimensionMismatch {
+                    expected,
+                    actual: embedding.len(),
+                });
+            }
+            if !self.vec_enabled {
+                self.enable_vec()?;
+            }
+        }
+
+        let mut clip_docs = Vec::new();
+        if let Some(index) = source.clip_index.as_ref() {
+            for (frame_id, page, embedding) in index.entries() {
+                if let Some(new_id) = merged(frame_id) {
+                    clip_docs.push((new_id, page, embedding.to_vec()));
+                }
+            }
+        }
+        if !clip_docs.is_empty() && !self.clip_enabled {
+            self.enable_clip()?;
+        }
+
+        let mut dictionaries = Vec::new();
+        for manifest in source.toc.compression.dictionaries.clone() {
+            match self
+                .toc
+                .compression
+                .dictionaries
+                .iter()
+                .find(|existing| existing.dictionary_id == manifest.dictionary_id)
+            {
+                Some(existing) if existing.checksum == manifest.checksum => {}
+                Some(_) => {
+                    return Err(MemvidError::InvalidCompressionDictionary {
+                        reason: format!(
+                            "dictionary id {} differs between the memories",
+                            manifest.dictionary_id
+                        )
+                        .into(),
+                    });
+                }
+                None => {
+                    let bytes = source.compression_dictionary(manifest.dictionary_id)?;
+                    dictionaries.push((manifest, bytes));
+                }
+            }
+        }
+
+        report.embeddings_merged = vec_docs.len() as u64;
+        report.clip_entries_merged = clip_docs.len() as u64;
+        let payload_source = source.file.try_clone()?;
+
+        self.with_staged_tail(|mem| {
+            mem.begin_generation()?;
+            for (chunk, stored) in chunks {
+                let offset = mem.data_end;
+                let stored_length = mem.write_region(offset, RegionKind::Payload, &stored)?;
+                mem.blob_store.insert(BlobChunk {
+                    offset,
+                    stored_length,
+                    ..chunk
+                });
+                mem.data_end += stored_length;
+                report.payload_bytes += stored_length;
+            }
+            report.payload_bytes += mem.append_merged_frames(&payload_source, incoming)?;
+            for (manifest, bytes) in dictionaries {
+                let stored = mem.append_dictionary(
+                    mem.data_end,
+                    bytes.to_vec(),
+                    manifest.track,
+                    manifest.sample_count,
+                )?;
+                mem.data_end += stored.bytes_length;
+                mem.header.footer_offset = mem.header.footer_offset.max(mem.data_end);
+            }
+
+            if !clip_docs.is_empty() {
+                let clip_index = Arc::make_mut(
+                    mem.clip_index
+                        .get_or_insert_with(|| Arc::new(ClipIndex::new())),
+                );
+                for (frame_id, page, embedding) in clip_docs {
+                    clip_index.add_document(frame_id, page, embedding);
+                }
+            }
+            (report.cards_merged, report.mesh_nodes_merged) =
+                mem.merge_cards_and_mesh(&source, &report.frame_ids, first_id);
+
+            mem.rebuild_indexes(&vec_docs)?;
+            if !mem.sketch_track.is_empty() {
+                mem.persist_sketch_track()?;
+            }
+            Ok(())
+        })?;
+        self.dirty = false;
+
+        tracing::info!(
+            frames_merged = report.frames_merged,
+            frames_deduplicated = report.frames_deduplicated,
+            payload_bytes = report.payload_bytes,
+            "merge completed"
+        );
+        Ok(report)
+    }
+
+    /// Select the source frames to copy and assign their new ids.
+    ///
+    /// Returns the frames to append with ids and frame links already remapped; deduplicated
+    /// frames are only recorded in `report.frame_ids`.
+    fn plan_merged_frames(
+        &self,
+        source: &Memvid,
+        options: &MergeOptions,
+        report: &mut MergeReport,
+    ) -> Result<Vec<Frame>> {
+        // Later frames win, matching `find_frame_by_hash`.
+        let mut known: HashMap<[u8; 32], FrameId> = HashMap::new();
+        if options.dedup {
+            for frame in self.toc.frames.try_iter() {
+                let frame = frame?;
+                if frame.status == FrameStatus::Active {
+                    known.insert(frame.checksum, frame.id);
+                }
+            }
+        }
+
+        let mut next_id = self.toc.frames.len() as FrameId;
+        let mut incoming = Vec::new();
+        for frame in source.toc.frames.try_iter() {
+            let frame = frame?;
+            if frame.status != FrameStatus::Active {
+                continue;
+            }
+            if options.dedup && frame.payload_length > 0 {
+                if let Some(&existing) = known.get(&frame.checksum) {
+                    report.frame_ids.insert(frame.id, existing);
+                    report.frames_deduplicated += 1;
+                    continue;
+                }
+                known.insert(frame.checksum, next_id);
+            }
+            report.frame_ids.insert(frame.id, next_id);
+            incoming.push(frame.clone());
+            next_id += 1;
+        }
+
+        let remap = |frame_id: Option<FrameId>| {
+            frame_id.and_then(|frame_id| report.frame_ids.get(&frame_id).copied())
+        };
+        for frame in &mut incoming {
+            frame.id = report.frame_ids[&frame.id];
+            frame.parent_id = remap(frame.parent_id);
+            frame.supersedes = remap(frame.supersedes);
+            frame.superseded_by = remap(frame.superseded_by);
+        }
+        report.frames_merged = incoming.len() as u64;
+        Ok(incoming)
+    }
+
+    /// Append `frames` to the TOC, copying their payloads from `source` after `data_end`.
+    ///
+    /// Frames sharing a stored payload in the source keep sharing it. Returns the number of
+    /// payload bytes written.
+    fn append_merged_frames(&mut self, source: &File, frames: Vec<Frame>) -> Result<u64> {
+        let mut reader = source;
+        let source_len = source.metadata()?.len();
+        let mut cursor = self.data_end;
+        let mut relocated: HashMap<(u64, u64), (u64, u64)> = HashMap::new();
+
+        self.file.seek(SeekFrom::Start(cursor))?;
+        for mut frame in frames {
+            if frame.payload_length == 0 {
+                frame.payload_offset = 0;
+            } else {
+                let key = (frame.payload_offset, frame.payload_length);
+                if let Some(&(offset, length)) = relocated.get(&key) {
+                    frame.payload_offset = offset;
+                    frame.payload_length = length;
+                } else {
+                    let end = frame.payload_offset.checked_add(frame.payload_length);
+                    if end.is_none_or(|end| end > source_len) {
+                        return Err(MemvidError::InvalidFrame {
+                            frame_id: frame.id,
+                            reason: "payload outside data region",
+                        });
+                    }
+                    reader.seek(SeekFrom::Start(frame.payload_offset))?;
+                    let copied = if self.regions.is_encrypted() {
+                        // Source payloads are plaintext; seal them for this memory.
+                        let mut payload = Vec::with_capacity(frame.payload_length as usize);
+                        (&mut reader)
+                            .take(frame.payload_length)
+                            .read_to_end(&mut payload)?;
+                        if payload.len() as u64 != frame.payload_length {
+                            return Err(MemvidError::InvalidFrame {
+                                frame_id: frame.id,
+                                reason: "payload truncated",
+                            });
+                        }
+                        let length = self.write_region(cursor, RegionKind::Payload, &payload)?;
+                        frame.payload_length = length;
+                        length
+                    } else {
+                        std::io::copy(
+                            &mut (&mut reader).take(frame.payload_length),
+                            &mut self.file,
+                        )?
+                    };
+                    if copied != frame.payload_length {
+                        return Err(MemvidError::InvalidFrame {
+                            frame_id: frame.id,
+                            reason: "payload truncated",
+                        });
+                    }
+                    relocated.insert(key, (cursor, copied));
+                    frame.payload_offset = cursor;
+                    cursor += copied;
+                }
+            }
+            if frame.status == FrameStatus::Active {
+                self.log_frame_insert(&frame);
+                self.note_quota_frame(frame.track.as_deref(), frame.payload_length, true);
+            }
+            self.toc.frames.push(frame);
+        }
+
+        let written = cursor - self.data_end;
+        self.data_end = cursor;
+        self.header.footer_offset = self.header.footer_offset.max(cursor);
+        Ok(written)
+    }
+
+    /// Copy memory cards, enrichment records and the Logic-Mesh of `source`, keeping only
+    /// references to frames that exist in this memory after the merge.
+    ///
+    /// Returns the number of cards and mesh nodes carried over.
+    fn merge_cards_and_mesh(
+        &mut self,
+        source: &Memvid,
+        frame_ids: &BTreeMap<FrameId, FrameId>,
+        first_id: FrameId,
+    ) -> (u64, u64) {
+        let mut cards_merged = 0;
+        let mut nodes_merged = 0;
+        let mut card_ids = HashMap::new();
+        for card in source.memories_track.cards() {
+            // Cards of deduplicated frames already exist in this memory.
+            let Some(&frame_id) = frame_ids
+                .get(&card.source_frame_id)
+                .filter(|id| **id >= first_id)
+            else {
+                continue;
+            };
+            let mut card = card.clone();
+            let source_id = card.id;
+            card.source_frame_id = frame_id;
+            card_ids.insert(
+                source_id,
+                Arc::make_mut(&mut self.memories_track).add_card(card),
+            );
+            cards_merged += 1;
+        }
+
+        let manifest = source.memories_track.enrichment_manifest();
+        for source_frame in manifest.enriched_frames() {
+            let (Some(&frame_id), Some(record)) = (
+                frame_ids.get(&source_frame).filter(|id| **id >= first_id),
+                manifest.get_record(source_frame),
+            ) else {
+                continue;
+            };
+            for stamp in &record.stamps {
+                let cards = stamp
+                    .card_ids
+                    .iter()
+                    .filter_map(|id| card_ids.get(id).copied())
+                    .collect();
+                Arc::make_mut(&mut self.memories_track).record_enrichment(
+                    frame_id,
+                    &stamp.engine_kind,
+                    &stamp.engine_version,
+                    cards,
+                );
+            }
+        }
+
+        for node in &source.logic_mesh.nodes {
+            let mut node = node.clone();
+            node.frame_ids = node
+                .frame_ids
+                .iter()
+                .filter_map(|id| frame_ids.get(id).copied())
+                .collect();
+            node.frame_ids.dedup();
+            node.mentions = node
+                .mentions
+                .iter()
+                .filter_map(|(id, start, len)| frame_ids.get(id).map(|id| (*id, *start, *len)))
+                .collect();
+            if node.frame_ids.is_empty() {
+                continue;
+            }
+            Arc::make_mut(&mut self.logic_mesh).merge_node(node);
+            nodes_merged += 1;
+        }
+        // Node ids derive from name and kind, so edges stay valid once both ends exist.
+        for edge in &source.logic_mesh.edges {
+            let endpoints_known = [edge.from_node, edge.to_node]
+                .iter()
+                .all(|id| self.logic_mesh.nodes.iter().any(|node| node.id == *id));
+            if let Some(&frame_id) = frame_ids.get(&edge.frame_id).filter(|_| endpoints_known) {
+                let mut edge = edge.clone();
+                edge.frame_id = frame_id;
+                Arc::make_mut(&mut self.logic_mesh).merge_edge(edge);
+            }
+        }
+        if nodes_merged > 0 {
+            Arc::make_mut(&mut self.logic_mesh).finalize();
+        }
+        (cards_merged, nodes_merged)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{LogicMesh, MemoryCardBuilder, MeshEdge, MeshNode, SearchRequest};
+    use crate::{EntityKind, LinkType, fast_options, run_serial_test};
+    use tempfile::tempdir;
+
+    #[test]
+    fn merge_remaps_frames_cards_and_mesh() {
+        run_serial_test(|| {
+            let dir = tempdir().expect("tmp");
+            let target_path = dir.path().join("target.mv2");
+            let source_path = dir.path().join("source.mv2");
+
+            let mut target = Memvid::create(&target_path).expect("create target");
+            target
+                .put_bytes_with_options(b"shared onboarding checklist", fast_options())
+                .expect("put");
+            target
+                .put_bytes_with_options(b"target only notes", fast_options())
+                .expect("put");
+            target.commit().expect("commit");
+
+            {
+                let mut source = Memvid::create(&source_path).expect("create source");
+                source
+                    .put_bytes_with_options(b"obsolete harbour plan", fast_options())
+                    .expect("put");
+                source
+                    .put_bytes_with_options(b"shared onboarding checklist", fast_options())
+                    .expect("put");
+                source
+                    .put_bytes_with_options(b"lighthouse maintenance schedule", fast_options())
+                    .expect("put");
+                source.commit().expect("commit");
+                source.delete_frame(0).expect("delete");
+                source
+                    .put_memory_card(
+                        MemoryCardBuilder::new()
+                            .fact()
+                            .entity("lighthouse")
+                            .slot("schedule")
+                            .value("weekly")
+                            .source(2, None)
+                            .engine("rules-v1", "1.0.0")
+                            .build(0)
+                            .expect("card"),
+                    )
+                    .expect("card");
+                let mut mesh = LogicMesh::new();
+                mesh.merge_node(MeshNode::new(
+                    "lighthouse".into(),
+                    "Lighthouse".into(),
+                    EntityKind::Location,
+                    0.9,
+                    2,
+                    0,
+                    10,
+                ));
+                mesh.merge_node(MeshNode::new(
+                    "harbour".into(),
+                    "Harbour".into(),
+                    EntityKind::Location,
+                    0.9,
+                    0,
+                    0,
+                    7,
+                ));
+                let lighthouse = mesh.nodes[0].id;
+                let harbour = mesh.nodes[1].id;
+                mesh.merge_edge(MeshEdge::new(
+                    lighthouse,
+                    harbour,
+                    LinkType::Location,
+                    0.8,
+                    2,
+                ));
+                source.set_logic_mesh(mesh);
+                source.commit().expect("commit");
+            }
+
+            let report = target
+                .merge_from(&source_path, MergeOptions { dedup: true })
+                .expect("merge");
+            assert_eq!(report.frames_merged, 1);
+            assert_eq!(report.frames_deduplicated, 1);
+            assert_eq!(report.frame_ids.get(&0), None);
+            assert_eq!(report.frame_ids[&1], 0);
+            assert_eq!(report.frame_ids[&2], 2);
+            assert_eq!(report.cards_merged, 1);
+            assert_eq!(report.mesh_nodes_merged, 1);
+            drop(target);
+
+            let mut reopened = Memvid::open(&target_path).expect("reopen");
+            assert_eq!(reopened.frame_count(), 3);
+            assert_eq!(
+                reopened.frame_canonical_payload(2).expect("payload"),
+                b"lighthouse maintenance schedule"
+            );
+            let card = reopened
+                .get_current_memory("lighthouse", "schedule")
+                .expect("card");
+            assert_eq!(card.source_frame_id, 2);
+            let node = reopened.find_entity("lighthouse").expect("node");
+            assert_eq!(node.frame_ids, vec![2]);
+            assert!(reopened.find_entity("harbour").is_none());
+            assert!(reopened.logic_mesh().edges.is_empty());
+
+            let response = reopened
+                .search(SearchRequest {
+                    query: "lighthouse".into(),
+                    top_k: 5,
+                    snippet_chars: 80,
+                    uri: None,
+                    scope: None,
+                    cursor: None,
+                    #[cfg(feature = "temporal_track")]
+                    temporal: None,
+                    as_of_frame: None,
+                    as_of_ts: None,
+                    no_sketch: false,
+                })
+                .expect("search");
+            let hits: Vec<_> = response.hits.iter().map(|hit| hit.frame_id).collect();
+            assert_eq!(hits, vec![2]);
+        });
+    }
+
+    #[test]
+    fn merge_without_dedup_copies_duplicates_and_embeddings() {
+        run_serial_test(|| {
+            let dir = tempdir().expect("tmp");
+            let target_path = dir.path().join("target.mv2");
+            let source_path = dir.path().join("source.mv2");
+
+            let mut target = Memvid::create(&target_path).expect("create target");
+            target
+                .put_with_embedding_and_options(
+                    b"first draft",
+                    vec![1.0, 0.0, 0.0, 0.0],
+                    fast_options(),
+                )
+                .expect("put");
+            target.commit().expect("commit");
+
+            {
+                let mut source = Memvid::create(&source_path).expect("create source");
+                source
+                    .put_with_embedding_and_options(
+                        b"first draft",
+                        vec![0.0, 1.0, 0.0, 0.0],
+                        fast_options(),
+                    )
+                    .expect("put");
+                source.commit().expect("commit");
+                source
+                    .update_frame(
+                        0,
+                        Some(b"second draft".to_vec()),
+                        fast_options(),
+                        Some(vec![0.0, 0.0, 1.0, 0.0]),
+                    )
+                    .expect("update");
+                source.commit().expect("commit");
+            }
+
+            let report = target
+                .merge_from(&source_path, MergeOptions::default())
+                .expect("merge");
+            assert_eq!(report.frames_merged, 1);
+            assert_eq!(report.frames_deduplicated, 0);
+            assert_eq!(report.embeddings_merged, 1);
+            assert_eq!(report.frame_ids[&1], 1);
+
+            // The superseded source frame is not copied, so the link is dropped.
+            let frame = target.frame_by_id(1).expect("frame");
+            assert_eq!(frame.supersedes, None);
+            assert_eq!(
+                target.frame_canonical_payload(1).expect("payload"),
+                b"second draft"
+            );
+            assert_eq!(
+                target.frame_canonical_payload(0).expect("payload"),
+                b"first draft"
+            );
+
+            target.ensure_vec_index().expect("vec index");
+            let index = target.vec_index.as_ref().expect("vec index loaded");
+            assert_eq!(index.embedding_for(0), Some(&[1.0, 0.0, 0.0, 0.0][..]));
+            assert_eq!(index.embedding_for(1), Some(&[0.0, 0.0, 1.0, 0.0][..]));
+
+            let mut mismatched = Memvid::create(dir.path().join("wide.mv2")).expect("create");
+            mismatched
+                .put_with_embedding_and_options(b"wide", vec![1.0; 8], fast_options())
+                .expect("put");
+            mismatched.commit().expect("commit");
+            drop(mismatched);
+            let err = target
+                .merge_from(&dir.path().join("wide.mv2"), MergeOptions::default())
+                .expect_err("dimension mismatch");
+            assert!(matches!(err, MemvidError::VecDimensionMismatch { .. }));
+            assert_eq!(target.frame_count(), 2);
+        });
+    }
+
+    #[test]
+    fn merge_appends_in_place_and_a_failed_tail_leaves_the_last_commit() {
+        run_serial_test(|| {
+            let dir = tempdir().expect("tmp");
+            let target_path = dir.path().join("target.mv2");
+            let source_path = dir.path().join("source.mv2");
+
+            let mut target = Memvid::create(&target_path).expect("create target");
+            target
+                .put_bytes_with_options(b"target notes", fast_options())
+                .expect("put");
+            target.commit().expect("commit");
+            {
+                let mut source = Memvid::create(&source_path).expect("create source");
+                source
+                    .put_bytes_with_options(b"source notes", fast_options())
+                    .expect("put");
+                source.commit().expect("commit");
+            }
+
+            let data_start = (target.header.wal_offset + target.header.wal_size) as usize;
+            let committed = std::fs::read(&target_path).expect("read");
+            let handle = File::open(&target_path).expect("open");
+            target
+                .merge_from(&source_path, MergeOptions::default())
+                .expect("merge");
+            let merged = std::fs::read(&target_path).expect("read");
+            // The file is extended rather than replaced by a copy.
+            assert_eq!(
+                handle.metadata().expect("metadata").len(),
+                merged.len() as u64
+            );
+            assert_eq!(
+                merged[data_start..committed.len()],
+                committed[data_start..],
+                "merge only appends after the committed bytes"
+            );
+            assert_eq!(target.frame_count(), 2);
+
+            // A crash part-way through a tail, or its failure, keeps the last commit.
+            let committed_len = merged.len() as u64;
+            let crashed_path = dir.path().join("crashed.mv2");
+            let err = target
+                .with_staged_tail(|mem| {
+                    mem.begin_generation()?;
+                    let offset = mem.data_end;
+                    mem.data_end += mem.write_region(offset, RegionKind::Payload, b"orphan")?;
+                    mem.rebuild_indexes(&[])?;
+                    std::fs::copy(mem.path(), &crashed_path)?;
+                    Err(MemvidError::CheckpointFailed {
+                        reason: "injected".into(),
+                    })
+                })
+                .expect_err("injected failure");
+            assert!(matches!(err, MemvidError::CheckpointFailed { .. }));
+            assert_eq!(
+                std::fs::metadata(&target_path).expect("metadata").len(),
+                committed_len
+            );
+            drop(target);
+
+            for path in [&target_path, &crashed_path] {
+                let mut reopened = Memvid::open(path).expect("reopen");
+                assert_eq!(reopened.frame_count(), 2);
+                assert_eq!(
+                    reopened.frame_canonical_payload(1).expect("payload"),
+                    b"source notes"
+                );
+            }
+        });
+    }
+}
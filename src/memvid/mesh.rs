@@ -4,6 +4,8 @@
 //! graph within an MV2 file, including adding nodes/edges, traversing relationships,
 //! and querying entities.
 
+use std::sync::Arc;
+
 use crate::memvid::lifecycle::Memvid;
 use crate::types::{
     EntityKind, FollowResult, FrameId, LogicMesh, LogicMeshStats, MeshEdge, MeshNode,
@@ -26,7 +28,7 @@ impl Memvid {
     /// Changes are persisted when the file is committed or sealed.
     pub fn logic_mesh_mut(&mut self) -> &mut LogicMesh {
         self.dirty = true;
-        &mut self.logic_mesh
+        Arc::make_mut(&mut self.logic_mesh)
     }
 
     /// Replace the entire Logic-Mesh with a new one.
@@ -35,7 +37,7 @@ impl Memvid {
     /// Changes are persisted when the file is committed or sealed.
     pub fn set_logic_mesh(&mut self, mesh: LogicMesh) {
         self.dirty = true;
-        self.logic_mesh = mesh;
+        self.logic_mesh = Arc::new(mesh);
     }
 
     /// Add a mesh node (entity) to the Logic-Mesh.
@@ -47,7 +49,7 @@ impl Memvid {
     /// * `node` - The mesh node to add
     pub fn add_mesh_node(&mut self, node: MeshNode) {
         self.dirty = true;
-        self.logic_mesh.merge_node(node);
+        Arc::make_mut(&mut self.logic_mesh).merge_node(node);
     }
 
     /// Add multiple mesh nodes at once.
@@ -57,7 +59,7 @@ impl Memvid {
     pub fn add_mesh_nodes(&mut self, nodes: Vec<MeshNode>) {
         self.dirty = true;
         for node in nodes {
-            self.logic_mesh.merge_node(node);
+            Arc::make_mut(&mut self.logic_mesh).merge_node(node);
         }
     }
 
@@ -69,7 +71,7 @@ impl Memvid {
     /// * `edge` - The mesh edge to add
     pub fn add_mesh_edge(&mut self, edge: MeshEdge) {
         self.dirty = true;
-        self.logic_mesh.merge_edge(edge);
+        Arc::make_mut(&mut self.logic_mesh).merge_edge(edge);
     }
 
     /// Add multiple mesh edges at once.
@@ -79,7 +81,7 @@ impl Memvid {
     pub fn add_mesh_edges(&mut self, edges: Vec<MeshEdge>) {
         self.dirty = true;
         for edge in edges {
-            self.logic_mesh.merge_edge(edge);
+            Arc::make_mut(&mut self.logic_mesh).merge_edge(edge);
         }
     }
 
@@ -1,28 +1,43 @@
 //! Core `Memvid` type orchestrating `.mv2` lifecycle and mutations.
 
+pub mod archive;
 pub mod ask;
 pub mod audit;
+pub mod blob_store;
 #[cfg(feature = "parallel_segments")]
 pub mod builder;
+pub mod changes;
 pub mod chunks;
+pub mod compression;
+pub mod diff;
 pub mod doctor;
 pub mod enrichment;
+pub mod erasure;
 pub mod frame;
 mod helpers;
 pub mod lifecycle;
 pub mod maintenance;
 pub mod memory;
+pub mod merge;
 pub mod mesh;
 pub mod mutation;
 #[cfg(feature = "parallel_segments")]
 pub mod planner;
+pub mod quota;
+pub mod reader;
+pub mod refresh;
 #[cfg(feature = "replay")]
 pub mod replay_ops;
+pub mod retention;
 pub mod search;
 mod segments;
 pub mod sketch;
+pub mod stream;
 pub mod ticket;
 pub mod timeline;
+pub mod transaction;
+pub mod vacuum;
+pub mod versions;
 #[cfg(feature = "parallel_segments")]
 pub mod workers;
 
@@ -34,4 +49,6 @@ pub use enrichment::{
 };
 pub use frame::BlobReader;
 pub use lifecycle::{LockSettings, Memvid, OpenReadOptions};
+pub use reader::MemvidReader;
 pub use sketch::{SketchCandidate, SketchSearchOptions, SketchSearchStats};
+pub use transaction::Transaction;
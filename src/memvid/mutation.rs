@@ -10,13 +10,14 @@
 use std::cmp::min;
 use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fs::{File, OpenOptions};
-use std::io::{Read, Seek, SeekFrom, Write};
+use std::io::{Cursor, Read, Seek, SeekFrom, Write};
 use std::path::Path;
-use std::sync::OnceLock;
+use std::sync::{Arc, OnceLock};
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use bincode::serde::{decode_from_slice, encode_to_vec};
 use blake3::hash;
+use ed25519_dalek::SigningKey;
 use log::info;
 #[cfg(feature = "temporal_track")]
 use once_cell::sync::OnceCell;
@@ -41,6 +42,8 @@ use crate::TemporalTrackManifest;
 use crate::analysis::auto_tag::AutoTagger;
 use crate::constants::{WAL_SIZE_LARGE, WAL_SIZE_MEDIUM};
 use crate::footer::CommitFooter;
+use crate::frame_table::FrameTable;
+use crate::io::region::RegionKind;
 use crate::io::wal::{EmbeddedWal, WalRecord};
 use crate::memvid::chunks::{plan_document_chunks, plan_text_chunks};
 use crate::memvid::lifecycle::{Memvid, prepare_toc_bytes};
@@ -50,12 +53,13 @@ use crate::reader::{
 };
 #[cfg(feature = "lex")]
 use crate::search::{EmbeddedLexSegment, LexWalBatch, TantivySnapshot};
+use crate::signature::sign_commit;
 use crate::triplet::TripletExtractor;
 #[cfg(feature = "lex")]
 use crate::types::TantivySegmentDescriptor;
 use crate::types::{
-    CanonicalEncoding, DocMetadata, Frame, FrameId, FrameRole, FrameStatus, PutOptions,
-    SegmentCommon, TextChunkManifest, Tier,
+    BlobChunk, CanonicalEncoding, ChangeKind, CommitSignature, DocMetadata, Frame, FrameId,
+    FrameRole, FrameStatus, MemoryCard, PutOptions, SegmentCommon, TextChunkManifest, Tier,
 };
 #[cfg(feature = "parallel_segments")]
 use crate::types::{IndexSegmentRef, SegmentKind, SegmentSpan, SegmentStats};
@@ -66,8 +70,9 @@ use crate::{
     TemporalResolutionValue,
 };
 use crate::{
-    DEFAULT_SEARCH_TEXT_LIMIT, ExtractedDocument, MemvidError, Result, TimeIndexEntry,
-    TimeIndexManifest, VecIndexManifest, normalize_text, time_index_append, wal_config,
+    DEFAULT_SEARCH_TEXT_LIMIT, ExtractedDocument, HnswGraphManifest, MemvidError, Result,
+    TimeIndexEntry, TimeIndexManifest, VecIndexManifest, normalize_text, time_index_append,
+    wal_config,
 };
 #[cfg(feature = "temporal_track")]
 use time::{Date, Month, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
@@ -145,6 +150,24 @@ impl CommitStaging {
         Ok(())
     }
 
+    fn copy_prefix(&mut self, source: &File, len: u64) -> Result<()> {
+        let mut reader = source.try_clone()?;
+        reader.seek(SeekFrom::Start(0))?;
+
+        let writer = self.atomic.as_file_mut();
+        writer.set_len(0)?;
+        writer.seek(SeekFrom::Start(0))?;
+        let copied = std::io::copy(&mut (&mut reader).take(len), writer)?;
+        if copied != len {
+            return Err(MemvidError::InvalidHeader {
+                reason: "file shorter than header and wal region".into(),
+            });
+        }
+        writer.flush()?;
+        writer.sync_all()?;
+        Ok(())
+    }
+
     fn clone_file(&self) -> Result<File> {
         Ok(self.atomic.as_file().try_clone()?)
     }
@@ -163,6 +186,7 @@ struct IngestionDelta {
     inserted_frames: Vec<FrameId>,
     inserted_embeddings: Vec<(FrameId, Vec<f32>)>,
     inserted_time_entries: Vec<TimeIndexEntry>,
+    inserted_cards: usize,
     mutated_frames: bool,
     #[cfg(feature = "temporal_track")]
     inserted_temporal_mentions: Vec<TemporalMention>,
@@ -176,6 +200,7 @@ impl IngestionDelta {
         let mut empty = self.inserted_frames.is_empty()
             && self.inserted_embeddings.is_empty()
             && self.inserted_time_entries.is_empty()
+            && self.inserted_cards == 0
             && !self.mutated_frames;
         #[cfg(feature = "temporal_track")]
         {
@@ -199,10 +224,12 @@ impl Default for CommitMode {
     }
 }
 
-#[derive(Clone, Copy, Debug, Default)]
+#[derive(Clone, Debug, Default)]
 pub struct CommitOptions {
     pub mode: CommitMode,
     pub background: bool,
+    /// Sign the commit, chaining it to the previous generation's signature.
+    pub signing_key: Option<SigningKey>,
 }
 
 impl CommitOptions {
@@ -210,6 +237,7 @@ impl CommitOptions {
         Self {
             mode,
             background: false,
+            signing_key: None,
         }
     }
 
@@ -217,6 +245,17 @@ impl CommitOptions {
         self.background = background;
         self
     }
+
+    pub fn signing_key(mut self, signing_key: SigningKey) -> Self {
+        self.signing_key = Some(signing_key);
+        self
+    }
+}
+
+/// Key signing the current generation, with the signature of the generation it follows.
+pub(crate) struct CommitSigner {
+    key: SigningKey,
+    previous: Option<Vec<u8>>,
 }
 
 fn default_reader_registry() -> &'static ReaderRegistry {
@@ -411,12 +450,25 @@ impl Memvid {
     // -- Public ingestion entrypoints ---------------------------------------------------------
 
     fn with_staging_lock<F>(&mut self, op: F) -> Result<()>
+    where
+        F: FnOnce(&mut Self) -> Result<()>,
+    {
+        self.with_staging(None, op)
+    }
+
+    /// Run `op` against a temporary sibling of the memory file and rename it over the
+    /// original on success. The sibling starts as a full copy of the file, or only its
+    /// first `seed_len` bytes when given; the original is never written either way.
+    pub(super) fn with_staging<F>(&mut self, seed_len: Option<u64>, op: F) -> Result<()>
     where
         F: FnOnce(&mut Self) -> Result<()>,
     {
         self.file.sync_all()?;
         let mut staging = CommitStaging::prepare(self.path())?;
-        staging.copy_from(&self.file)?;
+        match seed_len {
+            Some(len) => staging.copy_prefix(&self.file, len)?,
+            None => staging.copy_from(&self.file)?,
+        }
 
         let staging_handle = staging.clone_file()?;
         let new_wal = EmbeddedWal::open(&staging_handle, &self.header)?;
@@ -424,6 +476,8 @@ impl Memvid {
         let original_wal = std::mem::replace(&mut self.wal, new_wal);
         let original_header = self.header.clone();
         let original_toc = self.toc.clone();
+        let original_blob_store = self.blob_store.clone();
+        let original_change_log = self.change_log.clone();
         let original_data_end = self.data_end;
         let original_generation = self.generation;
         let original_dirty = self.dirty;
@@ -446,6 +500,7 @@ impl Memvid {
                             .write(true)
                             .open(&destination_path)?;
                         self.wal = EmbeddedWal::open(&self.file, &self.header)?;
+                        self.publish_reader();
                         Ok(())
                     }
                     Err(commit_err) => {
@@ -457,6 +512,10 @@ impl Memvid {
                         }
                         self.header = original_header;
                         self.toc = original_toc;
+                        self.quota_usage = None;
+                        self.retention_check = None;
+                        self.blob_store = original_blob_store;
+                        self.change_log = original_change_log;
                         self.data_end = original_data_end;
                         self.generation = original_generation;
                         self.dirty = original_dirty;
@@ -478,6 +537,65 @@ impl Memvid {
                 }
                 self.header = original_header;
                 self.toc = original_toc;
+                self.quota_usage = None;
+                self.retention_check = None;
+                self.blob_store = original_blob_store;
+                self.change_log = original_change_log;
+                self.data_end = original_data_end;
+                self.generation = original_generation;
+                self.dirty = original_dirty;
+                #[cfg(feature = "lex")]
+                {
+                    self.tantivy_dirty = original_tantivy_dirty;
+                }
+                Err(err)
+            }
+        }
+    }
+
+    /// Run `op` appending after the committed end of the memory file in place, then write
+    /// the commit footer once. Until then footer writes are skipped and the WAL is left
+    /// alone, so neither a failure, which truncates the file back, nor a crash exposes
+    /// anything written by `op`.
+    pub(super) fn with_staged_tail<F>(&mut self, op: F) -> Result<()>
+    where
+        F: FnOnce(&mut Self) -> Result<()>,
+    {
+        self.file.sync_all()?;
+        let committed_len = self.file.metadata()?.len();
+        let original_header = self.header.clone();
+        let original_toc = self.toc.clone();
+        let original_blob_store = self.blob_store.clone();
+        let original_change_log = self.change_log.clone();
+        let original_data_end = self.data_end;
+        let original_generation = self.generation;
+        let original_dirty = self.dirty;
+        #[cfg(feature = "lex")]
+        let original_tantivy_dirty = self.tantivy_dirty;
+
+        self.staged_tail = Some(committed_len);
+        let result = op(self);
+        self.staged_tail = None;
+        let result = result.and_then(|()| {
+            self.rewrite_toc_footer()?;
+            self.header.toc_checksum = self.toc.toc_checksum;
+            crate::persist_header(&mut self.file, &self.header)?;
+            self.file.sync_all()?;
+            Ok(())
+        });
+
+        match result {
+            Ok(()) => {
+                self.publish_reader();
+                Ok(())
+            }
+            Err(err) => {
+                self.header = original_header;
+                self.toc = original_toc;
+                self.quota_usage = None;
+                self.retention_check = None;
+                self.blob_store = original_blob_store;
+                self.change_log = original_change_log;
                 self.data_end = original_data_end;
                 self.generation = original_generation;
                 self.dirty = original_dirty;
@@ -485,6 +603,15 @@ impl Memvid {
                 {
                     self.tantivy_dirty = original_tantivy_dirty;
                 }
+                let truncated = self
+                    .file
+                    .set_len(committed_len)
+                    .map_err(MemvidError::from)
+                    .and_then(|()| crate::persist_header(&mut self.file, &self.header))
+                    .and_then(|()| Ok(self.file.sync_all()?));
+                if let Err(undo_err) = truncated {
+                    tracing::error!(error = %undo_err, "failed to truncate the staged tail");
+                }
                 Err(err)
             }
         }
@@ -538,7 +665,7 @@ impl Memvid {
 
         if let Some(manifest) = self.toc.indexes.vec.as_ref() {
             if manifest.bytes_length != 0 {
-                max_end = max_end.max(manifest.bytes_offset + manifest.bytes_length);
+                max_end = max_end.max(manifest.end_offset());
             }
         }
 
@@ -558,47 +685,27 @@ impl Memvid {
         max_end
     }
 
-    fn payload_region_end(&self) -> u64 {
+    pub(crate) fn payload_region_end(&self) -> u64 {
         let wal_region_end = self.header.wal_offset + self.header.wal_size;
-        let frames_with_payload: Vec<_> = self
-            .toc
-            .frames
-            .iter()
-            .filter(|frame| frame.payload_length != 0)
-            .collect();
-
+        let result = wal_region_end.max(self.toc.frames.payload_end());
         tracing::info!(
-            "payload_region_end: found {} frames with payloads out of {} total frames, wal_region_end={}",
-            frames_with_payload.len(),
+            "payload_region_end: {} frames, wal_region_end={}, returning {}",
             self.toc.frames.len(),
-            wal_region_end
+            wal_region_end,
+            result
         );
-
-        for (idx, frame) in frames_with_payload.iter().enumerate().take(3) {
-            tracing::info!(
-                "  frame[{}]: id={} offset={} length={} status={:?}",
-                idx,
-                frame.id,
-                frame.payload_offset,
-                frame.payload_length,
-                frame.status
-            );
-        }
-
-        let result = frames_with_payload
-            .iter()
-            .fold(wal_region_end, |max_end, frame| {
-                match frame.payload_offset.checked_add(frame.payload_length) {
-                    Some(end) => max_end.max(end),
-                    None => max_end,
-                }
-            });
-
-        tracing::info!("payload_region_end: returning {}", result);
         result
     }
 
     fn append_wal_entry(&mut self, payload: &[u8]) -> Result<u64> {
+        // A replayed record could refer to a tail that was never committed.
+        if self.staged_tail.is_some() {
+            return Err(MemvidError::CheckpointFailed {
+                reason: "WAL is read-only while the file is extended in place".into(),
+            });
+        }
+        let payload = self.regions.seal(RegionKind::Wal, payload)?;
+        let payload: &[u8] = &payload;
         loop {
             match self.wal.append_entry(payload) {
                 Ok(seq) => return Ok(seq),
@@ -618,6 +725,24 @@ impl Memvid {
         }
     }
 
+    pub(crate) fn append_wal_marker(&mut self, marker: WalGroupMarker) -> Result<u64> {
+        let payload = encode_to_vec(WalEntry::Group(marker), wal_config())?;
+        self.append_wal_entry(&payload)
+    }
+
+    pub(crate) fn append_wal_memory_cards(&mut self, cards: &[MemoryCard]) -> Result<u64> {
+        let cards = serde_json::to_vec(cards).map_err(|err| MemvidError::CheckpointFailed {
+            reason: format!("failed to encode memory cards: {err}"),
+        })?;
+        let payload = encode_to_vec(WalEntry::MemoryCards(cards), wal_config())?;
+        self.append_wal_entry(&payload)
+    }
+
+    pub(crate) fn append_wal_blob_chunks(&mut self, chunks: &[BlobChunk]) -> Result<u64> {
+        let payload = encode_to_vec(WalEntry::BlobChunks(chunks.to_vec()), wal_config())?;
+        self.append_wal_entry(&payload)
+    }
+
     fn grow_wal_region(&mut self, required_entry_size: u64) -> Result<()> {
         let mut new_size = self.header.wal_size;
         let mut target = required_entry_size;
@@ -636,16 +761,50 @@ impl Memvid {
             return Ok(());
         }
 
-        self.shift_data_for_wal_growth(delta)?;
+        // Snapshot what the growth changes in memory so a failure leaves everything,
+        // including frames applied but not yet written, as it was.
+        let original_len = self.file.metadata()?.len();
+        let header = self.header.clone();
+        let toc = self.toc.clone();
+        let data_end = self.data_end;
+        let blob_store = self.blob_store.clone();
+        let change_log = self.change_log.clone();
+        #[cfg(feature = "lex")]
+        let lex_storage = self.lex_storage.read().ok().map(|storage| storage.clone());
+
+        self.shift_data_for_wal_growth(delta, original_len)?;
+        if let Err(err) = self.finish_wal_growth(new_size, delta, original_len) {
+            self.header = header;
+            self.toc = toc;
+            self.quota_usage = None;
+            self.retention_check = None;
+            self.data_end = data_end;
+            self.blob_store = blob_store;
+            self.change_log = change_log;
+            #[cfg(feature = "lex")]
+            if let (Some(saved), Ok(mut storage)) = (lex_storage, self.lex_storage.write()) {
+                *storage = saved;
+            }
+            if let Err(undo_err) = self.undo_wal_growth(delta, original_len) {
+                tracing::error!(error = %undo_err, "failed to undo WAL growth");
+            }
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    fn finish_wal_growth(&mut self, new_size: u64, delta: u64, original_len: u64) -> Result<()> {
         self.header.wal_size = new_size;
         self.header.footer_offset = self.header.footer_offset.saturating_add(delta);
         self.data_end = self.data_end.saturating_add(delta);
-        self.adjust_offsets_after_wal_growth(delta);
+        self.adjust_offsets_after_wal_growth(delta)?;
 
+        // Write past the shifted bytes so they can still be moved back on failure.
         let catalog_end = self.catalog_data_end();
         self.header.footer_offset = catalog_end
             .max(self.header.footer_offset)
-            .max(self.data_end);
+            .max(self.data_end)
+            .max(original_len + delta);
 
         self.rewrite_toc_footer()?;
         self.header.toc_checksum = self.toc.toc_checksum;
@@ -655,48 +814,44 @@ impl Memvid {
         Ok(())
     }
 
-    fn shift_data_for_wal_growth(&mut self, delta: u64) -> Result<()> {
-        if delta == 0 {
-            return Ok(());
-        }
-        let original_len = self.file.metadata()?.len();
+    /// Moves everything after the WAL region `delta` bytes further into the file and
+    /// zeroes the gap. On failure the bytes already moved are copied back.
+    fn shift_data_for_wal_growth(&mut self, delta: u64, original_len: u64) -> Result<()> {
         let data_start = self.header.wal_offset + self.header.wal_size;
         self.file.set_len(original_len + delta)?;
 
-        let mut remaining = original_len.saturating_sub(data_start);
-        let mut buffer = vec![0u8; WAL_SHIFT_BUFFER_SIZE];
-        while remaining > 0 {
-            let chunk = min(remaining, buffer.len() as u64);
-            let src = data_start + remaining - chunk;
-            self.file.seek(SeekFrom::Start(src))?;
-            self.file.read_exact(&mut buffer[..chunk as usize])?;
-            let dst = src + delta;
-            self.file.seek(SeekFrom::Start(dst))?;
-            self.file.write_all(&buffer[..chunk as usize])?;
-            remaining -= chunk;
-        }
-
-        self.file.seek(SeekFrom::Start(data_start))?;
-        let zero_buf = vec![0u8; WAL_SHIFT_BUFFER_SIZE];
-        let mut remaining = delta;
-        while remaining > 0 {
-            let write = min(remaining, zero_buf.len() as u64);
-            self.file.write_all(&zero_buf[..write as usize])?;
-            remaining -= write;
+        let mut moved_from = original_len;
+        let shifted = move_forward(&mut self.file, data_start, delta, &mut moved_from)
+            .and_then(|()| zero_range(&mut self.file, data_start, delta));
+        if let Err(err) = shifted {
+            move_back(&mut self.file, moved_from, original_len, delta)?;
+            self.file.set_len(original_len)?;
+            return Err(err);
         }
         Ok(())
     }
 
-    fn adjust_offsets_after_wal_growth(&mut self, delta: u64) {
+    /// Moves the data back to where it was before a failed WAL growth and restores the
+    /// header on disk; the in-memory state must already be restored.
+    fn undo_wal_growth(&mut self, delta: u64, original_len: u64) -> Result<()> {
+        let data_start = self.header.wal_offset + self.header.wal_size;
+        move_back(&mut self.file, data_start, original_len, delta)?;
+        self.file.set_len(original_len)?;
+        crate::persist_header(&mut self.file, &self.header)?;
+        self.file.sync_all()?;
+        Ok(())
+    }
+
+    pub(crate) fn adjust_offsets_after_wal_growth(&mut self, delta: u64) -> Result<()> {
         if delta == 0 {
-            return;
+            return Ok(());
         }
 
-        for frame in &mut self.toc.frames {
-            if frame.payload_offset != 0 {
-                frame.payload_offset += delta;
-            }
+        // Frame table pages moved with the data; frames not written yet stay in memory.
+        if let Some(table) = self.toc.frame_table.as_mut() {
+            table.shift(delta);
         }
+        self.toc.frames.shift(&self.file, delta)?;
 
         for segment in &mut self.toc.segments {
             if segment.bytes_offset != 0 {
@@ -718,6 +873,9 @@ impl Memvid {
             if vec.bytes_offset != 0 {
                 vec.bytes_offset += delta;
             }
+            if let Some(graph) = vec.hnsw.as_mut() {
+                graph.bytes_offset += delta;
+            }
         }
         if let Some(time_index) = self.toc.time_index.as_mut() {
             if time_index.bytes_offset != 0 {
@@ -730,6 +888,33 @@ impl Memvid {
                 track.bytes_offset += delta;
             }
         }
+        if let Some(clip) = self.toc.indexes.clip.as_mut() {
+            if clip.bytes_offset != 0 {
+                clip.bytes_offset += delta;
+            }
+        }
+        if let Some(track) = self.toc.memories_track.as_mut() {
+            track.bytes_offset += delta;
+        }
+        if let Some(mesh) = self.toc.logic_mesh.as_mut() {
+            mesh.bytes_offset += delta;
+        }
+        if let Some(track) = self.toc.sketch_track.as_mut() {
+            track.bytes_offset += delta;
+        }
+        if let Some(replay) = self.toc.replay_manifest.as_mut() {
+            replay.segment_offset += delta;
+        }
+        for dictionary in &mut self.toc.compression.dictionaries {
+            dictionary.bytes_offset += delta;
+        }
+        if let Some(manifest) = self.toc.blob_store.as_mut() {
+            manifest.bytes_offset += delta;
+        }
+        self.blob_store.shift(delta);
+        if let Some(manifest) = self.toc.change_log.as_mut() {
+            manifest.bytes_offset += delta;
+        }
 
         let catalog = &mut self.toc.segment_catalog;
         for descriptor in &mut catalog.lex_segments {
@@ -763,7 +948,31 @@ impl Memvid {
         if let Ok(mut storage) = self.lex_storage.write() {
             storage.adjust_offsets(delta);
         }
+        Ok(())
+    }
+    /// Start a new commit generation on top of the committed file.
+    ///
+    /// Everything the new generation writes is appended after the last commit footer,
+    /// so earlier generations stay readable via `open_at_generation` until a vacuum.
+    pub(crate) fn begin_generation(&mut self) -> Result<()> {
+        self.generation = self.generation.wrapping_add(1);
+        let committed_end = self.file.metadata()?.len();
+        self.data_end = self.data_end.max(committed_end);
+        self.header.footer_offset = self.header.footer_offset.max(committed_end);
+        self.toc.committed_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .ok()
+            .map(|d| d.as_secs() as i64);
+        if let Some(signer) = self.commit_signer.as_mut() {
+            signer.previous = self
+                .toc
+                .commit_signature
+                .as_ref()
+                .map(|signature| signature.signature.clone());
+        }
+        Ok(())
     }
+
     pub fn commit_with_options(&mut self, options: CommitOptions) -> Result<()> {
         self.ensure_writable()?;
         if options.background {
@@ -774,6 +983,10 @@ impl Memvid {
         if records.is_empty() && !self.dirty && !self.tantivy_index_pending() {
             return Ok(());
         }
+        self.commit_signer = options.signing_key.map(|key| CommitSigner {
+            key,
+            previous: None,
+        });
         self.with_staging_lock(move |mem| mem.commit_from_records(records, mode))
     }
 
@@ -783,9 +996,13 @@ impl Memvid {
     }
 
     fn commit_from_records(&mut self, records: Vec<WalRecord>, _mode: CommitMode) -> Result<()> {
-        self.generation = self.generation.wrapping_add(1);
+        self.begin_generation()?;
 
-        let delta = self.apply_records(records)?;
+        let mut delta = self.apply_records(records)?;
+        if self.enforce_retention()? {
+            delta.mutated_frames = true;
+        }
+        self.log_track_changes();
         let mut indexes_rebuilt = false;
 
         // Check if CLIP index has pending embeddings that need to be persisted
@@ -849,6 +1066,8 @@ impl Memvid {
             wal.truncate()?;
         }
         self.pending_frame_inserts = 0;
+        self.pending_track_bytes.clear();
+        self.pending_versions.clear();
         self.dirty = false;
         Ok(())
     }
@@ -868,9 +1087,13 @@ impl Memvid {
         if !self.dirty && !self.tantivy_index_pending() {
             return Ok(());
         }
+        self.begin_generation()?;
         let records = self.wal.pending_records()?;
-        let delta = self.apply_records(records)?;
-        self.generation = self.generation.wrapping_add(1);
+        let mut delta = self.apply_records(records)?;
+        if self.enforce_retention()? {
+            delta.mutated_frames = true;
+        }
+        self.log_track_changes();
         let mut indexes_rebuilt = false;
         if !delta.is_empty() {
             tracing::info!(
@@ -913,7 +1136,7 @@ impl Memvid {
 
                     for frame_id in &delta.inserted_frames {
                         // Look up the actual Frame from the TOC
-                        let frame = match self.toc.frames.get(*frame_id as usize) {
+                        let frame = match self.toc.frames.try_get(*frame_id as usize)? {
                             Some(f) => f.clone(),
                             None => continue,
                         };
@@ -973,18 +1196,16 @@ impl Memvid {
 
                 // Time index stores all entries together for timeline queries.
                 // Unlike Tantivy which is incremental, time index needs full rebuild.
-                self.file.seek(SeekFrom::Start(self.data_end))?;
-                let mut time_entries: Vec<TimeIndexEntry> = self
-                    .toc
-                    .frames
-                    .iter()
-                    .filter(|frame| {
-                        frame.status == FrameStatus::Active && frame.role == FrameRole::Document
-                    })
-                    .map(|frame| TimeIndexEntry::new(frame.timestamp, frame.id))
-                    .collect();
-                let (ti_offset, ti_length, ti_checksum) =
-                    time_index_append(&mut self.file, &mut time_entries)?;
+                let mut time_entries: Vec<TimeIndexEntry> = Vec::new();
+                for frame in self.toc.frames.try_iter() {
+                    let frame = frame?;
+                    if frame.status == FrameStatus::Active && frame.role == FrameRole::Document {
+                        time_entries.push(TimeIndexEntry::new(frame.timestamp, frame.id));
+                    }
+                }
+                let ti_offset = self.data_end;
+                let (ti_length, ti_checksum) =
+                    self.write_time_index(ti_offset, &mut time_entries)?;
                 self.toc.time_index = Some(TimeIndexManifest {
                     bytes_offset: ti_offset,
                     bytes_length: ti_length,
@@ -1049,6 +1270,8 @@ impl Memvid {
             wal.truncate()?;
         }
         self.pending_frame_inserts = 0;
+        self.pending_track_bytes.clear();
+        self.pending_versions.clear();
         self.dirty = false;
         Ok(())
     }
@@ -1062,6 +1285,7 @@ impl Memvid {
             return Ok(());
         }
         let delta = self.apply_records(records)?;
+        self.log_track_changes();
         if !delta.is_empty() {
             tracing::debug!(
                 inserted_frames = delta.inserted_frames.len(),
@@ -1083,6 +1307,8 @@ impl Memvid {
         }
         self.file.sync_all()?;
         self.pending_frame_inserts = 0;
+        self.pending_track_bytes.clear();
+        self.pending_versions.clear();
         self.dirty = false;
         Ok(())
     }
@@ -1102,14 +1328,46 @@ impl Memvid {
 
         if !records.is_empty() {
             self.file.seek(SeekFrom::Start(data_cursor))?;
+            let mut entries = Vec::with_capacity(records.len());
             for record in records {
-                let mut entry = match decode_wal_entry(&record.payload)? {
+                let record_payload = self.regions.open(RegionKind::Wal, &record.payload)?;
+                entries.push((record.sequence, decode_wal_entry(&record_payload)?));
+            }
+            for (sequence, entry) in resolve_wal_groups(entries) {
+                let mut entry = match entry {
                     WalEntry::Frame(entry) => entry,
                     #[cfg(feature = "lex")]
                     WalEntry::Lex(batch) => {
                         self.apply_lex_wal(batch)?;
                         continue;
                     }
+                    #[cfg(not(feature = "lex"))]
+                    WalEntry::Lex(_) => continue,
+                    WalEntry::Group(_) => continue,
+                    WalEntry::MemoryCards(bytes) => {
+                        let cards: Vec<MemoryCard> =
+                            serde_json::from_slice(&bytes).map_err(|err| {
+                                MemvidError::CheckpointFailed {
+                                    reason: format!(
+                                        "invalid memory cards in WAL record {sequence}: {err}"
+                                    ),
+                                }
+                            })?;
+                        delta.inserted_cards += Arc::make_mut(&mut self.memories_track)
+                            .add_cards(cards)
+                            .len();
+                        continue;
+                    }
+                    WalEntry::BlobChunks(chunks) => {
+                        // Chunks already held were stored since the record was written,
+                        // possibly moved by a WAL growth; the record's offsets are stale.
+                        for chunk in chunks {
+                            if !self.blob_store.contains(&chunk.hash) {
+                                self.blob_store.insert(chunk);
+                            }
+                        }
+                        continue;
+                    }
                 };
 
                 match entry.op {
@@ -1128,12 +1386,15 @@ impl Memvid {
                                     reason: "reused payload entry contained inline bytes",
                                 });
                             }
-                            let source = self.toc.frames.get(source_id as usize).cloned().ok_or(
-                                MemvidError::InvalidFrame {
+                            let source = self
+                                .toc
+                                .frames
+                                .try_get(source_id as usize)?
+                                .cloned()
+                                .ok_or(MemvidError::InvalidFrame {
                                     frame_id: source_id,
                                     reason: "reused payload source missing",
-                                },
-                            )?;
+                                })?;
                             (
                                 source.payload_offset,
                                 source.payload_length,
@@ -1144,10 +1405,31 @@ impl Memvid {
                                     .unwrap_or(source.payload_length),
                             )
                         } else {
-                            self.file.seek(SeekFrom::Start(data_cursor))?;
-                            self.file.write_all(&entry.payload)?;
-                            let checksum = hash(&entry.payload);
-                            let payload_length = entry.payload.len() as u64;
+                            // Chunked payloads arrive raw; their new chunks go first and
+                            // the frame stores only the recipe. Streamed payloads already
+                            // wrote their chunks and carry the recipe with the raw hash.
+                            let streamed_checksum = entry
+                                .source_sha256
+                                .filter(|_| entry.canonical_encoding == CanonicalEncoding::Chunked);
+                            let recipe = if streamed_checksum.is_some() {
+                                None
+                            } else if entry.canonical_encoding == CanonicalEncoding::Chunked {
+                                let (recipe, written) =
+                                    self.append_blob_chunks(data_cursor, &entry.payload)?;
+                                data_cursor += written;
+                                Some(recipe)
+                            } else {
+                                None
+                            };
+                            // Checksums cover the plaintext; the stored length includes
+                            // the nonce and tag of encrypted memories.
+                            let payload_length = self.write_region(
+                                data_cursor,
+                                RegionKind::Payload,
+                                recipe.as_deref().unwrap_or(&entry.payload),
+                            )?;
+                            let checksum = streamed_checksum
+                                .unwrap_or_else(|| *hash(&entry.payload).as_bytes());
                             let canonical_length =
                                 if entry.canonical_encoding == CanonicalEncoding::Zstd {
                                     match entry.canonical_length {
@@ -1166,12 +1448,7 @@ impl Memvid {
                                 };
                             let payload_offset = data_cursor;
                             data_cursor += payload_length;
-                            (
-                                payload_offset,
-                                payload_length,
-                                *checksum.as_bytes(),
-                                canonical_length,
-                            )
+                            (payload_offset, payload_length, checksum, canonical_length)
                         };
 
                         let uri = entry
@@ -1251,7 +1528,7 @@ impl Memvid {
                                     // Look backwards through recently inserted frames
                                     for &candidate_id in delta.inserted_frames.iter().rev() {
                                         if let Some(candidate) =
-                                            self.toc.frames.get(candidate_id as usize)
+                                            self.toc.frames.try_get(candidate_id as usize)?
                                         {
                                             if candidate.role == FrameRole::Document
                                                 && candidate.chunk_manifest.is_some()
@@ -1309,7 +1586,7 @@ impl Memvid {
                                     crate::types::SketchVariant::Small,
                                     None,
                                 );
-                                self.sketch_track.insert(entry);
+                                Arc::make_mut(&mut self.sketch_track).insert(entry);
                             }
                         }
 
@@ -1344,9 +1621,17 @@ impl Memvid {
                             self.mark_frame_superseded(predecessor, frame_id)?;
                         }
 
+                        self.log_frame_insert(&frame);
+                        if frame.status == FrameStatus::Active {
+                            self.note_quota_frame(
+                                frame.track.as_deref(),
+                                frame.payload_length,
+                                true,
+                            );
+                        }
                         self.toc.frames.push(frame);
                         delta.inserted_frames.push(frame_id);
-                        sequence_to_frame.insert(record.sequence, frame_id);
+                        sequence_to_frame.insert(sequence, frame_id);
                     }
                     FrameWalOp::Tombstone => {
                         let target = entry.target_frame_id.ok_or(MemvidError::InvalidFrame {
@@ -1364,32 +1649,31 @@ impl Memvid {
         // Second pass: resolve any orphan DocumentChunk frames that are missing parent_id.
         // This handles edge cases where chunks couldn't be linked during the first pass.
         // First, collect orphan chunks and their resolved parents to avoid borrow conflicts.
-        let orphan_resolutions: Vec<(u64, u64)> = delta
-            .inserted_frames
-            .iter()
-            .filter_map(|&frame_id| {
-                let frame = self.toc.frames.get(frame_id as usize)?;
-                if frame.role != FrameRole::DocumentChunk || frame.parent_id.is_some() {
-                    return None;
-                }
-                // Find the most recent Document frame before this chunk that has a manifest
-                for candidate_id in (0..frame_id).rev() {
-                    if let Some(candidate) = self.toc.frames.get(candidate_id as usize) {
-                        if candidate.role == FrameRole::Document
-                            && candidate.chunk_manifest.is_some()
-                            && candidate.status == FrameStatus::Active
-                        {
-                            return Some((frame_id, candidate_id));
-                        }
+        let mut orphan_resolutions: Vec<(u64, u64)> = Vec::new();
+        for &frame_id in &delta.inserted_frames {
+            let Some(frame) = self.toc.frames.try_get(frame_id as usize)? else {
+                continue;
+            };
+            if frame.role != FrameRole::DocumentChunk || frame.parent_id.is_some() {
+                continue;
+            }
+            // Find the most recent Document frame before this chunk that has a manifest
+            for candidate_id in (0..frame_id).rev() {
+                if let Some(candidate) = self.toc.frames.try_get(candidate_id as usize)? {
+                    if candidate.role == FrameRole::Document
+                        && candidate.chunk_manifest.is_some()
+                        && candidate.status == FrameStatus::Active
+                    {
+                        orphan_resolutions.push((frame_id, candidate_id));
+                        break;
                     }
                 }
-                None
-            })
-            .collect();
+            }
+        }
 
         // Now apply the resolutions
         for (chunk_id, parent_id) in orphan_resolutions {
-            if let Some(frame) = self.toc.frames.get_mut(chunk_id as usize) {
+            if let Some(frame) = self.toc.frames.try_get_mut(chunk_id as usize)? {
                 frame.parent_id = Some(parent_id);
                 tracing::debug!(
                     chunk_frame_id = chunk_id,
@@ -1677,7 +1961,7 @@ impl Memvid {
         let segment_id = self.toc.segment_catalog.next_segment_id;
         #[cfg(feature = "parallel_segments")]
         let span =
-            self.segment_span_from_iter(delta.inserted_frames.iter().map(|frame_id| *frame_id));
+            self.segment_span_from_iter(delta.inserted_frames.iter().map(|frame_id| *frame_id))?;
 
         #[cfg_attr(not(feature = "parallel_segments"), allow(unused_mut))]
         let mut descriptor = self.append_lex_segment(&artifact, segment_id)?;
@@ -1730,7 +2014,8 @@ impl Memvid {
         let segment_id = self.toc.segment_catalog.next_segment_id;
         #[cfg(feature = "parallel_segments")]
         #[cfg(feature = "parallel_segments")]
-        let span = self.segment_span_from_iter(delta.inserted_embeddings.iter().map(|(id, _)| *id));
+        let span =
+            self.segment_span_from_iter(delta.inserted_embeddings.iter().map(|(id, _)| *id))?;
 
         #[cfg_attr(not(feature = "parallel_segments"), allow(unused_mut))]
         let mut descriptor = self.append_vec_segment(&artifact, segment_id)?;
@@ -1770,6 +2055,8 @@ impl Memvid {
                 bytes_length: 0,
                 checksum: empty_checksum,
                 compression_mode: self.vec_compression.clone(),
+                metric: self.vec_metric(),
+                hnsw: None,
             });
         }
         if let Some(manifest) = self.toc.indexes.vec.as_mut() {
@@ -1805,7 +2092,7 @@ impl Memvid {
                 .inserted_time_entries
                 .iter()
                 .map(|entry| entry.frame_id),
-        );
+        )?;
 
         #[cfg_attr(not(feature = "parallel_segments"), allow(unused_mut))]
         let mut descriptor = self.append_time_segment(&artifact, segment_id)?;
@@ -1889,13 +2176,18 @@ impl Memvid {
         let frame =
             self.toc
                 .frames
-                .get_mut(frame_id as usize)
+                .try_get_mut(frame_id as usize)?
                 .ok_or(MemvidError::InvalidFrame {
                     frame_id,
                     reason: "supersede target missing",
                 })?;
+        let was_active = frame.status == FrameStatus::Active;
         frame.status = FrameStatus::Superseded;
         frame.superseded_by = Some(successor_id);
+        if was_active {
+            let (track, bytes) = (frame.track.clone(), frame.payload_length);
+            self.note_quota_frame(track.as_deref(), bytes, false);
+        }
         self.remove_frame_from_indexes(frame_id)
     }
 
@@ -1903,9 +2195,11 @@ impl Memvid {
         if self.toc.frames.is_empty() && !self.lex_enabled && !self.vec_enabled {
             return Ok(());
         }
-
-        let payload_end = self.payload_region_end();
+        // Start after `data_end` as well so a rebuild never lands on an earlier generation.
+        let payload_end = self.payload_region_end().max(self.data_end);
         self.data_end = payload_end;
+        // Frame table pages stored in the region the rebuild overwrites are written again.
+        self.toc.frames.reclaim_from(payload_end)?;
         // Don't truncate if footer_offset is higher - there may be replay segments
         // or other data written after payload_end that must be preserved.
         let safe_truncate_len = self.header.footer_offset.max(payload_end);
@@ -1927,17 +2221,15 @@ impl Memvid {
         // Drop any stale embedded lex manifest entries before rebuilding Tantivy.
         self.toc.indexes.lex_segments.clear();
 
-        let mut time_entries: Vec<TimeIndexEntry> = self
-            .toc
-            .frames
-            .iter()
-            .filter(|frame| {
-                frame.status == FrameStatus::Active && frame.role == FrameRole::Document
-            })
-            .map(|frame| TimeIndexEntry::new(frame.timestamp, frame.id))
-            .collect();
-        let (ti_offset, ti_length, ti_checksum) =
-            time_index_append(&mut self.file, &mut time_entries)?;
+        let mut time_entries = Vec::new();
+        for frame in self.toc.frames.try_iter() {
+            let frame = frame?;
+            if frame.status == FrameStatus::Active && frame.role == FrameRole::Document {
+                time_entries.push(TimeIndexEntry::new(frame.timestamp, frame.id));
+            }
+        }
+        let ti_offset = payload_end;
+        let (ti_length, ti_checksum) = self.write_time_index(ti_offset, &mut time_entries)?;
         self.toc.time_index = Some(TimeIndexManifest {
             bytes_offset: ti_offset,
             bytes_length: ti_length,
@@ -2008,20 +2300,43 @@ impl Memvid {
             }
         }
 
-        if let Some((artifact, index)) = self.build_vec_artifact(new_vec_docs)? {
+        if let Some((artifact, mut index)) = self.build_vec_artifact(new_vec_docs)? {
             let vec_offset = footer_offset;
-            self.file.seek(SeekFrom::Start(vec_offset))?;
-            self.file.write_all(&artifact.bytes)?;
-            footer_offset += artifact.bytes.len() as u64;
+            let vec_length = self.write_region(vec_offset, RegionKind::Index, &artifact.bytes)?;
+            footer_offset += vec_length;
+
+            // Persist the HNSW graph right after the vectors so readers never rebuild it.
+            index.ensure_graph(self.vec_metric());
+            let hnsw = match index.graph() {
+                Some(graph) => {
+                    let graph_artifact = graph.encode()?;
+                    let graph_offset = footer_offset;
+                    let graph_length =
+                        self.write_region(graph_offset, RegionKind::Index, &graph_artifact.bytes)?;
+                    footer_offset += graph_length;
+                    Some(HnswGraphManifest {
+                        bytes_offset: graph_offset,
+                        bytes_length: graph_length,
+                        checksum: graph_artifact.checksum,
+                        node_count: graph_artifact.node_count,
+                        m: graph.m(),
+                        ef_construction: graph.ef_construction(),
+                    })
+                }
+                None => None,
+            };
+
             self.toc.indexes.vec = Some(VecIndexManifest {
                 vector_count: artifact.vector_count,
                 dimension: artifact.dimension,
                 bytes_offset: vec_offset,
-                bytes_length: artifact.bytes.len() as u64,
+                bytes_length: vec_length,
                 checksum: artifact.checksum,
                 compression_mode: self.vec_compression.clone(),
+                metric: self.vec_metric(),
+                hnsw,
             });
-            self.vec_index = Some(index);
+            self.vec_index = Some(Arc::new(index));
         } else {
             // Only clear manifest if vec is disabled, keep empty placeholder if enabled
             if !self.vec_enabled {
@@ -2036,16 +2351,17 @@ impl Memvid {
                 if !clip_index.is_empty() {
                     let artifact = clip_index.encode()?;
                     let clip_offset = footer_offset;
-                    self.file.seek(SeekFrom::Start(clip_offset))?;
-                    self.file.write_all(&artifact.bytes)?;
-                    footer_offset += artifact.bytes.len() as u64;
+                    let clip_length =
+                        self.write_region(clip_offset, RegionKind::Index, &artifact.bytes)?;
+                    footer_offset += clip_length;
                     self.toc.indexes.clip = Some(crate::clip::ClipIndexManifest {
                         bytes_offset: clip_offset,
-                        bytes_length: artifact.bytes.len() as u64,
+                        bytes_length: clip_length,
                         vector_count: artifact.vector_count,
                         dimension: artifact.dimension,
                         checksum: artifact.checksum,
                         model_name: crate::clip::default_model_info().name.to_string(),
+                        metric: self.clip_metric(),
                     });
                     tracing::info!(
                         "rebuild_indexes: persisted CLIP index with {} vectors at offset {}",
@@ -2063,14 +2379,14 @@ impl Memvid {
             let memories_offset = footer_offset;
             let memories_bytes = self.memories_track.serialize()?;
             let memories_checksum = blake3::hash(&memories_bytes).into();
-            self.file.seek(SeekFrom::Start(memories_offset))?;
-            self.file.write_all(&memories_bytes)?;
-            footer_offset += memories_bytes.len() as u64;
+            let memories_length =
+                self.write_region(memories_offset, RegionKind::Index, &memories_bytes)?;
+            footer_offset += memories_length;
 
             let stats = self.memories_track.stats();
             self.toc.memories_track = Some(crate::types::MemoriesTrackManifest {
                 bytes_offset: memories_offset,
-                bytes_length: memories_bytes.len() as u64,
+                bytes_length: memories_length,
                 card_count: stats.card_count as u64,
                 entity_count: stats.entity_count as u64,
                 checksum: memories_checksum,
@@ -2084,14 +2400,13 @@ impl Memvid {
             let mesh_offset = footer_offset;
             let mesh_bytes = self.logic_mesh.serialize()?;
             let mesh_checksum: [u8; 32] = blake3::hash(&mesh_bytes).into();
-            self.file.seek(SeekFrom::Start(mesh_offset))?;
-            self.file.write_all(&mesh_bytes)?;
-            footer_offset += mesh_bytes.len() as u64;
+            let mesh_length = self.write_region(mesh_offset, RegionKind::Index, &mesh_bytes)?;
+            footer_offset += mesh_length;
 
             let stats = self.logic_mesh.stats();
             self.toc.logic_mesh = Some(crate::types::LogicMeshManifest {
                 bytes_offset: mesh_offset,
-                bytes_length: mesh_bytes.len() as u64,
+                bytes_length: mesh_length,
                 node_count: stats.node_count as u64,
                 edge_count: stats.edge_count as u64,
                 checksum: mesh_checksum,
@@ -2126,21 +2441,19 @@ impl Memvid {
         if self.lex_enabled {
             if let Some(ref engine) = self.tantivy {
                 let doc_count = engine.num_docs();
-                let active_frame_count = self
-                    .toc
-                    .frames
-                    .iter()
-                    .filter(|f| f.status == FrameStatus::Active)
-                    .count();
-
                 // Count frames that would actually be indexed by rebuild_tantivy_engine
                 // Uses the same logic: content-type based check + size limit
-                let text_indexable_count = self
-                    .toc
-                    .frames
-                    .iter()
-                    .filter(|f| crate::memvid::search::is_frame_text_indexable(f))
-                    .count();
+                let mut active_frame_count = 0;
+                let mut text_indexable_count = 0;
+                for frame in self.toc.frames.try_iter() {
+                    let frame = frame?;
+                    if frame.status == FrameStatus::Active {
+                        active_frame_count += 1;
+                    }
+                    if crate::memvid::search::is_frame_text_indexable(frame) {
+                        text_indexable_count += 1;
+                    }
+                }
 
                 // Only fail if we have text-indexable frames but none got indexed
                 // This avoids false positives for binary files (videos, images)
@@ -2182,20 +2495,20 @@ impl Memvid {
         let memories_bytes = self.memories_track.serialize()?;
         let memories_checksum: [u8; 32] = blake3::hash(&memories_bytes).into();
 
-        self.file.seek(SeekFrom::Start(memories_offset))?;
-        self.file.write_all(&memories_bytes)?;
+        let memories_length =
+            self.write_region(memories_offset, RegionKind::Index, &memories_bytes)?;
 
         let stats = self.memories_track.stats();
         self.toc.memories_track = Some(crate::types::MemoriesTrackManifest {
             bytes_offset: memories_offset,
-            bytes_length: memories_bytes.len() as u64,
+            bytes_length: memories_length,
             card_count: stats.card_count as u64,
             entity_count: stats.entity_count as u64,
             checksum: memories_checksum,
         });
 
         // Update footer_offset to account for the memories track
-        self.header.footer_offset = memories_offset + memories_bytes.len() as u64;
+        self.header.footer_offset = memories_offset + memories_length;
 
         // Ensure the file length covers the memories track
         if self.file.metadata()?.len() < self.header.footer_offset {
@@ -2228,16 +2541,16 @@ impl Memvid {
 
         // Write after the current footer_offset
         let clip_offset = self.header.footer_offset;
-        self.file.seek(SeekFrom::Start(clip_offset))?;
-        self.file.write_all(&artifact.bytes)?;
+        let clip_length = self.write_region(clip_offset, RegionKind::Index, &artifact.bytes)?;
 
         self.toc.indexes.clip = Some(crate::clip::ClipIndexManifest {
             bytes_offset: clip_offset,
-            bytes_length: artifact.bytes.len() as u64,
+            bytes_length: clip_length,
             vector_count: artifact.vector_count,
             dimension: artifact.dimension,
             checksum: artifact.checksum,
             model_name: crate::clip::default_model_info().name.to_string(),
+            metric: self.clip_metric(),
         });
 
         tracing::info!(
@@ -2247,7 +2560,7 @@ impl Memvid {
         );
 
         // Update footer_offset to account for the CLIP index
-        self.header.footer_offset = clip_offset + artifact.bytes.len() as u64;
+        self.header.footer_offset = clip_offset + clip_length;
 
         // Ensure the file length covers the CLIP index
         if self.file.metadata()?.len() < self.header.footer_offset {
@@ -2272,20 +2585,19 @@ impl Memvid {
         let mesh_bytes = self.logic_mesh.serialize()?;
         let mesh_checksum: [u8; 32] = blake3::hash(&mesh_bytes).into();
 
-        self.file.seek(SeekFrom::Start(mesh_offset))?;
-        self.file.write_all(&mesh_bytes)?;
+        let mesh_length = self.write_region(mesh_offset, RegionKind::Index, &mesh_bytes)?;
 
         let stats = self.logic_mesh.stats();
         self.toc.logic_mesh = Some(crate::types::LogicMeshManifest {
             bytes_offset: mesh_offset,
-            bytes_length: mesh_bytes.len() as u64,
+            bytes_length: mesh_length,
             node_count: stats.node_count as u64,
             edge_count: stats.edge_count as u64,
             checksum: mesh_checksum,
         });
 
         // Update footer_offset to account for the logic mesh
-        self.header.footer_offset = mesh_offset + mesh_bytes.len() as u64;
+        self.header.footer_offset = mesh_offset + mesh_length;
 
         // Ensure the file length covers the logic mesh
         if self.file.metadata()?.len() < self.header.footer_offset {
@@ -2299,18 +2611,19 @@ impl Memvid {
     ///
     /// This is used when the sketch track has been modified (e.g., after
     /// running `sketch build`).
-    fn persist_sketch_track(&mut self) -> Result<()> {
+    pub(crate) fn persist_sketch_track(&mut self) -> Result<()> {
         if self.sketch_track.is_empty() {
             self.toc.sketch_track = None;
             return Ok(());
         }
 
-        // Seek to write after the current footer_offset
-        self.file.seek(SeekFrom::Start(self.header.footer_offset))?;
-
-        // Write the sketch track and get (offset, length, checksum)
-        let (sketch_offset, sketch_length, sketch_checksum) =
-            crate::types::write_sketch_track(&mut self.file, &self.sketch_track)?;
+        // Encode the sketch track and write it after the current footer_offset
+        let mut encoded = Cursor::new(Vec::new());
+        let (_, _, sketch_checksum) =
+            crate::types::write_sketch_track(&mut encoded, &self.sketch_track)?;
+        let sketch_offset = self.header.footer_offset;
+        let sketch_length =
+            self.write_region(sketch_offset, RegionKind::Index, encoded.get_ref())?;
 
         let stats = self.sketch_track.stats();
         self.toc.sketch_track = Some(crate::types::SketchTrackManifest {
@@ -2359,6 +2672,10 @@ impl Memvid {
 
     #[cfg(feature = "lex")]
     fn append_lex_batch(&mut self, batch: &LexWalBatch) -> Result<()> {
+        // The footer written after a staged tail carries the manifest itself.
+        if self.staged_tail.is_some() {
+            return Ok(());
+        }
         let payload = encode_to_vec(&WalEntry::Lex(batch.clone()), wal_config())?;
         self.append_wal_entry(&payload)?;
         Ok(())
@@ -2402,12 +2719,11 @@ impl Memvid {
         } = snapshot;
 
         let mut footer_offset = self.data_end;
-        self.file.seek(SeekFrom::Start(footer_offset))?;
 
         let mut embedded_segments: Vec<EmbeddedLexSegment> = Vec::with_capacity(segments.len());
         for segment in segments {
-            let bytes_length = segment.bytes.len() as u64;
-            self.file.write_all(&segment.bytes)?;
+            let bytes_length =
+                self.write_region(footer_offset, RegionKind::Index, &segment.bytes)?;
             self.file.flush()?; // Flush segment data to disk
             embedded_segments.push(EmbeddedLexSegment {
                 path: segment.path,
@@ -2477,17 +2793,26 @@ impl Memvid {
         Ok(())
     }
 
-    fn mark_frame_deleted(&mut self, frame_id: FrameId) -> Result<()> {
+    pub(crate) fn mark_frame_deleted(&mut self, frame_id: FrameId) -> Result<()> {
         let frame =
             self.toc
                 .frames
-                .get_mut(frame_id as usize)
+                .try_get_mut(frame_id as usize)?
                 .ok_or(MemvidError::InvalidFrame {
                     frame_id,
                     reason: "delete target missing",
                 })?;
+        let newly_deleted = frame.status != FrameStatus::Deleted;
+        let was_active = frame.status == FrameStatus::Active;
         frame.status = FrameStatus::Deleted;
         frame.superseded_by = None;
+        let (uri, track, bytes) = (frame.uri.clone(), frame.track.clone(), frame.payload_length);
+        if was_active {
+            self.note_quota_frame(track.as_deref(), bytes, false);
+        }
+        if newly_deleted {
+            self.log_change(ChangeKind::Delete { frame_id, uri });
+        }
         self.remove_frame_from_indexes(frame_id)
     }
 
@@ -2498,29 +2823,46 @@ impl Memvid {
             self.tantivy_dirty = true;
         }
         if let Some(index) = self.lex_index.as_mut() {
-            index.remove_document(frame_id);
+            Arc::make_mut(index).remove_document(frame_id);
         }
         if let Some(index) = self.vec_index.as_mut() {
-            index.remove(frame_id);
+            Arc::make_mut(index).remove(frame_id);
         }
         Ok(())
     }
 
-    pub(crate) fn frame_is_active(&self, frame_id: FrameId) -> bool {
-        self.toc
+    pub(crate) fn frame_is_active(&self, frame_id: FrameId) -> Result<bool> {
+        Ok(self
+            .toc
             .frames
-            .get(frame_id as usize)
-            .map_or(false, |frame| frame.status == FrameStatus::Active)
+            .try_get(frame_id as usize)?
+            .is_some_and(|frame| frame.status == FrameStatus::Active))
+    }
+
+    /// The ids among `frame_ids` whose frame is missing or no longer active.
+    pub(crate) fn inactive_frames(
+        &self,
+        frame_ids: impl IntoIterator<Item = FrameId>,
+    ) -> Result<Vec<FrameId>> {
+        let mut inactive = Vec::new();
+        for frame_id in frame_ids {
+            if !self.frame_is_active(frame_id)? {
+                inactive.push(frame_id);
+            }
+        }
+        Ok(inactive)
     }
 
     #[cfg(feature = "parallel_segments")]
-    fn segment_span_from_iter<I>(&self, iter: I) -> Option<SegmentSpan>
+    fn segment_span_from_iter<I>(&self, iter: I) -> Result<Option<SegmentSpan>>
     where
         I: IntoIterator<Item = FrameId>,
     {
         let mut iter = iter.into_iter();
-        let first_id = iter.next()?;
-        let first_frame = self.toc.frames.get(first_id as usize);
+        let Some(first_id) = iter.next() else {
+            return Ok(None);
+        };
+        let first_frame = self.toc.frames.try_get(first_id as usize)?;
         let mut min_id = first_id;
         let mut max_id = first_id;
         let mut page_start = first_frame.and_then(|frame| frame.chunk_index).unwrap_or(0);
@@ -2535,7 +2877,7 @@ impl Memvid {
             if frame_id > max_id {
                 max_id = frame_id;
             }
-            if let Some(frame) = self.toc.frames.get(frame_id as usize) {
+            if let Some(frame) = self.toc.frames.try_get(frame_id as usize)? {
                 if let Some(idx) = frame.chunk_index {
                     page_start = page_start.min(idx);
                     if let Some(count) = frame.chunk_count {
@@ -2547,13 +2889,13 @@ impl Memvid {
                 }
             }
         }
-        Some(SegmentSpan {
+        Ok(Some(SegmentSpan {
             frame_start: min_id,
             frame_end: max_id,
             page_start,
             page_end,
             ..SegmentSpan::default()
-        })
+        }))
     }
 
     #[cfg(feature = "parallel_segments")]
@@ -2583,9 +2925,9 @@ impl Memvid {
         Ok(())
     }
 
-    fn ensure_mutation_allowed(&mut self) -> Result<()> {
+    pub(crate) fn ensure_mutation_allowed(&mut self) -> Result<()> {
         self.ensure_writable()?;
-        if self.toc.ticket_ref.issuer == "free-tier" {
+        if self.toc.ticket_ref.issuer == crate::types::ticket::FREE_TIER_ISSUER {
             return Ok(());
         }
         match self.tier() {
@@ -2611,22 +2953,107 @@ impl Memvid {
     }
 
     pub(crate) fn capacity_limit(&self) -> u64 {
-        if self.toc.ticket_ref.capacity_bytes != 0 {
-            self.toc.ticket_ref.capacity_bytes
-        } else {
-            self.tier().capacity_bytes()
+        let ticket = &self.toc.ticket_ref;
+        // The free-tier placeholder every memory starts with yields to configured quotas.
+        let issued = ticket.issuer != crate::types::ticket::FREE_TIER_ISSUER;
+        match self.toc.quotas.capacity_bytes {
+            Some(capacity) if !issued || ticket.capacity_bytes == 0 => capacity,
+            _ if ticket.capacity_bytes != 0 => ticket.capacity_bytes,
+            _ => self.tier().capacity_bytes(),
         }
     }
 
     /// Get current storage capacity in bytes.
     ///
-    /// Returns the capacity from the applied ticket, or the default
-    /// tier capacity (1 GB for free tier).
+    /// Returns the capacity from the applied ticket, then the one set in the
+    /// quota settings, or the default tier capacity.
     pub fn get_capacity(&self) -> u64 {
         self.capacity_limit()
     }
 
+    /// Seal `bytes` as a `kind` region and write it at `offset`, returning the stored length.
+    pub(crate) fn write_region(
+        &mut self,
+        offset: u64,
+        kind: RegionKind,
+        bytes: &[u8],
+    ) -> Result<u64> {
+        let stored = self.regions.seal(kind, bytes)?;
+        self.file.seek(SeekFrom::Start(offset))?;
+        self.file.write_all(&stored)?;
+        Ok(stored.len() as u64)
+    }
+
+    /// Write the time index track for `entries` at `offset`, returning its stored length
+    /// and checksum.
+    pub(crate) fn write_time_index(
+        &mut self,
+        offset: u64,
+        entries: &mut [TimeIndexEntry],
+    ) -> Result<(u64, [u8; 32])> {
+        let mut encoded = Cursor::new(Vec::new());
+        let (_, _, checksum) = time_index_append(&mut encoded, entries)?;
+        let length = self.write_region(offset, RegionKind::Index, encoded.get_ref())?;
+        Ok((length, checksum))
+    }
+
+    /// Writes the frame table pages that changed at `footer_offset`, ahead of the TOC,
+    /// and rebinds `toc.frames` to them. Returns the offset at which the TOC must be
+    /// written.
+    fn write_frame_table(&mut self) -> Result<u64> {
+        let offset = self.header.footer_offset;
+        if self.toc.frames.is_empty() {
+            self.toc.frames = FrameTable::new();
+            self.toc.frame_table = None;
+            return Ok(offset);
+        }
+        // Encrypted memories keep frames inline so they are sealed together with the TOC.
+        if self.regions.is_encrypted() {
+            self.toc.frame_table = None;
+            return Ok(offset);
+        }
+
+        // Stored pages are reused by later writes, so nothing may be written over them:
+        // new pages go after every stored one and the footer moves past them.
+        let offset = offset.max(self.toc.frames.stored_end());
+        let encoded = self.toc.frames.encode_pages(offset)?;
+        self.file.seek(SeekFrom::Start(offset))?;
+        self.file.write_all(&encoded.bytes)?;
+        self.toc.frames.bind(&self.file, &encoded.manifest)?;
+        let toc_offset = offset + encoded.manifest.bytes_length;
+        self.header.footer_offset = toc_offset;
+        self.toc.frame_table = Some(encoded.manifest);
+        Ok(toc_offset)
+    }
+
+    /// Records the commit signature for the current generation, or clears a stale one
+    /// when the generation is not signed.
+    fn sign_toc(&mut self) -> Result<()> {
+        self.toc.commit_signature = None;
+        let Some(signer) = self.commit_signer.as_ref() else {
+            return Ok(());
+        };
+        let toc_checksum = self.toc.unsigned_checksum()?;
+        let signature = sign_commit(
+            &signer.key,
+            signer.previous.as_deref(),
+            &self.toc.merkle_root,
+            &toc_checksum,
+            self.toc.committed_at,
+        )?;
+        self.toc.commit_signature = Some(CommitSignature {
+            public_key: signer.key.verifying_key().to_bytes(),
+            signature,
+            previous_signature: signer.previous.clone(),
+        });
+        Ok(())
+    }
+
     pub(crate) fn rewrite_toc_footer(&mut self) -> Result<()> {
+        // `with_staged_tail` writes the footer once the whole tail is in place.
+        if self.staged_tail.is_some() {
+            return Ok(());
+        }
         tracing::info!(
             vec_segments = self.toc.segment_catalog.vec_segments.len(),
             lex_segments = self.toc.segment_catalog.lex_segments.len(),
@@ -2635,8 +3062,14 @@ impl Memvid {
             data_end = self.data_end,
             "rewrite_toc_footer: about to serialize TOC"
         );
+        self.persist_blob_store()?;
+        self.persist_change_log()?;
+        let footer_offset = self.write_frame_table()?;
+        self.toc.merkle_root = self.toc.frames.merkle_root()?;
+        self.toc.wal_size = self.header.wal_size;
+        self.sign_toc()?;
         let toc_bytes = prepare_toc_bytes(&mut self.toc)?;
-        let footer_offset = self.header.footer_offset;
+        let toc_bytes = self.regions.seal(RegionKind::Toc, &toc_bytes)?.into_owned();
         self.file.seek(SeekFrom::Start(footer_offset))?;
         self.file.write_all(&toc_bytes)?;
         let footer = CommitFooter {
@@ -2699,12 +3132,15 @@ impl Memvid {
         );
         let mut chunks = Vec::with_capacity(delta.inserted_frames.len());
         for frame_id in &delta.inserted_frames {
-            let frame = self.toc.frames.get(*frame_id as usize).cloned().ok_or(
-                MemvidError::InvalidFrame {
+            let frame = self
+                .toc
+                .frames
+                .try_get(*frame_id as usize)?
+                .cloned()
+                .ok_or(MemvidError::InvalidFrame {
                     frame_id: *frame_id,
                     reason: "frame id out of range while planning segments",
-                },
-            )?;
+                })?;
             let text = self.frame_content(&frame)?;
             if text.trim().is_empty() {
                 continue;
@@ -2740,6 +3176,51 @@ impl Memvid {
     }
 }
 
+/// Moves the bytes from `start` up to `*moved_from` forward by `delta`, last chunk
+/// first, lowering `*moved_from` as chunks land. Chunks are at most `delta` long, so
+/// a failed write never overlaps bytes that have not been moved yet.
+fn move_forward(file: &mut File, start: u64, delta: u64, moved_from: &mut u64) -> Result<()> {
+    let mut buffer = vec![0u8; min(WAL_SHIFT_BUFFER_SIZE as u64, delta) as usize];
+    while *moved_from > start {
+        let chunk = min(*moved_from - start, buffer.len() as u64);
+        let src = *moved_from - chunk;
+        file.seek(SeekFrom::Start(src))?;
+        file.read_exact(&mut buffer[..chunk as usize])?;
+        file.seek(SeekFrom::Start(src + delta))?;
+        file.write_all(&buffer[..chunk as usize])?;
+        *moved_from = src;
+    }
+    Ok(())
+}
+
+/// Moves the bytes that [`move_forward`] placed at `start + delta..end + delta` back
+/// to `start..end`, first chunk first.
+fn move_back(file: &mut File, start: u64, end: u64, delta: u64) -> Result<()> {
+    let mut buffer = vec![0u8; min(WAL_SHIFT_BUFFER_SIZE as u64, delta) as usize];
+    let mut position = start;
+    while position < end {
+        let chunk = min(end - position, buffer.len() as u64);
+        file.seek(SeekFrom::Start(position + delta))?;
+        file.read_exact(&mut buffer[..chunk as usize])?;
+        file.seek(SeekFrom::Start(position))?;
+        file.write_all(&buffer[..chunk as usize])?;
+        position += chunk;
+    }
+    Ok(())
+}
+
+fn zero_range(file: &mut File, start: u64, len: u64) -> Result<()> {
+    file.seek(SeekFrom::Start(start))?;
+    let zero_buf = vec![0u8; min(WAL_SHIFT_BUFFER_SIZE as u64, len) as usize];
+    let mut remaining = len;
+    while remaining > 0 {
+        let write = min(remaining, zero_buf.len() as u64);
+        file.write_all(&zero_buf[..write as usize])?;
+        remaining -= write;
+    }
+    Ok(())
+}
+
 #[cfg(feature = "parallel_segments")]
 fn estimate_tokens(text: &str) -> usize {
     text.split_whitespace().count().max(1)
@@ -2760,74 +3241,6 @@ impl Memvid {
 }
 
 impl Memvid {
-    pub fn vacuum(&mut self) -> Result<()> {
-        self.commit()?;
-
-        let mut active_payloads: HashMap<FrameId, Vec<u8>> = HashMap::new();
-        let frames: Vec<Frame> = self
-            .toc
-            .frames
-            .iter()
-            .filter(|frame| frame.status == FrameStatus::Active)
-            .cloned()
-            .collect();
-        for frame in frames {
-            let bytes = self.read_frame_payload_bytes(&frame)?;
-            active_payloads.insert(frame.id, bytes);
-        }
-
-        let mut cursor = self.header.wal_offset + self.header.wal_size;
-        self.file.seek(SeekFrom::Start(cursor))?;
-        for frame in &mut self.toc.frames {
-            if frame.status == FrameStatus::Active {
-                if let Some(bytes) = active_payloads.get(&frame.id) {
-                    self.file.write_all(bytes)?;
-                    frame.payload_offset = cursor;
-                    frame.payload_length = bytes.len() as u64;
-                    cursor += bytes.len() as u64;
-                } else {
-                    frame.payload_offset = 0;
-                    frame.payload_length = 0;
-                }
-            } else {
-                frame.payload_offset = 0;
-                frame.payload_length = 0;
-            }
-        }
-
-        self.data_end = cursor;
-
-        self.toc.segments.clear();
-        self.toc.indexes.lex_segments.clear();
-        self.toc.segment_catalog.lex_segments.clear();
-        self.toc.segment_catalog.vec_segments.clear();
-        self.toc.segment_catalog.time_segments.clear();
-        #[cfg(feature = "temporal_track")]
-        {
-            self.toc.temporal_track = None;
-            self.toc.segment_catalog.temporal_segments.clear();
-        }
-        #[cfg(feature = "lex")]
-        {
-            self.toc.segment_catalog.tantivy_segments.clear();
-        }
-        #[cfg(feature = "parallel_segments")]
-        {
-            self.toc.segment_catalog.index_segments.clear();
-        }
-
-        // Clear in-memory Tantivy state so it doesn't write old segments on next commit
-        #[cfg(feature = "lex")]
-        {
-            self.tantivy = None;
-            self.tantivy_dirty = false;
-        }
-
-        self.rebuild_indexes(&[])?;
-        self.file.sync_all()?;
-        Ok(())
-    }
-
     /// Preview how a document would be chunked without actually ingesting it.
     ///
     /// This is useful when you need to compute embeddings for each chunk externally
@@ -2851,12 +3264,20 @@ impl Memvid {
 
     /// Append raw bytes as a document frame.
     pub fn put_bytes(&mut self, payload: &[u8]) -> Result<u64> {
-        self.put_internal(Some(payload), None, None, None, PutOptions::default(), None)
+        self.put_internal(
+            Some(payload),
+            None,
+            None,
+            None,
+            PutOptions::default(),
+            None,
+            None,
+        )
     }
 
     /// Append raw bytes with explicit metadata/options.
     pub fn put_bytes_with_options(&mut self, payload: &[u8], options: PutOptions) -> Result<u64> {
-        self.put_internal(Some(payload), None, None, None, options, None)
+        self.put_internal(Some(payload), None, None, None, options, None, None)
     }
 
     /// Append bytes and an existing embedding (bypasses on-device embedding).
@@ -2868,6 +3289,7 @@ impl Memvid {
             None,
             PutOptions::default(),
             None,
+            None,
         )
     }
 
@@ -2877,7 +3299,15 @@ impl Memvid {
         embedding: Vec<f32>,
         options: PutOptions,
     ) -> Result<u64> {
-        self.put_internal(Some(payload), None, Some(embedding), None, options, None)
+        self.put_internal(
+            Some(payload),
+            None,
+            Some(embedding),
+            None,
+            options,
+            None,
+            None,
+        )
     }
 
     /// Ingest a document with pre-computed embeddings for both parent and chunks.
@@ -2906,6 +3336,7 @@ impl Memvid {
             Some(chunk_embeddings),
             options,
             None,
+            None,
         )
     }
 
@@ -2914,12 +3345,25 @@ impl Memvid {
         &mut self,
         frame_id: FrameId,
         payload: Option<Vec<u8>>,
+        options: PutOptions,
+        embedding: Option<Vec<f32>>,
+    ) -> Result<u64> {
+        let existing = self.frame_by_id(frame_id)?;
+        self.replace_frame(frame_id, &existing, payload.as_deref(), options, embedding)
+    }
+
+    /// Append a successor to the active frame `frame_id`, taking whatever `payload`,
+    /// `options` and `embedding` leave out from `existing`.
+    pub(crate) fn replace_frame(
+        &mut self,
+        frame_id: FrameId,
+        existing: &Frame,
+        payload: Option<&[u8]>,
         mut options: PutOptions,
         embedding: Option<Vec<f32>>,
     ) -> Result<u64> {
         self.ensure_mutation_allowed()?;
-        let existing = self.frame_by_id(frame_id)?;
-        if existing.status != FrameStatus::Active {
+        if self.frame_by_id(frame_id)?.status != FrameStatus::Active {
             return Err(MemvidError::InvalidFrame {
                 frame_id,
                 reason: "frame is not active",
@@ -2968,22 +3412,26 @@ impl Memvid {
         let effective_embedding = if let Some(explicit) = embedding {
             Some(explicit)
         } else if self.vec_enabled {
-            self.frame_embedding(frame_id)?
+            self.frame_embedding(existing.id)?
         } else {
             None
         };
 
-        let payload_slice = payload.as_deref();
         let reuse_flag = reuse_frame.is_some();
-        let replace_flag = payload_slice.is_some();
+        let replace_flag = payload.is_some();
+        let successor = self.next_frame_id();
         let seq = self.put_internal(
-            payload_slice,
+            payload,
             reuse_frame,
             effective_embedding,
             None, // No chunk embeddings for update
             options,
             Some(frame_id),
+            None,
         )?;
+        if self.next_frame_id() > successor {
+            self.pending_versions.insert(frame_id, Some(successor));
+        }
         info!(
             "frame_update frame_id={} seq={} reused_payload={} replaced_payload={}",
             frame_id, seq, reuse_flag, replace_flag
@@ -3038,8 +3486,9 @@ impl Memvid {
 
         let payload_bytes = encode_to_vec(&WalEntry::Frame(tombstone), wal_config())?;
         let seq = self.append_wal_entry(&payload_bytes)?;
+        self.pending_versions.insert(frame_id, None);
         self.dirty = true;
-        if self.wal.should_checkpoint() {
+        if self.wal.should_checkpoint() && !self.in_transaction {
             self.commit()?;
         }
         info!("frame_delete frame_id={} seq={}", frame_id, seq);
@@ -3047,8 +3496,20 @@ impl Memvid {
     }
 }
 
+/// Raw payload already written to the blob store by
+/// [`Memvid::put_reader`](crate::Memvid::put_reader); `payload` is then only a sample of it.
+pub(crate) struct StreamedPayload {
+    pub recipe: Vec<u8>,
+    /// BLAKE3 hash of the whole payload.
+    pub checksum: [u8; 32],
+    pub length: u64,
+    /// Bytes of new chunks written for it.
+    pub stored_bytes: u64,
+}
+
 impl Memvid {
-    fn put_internal(
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn put_internal(
         &mut self,
         payload: Option<&[u8]>,
         reuse_frame: Option<Frame>,
@@ -3056,14 +3517,17 @@ impl Memvid {
         chunk_embeddings: Option<Vec<Vec<f32>>>,
         mut options: PutOptions,
         supersedes: Option<FrameId>,
+        streamed: Option<StreamedPayload>,
     ) -> Result<u64> {
         self.ensure_mutation_allowed()?;
 
         // Deduplication: if enabled and we have payload, check if identical content exists
         if options.dedup {
             if let Some(bytes) = payload {
-                let content_hash = hash(bytes);
-                if let Some(existing_frame) = self.find_frame_by_hash(content_hash.as_bytes()) {
+                let content_hash = streamed
+                    .as_ref()
+                    .map_or_else(|| *hash(bytes).as_bytes(), |streamed| streamed.checksum);
+                if let Some(existing_frame) = self.find_frame_by_hash(&content_hash)? {
                     // Found existing frame with same content hash, skip ingestion
                     tracing::debug!(
                         frame_id = existing_frame.id,
@@ -3142,10 +3606,13 @@ impl Memvid {
             }
         }
 
+        let encoder = self.canonical_encoder(options.encoding, options.track.as_deref())?;
         let mut prepared_payload: Option<(Vec<u8>, CanonicalEncoding, Option<u64>)> = None;
         let payload_tail = self.payload_region_end();
-        let projected = if let Some(bytes) = payload {
-            let (prepared, encoding, length) = prepare_canonical_payload(bytes)?;
+        let projected = if let Some(streamed) = streamed.as_ref() {
+            payload_tail.saturating_add(streamed.stored_bytes)
+        } else if let Some(bytes) = payload {
+            let (prepared, encoding, length) = encoder.encode(bytes)?;
             let len = prepared.len();
             prepared_payload = Some((prepared, encoding, length));
             payload_tail.saturating_add(len as u64)
@@ -3158,15 +3625,8 @@ impl Memvid {
             });
         };
 
-        let capacity_limit = self.capacity_limit();
-        if projected > capacity_limit {
-            let incoming_size = projected.saturating_sub(payload_tail);
-            return Err(MemvidError::CapacityExceeded {
-                current: payload_tail,
-                limit: capacity_limit,
-                required: incoming_size,
-            });
-        }
+        let incoming = projected.saturating_sub(payload_tail);
+        self.enforce_quotas(incoming, options.track.as_deref())?;
         let timestamp = options.timestamp.take().unwrap_or_else(|| {
             SystemTime::now()
                 .duration_since(UNIX_EPOCH)
@@ -3186,8 +3646,9 @@ impl Memvid {
         };
 
         // Try to create a chunk plan from raw UTF-8 bytes first
+        // A streamed sample is not the whole document, so only its extracted text is chunked
         let raw_chunk_plan = match (payload, reuse_frame.as_ref()) {
-            (Some(bytes), None) => plan_document_chunks(bytes),
+            (Some(bytes), None) if streamed.is_none() => plan_document_chunks(bytes),
             _ => None,
         };
 
@@ -3205,8 +3666,11 @@ impl Memvid {
                 // --no-raw mode: don't store the raw binary, only compute hash
                 if let Some(bytes) = payload {
                     // Compute BLAKE3 hash of original binary for verification
-                    let hash_result = hash(bytes);
-                    source_sha256 = Some(*hash_result.as_bytes());
+                    source_sha256 = Some(
+                        streamed
+                            .as_ref()
+                            .map_or_else(|| *hash(bytes).as_bytes(), |streamed| streamed.checksum),
+                    );
                     // Store empty payload - the extracted text is in search_text
                     (Vec::new(), CanonicalEncoding::Plain, Some(0), None)
                 } else {
@@ -3215,10 +3679,19 @@ impl Memvid {
                         reason: "payload required for --no-raw mode",
                     });
                 }
+            } else if let Some(streamed) = streamed.as_ref() {
+                // The WAL carries the recipe; `source_sha256` marks it as already stored
+                source_sha256 = Some(streamed.checksum);
+                (
+                    streamed.recipe.clone(),
+                    CanonicalEncoding::Chunked,
+                    Some(streamed.length),
+                    None,
+                )
             } else if let Some((prepared, encoding, length)) = prepared_payload.take() {
                 (prepared, encoding, length, None)
             } else if let Some(bytes) = payload {
-                let (prepared, encoding, length) = prepare_canonical_payload(bytes)?;
+                let (prepared, encoding, length) = encoder.encode(bytes)?;
                 (prepared, encoding, length, None)
             } else if let Some(frame) = reuse_frame.as_ref() {
                 (
@@ -3332,7 +3805,11 @@ impl Memvid {
         };
 
         if let Some(err) = extraction_error {
-            return Err(err);
+            // A sample cut mid-structure may not parse; the streamed bytes are still stored
+            if streamed.is_none() {
+                return Err(err);
+            }
+            tracing::warn!(?err, "extraction of streamed payload sample failed");
         }
 
         if let Some(doc) = &extracted {
@@ -3443,7 +3920,7 @@ impl Memvid {
 
             for (idx, chunk_text) in plan.chunks.iter().enumerate() {
                 let (chunk_payload, chunk_encoding, chunk_length) =
-                    prepare_canonical_payload(chunk_text.as_bytes())?;
+                    encoder.encode(chunk_text.as_bytes())?;
                 let chunk_search_text = normalize_text(chunk_text, DEFAULT_SEARCH_TEXT_LIMIT)
                     .map(|n| n.text)
                     .filter(|text| !text.trim().is_empty());
@@ -3505,7 +3982,7 @@ impl Memvid {
             // This works because sequence numbers are assigned incrementally
             self.toc
                 .frames
-                .get(parent_id as usize)
+                .try_get(parent_id as usize)?
                 .map(|_| parent_id + 2) // WAL sequences start at 2
         } else {
             None
@@ -3571,9 +4048,11 @@ impl Memvid {
             enrichment_state,
         };
 
+        let quota_track = entry.track.clone();
         let parent_bytes = encode_to_vec(&WalEntry::Frame(entry), wal_config())?;
         let parent_seq = self.append_wal_entry(&parent_bytes)?;
         self.pending_frame_inserts = self.pending_frame_inserts.saturating_add(1);
+        self.note_pending_put_bytes(quota_track.as_deref(), incoming);
 
         // Instant indexing: make frame searchable immediately (<1s) without full commit
         // This is Phase 1 of progressive ingestion - frame is searchable but not fully enriched
@@ -3656,13 +4135,16 @@ impl Memvid {
 
         for mut chunk_entry in chunk_entries {
             chunk_entry.parent_sequence = Some(parent_seq);
+            let chunk_track = chunk_entry.track.clone();
+            let chunk_len = chunk_entry.payload.len() as u64;
             let chunk_bytes = encode_to_vec(&WalEntry::Frame(chunk_entry), wal_config())?;
             self.append_wal_entry(&chunk_bytes)?;
             self.pending_frame_inserts = self.pending_frame_inserts.saturating_add(1);
+            self.note_pending_put_bytes(chunk_track.as_deref(), chunk_len);
         }
 
         self.dirty = true;
-        if self.wal.should_checkpoint() {
+        if self.wal.should_checkpoint() && !self.in_transaction {
             self.commit()?;
         }
 
@@ -3689,10 +4171,10 @@ impl Memvid {
 
                     if !cards.is_empty() {
                         // Add cards to memories track
-                        let card_ids = self.memories_track.add_cards(cards);
+                        let card_ids = Arc::make_mut(&mut self.memories_track).add_cards(cards);
 
                         // Record enrichment for incremental processing
-                        self.memories_track
+                        Arc::make_mut(&mut self.memories_track)
                             .record_enrichment(frame_id, "rules", "1.0.0", card_ids);
                     }
                 }
@@ -3716,11 +4198,63 @@ impl Default for FrameWalOp {
     }
 }
 
+/// A WAL record. Variants are tagged by position, so every build declares all of them and
+/// new ones go at the end.
 #[derive(Debug, Serialize, Deserialize)]
-enum WalEntry {
+pub(crate) enum WalEntry {
     Frame(WalEntryData),
-    #[cfg(feature = "lex")]
     Lex(LexWalBatch),
+    Group(WalGroupMarker),
+    /// Memory cards as JSON, since cards leave absent fields out when serialized.
+    MemoryCards(Vec<u8>),
+    /// Chunks a streamed put wrote past the committed end, ahead of its frame.
+    BlobChunks(Vec<BlobChunk>),
+}
+
+/// Layout of a Tantivy WAL batch for builds without `lex`, which decode and skip these records.
+#[cfg(not(feature = "lex"))]
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct LexWalBatch {
+    generation: u64,
+    doc_count: u64,
+    checksum: [u8; 32],
+    segments: Vec<(String, u64, u64, [u8; 32])>,
+}
+
+/// Bounds of a transaction's records in the WAL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum WalGroupMarker {
+    Begin,
+    Commit,
+    Abort,
+}
+
+/// Keep the records of committed groups and records outside any group, in order, dropping
+/// the markers and every group that was aborted or never reached its commit marker.
+fn resolve_wal_groups(entries: Vec<(u64, WalEntry)>) -> Vec<(u64, WalEntry)> {
+    let mut resolved = Vec::with_capacity(entries.len());
+    let mut group: Option<Vec<(u64, WalEntry)>> = None;
+    for (sequence, entry) in entries {
+        match entry {
+            WalEntry::Group(WalGroupMarker::Begin) => {
+                if let Some(torn) = group.replace(Vec::new()) {
+                    tracing::debug!(records = torn.len(), "discarding unterminated WAL group");
+                }
+            }
+            WalEntry::Group(WalGroupMarker::Commit) => {
+                resolved.extend(group.take().unwrap_or_default());
+            }
+            WalEntry::Group(WalGroupMarker::Abort) => group = None,
+            entry => match group.as_mut() {
+                Some(records) => records.push((sequence, entry)),
+                None => resolved.push((sequence, entry)),
+            },
+        }
+    }
+    if let Some(torn) = group {
+        tracing::debug!(records = torn.len(), "discarding unterminated WAL group");
+    }
+    resolved
 }
 
 fn decode_wal_entry(bytes: &[u8]) -> Result<WalEntry> {
@@ -0,0 +1,288 @@
+//! Capacity quotas: a total byte limit, per-track limits and a frame limit stored in the
+//! TOC and checked by every put.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::error::{MemvidError, Result};
+use crate::memvid::lifecycle::Memvid;
+use crate::types::{FrameStatus, PutPreflight, QuotaSettings};
+
+impl Memvid {
+    /// Create a new `.mv2` file with `quotas` stored in its first TOC.
+    pub fn create_with_quotas<P: AsRef<Path>>(path: P, quotas: QuotaSettings) -> Result<Self> {
+        let mut memvid = Self::create(path)?;
+        memvid.toc.quotas = quotas;
+        memvid.rewrite_toc_footer()?;
+        memvid.header.toc_checksum = memvid.toc.toc_checksum;
+        crate::persist_header(&mut memvid.file, &memvid.header)?;
+        memvid.file.sync_all()?;
+        Ok(memvid)
+    }
+
+    /// Quota settings stored in the memory.
+    #[must_use]
+    pub fn quotas(&self) -> &QuotaSettings {
+        &self.toc.quotas
+    }
+
+    /// Replace the quota settings; they apply to the next put and are persisted by the
+    /// next commit. Existing frames above a lowered limit are kept.
+    pub fn set_quotas(&mut self, quotas: QuotaSettings) -> Result<()> {
+        self.ensure_mutation_allowed()?;
+        self.toc.quotas = quotas;
+        self.dirty = true;
+        Ok(())
+    }
+
+    /// Estimate whether a put of `size` payload bytes would be accepted.
+    ///
+    /// Payloads are compressed before they are stored, so `size` is an upper bound on what
+    /// the put adds. Per-track limits are not considered.
+    pub fn preflight_put(&self, size: u64) -> Result<PutPreflight> {
+        let used_bytes = self.payload_region_end();
+        let capacity_bytes = self.capacity_limit();
+        let projected = used_bytes.saturating_add(size);
+        let active_frames = match &self.quota_usage {
+            Some(usage) => usage.active_frames,
+            None => self.count_quota_usage()?.active_frames,
+        };
+        let frame_count = active_frames.saturating_add(self.pending_frame_inserts);
+        let max_frames = self.toc.quotas.max_frames;
+        let fits = projected <= capacity_bytes && max_frames.is_none_or(|max| frame_count < max);
+        Ok(PutPreflight {
+            size,
+            used_bytes,
+            capacity_bytes,
+            remaining_bytes: if fits { capacity_bytes - projected } else { 0 },
+            frame_count,
+            max_frames,
+            fits,
+        })
+    }
+
+    /// Reject a put adding `incoming` stored bytes to `track` if it would exceed a quota.
+    pub(crate) fn enforce_quotas(&mut self, incoming: u64, track: Option<&str>) -> Result<()> {
+        let payload_tail = self.payload_region_end();
+        let capacity_limit = self.capacity_limit();
+        if payload_tail.saturating_add(incoming) > capacity_limit {
+            return Err(MemvidError::CapacityExceeded {
+                current: payload_tail,
+                limit: capacity_limit,
+                required: incoming,
+            });
+        }
+
+        let max_frames = self.toc.quotas.max_frames;
+        let track_limit = track.and_then(|track| {
+            let limit = *self.toc.quotas.track_bytes.get(track)?;
+            Some((track, limit))
+        });
+        if max_frames.is_none() && track_limit.is_none() {
+            return Ok(());
+        }
+        let usage = match self.quota_usage.take() {
+            Some(usage) => usage,
+            None => self.count_quota_usage()?,
+        };
+        let active_frames = usage.active_frames;
+        let track_bytes = track_limit.map_or(0, |(track, _)| {
+            usage.track_bytes.get(track).copied().unwrap_or(0)
+        });
+        self.quota_usage = Some(usage);
+
+        if let Some(limit) = max_frames {
+            let current = active_frames.saturating_add(self.pending_frame_inserts);
+            if current >= limit {
+                return Err(MemvidError::FrameLimitExceeded { current, limit });
+            }
+        }
+
+        let Some((track, limit)) = track_limit else {
+            return Ok(());
+        };
+        let pending = self.pending_track_bytes.get(track).copied().unwrap_or(0);
+        let current = track_bytes.saturating_add(pending);
+        if current.saturating_add(incoming) > limit {
+            return Err(MemvidError::TrackCapacityExceeded {
+                track: track.to_string(),
+                current,
+                limit,
+                required: incoming,
+            });
+        }
+        Ok(())
+    }
+
+    /// Note `bytes` appended to the WAL for a put to `track`, counted by the track quota
+    /// until the put is applied.
+    pub(crate) fn note_pending_put_bytes(&mut self, track: Option<&str>, bytes: u64) {
+        if let Some(track) = track {
+            let pending = self
+                .pending_track_bytes
+                .entry(track.to_string())
+                .or_default();
+            *pending = pending.saturating_add(bytes);
+        }
+    }
+
+    /// Keep the quota counters in step with a frame that became active (`added`) or
+    /// stopped being active.
+    pub(crate) fn note_quota_frame(&mut self, track: Option<&str>, bytes: u64, added: bool) {
+        let Some(usage) = self.quota_usage.as_mut() else {
+            return;
+        };
+        if added {
+            usage.active_frames += 1;
+        } else {
+            usage.active_frames = usage.active_frames.saturating_sub(1);
+        }
+        if let Some(track) = track {
+            let total = usage.track_bytes.entry(track.to_string()).or_default();
+            *total = if added {
+                total.saturating_add(bytes)
+            } else {
+                total.saturating_sub(bytes)
+            };
+        }
+    }
+
+    /// Count the active committed frames and their bytes per track with one pass over the
+    /// frame table.
+    fn count_quota_usage(&self) -> Result<QuotaUsage> {
+        let mut usage = QuotaUsage::default();
+        for frame in self.toc.frames.try_iter() {
+            let frame = frame?;
+            if frame.status != FrameStatus::Active {
+                continue;
+            }
+            usage.active_frames += 1;
+            if let Some(track) = &frame.track {
+                let total = usage.track_bytes.entry(track.clone()).or_default();
+                *total = total.saturating_add(frame.payload_length);
+            }
+        }
+        Ok(usage)
+    }
+}
+
+/// Active committed frames and their stored bytes per track. Counted on the first put
+/// that needs them and then kept current as frames are applied and deleted; dropped
+/// whenever the TOC is replaced wholesale.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct QuotaUsage {
+    active_frames: u64,
+    track_bytes: BTreeMap<String, u64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PutOptions, Ticket, run_serial_test};
+    use tempfile::tempdir;
+
+    fn put(mem: &mut Memvid, payload: &[u8], track: Option<&str>) -> Result<u64> {
+        let mut builder = PutOptions::builder()
+            .auto_tag(false)
+            .extract_dates(false)
+            .extract_triplets(false);
+        if let Some(track) = track {
+            builder = builder.track(track);
+        }
+        mem.put_bytes_with_options(payload, builder.build())
+    }
+
+    #[test]
+    fn quotas_limit_puts_and_persist() {
+        run_serial_test(|| {
+            let dir = tempdir().expect("tmp");
+            let path = dir.path().join("quota.mv2");
+            let quotas = QuotaSettings::new().track_bytes("chat", 48).max_frames(3);
+            let mut mem = Memvid::create_with_quotas(&path, quotas.clone()).expect("create");
+            assert_eq!(mem.quotas(), &quotas);
+
+            put(&mut mem, &[0xF1; 32], Some("chat")).expect("put");
+            mem.commit().expect("commit");
+            let err = put(&mut mem, &[0xF2; 32], Some("chat")).expect_err("track quota");
+            assert!(matches!(
+                err,
+                MemvidError::TrackCapacityExceeded { ref track, current: 32, limit: 48, .. }
+                    if track == "chat"
+            ));
+            put(&mut mem, &[0xF3; 32], Some("notes")).expect("other track");
+            put(&mut mem, &[0xF4; 32], None).expect("no track");
+
+            let preflight = mem.preflight_put(32).expect("preflight");
+            assert_eq!(preflight.frame_count, 3);
+            assert!(!preflight.fits);
+            let err = put(&mut mem, &[0xF5; 32], None).expect_err("frame limit");
+            assert!(matches!(
+                err,
+                MemvidError::FrameLimitExceeded {
+                    current: 3,
+                    limit: 3
+                }
+            ));
+            mem.commit().expect("commit");
+            drop(mem);
+
+            let mut mem = Memvid::open(&path).expect("reopen");
+            assert_eq!(mem.quotas(), &quotas);
+            let used = mem.preflight_put(0).expect("preflight").used_bytes;
+            mem.set_quotas(QuotaSettings::new().capacity_bytes(used + 64))
+                .expect("set quotas");
+            let preflight = mem.preflight_put(64).expect("preflight");
+            assert!(preflight.fits);
+            assert_eq!(preflight.remaining_bytes, 0);
+            assert!(!mem.preflight_put(65).expect("preflight").fits);
+            let err = put(&mut mem, &[0xF6; 65], None).expect_err("capacity");
+            assert!(matches!(err, MemvidError::CapacityExceeded { .. }));
+
+            // A ticket's capacity wins over the stored quota.
+            mem.apply_ticket(Ticket::new("issuer", 2).capacity_bytes(used + 1024))
+                .expect("ticket");
+            assert_eq!(mem.get_capacity(), used + 1024);
+            put(&mut mem, &[0xF6; 65], None).expect("put within ticket capacity");
+        });
+    }
+
+    #[test]
+    fn quota_counters_track_pending_puts_and_deletes() {
+        run_serial_test(|| {
+            let dir = tempdir().expect("tmp");
+            let path = dir.path().join("quota.mv2");
+            let quotas = QuotaSettings::new().track_bytes("chat", 48).max_frames(2);
+            let mut mem = Memvid::create_with_quotas(&path, quotas).expect("create");
+
+            let options = PutOptions::builder()
+                .uri("mv2://chat/first")
+                .track("chat")
+                .auto_tag(false)
+                .extract_dates(false)
+                .extract_triplets(false)
+                .build();
+            mem.put_bytes_with_options(&[0xA1; 32], options)
+                .expect("put");
+            // The first put is still in the WAL, yet counts against the track.
+            let err = put(&mut mem, &[0xA2; 32], Some("chat")).expect_err("track quota");
+            assert!(matches!(
+                err,
+                MemvidError::TrackCapacityExceeded { current: 32, .. }
+            ));
+            mem.commit().expect("commit");
+            put(&mut mem, &[0xA3; 8], None).expect("put");
+            mem.commit().expect("commit");
+            let err = put(&mut mem, &[0xA4; 8], None).expect_err("frame limit");
+            assert!(matches!(
+                err,
+                MemvidError::FrameLimitExceeded { current: 2, .. }
+            ));
+
+            let first = mem.frame_by_uri("mv2://chat/first").expect("first").id;
+            mem.delete_frame(first).expect("delete");
+            mem.commit().expect("commit");
+            assert_eq!(mem.preflight_put(0).expect("preflight").frame_count, 1);
+            put(&mut mem, &[0xA5; 32], Some("chat")).expect("track bytes released");
+        });
+    }
+}
@@ -0,0 +1,374 @@
+//! Shareable read handles for querying one memory from many threads.
+//!
+//! A [`MemvidReader`] points at a snapshot: one committed generation's TOC plus the lexical,
+//! vector and CLIP indexes loaded for it. Queries borrow a read-only [`Memvid`] from the
+//! snapshot's pool, forking a new one when every handle is busy. Forks share the origin's
+//! descriptor and lock, and its indexes, tracks and decoded frames behind `Arc`; they read
+//! through the file mapping or at offsets, so no handle moves another's cursor. A writer
+//! that handed out readers with
+//! [`Memvid::reader`] publishes a new snapshot after every commit; queries already running
+//! finish against the snapshot they started on.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::{Arc, Mutex, RwLock};
+
+use crate::VecEmbedder;
+use crate::error::{MemvidError, Result};
+use crate::io::wal::EmbeddedWal;
+use crate::memvid::lifecycle::Memvid;
+#[cfg(feature = "lex")]
+use crate::search::{EmbeddedLexStorage, TantivyEngine};
+use crate::types::{
+    AskRequest, AskResponse, FrameId, SearchRequest, SearchResponse, TimelineEntry, TimelineQuery,
+};
+
+/// A `Send + Sync` handle that runs queries against the latest published snapshot of a
+/// memory. Clones share the snapshot and its pool of handles.
+#[derive(Clone)]
+pub struct MemvidReader {
+    current: Arc<RwLock<Arc<Snapshot>>>,
+}
+
+struct Snapshot {
+    generation: u64,
+    /// The handle every pooled one is forked from.
+    origin: Mutex<Memvid>,
+    idle: Mutex<Vec<Memvid>>,
+}
+
+impl MemvidReader {
+    /// Open a reader on the last committed generation of the memory at `path`.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Ok(Self::from_origin(Memvid::open_read_only(path)?))
+    }
+
+    fn from_origin(origin: Memvid) -> Self {
+        Self {
+            current: Arc::new(RwLock::new(Snapshot::new(origin))),
+        }
+    }
+
+    /// Generation of the snapshot new queries run against.
+    pub fn generation(&self) -> Result<u64> {
+        Ok(self.snapshot()?.generation)
+    }
+
+    pub fn search(&self, request: SearchRequest) -> Result<SearchResponse> {
+        self.with_handle(|mem| mem.search(request))
+    }
+
+    pub fn ask<E>(&self, request: AskRequest, embedder: Option<&E>) -> Result<AskResponse>
+    where
+        E: VecEmbedder + ?Sized,
+    {
+        self.with_handle(|mem| mem.ask(request, embedder))
+    }
+
+    pub fn frame_text_by_id(&self, frame_id: FrameId) -> Result<String> {
+        self.with_handle(|mem| mem.frame_text_by_id(frame_id))
+    }
+
+    pub fn timeline(&self, query: TimelineQuery) -> Result<Vec<TimelineEntry>> {
+        self.with_handle(|mem| mem.timeline(query))
+    }
+
+    fn snapshot(&self) -> Result<Arc<Snapshot>> {
+        let current = self
+            .current
+            .read()
+            .map_err(|_| MemvidError::Lock("reader snapshot lock poisoned".into()))?;
+        Ok(Arc::clone(&current))
+    }
+
+    fn publish(&self, origin: Memvid) -> Result<()> {
+        let snapshot = Snapshot::new(origin);
+        *self
+            .current
+            .write()
+            .map_err(|_| MemvidError::Lock("reader snapshot lock poisoned".into()))? = snapshot;
+        Ok(())
+    }
+
+    fn with_handle<T>(&self, query: impl FnOnce(&mut Memvid) -> Result<T>) -> Result<T> {
+        let snapshot = self.snapshot()?;
+        let idle = snapshot.idle_pool()?.pop();
+        let mut handle = match idle {
+            Some(handle) => handle,
+            None => snapshot
+                .origin
+                .lock()
+                .map_err(|_| MemvidError::Lock("reader origin lock poisoned".into()))?
+                .fork_reader()?,
+        };
+        let result = query(&mut handle);
+        snapshot.idle_pool()?.push(handle);
+        result
+    }
+}
+
+impl Snapshot {
+    fn new(origin: Memvid) -> Arc<Self> {
+        Arc::new(Self {
+            generation: origin.generation,
+            origin: Mutex::new(origin),
+            idle: Mutex::new(Vec::new()),
+        })
+    }
+
+    fn idle_pool(&self) -> Result<std::sync::MutexGuard<'_, Vec<Memvid>>> {
+        self.idle
+            .lock()
+            .map_err(|_| MemvidError::Lock("reader pool lock poisoned".into()))
+    }
+}
+
+impl Memvid {
+    /// A [`MemvidReader`] over the generation this handle last committed. Every later
+    /// commit through this handle publishes its generation to the reader and its clones.
+    pub fn reader(&mut self) -> Result<MemvidReader> {
+        if let Some(reader) = &self.published_reader {
+            return Ok(reader.clone());
+        }
+        let reader = MemvidReader::from_origin(self.fork_reader()?);
+        self.published_reader = Some(reader.clone());
+        Ok(reader)
+    }
+
+    /// Hand the generation just committed to the readers of this handle. Readers keep
+    /// their previous snapshot when that fails.
+    pub(crate) fn publish_reader(&self) {
+        let Some(reader) = &self.published_reader else {
+            return;
+        };
+        let published = self.fork_reader().and_then(|origin| reader.publish(origin));
+        if let Err(err) = published {
+            tracing::warn!(
+                generation = self.generation,
+                "failed to publish reader snapshot: {err}"
+            );
+        }
+    }
+
+    /// A read-only handle on the same generation, sharing this handle's descriptor and
+    /// lock, so it reads the file this handle has open even after the path is replaced.
+    pub(crate) fn fork_reader(&self) -> Result<Self> {
+        let file = self.file.try_clone()?;
+        let lock = self.lock.share();
+        let wal = EmbeddedWal::open_read_only(&file, &self.header)?;
+        let toc = self.toc.clone();
+
+        #[cfg(feature = "lex")]
+        let lex_storage = Arc::new(RwLock::new(EmbeddedLexStorage::from_manifest(
+            toc.indexes.lex.as_ref(),
+            &toc.indexes.lex_segments,
+        )));
+        #[cfg(feature = "lex")]
+        let tantivy = self
+            .tantivy
+            .as_ref()
+            .map(TantivyEngine::share)
+            .transpose()?;
+
+        Ok(Self {
+            file,
+            path: self.path.clone(),
+            lock,
+            read_only: true,
+            header: self.header.clone(),
+            toc,
+            wal,
+            regions: self.regions.clone(),
+            #[cfg(feature = "mmap")]
+            mapped: self.mapped.clone(),
+            commit_signer: None,
+            retention_report: None,
+            retention_check: None,
+            compression_dictionaries: self.compression_dictionaries.clone(),
+            blob_store: self.blob_store.clone(),
+            change_log: self.change_log.clone(),
+            pending_frame_inserts: 0,
+            pending_track_bytes: BTreeMap::new(),
+            quota_usage: None,
+            pending_versions: HashMap::new(),
+            data_end: self.data_end,
+            generation: self.generation,
+            staged_tail: None,
+            lock_settings: self.lock_settings.clone(),
+            lex_enabled: self.lex_enabled,
+            lex_index: self.lex_index.clone(),
+            #[cfg(feature = "lex")]
+            lex_storage,
+            vec_enabled: self.vec_enabled,
+            vec_compression: self.vec_compression.clone(),
+            vec_index: self.vec_index.clone(),
+            clip_enabled: self.clip_enabled,
+            clip_index: self.clip_index.clone(),
+            dirty: false,
+            #[cfg(feature = "lex")]
+            tantivy,
+            #[cfg(feature = "lex")]
+            tantivy_dirty: false,
+            #[cfg(feature = "temporal_track")]
+            temporal_track: self.temporal_track.clone(),
+            #[cfg(feature = "parallel_segments")]
+            manifest_wal: None,
+            memories_track: self.memories_track.clone(),
+            logic_mesh: self.logic_mesh.clone(),
+            sketch_track: self.sketch_track.clone(),
+            schema_registry: self.schema_registry.clone(),
+            schema_strict: self.schema_strict,
+            published_reader: None,
+            refresh: self.refresh,
+            in_transaction: false,
+            #[cfg(feature = "replay")]
+            active_session: None,
+            #[cfg(feature = "replay")]
+            completed_sessions: self.completed_sessions.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lock::FileLock;
+    use crate::{PutOptions, run_serial_test};
+    use std::thread;
+    use tempfile::tempdir;
+
+    fn put(mem: &mut Memvid, uri: &str, text: &str) {
+        let options = PutOptions::builder()
+            .uri(uri)
+            .auto_tag(false)
+            .extract_dates(false)
+            .extract_triplets(false)
+            .build();
+        mem.put_bytes_with_options(text.as_bytes(), options)
+            .expect("put");
+    }
+
+    #[test]
+    fn readers_search_concurrently_and_follow_commits() {
+        fn assert_shareable<T: Send + Sync + Clone>() {}
+        assert_shareable::<MemvidReader>();
+
+        run_serial_test(|| {
+            let dir = tempdir().expect("tmp");
+            let path = dir.path().join("reader.mv2");
+            let mut mem = Memvid::create(&path).expect("create");
+            mem.enable_lex().expect("enable lex");
+            for index in 0..20 {
+                put(
+                    &mut mem,
+                    &format!("mv2://notes/{index}"),
+                    &format!("lighthouse keeper log entry {index}"),
+                );
+            }
+            mem.commit().expect("commit");
+
+            let reader = mem.reader().expect("reader");
+            let generation = reader.generation().expect("generation");
+            let workers: Vec<_> = (0..4)
+                .map(|_| {
+                    let reader = reader.clone();
+                    thread::spawn(move || {
+                        for _ in 0..10 {
+                            let response = reader
+                                .search(SearchRequest::test_query("lighthouse"))
+                                .expect("search");
+                            assert_eq!(response.hits.len(), 10);
+                            let text = reader
+                                .frame_text_by_id(response.hits[0].frame_id)
+                                .expect("text");
+                            assert!(text.contains("lighthouse"));
+                        }
+                    })
+                })
+                .collect();
+            for worker in workers {
+                worker.join().expect("worker");
+            }
+
+            // Readers keep answering from the published snapshot until the next commit.
+            put(
+                &mut mem,
+                "mv2://notes/storm",
+                "storm warning for the harbour",
+            );
+            assert!(
+                reader
+                    .search(SearchRequest::test_query("harbour"))
+                    .expect("search")
+                    .hits
+                    .is_empty()
+            );
+            mem.commit().expect("commit");
+            assert!(reader.generation().expect("generation") > generation);
+            let hits = reader
+                .search(SearchRequest::test_query("harbour"))
+                .expect("search")
+                .hits;
+            assert_eq!(hits.len(), 1);
+            assert_eq!(hits[0].uri, "mv2://notes/storm");
+            drop(mem);
+
+            let reopened = MemvidReader::open(&path).expect("open reader");
+            let handle = reopened.clone();
+            let hits = thread::spawn(move || {
+                handle
+                    .search(SearchRequest::test_query("harbour"))
+                    .expect("search")
+            })
+            .join()
+            .expect("thread")
+            .hits;
+            assert_eq!(hits.len(), 1);
+        });
+    }
+
+    #[test]
+    fn forks_keep_the_file_and_its_lock() {
+        run_serial_test(|| {
+            let dir = tempdir().expect("tmp");
+            let path = dir.path().join("reader.mv2");
+            let mut mem = Memvid::create(&path).expect("create");
+            mem.enable_lex().expect("enable lex");
+            put(&mut mem, "mv2://notes/keeper", "lighthouse keeper log");
+            mem.commit().expect("commit");
+            drop(mem);
+            let file = std::fs::File::open(&path).expect("open");
+            let exclusive = || FileLock::try_acquire(&file, &path).expect("try lock");
+
+            // A fork holds the lock shared for as long as it lives, even past its origin.
+            let origin = Memvid::open_read_only(&path).expect("open read only");
+            let fork = origin.fork_reader().expect("fork");
+            drop(origin);
+            assert!(exclusive().is_none());
+            drop(fork);
+            assert!(exclusive().is_some());
+
+            // Forks read the file the snapshot was taken from, not whatever the path names.
+            let reader = MemvidReader::open(&path).expect("open reader");
+            let other = dir.path().join("other.mv2");
+            let mut replacement = Memvid::create(&other).expect("create");
+            put(&mut replacement, "mv2://notes/storm", "storm warning");
+            replacement.commit().expect("commit");
+            drop(replacement);
+            std::fs::rename(&other, &path).expect("replace");
+            let workers: Vec<_> = (0..4)
+                .map(|_| {
+                    let reader = reader.clone();
+                    thread::spawn(move || {
+                        reader
+                            .search(SearchRequest::test_query("lighthouse"))
+                            .expect("search")
+                    })
+                })
+                .collect();
+            for worker in workers {
+                assert_eq!(worker.join().expect("worker").hits.len(), 1);
+            }
+        });
+    }
+}
@@ -0,0 +1,207 @@
+//! Picking up commits made by another process.
+//!
+//! Writers never rewrite committed bytes: a commit stages a copy of the file, appends the
+//! new generation and renames the copy over the original, persisting the header last. A
+//! read-only handle keeps reading the file it opened until [`Memvid::refresh`] sees a
+//! different `footer_offset` or `toc_checksum` in the header at its path. It then takes a
+//! shared lock on the current file, like a fresh read-only open would, loads its TOC and
+//! reloads only the indexes and tracks whose manifests changed.
+
+use std::fs::OpenOptions;
+use std::sync::Arc;
+
+use serde::Serialize;
+
+use crate::error::Result;
+use crate::io::header::HeaderCodec;
+#[cfg(feature = "mmap")]
+use crate::io::mapped::MappedFile;
+use crate::io::wal::EmbeddedWal;
+use crate::lock::{FileLock, LockMode};
+use crate::memvid::lifecycle::{Memvid, TailSnapshot, has_lex_index, load_tail_snapshot};
+#[cfg(feature = "lex")]
+use crate::search::EmbeddedLexStorage;
+use crate::types::{Header, LogicMesh, MemoriesTrack, SketchTrack, Toc};
+
+/// The header fields a read-only handle compares to notice a new commit.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RefreshState {
+    footer_offset: u64,
+    toc_checksum: [u8; 32],
+    /// Refresh before every search, ask and timeline query.
+    auto: bool,
+}
+
+impl RefreshState {
+    pub(crate) fn new(header: &Header) -> Self {
+        Self {
+            footer_offset: header.footer_offset,
+            toc_checksum: header.toc_checksum,
+            auto: false,
+        }
+    }
+
+    fn matches(&self, header: &Header) -> bool {
+        self.footer_offset == header.footer_offset && self.toc_checksum == header.toc_checksum
+    }
+}
+
+impl Memvid {
+    /// Load the latest commit of the file at [`Memvid::path`] if it is newer than the one
+    /// this handle reads, returning whether it was.
+    ///
+    /// Only read-only handles opened on the latest generation refresh; writers already see
+    /// their own commits and views pinned to a generation never move. When nothing was
+    /// committed this only reads the header.
+    pub fn refresh(&mut self) -> Result<bool> {
+        let Some(state) = self.refresh else {
+            return Ok(false);
+        };
+        let mut file = OpenOptions::new().read(true).open(&self.path)?;
+        let disk_header = HeaderCodec::read(&mut file)?;
+        if state.matches(&disk_header) {
+            return Ok(false);
+        }
+
+        let lock = FileLock::acquire_with_mode(&file, LockMode::Shared)?;
+        let TailSnapshot {
+            toc,
+            footer_offset,
+            data_end,
+            generation,
+            ..
+        } = load_tail_snapshot(&file, &self.regions)?;
+        self.refresh = Some(RefreshState {
+            auto: state.auto,
+            ..RefreshState::new(&disk_header)
+        });
+        if toc.toc_checksum == self.toc.toc_checksum {
+            return Ok(false);
+        }
+
+        let mut header = disk_header;
+        header.footer_offset = footer_offset;
+        header.toc_checksum = toc.toc_checksum;
+        self.wal = EmbeddedWal::open_read_only(&file, &header)?;
+        #[cfg(feature = "mmap")]
+        {
+            self.mapped = Some(MappedFile::new(&file)?);
+        }
+        self.file = file;
+        self.lock = lock;
+        self.header = header;
+        self.data_end = data_end;
+        self.generation = generation;
+        let previous = std::mem::replace(&mut self.toc, toc);
+        self.quota_usage = None;
+        self.reload_changed_indexes(&previous)?;
+        Ok(true)
+    }
+
+    /// Turn automatic refreshing before every search, ask and timeline query on or off.
+    /// Handles that never refresh ignore this.
+    pub fn set_auto_refresh(&mut self, enabled: bool) {
+        if let Some(state) = self.refresh.as_mut() {
+            state.auto = enabled;
+        }
+    }
+
+    /// Refresh first when automatic refreshing is on.
+    pub(crate) fn auto_refresh(&mut self) -> Result<()> {
+        if self.refresh.is_some_and(|state| state.auto) {
+            self.refresh()?;
+        }
+        Ok(())
+    }
+
+    /// Reload what the new TOC describes differently from `previous`.
+    fn reload_changed_indexes(&mut self, previous: &Toc) -> Result<()> {
+        let toc = &self.toc;
+        let lex_changed = changed(&previous.indexes.lex, &toc.indexes.lex)
+            || changed(&previous.indexes.lex_segments, &toc.indexes.lex_segments)
+            || changed(
+                &previous.segment_catalog.lex_segments,
+                &toc.segment_catalog.lex_segments,
+            )
+            || changed(
+                &previous.segment_catalog.tantivy_segments,
+                &toc.segment_catalog.tantivy_segments,
+            );
+        let vec_changed = changed(&previous.indexes.vec, &toc.indexes.vec)
+            || changed(
+                &previous.segment_catalog.vec_segments,
+                &toc.segment_catalog.vec_segments,
+            );
+        let clip_changed = changed(&previous.indexes.clip, &toc.indexes.clip);
+        let memories_changed = changed(&previous.memories_track, &toc.memories_track);
+        let mesh_changed = changed(&previous.logic_mesh, &toc.logic_mesh);
+        let sketch_changed = changed(&previous.sketch_track, &toc.sketch_track);
+        let blob_store_changed = changed(&previous.blob_store, &toc.blob_store);
+        let change_log_changed = changed(&previous.change_log, &toc.change_log);
+        #[cfg(feature = "temporal_track")]
+        let temporal_changed = changed(&previous.temporal_track, &toc.temporal_track);
+
+        if lex_changed {
+            self.lex_enabled = has_lex_index(&self.toc);
+            #[cfg(feature = "lex")]
+            if let Ok(mut storage) = self.lex_storage.write() {
+                *storage = EmbeddedLexStorage::from_manifest(
+                    self.toc.indexes.lex.as_ref(),
+                    &self.toc.indexes.lex_segments,
+                );
+            }
+            self.load_lex_index_from_manifest()?;
+            #[cfg(feature = "lex")]
+            self.init_tantivy()?;
+        }
+        if vec_changed {
+            self.vec_enabled =
+                self.toc.indexes.vec.is_some() || !self.toc.segment_catalog.vec_segments.is_empty();
+            self.vec_index = None;
+            if self.vec_enabled {
+                self.load_vec_index_from_manifest()?;
+            }
+        }
+        if clip_changed {
+            self.clip_enabled = self.toc.indexes.clip.is_some();
+            self.load_clip_index_from_manifest()?;
+        }
+        if memories_changed {
+            self.memories_track = Arc::new(MemoriesTrack::new());
+            self.load_memories_track()?;
+        }
+        if mesh_changed {
+            self.logic_mesh = Arc::new(LogicMesh::new());
+            self.load_logic_mesh()?;
+        }
+        if sketch_changed {
+            self.sketch_track = Arc::new(SketchTrack::default());
+            self.load_sketch_track()?;
+        }
+        if blob_store_changed {
+            self.load_blob_store()?;
+        }
+        if change_log_changed {
+            self.load_change_log()?;
+        }
+        self.bootstrap_segment_catalog();
+        #[cfg(feature = "temporal_track")]
+        if temporal_changed {
+            self.temporal_track = None;
+            self.ensure_temporal_track_loaded()?;
+        }
+        Ok(())
+    }
+}
+
+/// Whether two manifests differ, compared by their encoding.
+fn changed<T: Serialize>(before: &T, after: &T) -> bool {
+    let config = bincode::config::standard();
+    match (
+        bincode::serde::encode_to_vec(before, config),
+        bincode::serde::encode_to_vec(after, config),
+    ) {
+        (Ok(before), Ok(after)) => before != after,
+        _ => true,
+    }
+}
@@ -4,6 +4,7 @@
 //! enabling recording and replaying of agent sessions.
 
 use crate::error::Result;
+use crate::io::region::RegionKind;
 use crate::memvid::lifecycle::Memvid;
 use crate::replay::{
     ActionType, ActiveSession, ReplayAction, ReplayConfig, ReplayManifest, ReplaySession,
@@ -92,7 +93,12 @@ impl Memvid {
         // Create a state snapshot
         let snapshot = StateSnapshot {
             frame_count: self.toc.frames.len(),
-            frame_ids: self.toc.frames.iter().map(|f| f.id).collect(),
+            frame_ids: self
+                .toc
+                .frames
+                .try_iter()
+                .map(|f| f.map(|f| f.id))
+                .collect::<Result<_>>()?,
             lex_index_hash: self.toc.indexes.lex.as_ref().map(|m| m.checksum),
             vec_index_hash: self.toc.indexes.vec.as_ref().map(|m| m.checksum),
             wal_sequence: self.header.wal_sequence,
@@ -249,6 +255,10 @@ impl Memvid {
 
         // Build the replay segment
         let segment_data = storage::build_segment(&self.completed_sessions)?;
+        let segment_data = self
+            .regions
+            .seal(RegionKind::Index, &segment_data)?
+            .into_owned();
         let segment_size = segment_data.len() as u64;
 
         // Debug: show first 32 bytes being written
@@ -274,8 +284,13 @@ impl Memvid {
         // overwrite indexes and (worse) move `footer_offset` backwards, corrupting the TOC.
         //
         // Instead, append replay at the current footer boundary, which is always after any
-        // embedded index / metadata bytes for the current generation.
-        let segment_offset = self.header.footer_offset.max(self.data_end);
+        // embedded index / metadata bytes for the current generation, and never before the
+        // end of the file so committed generations keep their TOCs.
+        let segment_offset = self
+            .header
+            .footer_offset
+            .max(self.data_end)
+            .max(self.file.metadata()?.len());
         tracing::debug!(
             "Writing replay segment: offset={}, size={}, footer_offset={}, data_end={}",
             segment_offset,
@@ -367,6 +382,7 @@ impl Memvid {
             );
             e
         })?;
+        let buf = self.regions.open_vec(RegionKind::Index, buf)?;
 
         // Debug: show first 32 bytes as hex
         let preview_len = buf.len().min(32);
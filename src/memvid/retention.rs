@@ -0,0 +1,454 @@
+//! Retention rules: declarative expiry of frames by track, tag or kind.
+//!
+//! Rules live in the TOC and are evaluated on every commit after the WAL has been
+//! applied, in a single pass over the frames. The pass records, per rule, how many
+//! frames it keeps and the oldest of them; later commits only fold the frames appended
+//! since into that summary, and scan again once it shows a rule over its limits or the
+//! rules or the table itself were replaced. Expired frames are tombstoned with a reason
+//! code in their extra metadata, and memory cards extracted from them are dropped with
+//! them.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::error::Result;
+use crate::frame_table::FrameTable;
+use crate::memvid::lifecycle::Memvid;
+use crate::types::{
+    Frame, FrameId, FrameRole, FrameStatus, MEMVID_TOMBSTONE_REASON_KEY, RetentionCandidate,
+    RetentionReason, RetentionReport, RetentionRule,
+};
+
+impl Memvid {
+    /// Retention rules stored in the memory.
+    #[must_use]
+    pub fn retention_rules(&self) -> &[RetentionRule] {
+        &self.toc.retention_rules
+    }
+
+    /// Replace the retention rules; they are persisted and first enforced by the next commit.
+    pub fn set_retention_rules(&mut self, rules: Vec<RetentionRule>) -> Result<()> {
+        self.ensure_mutation_allowed()?;
+        self.toc.retention_rules = rules;
+        self.dirty = true;
+        Ok(())
+    }
+
+    /// List the committed frames and memory cards the retention rules would remove now,
+    /// without changing anything.
+    pub fn retention_dry_run(&self) -> Result<RetentionReport> {
+        let (mut report, _) = self.evaluate_retention(unix_now())?;
+        report.dry_run = true;
+        Ok(report)
+    }
+
+    /// Commit pending changes and enforce the retention rules, returning what was removed.
+    pub fn apply_retention(&mut self) -> Result<RetentionReport> {
+        self.ensure_mutation_allowed()?;
+        if !self.evaluate_retention(unix_now())?.0.is_empty() {
+            self.dirty = true;
+        }
+        self.retention_report = None;
+        self.commit()?;
+        Ok(self
+            .retention_report
+            .clone()
+            .unwrap_or_else(|| RetentionReport {
+                evaluated_at: self.toc.committed_at.unwrap_or_else(unix_now),
+                ..RetentionReport::default()
+            }))
+    }
+
+    /// What the retention rules removed during the last commit, if anything.
+    #[must_use]
+    pub fn last_retention_report(&self) -> Option<&RetentionReport> {
+        self.retention_report.as_ref()
+    }
+
+    /// Tombstone the frames expired at the time of the commit in progress.
+    ///
+    /// Returns whether any frame was removed.
+    pub(crate) fn enforce_retention(&mut self) -> Result<bool> {
+        self.retention_report = None;
+        if self.toc.retention_rules.is_empty() {
+            return Ok(false);
+        }
+        let now = self.toc.committed_at.unwrap_or_else(unix_now);
+        if let Some(mut check) = self.retention_check.take()
+            && check.absorb(&self.toc.retention_rules, &self.toc.frames, now)?
+        {
+            self.retention_check = Some(check);
+            return Ok(false);
+        }
+        let (report, kept) = self.evaluate_retention(now)?;
+        for candidate in &report.frames {
+            self.mark_frame_deleted(candidate.frame_id)?;
+            if let Some(frame) = self.toc.frames.try_get_mut(candidate.frame_id as usize)? {
+                frame.extra_metadata.insert(
+                    MEMVID_TOMBSTONE_REASON_KEY.to_string(),
+                    candidate.reason.code().to_string(),
+                );
+            }
+        }
+        self.retention_check = Some(RetentionCheck {
+            rules: self.toc.retention_rules.clone(),
+            frames_lineage: self.toc.frames.lineage(),
+            frames_seen: self.toc.frames.len(),
+            kept,
+        });
+        if report.is_empty() {
+            return Ok(false);
+        }
+        let expired: BTreeSet<FrameId> = report.frames.iter().map(|c| c.frame_id).collect();
+        let removed = Arc::make_mut(&mut self.memories_track).remove_cards_from_frames(&expired);
+        self.log_card_removals(&removed);
+        tracing::info!(
+            frames = report.frames.len(),
+            memory_cards = report.memory_cards.len(),
+            "retention tombstoned expired frames"
+        );
+        self.retention_report = Some(report);
+        Ok(true)
+    }
+
+    /// Evaluate every rule in one pass over the frames, returning what expires at `now`
+    /// and what each rule keeps.
+    fn evaluate_retention(&self, now: i64) -> Result<(RetentionReport, Vec<KeptFrames>)> {
+        let rules = &self.toc.retention_rules;
+        let mut matching: Vec<Vec<&Frame>> = vec![Vec::new(); rules.len()];
+        let mut chunks = Vec::new();
+        if !rules.is_empty() {
+            for frame in self.toc.frames.try_iter() {
+                let frame = frame?;
+                if frame.status != FrameStatus::Active {
+                    continue;
+                }
+                if frame.role == FrameRole::DocumentChunk {
+                    if frame.parent_id.is_some() {
+                        chunks.push(frame);
+                    }
+                    continue;
+                }
+                for (rule, matching) in rules.iter().zip(&mut matching) {
+                    if rule.matches(frame.track.as_deref(), frame.kind.as_deref(), &frame.tags) {
+                        matching.push(frame);
+                    }
+                }
+            }
+        }
+
+        let mut expired: BTreeMap<FrameId, RetentionCandidate> = BTreeMap::new();
+        for (rule, matching) in rules.iter().zip(&mut matching) {
+            // Newest first, so `max_count` keeps the head of the list.
+            matching.sort_by(|a, b| b.timestamp.cmp(&a.timestamp).then(b.id.cmp(&a.id)));
+            let max_age = rule
+                .max_age
+                .map(|max_age| i64::try_from(max_age).unwrap_or(i64::MAX));
+            let cutoff = max_age.map(|max_age| now.saturating_sub(max_age));
+            let keep = rule.max_count.map_or(usize::MAX, |count| {
+                usize::try_from(count).unwrap_or(usize::MAX)
+            });
+            for (position, &frame) in matching.iter().enumerate() {
+                let reason = if cutoff.is_some_and(|cutoff| frame.timestamp < cutoff) {
+                    RetentionReason::MaxAge
+                } else if position >= keep {
+                    RetentionReason::MaxCount
+                } else {
+                    continue;
+                };
+                expired
+                    .entry(frame.id)
+                    .or_insert_with(|| RetentionCandidate {
+                        frame_id: frame.id,
+                        uri: frame.uri.clone(),
+                        timestamp: frame.timestamp,
+                        rule: rule.to_string(),
+                        reason,
+                    });
+            }
+        }
+
+        // Chunks go with the document they were split from.
+        for frame in chunks {
+            let Some(parent) = frame.parent_id.and_then(|id| expired.get(&id)) else {
+                continue;
+            };
+            let candidate = RetentionCandidate {
+                frame_id: frame.id,
+                uri: frame.uri.clone(),
+                timestamp: frame.timestamp,
+                rule: parent.rule.clone(),
+                reason: parent.reason,
+            };
+            expired.insert(frame.id, candidate);
+        }
+
+        // A frame kept by one rule may still expire under another.
+        let kept = matching
+            .iter()
+            .map(|matching| {
+                let mut kept = KeptFrames::default();
+                for frame in matching {
+                    if !expired.contains_key(&frame.id) {
+                        kept.add(frame.timestamp);
+                    }
+                }
+                kept
+            })
+            .collect();
+
+        let memory_cards = self
+            .memories_track
+            .cards()
+            .iter()
+            .filter(|card| expired.contains_key(&card.source_frame_id))
+            .map(|card| card.id)
+            .collect();
+        let report = RetentionReport {
+            dry_run: false,
+            evaluated_at: now,
+            frames: expired.into_values().collect(),
+            memory_cards,
+        };
+        Ok((report, kept))
+    }
+}
+
+/// What the rules kept after the last evaluation, carried forward over appended frames.
+///
+/// Frames already counted only ever change in place by leaving the rules' view
+/// (deleted, superseded or compacted), which can only make the summary conservative.
+/// While every rule stays within its limits nothing can expire, so commits skip the
+/// scan.
+pub(crate) struct RetentionCheck {
+    rules: Vec<RetentionRule>,
+    frames_lineage: u64,
+    /// Frames folded into the summary; the ones past it were appended since.
+    frames_seen: usize,
+    /// One entry per rule.
+    kept: Vec<KeptFrames>,
+}
+
+impl RetentionCheck {
+    /// Fold the frames appended since the summary was taken into it, returning whether
+    /// every rule is still within its limits at `now`.
+    fn absorb(&mut self, rules: &[RetentionRule], frames: &FrameTable, now: i64) -> Result<bool> {
+        if self.rules != rules
+            || self.frames_lineage != frames.lineage()
+            || self.frames_seen > frames.len()
+        {
+            return Ok(false);
+        }
+        for index in self.frames_seen..frames.len() {
+            let Some(frame) = frames.try_get(index)? else {
+                break;
+            };
+            // Chunks follow their document, which is counted on its own.
+            if frame.status != FrameStatus::Active || frame.role == FrameRole::DocumentChunk {
+                continue;
+            }
+            for (rule, kept) in rules.iter().zip(&mut self.kept) {
+                if rule.matches(frame.track.as_deref(), frame.kind.as_deref(), &frame.tags) {
+                    kept.add(frame.timestamp);
+                }
+            }
+        }
+        self.frames_seen = frames.len();
+        Ok(rules
+            .iter()
+            .zip(&self.kept)
+            .all(|(rule, kept)| kept.within(rule, now)))
+    }
+}
+
+/// How many frames a rule keeps and the timestamp of the oldest.
+#[derive(Clone, Copy, Default)]
+pub(crate) struct KeptFrames {
+    count: u64,
+    oldest: Option<i64>,
+}
+
+impl KeptFrames {
+    fn add(&mut self, timestamp: i64) {
+        self.count += 1;
+        self.oldest = Some(self.oldest.unwrap_or(timestamp).min(timestamp));
+    }
+
+    fn within(self, rule: &RetentionRule, now: i64) -> bool {
+        let aged = rule.max_age.is_some_and(|max_age| {
+            let cutoff = now.saturating_sub(i64::try_from(max_age).unwrap_or(i64::MAX));
+            self.oldest.is_some_and(|oldest| oldest < cutoff)
+        });
+        !aged && rule.max_count.is_none_or(|count| self.count <= count)
+    }
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::MemoryCardBuilder;
+    use crate::{PutOptions, run_serial_test};
+    use tempfile::tempdir;
+
+    fn put(mem: &mut Memvid, uri: &str, timestamp: i64, track: Option<&str>, tag: Option<&str>) {
+        let mut builder = PutOptions::builder()
+            .uri(uri)
+            .timestamp(timestamp)
+            .auto_tag(false)
+            .extract_dates(false)
+            .extract_triplets(false);
+        if let Some(track) = track {
+            builder = builder.track(track);
+        }
+        if let Some(tag) = tag {
+            builder = builder.push_tag(tag);
+        }
+        mem.put_bytes_with_options(uri.as_bytes(), builder.build())
+            .expect("put");
+    }
+
+    #[test]
+    fn retention_tombstones_expired_frames_and_their_cards() {
+        run_serial_test(|| {
+            let dir = tempdir().expect("tmp");
+            let path = dir.path().join("retention.mv2");
+            let now = unix_now();
+            let old = now - 40 * 24 * 60 * 60;
+
+            let mut mem = Memvid::create(&path).expect("create");
+            put(&mut mem, "mv2://chat/old", old, Some("chat"), None);
+            put(&mut mem, "mv2://chat/new", now, Some("chat"), None);
+            put(&mut mem, "mv2://docs/manual", old, None, None);
+            for index in 0..3 {
+                let uri = format!("mv2://scratch/{index}");
+                put(&mut mem, &uri, now - 10 + index, None, Some("scratch"));
+            }
+            mem.commit().expect("commit");
+            let old_chat = mem.frame_by_uri("mv2://chat/old").expect("frame").id;
+            let card = MemoryCardBuilder::new()
+                .fact()
+                .entity("user")
+                .slot("topic")
+                .value("retention")
+                .source(old_chat, None)
+                .engine("rules-v1", "1.0.0")
+                .build(0)
+                .expect("card");
+            mem.put_memory_card(card).expect("card");
+
+            let rules = ["track=chat max_age=30d", "tag=scratch max_count=1"]
+                .iter()
+                .map(|rule| rule.parse().expect("rule"))
+                .collect();
+            mem.set_retention_rules(rules).expect("rules");
+
+            let dry_run = mem.retention_dry_run().expect("dry run");
+            assert!(dry_run.dry_run);
+            let uris: Vec<_> = dry_run
+                .frames
+                .iter()
+                .filter_map(|candidate| candidate.uri.as_deref())
+                .collect();
+            assert_eq!(
+                uris,
+                ["mv2://chat/old", "mv2://scratch/0", "mv2://scratch/1"]
+            );
+            assert_eq!(dry_run.frames[0].reason, RetentionReason::MaxAge);
+            assert_eq!(dry_run.frames[1].reason, RetentionReason::MaxCount);
+            assert_eq!(dry_run.memory_cards.len(), 1);
+            assert_eq!(
+                mem.frame_by_id(old_chat).expect("frame").status,
+                FrameStatus::Active
+            );
+
+            let report = mem.apply_retention().expect("apply");
+            assert!(!report.dry_run);
+            assert_eq!(report.frames, dry_run.frames);
+            assert_eq!(mem.memory_card_count(), 0);
+            assert!(mem.retention_dry_run().expect("dry run").is_empty());
+            drop(mem);
+
+            let mem = Memvid::open_read_only(&path).expect("reopen");
+            assert_eq!(mem.retention_rules().len(), 2);
+            let frame = mem.frame_by_id(old_chat).expect("frame");
+            assert_eq!(frame.status, FrameStatus::Deleted);
+            assert_eq!(
+                frame
+                    .extra_metadata
+                    .get(MEMVID_TOMBSTONE_REASON_KEY)
+                    .map(String::as_str),
+                Some("retention.max_age")
+            );
+            let manual = mem.frame_by_uri("mv2://docs/manual").expect("frame");
+            assert_eq!(manual.status, FrameStatus::Active);
+            assert_eq!(mem.memory_card_count(), 0);
+        });
+    }
+
+    #[test]
+    fn retention_folds_appended_frames_into_its_summary() {
+        run_serial_test(|| {
+            let dir = tempdir().expect("tmp");
+            let path = dir.path().join("retention-check.mv2");
+            let now = unix_now();
+            let hour = 60 * 60;
+
+            let mut mem = Memvid::create(&path).expect("create");
+            put(&mut mem, "mv2://scratch/a", now - 10, None, Some("scratch"));
+            put(&mut mem, "mv2://scratch/b", now, None, Some("scratch"));
+            let rules = vec!["tag=scratch max_age=1h".parse().expect("rule")];
+            mem.set_retention_rules(rules).expect("rules");
+            mem.commit().expect("commit");
+            let check = mem.retention_check.as_ref().expect("check");
+            assert_eq!(check.frames_seen, 2);
+            assert_eq!(check.kept[0].count, 2);
+            assert_eq!(check.kept[0].oldest, Some(now - 10));
+
+            // Frames already in the summary are not read again; a scan would expire this one.
+            let a = mem.frame_by_uri("mv2://scratch/a").expect("frame").id;
+            let frame = mem.toc.frames.try_get_mut(a as usize).expect("page");
+            frame.expect("frame").timestamp = now - 2 * hour;
+            put(&mut mem, "mv2://scratch/c", now, None, Some("scratch"));
+            mem.commit().expect("commit");
+            assert_eq!(
+                mem.frame_by_id(a).expect("frame").status,
+                FrameStatus::Active
+            );
+            let check = mem.retention_check.as_ref().expect("check");
+            assert_eq!((check.frames_seen, check.kept[0].count), (3, 3));
+
+            // An appended frame past the rule's limits brings the scan back.
+            put(
+                &mut mem,
+                "mv2://scratch/old",
+                now - 2 * hour,
+                None,
+                Some("scratch"),
+            );
+            mem.commit().expect("commit");
+            let report = mem.last_retention_report().expect("report");
+            let uris: Vec<_> = report
+                .frames
+                .iter()
+                .filter_map(|candidate| candidate.uri.as_deref())
+                .collect();
+            assert_eq!(uris, ["mv2://scratch/a", "mv2://scratch/old"]);
+
+            // So does the clock, once the oldest kept frame outlives `max_age`.
+            mem.toc.committed_at = Some(now + hour);
+            assert!(!mem.enforce_retention().expect("enforce"));
+            mem.toc.committed_at = Some(now + hour + 1);
+            assert!(mem.enforce_retention().expect("enforce"));
+            let report = mem.last_retention_report().expect("report");
+            assert_eq!(report.frames.len(), 2);
+        });
+    }
+}
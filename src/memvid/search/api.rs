@@ -3,17 +3,19 @@ use crate::search::{EmbeddedLexSegment, TantivyEngine};
 #[cfg(feature = "lex")]
 use std::fs::{self, File};
 #[cfg(feature = "lex")]
-use std::io::{Read, Seek, SeekFrom, Write};
+use std::io::Write;
+use std::sync::Arc;
 #[cfg(feature = "lex")]
 use tempfile::TempDir;
 
+use crate::io::read_at::read_exact_at;
 use crate::memvid::lifecycle::Memvid;
 use crate::types::{
     AdaptiveConfig, AdaptiveResult, AdaptiveStats, EmbeddingQualityStats, Frame, FrameId,
     FrameStatus, SearchHit, TimelineEntry, TimelineQuery, VecSegmentDescriptor,
     compute_embedding_quality, find_adaptive_cutoff,
 };
-use crate::{LexSearchHit, MemvidError, Result, VecSearchHit};
+use crate::{DistanceMetric, LexSearchHit, MemvidError, Result, VecSearchHit};
 
 impl Memvid {
     pub fn enable_lex(&mut self) -> Result<()> {
@@ -67,6 +69,17 @@ impl Memvid {
 
     pub fn enable_vec(&mut self) -> Result<()> {
         self.ensure_writable()?;
+        let metric = self.vec_metric();
+        self.enable_vec_with_metric(metric)
+    }
+
+    /// Enable the vector index, ranking hits with `metric`.
+    ///
+    /// The metric is recorded in the vec manifest. Changing it on a populated
+    /// index keeps the stored vectors; queries scan exhaustively until the
+    /// HNSW graph is rebuilt for the new metric by the next index rebuild.
+    pub fn enable_vec_with_metric(&mut self, metric: DistanceMetric) -> Result<()> {
+        self.ensure_writable()?;
 
         // Always set vec_enabled to true when explicitly requested,
         // regardless of compile-time feature flags
@@ -87,14 +100,55 @@ impl Memvid {
                 bytes_length: 0,
                 checksum: empty_checksum,
                 compression_mode: self.vec_compression.clone(),
+                metric,
+                hnsw: None,
             });
+        } else if let Some(manifest) = self.toc.indexes.vec.as_mut() {
+            manifest.metric = metric;
         }
 
         // No need to commit here - the manifest will be written during next commit/seal
         Ok(())
     }
 
+    /// Distance metric used to rank vector hits.
+    ///
+    /// Defaults to cosine for new indexes; files written before the metric
+    /// was recorded report L2.
+    pub fn vec_metric(&self) -> DistanceMetric {
+        self.toc
+            .indexes
+            .vec
+            .as_ref()
+            .map(|manifest| manifest.metric)
+            .unwrap_or_default()
+    }
+
+    /// Distance metric used to rank CLIP hits.
+    pub fn clip_metric(&self) -> DistanceMetric {
+        self.toc
+            .indexes
+            .clip
+            .as_ref()
+            .map(|manifest| manifest.metric)
+            .unwrap_or_default()
+    }
+
     pub fn search_vec(&mut self, query: &[f32], limit: usize) -> Result<Vec<VecSearchHit>> {
+        self.search_vec_with_ef(query, limit, crate::vec_hnsw::DEFAULT_EF_SEARCH)
+    }
+
+    /// Vector search with an explicit HNSW candidate pool size.
+    ///
+    /// Larger `ef_search` values improve recall at the cost of latency. The
+    /// knob has no effect when the index is scanned exhaustively (small or
+    /// PQ-compressed indexes).
+    pub fn search_vec_with_ef(
+        &mut self,
+        query: &[f32],
+        limit: usize,
+        ef_search: usize,
+    ) -> Result<Vec<VecSearchHit>> {
         if !self.vec_enabled {
             return Err(MemvidError::VecNotEnabled);
         }
@@ -121,7 +175,7 @@ impl Memvid {
             self.ensure_vec_index()?;
         }
         let index = self.vec_index.as_ref().ok_or(MemvidError::VecNotEnabled)?;
-        Ok(index.search(query, limit))
+        Ok(index.search_with_ef(query, limit, self.vec_metric(), ef_search))
     }
 
     /// Enable CLIP visual embeddings index.
@@ -131,6 +185,13 @@ impl Memvid {
     /// fixed 512 dimensions (MobileCLIP-S2) and are stored in a separate index.
     pub fn enable_clip(&mut self) -> Result<()> {
         self.ensure_writable()?;
+        let metric = self.clip_metric();
+        self.enable_clip_with_metric(metric)
+    }
+
+    /// Enable the CLIP index, ranking hits with `metric`.
+    pub fn enable_clip_with_metric(&mut self, metric: DistanceMetric) -> Result<()> {
+        self.ensure_writable()?;
 
         self.clip_enabled = true;
         self.dirty = true;
@@ -147,7 +208,10 @@ impl Memvid {
                 dimension: crate::clip::MOBILECLIP_DIMS,
                 checksum: empty_checksum,
                 model_name: "mobileclip-s2".to_string(),
+                metric,
             });
+        } else if let Some(manifest) = self.toc.indexes.clip.as_mut() {
+            manifest.metric = metric;
         }
 
         Ok(())
@@ -176,14 +240,11 @@ impl Memvid {
         }
 
         // Initialize clip index if needed
-        if self.clip_index.is_none() {
-            self.clip_index = Some(crate::clip::ClipIndex::new());
-        }
-
-        // Add the document to the index
-        if let Some(ref mut index) = self.clip_index {
-            index.add_document(frame_id, page, embedding);
-        }
+        // Add the document to the index, initializing it if needed
+        let index = self
+            .clip_index
+            .get_or_insert_with(|| Arc::new(crate::clip::ClipIndex::new()));
+        Arc::make_mut(index).add_document(frame_id, page, embedding);
 
         self.dirty = true;
         Ok(())
@@ -213,7 +274,7 @@ impl Memvid {
             .as_ref()
             .ok_or(MemvidError::ClipNotEnabled)?;
         tracing::debug!("search_clip: clip_index has {} documents", index.len());
-        let hits = index.search(query, limit);
+        let hits = index.search_with_metric(query, limit, self.clip_metric());
         tracing::debug!("search_clip: returning {} hits", hits.len());
         Ok(hits)
     }
@@ -277,7 +338,8 @@ impl Memvid {
         let vec_index = self.vec_index.as_ref().ok_or(MemvidError::VecNotEnabled)?;
 
         // Do pure vector search over entire index
-        let vec_hits = vec_index.search(query_embedding, top_k * 2);
+        let metric = self.vec_metric();
+        let vec_hits = vec_index.search_with_metric(query_embedding, top_k * 2, metric);
 
         if vec_hits.is_empty() {
             let elapsed_ms = start_time.elapsed().as_millis();
@@ -303,7 +365,7 @@ impl Memvid {
 
         for vec_hit in vec_hits {
             // Apply scope filter if provided
-            let frame = match self.toc.frames.get(vec_hit.frame_id as usize) {
+            let frame = match self.toc.frames.try_get(vec_hit.frame_id as usize)? {
                 Some(f) => f.clone(),
                 None => continue,
             };
@@ -335,8 +397,7 @@ impl Memvid {
                 .or_else(|| crate::infer_title_from_uri(&uri));
 
             // VecIndex returns distance (lower is better), convert back to similarity (higher is better)
-            // distance = 1.0 - similarity, so similarity = 1.0 - distance
-            let similarity_score = 1.0 - vec_hit.distance;
+            let similarity_score = metric.similarity(vec_hit.distance);
 
             let metadata = SearchHitMetadata {
                 matches: 1,
@@ -566,7 +627,8 @@ impl Memvid {
         let frames = &self.toc.frames;
         let mut matching_ids: Vec<FrameId> = Vec::new();
 
-        for frame in frames {
+        for frame in frames.try_iter() {
+            let frame = frame?;
             if frame.status != FrameStatus::Active {
                 continue;
             }
@@ -592,6 +654,7 @@ impl Memvid {
     }
 
     pub fn timeline(&mut self, query: TimelineQuery) -> Result<Vec<TimelineEntry>> {
+        self.auto_refresh()?;
         let TimelineQuery {
             limit,
             since,
@@ -639,7 +702,6 @@ impl Memvid {
                 })?;
         let mut data_limit = self.header.footer_offset;
         let mut buffer = vec![0u8; 64 * 1024];
-        let cursor = self.file.seek(SeekFrom::Current(0))?;
         for segment in segments {
             let dest = dir.path().join(&segment.path);
             if let Some(parent) = dest.parent() {
@@ -692,11 +754,11 @@ impl Memvid {
                     });
                 }
             }
-            self.file.seek(SeekFrom::Start(segment.bytes_offset))?;
             let mut remaining = segment.bytes_length;
             while remaining > 0 {
                 let chunk = remaining.min(buffer.len() as u64) as usize;
-                if let Err(err) = self.file.read_exact(&mut buffer[..chunk]) {
+                let offset = segment.bytes_offset + (segment.bytes_length - remaining);
+                if let Err(err) = read_exact_at(&self.file, &mut buffer[..chunk], offset) {
                     return Err(MemvidError::Tantivy {
                         reason: format!(
                             "failed to read embedded segment {} (offset {}, remaining {}, chunk {}): {}",
@@ -708,10 +770,31 @@ impl Memvid {
                 remaining -= chunk as u64;
             }
         }
-        self.file.seek(SeekFrom::Start(cursor))?;
         Ok(dir)
     }
 
+    /// Decrypt embedded Tantivy segments into memory; encrypted memories never materialize
+    /// their index on disk.
+    fn load_tantivy_segments(
+        &mut self,
+        segments: &[EmbeddedLexSegment],
+    ) -> Result<Vec<(String, Vec<u8>)>> {
+        let mut files = Vec::with_capacity(segments.len());
+        for segment in segments {
+            let bytes = self.read_range(segment.bytes_offset, segment.bytes_length)?;
+            files.push((segment.path.clone(), bytes));
+        }
+        Ok(files)
+    }
+
+    fn new_tantivy_engine(&self) -> Result<TantivyEngine> {
+        if self.regions.is_encrypted() {
+            TantivyEngine::create_in_memory()
+        } else {
+            TantivyEngine::create()
+        }
+    }
+
     pub(crate) fn init_tantivy(&mut self) -> Result<()> {
         if !self.lex_enabled {
             self.tantivy = None;
@@ -748,21 +831,25 @@ impl Memvid {
 
         let mut engine = match segments {
             Some(segments) => {
-                match self
-                    .materialize_tantivy_segments(&segments)
-                    .and_then(TantivyEngine::open_from_dir)
-                {
+                let opened = if self.regions.is_encrypted() {
+                    self.load_tantivy_segments(&segments)
+                        .and_then(TantivyEngine::open_in_memory)
+                } else {
+                    self.materialize_tantivy_segments(&segments)
+                        .and_then(TantivyEngine::open_from_dir)
+                };
+                match opened {
                     Ok(engine) => engine,
                     Err(err) => {
                         tracing::debug!(
                             "failed to open embedded Tantivy index: {}, rebuilding",
                             err
                         );
-                        TantivyEngine::create()?
+                        self.new_tantivy_engine()?
                     }
                 }
             }
-            None => TantivyEngine::create()?,
+            None => self.new_tantivy_engine()?,
         };
 
         // Use consolidated helper for expected doc count
@@ -931,11 +1018,13 @@ pub fn is_frame_text_indexable(frame: &crate::types::Frame) -> bool {
 impl Memvid {
     pub(crate) fn rebuild_tantivy_engine(&mut self, engine: &mut TantivyEngine) -> Result<bool> {
         let mut prepared_docs: Vec<(Frame, String)> = Vec::new();
-        let frames = self.toc.frames.clone();
-        let active_frames: Vec<_> = frames
-            .into_iter()
-            .filter(|frame| frame.status == FrameStatus::Active)
-            .collect();
+        let mut active_frames = Vec::new();
+        for frame in self.toc.frames.try_iter() {
+            let frame = frame?;
+            if frame.status == FrameStatus::Active {
+                active_frames.push(frame.clone());
+            }
+        }
 
         let max_payload = max_index_payload();
 
@@ -1,10 +1,14 @@
+use std::borrow::Cow;
 use std::collections::HashMap;
-use std::io::{Read, Seek, SeekFrom};
 use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::sync::Arc;
 
+use crate::io::read_at::read_exact_at;
+use crate::io::region::RegionKind;
 use crate::lex::{LexIndex, LexIndexArtifact, LexIndexBuilder};
 use crate::memvid::lifecycle::Memvid;
 use crate::types::{Frame, FrameId, FrameStatus, VectorCompression};
+use crate::vec_hnsw::HnswGraph;
 use crate::{MemvidError, Result, VecIndex, VecIndexArtifact};
 
 impl Memvid {
@@ -15,13 +19,13 @@ impl Memvid {
         }
         let mut builder = LexIndexBuilder::new();
         let empty_tags = HashMap::new();
-        let frames: Vec<Frame> = self
-            .toc
-            .frames
-            .iter()
-            .filter(|frame| frame.status == FrameStatus::Active)
-            .cloned()
-            .collect();
+        let mut frames: Vec<Frame> = Vec::new();
+        for frame in self.toc.frames.try_iter() {
+            let frame = frame?;
+            if frame.status == FrameStatus::Active {
+                frames.push(frame.clone());
+            }
+        }
         for frame in frames {
             let content = self.frame_content(&frame)?;
             let uri = frame
@@ -49,18 +53,35 @@ impl Memvid {
             return Ok(None);
         }
         let mut builder = VecIndexBuilder::new();
-        if let Some(index) = self.vec_index.as_ref() {
-            for (frame_id, embedding) in index.entries() {
-                if self.frame_is_active(frame_id) {
-                    builder.add_document(frame_id, embedding.to_vec());
-                }
+        let mut previous_graph = None;
+        let stale = match self.vec_index.as_ref() {
+            Some(index) => self.inactive_frames(index.entries().map(|(frame_id, _)| frame_id))?,
+            None => Vec::new(),
+        };
+        if let Some(index) = self.vec_index.as_mut() {
+            let index = Arc::make_mut(index);
+            // The graph addresses documents by position, so deleted documents keep
+            // their slot until enough of them pile up to rebuild the graph.
+            for frame_id in stale {
+                index.remove(frame_id);
+            }
+            if index.graph().is_some_and(HnswGraph::needs_rebuild) {
+                index.purge_deleted();
+            }
+            for doc in index.slots() {
+                builder.add_document(doc.frame_id, doc.embedding.clone());
             }
+            previous_graph = index.take_graph();
         }
         for (frame_id, embedding) in new_docs {
             builder.add_document(*frame_id, embedding.clone());
         }
-        let artifact = builder.finish()?;
-        let index = VecIndex::decode(&artifact.bytes)?;
+        let mut artifact = builder.finish()?;
+        let mut index = VecIndex::decode(&artifact.bytes)?;
+        if let Some(graph) = previous_graph {
+            index.attach_graph(graph);
+        }
+        artifact.vector_count = index.entries().count() as u64;
         Ok(Some((artifact, index)))
     }
 
@@ -106,8 +127,8 @@ impl Memvid {
             };
             match LexIndex::decode(&bytes) {
                 Ok(mut index) => {
-                    self.hydrate_lex_index_metadata(&mut index);
-                    self.lex_index = Some(index);
+                    self.hydrate_lex_index_metadata(&mut index)?;
+                    self.lex_index = Some(Arc::new(index));
                 }
                 Err(_) => {
                     // Don't disable lex if decoding fails - keep it enabled
@@ -130,8 +151,9 @@ impl Memvid {
                 return Ok(());
             }
 
-            let bytes = match self.read_range(manifest.bytes_offset, manifest.bytes_length) {
-                Ok(bytes) => bytes,
+            let (offset, length) = (manifest.bytes_offset, manifest.bytes_length);
+            let decoded = match self.index_bytes(offset, length) {
+                Ok(bytes) => catch_unwind(AssertUnwindSafe(|| VecIndex::decode(&bytes))),
                 Err(_) => {
                     self.vec_index = None;
                     // Don't disable vec if loading fails - keep it enabled
@@ -139,8 +161,13 @@ impl Memvid {
                     return Ok(());
                 }
             };
-            match catch_unwind(AssertUnwindSafe(|| VecIndex::decode(&bytes))) {
-                Ok(Ok(index)) => self.vec_index = Some(index),
+            match decoded {
+                Ok(Ok(mut index)) => {
+                    if let Some(graph) = self.load_vec_graph_from_manifest() {
+                        index.attach_graph(graph);
+                    }
+                    self.vec_index = Some(Arc::new(index));
+                }
                 Ok(Err(_)) | Err(_) => {
                     self.vec_index = None;
                     // Don't disable vec if decoding fails - keep it enabled
@@ -155,6 +182,28 @@ impl Memvid {
         Ok(())
     }
 
+    /// Load the persisted HNSW graph referenced by the vec manifest.
+    ///
+    /// Any failure simply leaves the index without a graph, which falls back
+    /// to an exhaustive scan.
+    fn load_vec_graph_from_manifest(&mut self) -> Option<HnswGraph> {
+        let manifest = self.toc.indexes.vec.as_ref()?.hnsw.clone()?;
+        let bytes = self
+            .index_bytes(manifest.bytes_offset, manifest.bytes_length)
+            .ok()?;
+        if *blake3::hash(&bytes).as_bytes() != manifest.checksum {
+            tracing::warn!("hnsw graph checksum mismatch; falling back to exhaustive search");
+            return None;
+        }
+        match HnswGraph::decode(&bytes) {
+            Ok(graph) if graph.len() as u64 == manifest.node_count => Some(graph),
+            Ok(_) | Err(_) => {
+                tracing::warn!("hnsw graph failed to decode; falling back to exhaustive search");
+                None
+            }
+        }
+    }
+
     /// Load CLIP index from manifest.
     pub(crate) fn load_clip_index_from_manifest(&mut self) -> Result<()> {
         use crate::clip::ClipIndex;
@@ -166,15 +215,16 @@ impl Memvid {
                 return Ok(());
             }
 
-            let bytes = match self.read_range(manifest.bytes_offset, manifest.bytes_length) {
-                Ok(bytes) => bytes,
+            let (offset, length) = (manifest.bytes_offset, manifest.bytes_length);
+            let decoded = match self.index_bytes(offset, length) {
+                Ok(bytes) => catch_unwind(AssertUnwindSafe(|| ClipIndex::decode(&bytes))),
                 Err(_) => {
                     self.clip_index = None;
                     return Ok(());
                 }
             };
-            match catch_unwind(AssertUnwindSafe(|| ClipIndex::decode(&bytes))) {
-                Ok(Ok(index)) => self.clip_index = Some(index),
+            match decoded {
+                Ok(Ok(index)) => self.clip_index = Some(Arc::new(index)),
                 Ok(Err(_)) | Err(_) => {
                     self.clip_index = None;
                 }
@@ -185,7 +235,13 @@ impl Memvid {
         Ok(())
     }
 
+    /// Read an index region, decrypting it for encrypted memories.
     pub fn read_range(&mut self, offset: u64, length: u64) -> Result<Vec<u8>> {
+        self.index_bytes(offset, length).map(Cow::into_owned)
+    }
+
+    /// Like [`Memvid::read_range`], borrowing from the file mapping when there is one.
+    pub(crate) fn index_bytes(&mut self, offset: u64, length: u64) -> Result<Cow<'_, [u8]>> {
         let file_len = self.file.metadata()?.len();
         let end = offset.checked_add(length).ok_or(MemvidError::InvalidToc {
             reason: "manifest range overflow".into(),
@@ -195,10 +251,35 @@ impl Memvid {
                 reason: "manifest range invalid".into(),
             });
         }
-        self.file.seek(SeekFrom::Start(offset))?;
+        self.region_bytes(RegionKind::Index, offset, length)
+    }
+
+    /// Plaintext of the `kind` region stored at `offset`; callers check the bounds.
+    ///
+    /// Read-only opens with the `mmap` feature borrow it from the file mapping, remapped
+    /// first if the file changed size. Otherwise it is read from the file.
+    pub(crate) fn region_bytes(
+        &mut self,
+        kind: RegionKind,
+        offset: u64,
+        length: u64,
+    ) -> Result<Cow<'_, [u8]>> {
+        #[cfg(feature = "mmap")]
+        if let Some(mapped) = self.mapped.as_mut() {
+            mapped.refresh(&self.file)?;
+        }
+        #[cfg(feature = "mmap")]
+        if let Some(mapped) = self.mapped.as_ref() {
+            let stored = mapped
+                .slice(offset, length)
+                .ok_or(MemvidError::InvalidToc {
+                    reason: "region outside the mapped file".into(),
+                })?;
+            return self.regions.open(kind, stored);
+        }
         let mut buf = vec![0u8; length as usize];
-        self.file.read_exact(&mut buf)?;
-        Ok(buf)
+        read_exact_at(&self.file, &mut buf, offset)?;
+        self.regions.open_vec(kind, buf).map(Cow::Owned)
     }
 
     #[allow(dead_code)]
@@ -217,11 +298,24 @@ impl Memvid {
         let segments = self.toc.segment_catalog.vec_segments.clone();
 
         for segment_desc in &segments {
-            let bytes = match self.read_range(
+            // Use the compression stored in the descriptor - it's already correct
+            // The descriptor reflects the actual encoding used when the segment was written
+            let compression_hint = segment_desc.vector_compression.clone();
+
+            // Decoded straight from the file mapping when there is one
+            let decoded = match self.index_bytes(
                 segment_desc.common.bytes_offset,
                 segment_desc.common.bytes_length,
             ) {
-                Ok(bytes) => bytes,
+                Ok(bytes) => {
+                    tracing::debug!(
+                        segment_id = segment_desc.common.segment_id,
+                        compression_hint = ?compression_hint,
+                        bytes_len = bytes.len(),
+                        "attempting to decode vec segment"
+                    );
+                    VecIndex::decode_with_compression(&bytes, compression_hint)
+                }
                 Err(err) => {
                     tracing::warn!(
                         error = %err,
@@ -232,21 +326,10 @@ impl Memvid {
                 }
             };
 
-            // Use the compression stored in the descriptor - it's already correct
-            // The descriptor reflects the actual encoding used when the segment was written
-            let compression_hint = segment_desc.vector_compression.clone();
-
-            tracing::debug!(
-                segment_id = segment_desc.common.segment_id,
-                compression_hint = ?compression_hint,
-                bytes_len = bytes.len(),
-                "attempting to decode vec segment"
-            );
-
-            match VecIndex::decode_with_compression(&bytes, compression_hint) {
+            match decoded {
                 Ok(segment_index) => {
                     for (frame_id, embedding) in segment_index.entries() {
-                        if self.frame_is_active(frame_id) {
+                        if self.frame_is_active(frame_id)? {
                             builder.add_document(frame_id, embedding.to_vec());
                         }
                     }
@@ -265,15 +348,15 @@ impl Memvid {
         if artifact.vector_count > 0 {
             let index =
                 VecIndex::decode_with_compression(&artifact.bytes, VectorCompression::None)?;
-            self.vec_index = Some(index);
+            self.vec_index = Some(Arc::new(index));
         }
 
         Ok(())
     }
 
-    fn hydrate_lex_index_metadata(&self, index: &mut LexIndex) {
+    fn hydrate_lex_index_metadata(&self, index: &mut LexIndex) -> Result<()> {
         for document in index.documents_mut() {
-            let frame_meta = self.toc.frames.get(document.frame_id as usize);
+            let frame_meta = self.toc.frames.try_get(document.frame_id as usize)?;
 
             if document.uri.is_none() {
                 let derived = frame_meta
@@ -294,6 +377,7 @@ impl Memvid {
                 document.title = title;
             }
         }
+        Ok(())
     }
 }
 
@@ -45,11 +45,13 @@ pub(super) fn search_with_lex_fallback(
                 continue;
             }
         }
-        let frame_meta = memvid.toc.frames.get(matched.frame_id as usize).ok_or(
-            MemvidError::InvalidTimeIndex {
+        let frame_meta = memvid
+            .toc
+            .frames
+            .try_get(matched.frame_id as usize)?
+            .ok_or(MemvidError::InvalidTimeIndex {
                 reason: "frame id out of range".into(),
-            },
-        )?;
+            })?;
         let content_lower = matched.content.to_ascii_lowercase();
         let ctx = EvaluationContext {
             frame: frame_meta,
@@ -88,7 +90,7 @@ pub(super) fn search_with_lex_fallback(
         let frame_meta = memvid
             .toc
             .frames
-            .get(matched.frame_id as usize)
+            .try_get(matched.frame_id as usize)?
             .cloned()
             .ok_or(MemvidError::InvalidTimeIndex {
                 reason: "frame id out of range".into(),
@@ -207,15 +209,16 @@ pub(super) fn search_with_filters_only(
     let mut matches = Vec::new();
     let snippet_limit = request.snippet_chars.max(80);
     let frames: Vec<Frame> = if let Some(filter) = candidate_filter {
-        memvid
-            .toc
-            .frames
-            .iter()
-            .filter(|frame| filter.contains(&frame.id))
-            .cloned()
-            .collect()
+        let mut frames = Vec::new();
+        for frame in memvid.toc.frames.try_iter() {
+            let frame = frame?;
+            if filter.contains(&frame.id) {
+                frames.push(frame.clone());
+            }
+        }
+        frames
     } else {
-        memvid.toc.frames.clone()
+        memvid.toc.frames.to_vec()?
     };
 
     for frame in frames {
@@ -342,11 +342,14 @@ pub(crate) fn attach_temporal_metadata(memvid: &mut Memvid, hits: &mut [SearchHi
 
                 let text = if mention_end > mention_start {
                     if !canonical_cache.contains_key(&frame_id) {
-                        let frame = memvid.toc.frames.get(frame_id as usize).cloned().ok_or(
-                            MemvidError::InvalidTimeIndex {
+                        let frame = memvid
+                            .toc
+                            .frames
+                            .try_get(frame_id as usize)?
+                            .cloned()
+                            .ok_or(MemvidError::InvalidTimeIndex {
                                 reason: "frame id out of range".into(),
-                            },
-                        )?;
+                            })?;
                         let content = memvid.frame_content(&frame)?;
                         canonical_cache.insert(frame_id, content);
                     }
@@ -398,13 +401,13 @@ pub(crate) fn attach_temporal_metadata(memvid: &mut Memvid, hits: &mut [SearchHi
 /// For each hit, looks up entities that are associated with the hit's frame.
 /// If the frame is a DocumentChunk (page), also checks the parent document frame
 /// for entities since NER extraction happens on the full document.
-pub(super) fn enrich_hits_with_entities(hits: &mut [SearchHit], memvid: &Memvid) {
+pub(super) fn enrich_hits_with_entities(hits: &mut [SearchHit], memvid: &Memvid) -> Result<()> {
     for hit in hits.iter_mut() {
         let mut entities = memvid.frame_entities_for_search(hit.frame_id);
 
         // If no entities found and this is a chunk, check the parent frame
         if entities.is_empty() {
-            if let Some(frame) = memvid.toc.frames.get(hit.frame_id as usize) {
+            if let Some(frame) = memvid.toc.frames.try_get(hit.frame_id as usize)? {
                 if let Some(parent_id) = frame.parent_id {
                     entities = memvid.frame_entities_for_search(parent_id);
                 }
@@ -416,4 +419,5 @@ pub(super) fn enrich_hits_with_entities(hits: &mut [SearchHit], memvid: &Memvid)
             metadata.entities = entities;
         }
     }
+    Ok(())
 }
@@ -44,6 +44,16 @@ use time_filter::frame_ids_in_date_range;
 #[cfg(feature = "lex")]
 impl Memvid {
     pub fn search(&mut self, request: SearchRequest) -> Result<SearchResponse> {
+        self.auto_refresh()?;
+        self.search_without_refresh(request)
+    }
+
+    /// [`Memvid::search`] against the generation already loaded, for callers that run
+    /// several searches which must agree with each other.
+    pub(crate) fn search_without_refresh(
+        &mut self,
+        request: SearchRequest,
+    ) -> Result<SearchResponse> {
         if !self.lex_enabled {
             return Err(MemvidError::LexNotEnabled);
         }
@@ -258,7 +268,7 @@ impl Memvid {
 
         // Enrich hits with Logic-Mesh entities if mesh is available
         if self.has_logic_mesh() {
-            helpers::enrich_hits_with_entities(&mut response.hits, self);
+            helpers::enrich_hits_with_entities(&mut response.hits, self)?;
         }
 
         // Record the search action if a replay session is active
@@ -123,7 +123,7 @@ pub(super) fn try_tantivy_search(
         let frame_meta = memvid
             .toc
             .frames
-            .get(hit.frame_id as usize)
+            .try_get(hit.frame_id as usize)?
             .cloned()
             .ok_or(MemvidError::InvalidTimeIndex {
                 reason: "frame id out of range".into(),
@@ -268,7 +268,7 @@ pub(super) fn try_tantivy_search(
         let frame_meta = memvid
             .toc
             .frames
-            .get(hit.frame_id as usize)
+            .try_get(hit.frame_id as usize)?
             .cloned()
             .ok_or(MemvidError::InvalidTimeIndex {
                 reason: "frame id out of range".into(),
@@ -3,6 +3,7 @@ use std::io::{Read, Seek, SeekFrom, Write};
 #[cfg(feature = "lex")]
 use std::collections::{HashMap, HashSet};
 
+use crate::io::region::RegionKind;
 use crate::lex::{LexIndexArtifact, LexIndexBuilder};
 #[cfg(feature = "lex")]
 use crate::types::TantivySegmentDescriptor;
@@ -103,12 +104,15 @@ impl Memvid {
         let mut builder = LexIndexBuilder::new();
         let empty_tags = std::collections::HashMap::new();
         for frame_id in frame_ids {
-            let frame = self.toc.frames.get(*frame_id as usize).cloned().ok_or(
-                MemvidError::InvalidFrame {
+            let frame = self
+                .toc
+                .frames
+                .try_get(*frame_id as usize)?
+                .cloned()
+                .ok_or(MemvidError::InvalidFrame {
                     frame_id: *frame_id,
                     reason: "frame id out of range for lex segment",
-                },
-            )?;
+                })?;
 
             if frame.status != FrameStatus::Active {
                 continue;
@@ -290,20 +294,13 @@ impl Memvid {
         }
 
         let offset = self.data_end;
-        let new_end = offset + artifact.bytes.len() as u64;
 
         // Write at current data_end
-        self.file.seek(SeekFrom::Start(offset))?;
-        self.file.write_all(&artifact.bytes)?;
+        let length = self.write_region(offset, RegionKind::Index, &artifact.bytes)?;
         self.file.sync_all()?;
-        self.data_end = new_end;
+        self.data_end = offset + length;
 
-        let common = SegmentCommon::new(
-            segment_id,
-            offset,
-            artifact.bytes.len() as u64,
-            artifact.checksum,
-        );
+        let common = SegmentCommon::new(segment_id, offset, length, artifact.checksum);
         Ok(LexSegmentDescriptor::from_common(
             common,
             artifact.doc_count,
@@ -322,20 +319,21 @@ impl Memvid {
         }
 
         let offset = self.data_end;
-        let new_end = offset + artifact.bytes.len() as u64;
+        let stored = self.regions.seal(RegionKind::Index, &artifact.bytes)?;
+        let new_end = offset + stored.len() as u64;
 
         // Seek to write position
         self.file.seek(SeekFrom::Start(offset))?;
 
         // Write the actual data
-        self.file.write_all(&artifact.bytes)?;
+        self.file.write_all(&stored)?;
         self.file.sync_all()?;
 
         // VERIFY: Read back the first few bytes to confirm write persisted
         self.file.seek(SeekFrom::Start(offset))?;
-        let mut verify_buf = vec![0u8; 16.min(artifact.bytes.len())];
+        let mut verify_buf = vec![0u8; 16.min(stored.len())];
         self.file.read_exact(&mut verify_buf)?;
-        let expected = &artifact.bytes[..verify_buf.len()];
+        let expected = &stored[..verify_buf.len()];
         if verify_buf != expected {
             return Err(MemvidError::CheckpointFailed {
                 reason: format!("vec segment write verification failed at offset {}", offset),
@@ -344,12 +342,7 @@ impl Memvid {
 
         self.data_end = new_end;
 
-        let common = SegmentCommon::new(
-            segment_id,
-            offset,
-            artifact.bytes.len() as u64,
-            artifact.checksum,
-        );
+        let common = SegmentCommon::new(segment_id, offset, stored.len() as u64, artifact.checksum);
 
         tracing::debug!(
             segment_id = common.segment_id,
@@ -451,20 +444,13 @@ impl Memvid {
         }
 
         let offset = self.data_end;
-        let new_end = offset + artifact.bytes.len() as u64;
 
         // Write at current data_end
-        self.file.seek(SeekFrom::Start(offset))?;
-        self.file.write_all(&artifact.bytes)?;
+        let length = self.write_region(offset, RegionKind::Index, &artifact.bytes)?;
         self.file.sync_all()?;
-        self.data_end = new_end;
+        self.data_end = offset + length;
 
-        let common = SegmentCommon::new(
-            segment_id,
-            offset,
-            artifact.bytes.len() as u64,
-            artifact.checksum,
-        );
+        let common = SegmentCommon::new(segment_id, offset, length, artifact.checksum);
         Ok(TimeSegmentDescriptor::from_common(
             common,
             artifact.entry_count,
@@ -484,17 +470,11 @@ impl Memvid {
         }
 
         let offset = self.data_end;
-        self.file.seek(SeekFrom::Start(offset))?;
-        self.file.write_all(&artifact.bytes)?;
+        let length = self.write_region(offset, RegionKind::Index, &artifact.bytes)?;
         self.file.flush()?;
-        self.data_end = offset + artifact.bytes.len() as u64;
+        self.data_end = offset + length;
 
-        let common = SegmentCommon::new(
-            segment_id,
-            offset,
-            artifact.bytes.len() as u64,
-            artifact.checksum,
-        );
+        let common = SegmentCommon::new(segment_id, offset, length, artifact.checksum);
         Ok(crate::types::TemporalSegmentDescriptor::from_common(
             common,
             artifact.entry_count,
@@ -517,17 +497,11 @@ impl Memvid {
         }
 
         let offset = self.data_end;
-        self.file.seek(SeekFrom::Start(offset))?;
-        self.file.write_all(&artifact.bytes)?;
+        let length = self.write_region(offset, RegionKind::Index, &artifact.bytes)?;
         self.file.flush()?;
-        self.data_end = offset + artifact.bytes.len() as u64;
+        self.data_end = offset + length;
 
-        let common = SegmentCommon::new(
-            segment_id,
-            offset,
-            artifact.bytes.len() as u64,
-            artifact.checksum,
-        );
+        let common = SegmentCommon::new(segment_id, offset, length, artifact.checksum);
         Ok(TantivySegmentDescriptor::from_common(
             common,
             artifact.path.clone(),
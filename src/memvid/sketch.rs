@@ -10,6 +10,9 @@
 //! - `find_sketch_candidates`: Find candidate frames matching a query
 //! - `sketch_stats`: Get statistics about the sketch track
 
+use std::sync::Arc;
+
+use crate::error::Result;
 use crate::memvid::lifecycle::Memvid;
 use crate::types::{
     DEFAULT_HAMMING_THRESHOLD, FrameId, QuerySketch, SketchEntry, SketchTrack, SketchTrackStats,
@@ -75,7 +78,7 @@ impl Memvid {
     /// Get a mutable reference to the sketch track.
     pub fn sketches_mut(&mut self) -> &mut SketchTrack {
         self.dirty = true;
-        &mut self.sketch_track
+        Arc::make_mut(&mut self.sketch_track)
     }
 
     /// Check if the sketch track has any entries.
@@ -106,7 +109,7 @@ impl Memvid {
         variant: SketchVariant,
     ) -> SketchEntry {
         let entry = generate_sketch(frame_id, text, variant, None);
-        self.sketch_track.insert(entry.clone());
+        Arc::make_mut(&mut self.sketch_track).insert(entry.clone());
         self.dirty = true;
         entry
     }
@@ -121,23 +124,22 @@ impl Memvid {
     ///
     /// # Returns
     /// Number of new sketches generated.
-    pub fn build_all_sketches(&mut self, variant: SketchVariant) -> usize {
+    pub fn build_all_sketches(&mut self, variant: SketchVariant) -> Result<usize> {
         let mut count = 0;
 
         // Collect frames that need sketches
-        let frames_to_sketch: Vec<(FrameId, String)> = self
-            .toc
-            .frames
-            .iter()
-            .filter(|f| f.status == crate::types::FrameStatus::Active)
-            .filter(|f| self.sketch_track.get(f.id).is_none())
-            .filter_map(|f| {
-                f.search_text
-                    .clone()
-                    .filter(|t| !t.is_empty())
-                    .map(|text| (f.id, text))
-            })
-            .collect();
+        let mut frames_to_sketch: Vec<(FrameId, String)> = Vec::new();
+        for frame in self.toc.frames.try_iter() {
+            let f = frame?;
+            if f.status != crate::types::FrameStatus::Active
+                || self.sketch_track.get(f.id).is_some()
+            {
+                continue;
+            }
+            if let Some(text) = f.search_text.clone().filter(|t| !t.is_empty()) {
+                frames_to_sketch.push((f.id, text));
+            }
+        }
 
         for (frame_id, text) in frames_to_sketch {
             self.insert_sketch(frame_id, &text, variant);
@@ -148,7 +150,7 @@ impl Memvid {
             self.dirty = true;
         }
 
-        count
+        Ok(count)
     }
 
     /// Find candidate frames matching a query using sketch filtering.
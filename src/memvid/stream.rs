@@ -0,0 +1,237 @@
+//! Streaming puts for payloads too large to hold in memory.
+//!
+//! [`Memvid::put_reader`] splits the source into content-defined chunks as it reads, hashing
+//! the whole payload and writing each new chunk (compressed when that helps) to the blob
+//! store. Text extraction, tagging and search text only see the first
+//! [`STREAM_SAMPLE_BYTES`] of the payload. Reads go through [`BlobReader`](crate::BlobReader)
+//! one chunk at a time.
+
+use std::io::{self, Cursor, Read};
+
+use blake3::Hasher;
+
+use crate::cdc;
+use crate::error::{MemvidError, Result};
+use crate::memvid::lifecycle::Memvid;
+use crate::memvid::mutation::StreamedPayload;
+use crate::types::blob_store::encode_chunk_recipe;
+use crate::types::{BlobChunk, PutOptions};
+
+/// Bytes read ahead for extraction; shorter payloads are put as a whole.
+pub const STREAM_SAMPLE_BYTES: usize = 4 * 1024 * 1024;
+
+impl Memvid {
+    /// Append the payload `reader` yields without holding it in memory.
+    ///
+    /// `len_hint` is checked against the quotas before anything is read. Payloads shorter
+    /// than [`STREAM_SAMPLE_BYTES`] go through [`Memvid::put_bytes_with_options`]; longer
+    /// ones are stored as a `Chunked` frame whose new chunks are written past the committed
+    /// end of the file and logged in the WAL ahead of the frame, so the commit that
+    /// publishes the frame publishes them too. Like other puts, the frame is visible after
+    /// the next commit.
+    pub fn put_reader<R: Read>(
+        &mut self,
+        mut reader: R,
+        len_hint: Option<u64>,
+        options: PutOptions,
+    ) -> Result<u64> {
+        self.ensure_mutation_allowed()?;
+        if let Some(len) = len_hint {
+            self.enforce_quotas(len, options.track.as_deref())?;
+        }
+
+        let mut sample = Vec::new();
+        (&mut reader)
+            .take(STREAM_SAMPLE_BYTES as u64)
+            .read_to_end(&mut sample)?;
+        if sample.len() < STREAM_SAMPLE_BYTES {
+            return self.put_bytes_with_options(&sample, options);
+        }
+
+        let mut source = Cursor::new(sample.as_slice()).chain(reader);
+        let streamed = if options.no_raw {
+            let mut hasher = Hasher::new();
+            let length = io::copy(&mut source, &mut hasher)?;
+            StreamedPayload {
+                recipe: Vec::new(),
+                checksum: *hasher.finalize().as_bytes(),
+                length,
+                stored_bytes: 0,
+            }
+        } else {
+            self.stream_blob_chunks(source)?
+        };
+        self.put_internal(
+            Some(&sample),
+            None,
+            None,
+            None,
+            options,
+            None,
+            Some(streamed),
+        )
+    }
+
+    /// Write the chunks of `source` the blob store does not hold yet after the end of the
+    /// file and log them in the WAL, leaving them to be published by the next commit.
+    fn stream_blob_chunks<R: Read>(&mut self, source: R) -> Result<StreamedPayload> {
+        let file_len = self.file.metadata()?.len();
+        let start = self.data_end.max(file_len);
+        let payload_tail = self.payload_region_end();
+        let capacity_limit = self.capacity_limit();
+        let available = capacity_limit.saturating_sub(payload_tail);
+
+        let mut hasher = Hasher::new();
+        let mut chunk_hashes = Vec::new();
+        let mut added = Vec::new();
+        let mut cursor = start;
+        let mut length = 0u64;
+        let original_data_end = self.data_end;
+        let original_footer_offset = self.header.footer_offset;
+        let result = (|| -> Result<()> {
+            for chunk in cdc::stream_chunks(source) {
+                let chunk = chunk?;
+                hasher.update(&chunk);
+                length += chunk.len() as u64;
+                let (chunk_hash, written) = self.append_blob_chunk(cursor, &chunk)?;
+                chunk_hashes.push(chunk_hash);
+                if written == 0 {
+                    continue;
+                }
+                added.push(chunk_hash);
+                cursor += written;
+                if cursor - start > available {
+                    return Err(MemvidError::CapacityExceeded {
+                        current: payload_tail,
+                        limit: capacity_limit,
+                        required: cursor - start,
+                    });
+                }
+            }
+            if added.is_empty() {
+                return Ok(());
+            }
+
+            // Keep later writes, footers included, clear of the run until it is committed.
+            self.data_end = cursor;
+            self.header.footer_offset = self.header.footer_offset.max(cursor);
+            // The record replays the chunks into the blob store, so they must be on disk
+            // before it is.
+            self.file.sync_data()?;
+            let chunks: Vec<BlobChunk> = added
+                .iter()
+                .filter_map(|chunk_hash| self.blob_store.get(chunk_hash).copied())
+                .collect();
+            self.append_wal_blob_chunks(&chunks)?;
+            Ok(())
+        })();
+        if let Err(err) = result {
+            for chunk_hash in &added {
+                self.blob_store.remove(chunk_hash);
+            }
+            self.data_end = original_data_end;
+            self.header.footer_offset = original_footer_offset;
+            self.file.set_len(file_len)?;
+            return Err(err);
+        }
+
+        Ok(StreamedPayload {
+            recipe: encode_chunk_recipe(&chunk_hashes),
+            checksum: *hasher.finalize().as_bytes(),
+            length,
+            stored_bytes: cursor - start,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::run_serial_test;
+    use crate::types::CanonicalEncoding;
+    use blake3::hash;
+    use std::io::{Seek, SeekFrom};
+    use tempfile::tempdir;
+
+    /// Hands out at most 3000 bytes per read, like a socket or pipe.
+    struct TrickleReader<'a>(&'a [u8]);
+
+    impl Read for TrickleReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let len = buf.len().min(self.0.len()).min(3000);
+            buf[..len].copy_from_slice(&self.0[..len]);
+            self.0 = &self.0[len..];
+            Ok(len)
+        }
+    }
+
+    fn options(uri: &str) -> PutOptions {
+        PutOptions::builder()
+            .uri(uri)
+            .auto_tag(false)
+            .extract_dates(false)
+            .extract_triplets(false)
+            .build()
+    }
+
+    #[test]
+    fn large_payloads_stream_into_the_blob_store() {
+        run_serial_test(|| {
+            let dir = tempdir().expect("tmp");
+            let path = dir.path().join("stream.mv2");
+            let mut rng = fastrand::Rng::with_seed(19);
+            let mut media: Vec<u8> = (0..6 * 1024 * 1024).map(|_| rng.u8(..)).collect();
+            media[0] = 0xFF;
+
+            let mut mem = Memvid::create(&path).expect("create");
+            mem.put_reader(
+                TrickleReader(&media),
+                Some(media.len() as u64),
+                options("mv2://media/clip"),
+            )
+            .expect("put reader");
+            mem.put_reader(&b"short note"[..], None, options("mv2://notes/short"))
+                .expect("put short");
+            // The chunks wait for the frame's commit, and a crash before it replays both.
+            assert_eq!(mem.frame_count(), 0);
+            let crashed = dir.path().join("crashed.mv2");
+            std::fs::copy(&path, &crashed).expect("copy crashed");
+            mem.commit().expect("commit");
+            drop(mem);
+
+            for path in [&crashed, &path] {
+                let mut mem = Memvid::open(path).expect("reopen");
+                let frame = mem.frame_by_uri("mv2://media/clip").expect("frame");
+                assert_eq!(frame.canonical_encoding, CanonicalEncoding::Chunked);
+                assert_eq!(frame.checksum, *hash(&media).as_bytes());
+                assert_eq!(frame.canonical_length, Some(media.len() as u64));
+
+                let mut reader = mem.blob_reader(frame.id).expect("blob reader");
+                assert_eq!(reader.len(), media.len() as u64);
+                let mut bytes = Vec::new();
+                reader.read_to_end(&mut bytes).expect("read");
+                assert!(bytes == media);
+
+                let middle = 3 * 1024 * 1024 + 17;
+                reader.seek(SeekFrom::Start(middle)).expect("seek");
+                let mut window = vec![0u8; 100_000];
+                reader.read_exact(&mut window).expect("read window");
+                assert!(window[..] == media[middle as usize..middle as usize + 100_000]);
+
+                let short = mem.frame_by_uri("mv2://notes/short").expect("short");
+                assert_eq!(
+                    mem.frame_canonical_payload(short.id).expect("payload"),
+                    b"short note"
+                );
+            }
+
+            // Streaming the same media again adds no chunks.
+            let mut mem = Memvid::open(&path).expect("reopen");
+            let chunks = mem.stats().expect("stats").blob_chunk_count;
+            mem.put_reader(&media[..], None, options("mv2://media/copy"))
+                .expect("put copy");
+            mem.commit().expect("commit");
+            assert_eq!(mem.stats().expect("stats").blob_chunk_count, chunks);
+        });
+    }
+}
@@ -1,6 +1,8 @@
+use std::collections::BTreeMap;
+
 use crate::error::{MemvidError, Result};
 use crate::memvid::lifecycle::Memvid;
-use crate::types::{FrameStatus, Stats, Ticket, TicketRef};
+use crate::types::{CanonicalEncoding, EncodingStats, FrameStatus, Stats, Ticket, TicketRef};
 
 impl Memvid {
     pub fn stats(&self) -> Result<Stats> {
@@ -8,22 +10,41 @@ impl Memvid {
         let mut payload_bytes = 0u64;
         let mut logical_bytes = 0u64;
         let mut active_frames = 0u64;
+        let mut encodings: BTreeMap<u8, EncodingStats> = BTreeMap::new();
 
-        for frame in self
-            .toc
-            .frames
-            .iter()
-            .filter(|frame| frame.status == FrameStatus::Active)
-        {
+        for frame in self.toc.frames.try_iter() {
+            let frame = frame?;
+            if frame.status != FrameStatus::Active {
+                continue;
+            }
             active_frames = active_frames.saturating_add(1);
             let stored = frame.payload_length;
             payload_bytes = payload_bytes.saturating_add(stored);
             if stored > 0 {
                 let logical = frame.canonical_length.unwrap_or(stored);
                 logical_bytes = logical_bytes.saturating_add(logical);
+                let entry = encodings
+                    .entry(frame.canonical_encoding.as_byte())
+                    .or_insert_with(|| EncodingStats {
+                        encoding: frame.canonical_encoding,
+                        frames: 0,
+                        payload_bytes: 0,
+                        logical_bytes: 0,
+                    });
+                entry.frames += 1;
+                entry.payload_bytes = entry.payload_bytes.saturating_add(stored);
+                entry.logical_bytes = entry.logical_bytes.saturating_add(logical);
             }
         }
 
+        // Chunked frames store only their recipe; the chunks are counted once here.
+        let blob_stored_bytes = self.blob_store.stored_bytes();
+        let blob_raw_bytes = self.blob_store.raw_bytes();
+        payload_bytes = payload_bytes.saturating_add(blob_stored_bytes);
+        let chunked_logical_bytes = encodings
+            .get(&CanonicalEncoding::Chunked.as_byte())
+            .map_or(0, |entry| entry.logical_bytes);
+
         let saved_bytes = logical_bytes.saturating_sub(payload_bytes);
         let round2 = |value: f64| (value * 100.0).round() / 100.0;
         let compression_ratio_percent = if logical_bytes > 0 {
@@ -42,6 +63,11 @@ impl Memvid {
             0.0
         };
         let remaining_capacity_bytes = self.capacity_limit().saturating_sub(metadata.len());
+        let dedup_ratio = if blob_raw_bytes > 0 {
+            round2(chunked_logical_bytes as f64 / blob_raw_bytes as f64)
+        } else {
+            1.0
+        };
         let average_payload = if active_frames > 0 {
             payload_bytes / active_frames
         } else {
@@ -121,6 +147,18 @@ impl Memvid {
             time_index_bytes,
             vector_count,
             clip_image_count,
+            encodings: encodings.into_values().collect(),
+            dictionary_bytes: self
+                .toc
+                .compression
+                .dictionaries
+                .iter()
+                .map(|dictionary| dictionary.bytes_length)
+                .sum(),
+            blob_chunk_count: self.blob_store.len() as u64,
+            blob_stored_bytes,
+            blob_raw_bytes,
+            dedup_ratio,
         })
     }
 
@@ -139,7 +177,7 @@ impl Memvid {
         self.toc.ticket_ref.seq_no = ticket.seq_no;
         self.toc.ticket_ref.expires_in_secs = ticket.expires_in_secs;
 
-        self.generation = self.generation.wrapping_add(1);
+        self.begin_generation()?;
         self.rewrite_toc_footer()?;
         self.header.toc_checksum = self.toc.toc_checksum;
         crate::persist_header(&mut self.file, &self.header)?;
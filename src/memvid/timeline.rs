@@ -1,6 +1,6 @@
 //! Timeline assembly helpers for `Memvid`.
 
-use crate::io::time_index::{TimeIndexEntry, read_track as time_index_read};
+use crate::io::time_index::TimeIndexEntry;
 use crate::memvid::lifecycle::Memvid;
 #[cfg(feature = "temporal_track")]
 use crate::memvid::search::frame_ids_for_temporal_filter;
@@ -46,15 +46,12 @@ pub(crate) fn build_timeline(
     };
 
     let mut entries = if let Some(manifest) = &memvid.toc.time_index {
-        let mut indexed = time_index_read(
-            &mut memvid.file,
-            manifest.bytes_offset,
-            manifest.bytes_length,
-        )?;
+        let mut indexed = memvid.read_time_index(manifest.bytes_offset, manifest.bytes_length)?;
         // Also include ExtractedImage frames (child frames) which may not be in time index
         let indexed_ids: std::collections::HashSet<FrameId> =
             indexed.iter().map(|e| e.frame_id).collect();
-        for frame in memvid.toc.frames.iter() {
+        for frame in memvid.toc.frames.try_iter() {
+            let frame = frame?;
             if frame.status == FrameStatus::Active
                 && frame.role == FrameRole::ExtractedImage
                 && !indexed_ids.contains(&frame.id)
@@ -64,13 +61,14 @@ pub(crate) fn build_timeline(
         }
         indexed
     } else {
-        memvid
-            .toc
-            .frames
-            .iter()
-            .filter(|frame| frame.status == FrameStatus::Active)
-            .map(|frame| TimeIndexEntry::new(frame.timestamp, frame.id))
-            .collect()
+        let mut entries = Vec::new();
+        for frame in memvid.toc.frames.try_iter() {
+            let frame = frame?;
+            if frame.status == FrameStatus::Active {
+                entries.push(TimeIndexEntry::new(frame.timestamp, frame.id));
+            }
+        }
+        entries
     };
 
     #[cfg(feature = "temporal_track")]
@@ -96,7 +94,7 @@ pub(crate) fn build_timeline(
         let frame = memvid
             .toc
             .frames
-            .get(entry.frame_id as usize)
+            .try_get(entry.frame_id as usize)?
             .ok_or(MemvidError::InvalidTimeIndex {
                 reason: "frame id out of range".into(),
             })?
@@ -109,15 +107,13 @@ pub(crate) fn build_timeline(
             .uri
             .clone()
             .or_else(|| Some(crate::default_uri(frame.id)));
-        let child_frames: Vec<FrameId> = memvid
-            .toc
-            .frames
-            .iter()
-            .filter(|candidate| {
-                candidate.status == FrameStatus::Active && candidate.parent_id == Some(frame.id)
-            })
-            .map(|candidate| candidate.id)
-            .collect();
+        let mut child_frames: Vec<FrameId> = Vec::new();
+        for candidate in memvid.toc.frames.try_iter() {
+            let candidate = candidate?;
+            if candidate.status == FrameStatus::Active && candidate.parent_id == Some(frame.id) {
+                child_frames.push(candidate.id);
+            }
+        }
         #[cfg(feature = "temporal_track")]
         let temporal_info = if let Some(track) = temporal_track_snapshot.as_ref() {
             build_timeline_temporal_metadata(memvid, track, &frame)?
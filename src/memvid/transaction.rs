@@ -0,0 +1,255 @@
+//! Atomic groups of puts, deletes and memory cards.
+//!
+//! [`Memvid::begin`] writes a begin marker to the WAL and every record a [`Transaction`]
+//! appends lands after it. [`Transaction::commit`] appends the transaction's memory cards and
+//! a commit marker before committing; [`Transaction::rollback`] appends an abort marker.
+//! Replaying the WAL, whether in a commit or in recovery after a crash, drops every record
+//! of a group that has no commit marker, so a transaction is applied entirely or not at all.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use crate::error::Result;
+use crate::memvid::lifecycle::Memvid;
+use crate::memvid::mutation::WalGroupMarker;
+use crate::table::{ExtractedTable, store_table};
+use crate::types::{FrameId, MemoriesTrack, MemoryCard, PutOptions};
+
+/// Mutations that become visible together on [`Transaction::commit`]. Dropping an
+/// unfinished transaction rolls it back.
+pub struct Transaction<'a> {
+    mem: &'a mut Memvid,
+    cards: Vec<MemoryCard>,
+    /// State the puts change in memory before they are committed, restored on rollback.
+    pending_frame_inserts: u64,
+    pending_track_bytes: BTreeMap<String, u64>,
+    pending_versions: HashMap<FrameId, Option<FrameId>>,
+    memories_track: Arc<MemoriesTrack>,
+    enrichment_tasks: usize,
+    /// The commit marker is in the WAL.
+    sealed: bool,
+    finished: bool,
+}
+
+impl Memvid {
+    /// Start a transaction. Until it finishes, puts and deletes go through it and
+    /// automatic WAL checkpoints are held back.
+    pub fn begin(&mut self) -> Result<Transaction<'_>> {
+        self.ensure_mutation_allowed()?;
+        self.append_wal_marker(WalGroupMarker::Begin)?;
+        self.in_transaction = true;
+        Ok(Transaction {
+            pending_frame_inserts: self.pending_frame_inserts,
+            pending_track_bytes: self.pending_track_bytes.clone(),
+            pending_versions: self.pending_versions.clone(),
+            memories_track: self.memories_track.clone(),
+            enrichment_tasks: self.toc.enrichment_queue.tasks.len(),
+            mem: self,
+            cards: Vec::new(),
+            sealed: false,
+            finished: false,
+        })
+    }
+}
+
+impl Transaction<'_> {
+    /// Like [`Memvid::put_bytes_with_options`]. Frames are not indexed instantly; they
+    /// become searchable once the transaction commits.
+    pub fn put(&mut self, payload: &[u8], mut options: PutOptions) -> Result<u64> {
+        options.instant_index = false;
+        self.mem.put_bytes_with_options(payload, options)
+    }
+
+    /// Like [`Memvid::delete_frame`].
+    pub fn delete(&mut self, frame_id: FrameId) -> Result<u64> {
+        self.mem.delete_frame(frame_id)
+    }
+
+    /// Validate `card` and add it to the memories track when the transaction commits.
+    pub fn put_memory_card(&mut self, card: MemoryCard) -> Result<()> {
+        self.mem.check_memory_card(&card)?;
+        self.cards.push(card);
+        Ok(())
+    }
+
+    /// Like [`store_table`], returning the table's meta frame and row frames.
+    pub fn store_table(
+        &mut self,
+        table: &ExtractedTable,
+        embed_rows: bool,
+    ) -> Result<(FrameId, Vec<FrameId>)> {
+        store_table(self.mem, table, embed_rows)
+    }
+
+    /// Seal the transaction in the WAL and commit it.
+    pub fn commit(mut self) -> Result<()> {
+        if !self.sealed {
+            self.seal()?;
+        }
+        self.finished = true;
+        self.mem.in_transaction = false;
+        self.mem.commit()
+    }
+
+    /// Discard everything done through the transaction.
+    pub fn rollback(mut self) -> Result<()> {
+        self.finished = true;
+        self.abort()
+    }
+
+    /// Append the memory cards and the commit marker; from here on the transaction
+    /// survives a crash.
+    fn seal(&mut self) -> Result<()> {
+        if !self.cards.is_empty() {
+            self.mem.append_wal_memory_cards(&self.cards)?;
+        }
+        self.mem.append_wal_marker(WalGroupMarker::Commit)?;
+        self.sealed = true;
+        Ok(())
+    }
+
+    fn abort(&mut self) -> Result<()> {
+        self.mem.in_transaction = false;
+        self.mem.append_wal_marker(WalGroupMarker::Abort)?;
+        self.mem.pending_frame_inserts = self.pending_frame_inserts;
+        self.mem.pending_track_bytes = std::mem::take(&mut self.pending_track_bytes);
+        self.mem.pending_versions = std::mem::take(&mut self.pending_versions);
+        self.mem.memories_track = std::mem::take(&mut self.memories_track);
+        self.mem
+            .toc
+            .enrichment_queue
+            .tasks
+            .truncate(self.enrichment_tasks);
+        Ok(())
+    }
+}
+
+impl Drop for Transaction<'_> {
+    fn drop(&mut self) {
+        if self.finished || self.sealed {
+            return;
+        }
+        if let Err(err) = self.abort() {
+            tracing::warn!("failed to roll back dropped transaction: {err}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::run_serial_test;
+    use crate::types::{FrameStatus, MemoryCardBuilder};
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn options(uri: &str) -> PutOptions {
+        PutOptions::builder()
+            .uri(uri)
+            .auto_tag(false)
+            .extract_dates(false)
+            .extract_triplets(false)
+            .build()
+    }
+
+    #[test]
+    fn crash_before_the_commit_marker_discards_the_whole_transaction() {
+        run_serial_test(|| {
+            let dir = tempdir().expect("tmp");
+            let path = dir.path().join("tx.mv2");
+            let mut mem = Memvid::create(&path).expect("create");
+            mem.put_bytes_with_options(b"draft outline", options("mv2://docs/draft"))
+                .expect("put draft");
+            mem.commit().expect("commit");
+            let draft = mem.frame_by_uri("mv2://docs/draft").expect("draft").id;
+
+            let mut tx = mem.begin().expect("begin");
+            tx.put(b"final report", options("mv2://docs/report"))
+                .expect("put report");
+            tx.put(b"report appendix", options("mv2://docs/appendix"))
+                .expect("put appendix");
+            tx.delete(draft).expect("delete draft");
+            let card = MemoryCardBuilder::new()
+                .fact()
+                .entity("report")
+                .slot("status")
+                .value("final")
+                .source(draft, None)
+                .engine("test", "1")
+                .build(0)
+                .expect("card");
+            tx.put_memory_card(card).expect("card");
+
+            // Crash with every record but the commit marker on disk.
+            let torn = dir.path().join("torn.mv2");
+            fs::copy(&path, &torn).expect("copy torn");
+            tx.seal().expect("seal");
+            // Crash right after the commit marker.
+            let sealed = dir.path().join("sealed.mv2");
+            fs::copy(&path, &sealed).expect("copy sealed");
+            tx.commit().expect("commit tx");
+
+            let recovered = Memvid::open(&torn).expect("open torn");
+            assert_eq!(recovered.frame_count(), 1);
+            assert!(recovered.frame_by_uri("mv2://docs/report").is_err());
+            let frame = recovered.frame_by_id(draft).expect("draft");
+            assert_eq!(frame.status, FrameStatus::Active);
+            assert_eq!(recovered.memories().card_count(), 0);
+            drop(recovered);
+
+            for copy in [&sealed, &path] {
+                drop(mem);
+                mem = Memvid::open(copy).expect("open");
+                assert_eq!(mem.frame_count(), 3);
+                assert!(mem.frame_by_uri("mv2://docs/report").is_ok());
+                assert!(mem.frame_by_uri("mv2://docs/appendix").is_ok());
+                let frame = mem.frame_by_id(draft).expect("draft");
+                assert_ne!(frame.status, FrameStatus::Active);
+                assert_eq!(mem.memories().card_count(), 1);
+            }
+        });
+    }
+
+    #[test]
+    fn rolled_back_and_dropped_transactions_leave_nothing() {
+        run_serial_test(|| {
+            let dir = tempdir().expect("tmp");
+            let path = dir.path().join("tx.mv2");
+            let mut mem = Memvid::create(&path).expect("create");
+
+            let mut tx = mem.begin().expect("begin");
+            tx.put(b"abandoned", options("mv2://docs/abandoned"))
+                .expect("put");
+            tx.rollback().expect("rollback");
+            {
+                let mut tx = mem.begin().expect("begin");
+                tx.put(b"dropped", options("mv2://docs/dropped"))
+                    .expect("put");
+            }
+            let mut tx = mem.begin().expect("begin");
+            tx.put(b"kept", options("mv2://docs/kept")).expect("put");
+            tx.commit().expect("commit tx");
+            assert_eq!(mem.next_frame_id(), 1);
+            drop(mem);
+
+            let mem = Memvid::open(&path).expect("reopen");
+            assert_eq!(mem.frame_count(), 1);
+            assert!(mem.frame_by_uri("mv2://docs/kept").is_ok());
+            assert!(mem.frame_by_uri("mv2://docs/abandoned").is_err());
+        });
+    }
+
+    #[test]
+    fn wal_markers_keep_their_tags_in_every_build() {
+        use crate::memvid::mutation::{WalEntry, WalGroupMarker};
+        use bincode::serde::encode_to_vec;
+
+        let tag = |entry: WalEntry| {
+            let bytes = encode_to_vec(entry, crate::wal_config()).expect("encode");
+            u32::from_le_bytes(bytes[..4].try_into().expect("tag"))
+        };
+        assert_eq!(tag(WalEntry::Group(WalGroupMarker::Commit)), 2);
+        assert_eq!(tag(WalEntry::MemoryCards(Vec::new())), 3);
+        assert_eq!(tag(WalEntry::BlobChunks(Vec::new())), 4);
+    }
+}
@@ -0,0 +1,442 @@
+//! Streaming compaction of `.mv2` files.
+//!
+//! Vacuum copies the payloads of active frames into a temporary sibling of the memory
+//! file, rebuilds every index segment from that copy and renames it over the original.
+//! Payloads are streamed one frame at a time, and the original file is only read, so an
+//! interruption at any point leaves it exactly as the last commit wrote it.
+
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::sync::Arc;
+
+use crate::error::{MemvidError, Result};
+use crate::memvid::lifecycle::Memvid;
+use crate::types::{
+    BlobChunk, BlobStore, ChunkHash, FrameStatus, VacuumPhase, VacuumProgress, VacuumReport,
+};
+
+#[cfg(test)]
+thread_local! {
+    /// Number of vacuum checkpoints to pass before failing; `None` disables injection.
+    static FAIL_AFTER: std::cell::Cell<Option<usize>> = const { std::cell::Cell::new(None) };
+}
+
+/// Marks a point where the vacuum may be interrupted. Tests use it to inject failures.
+fn checkpoint() -> Result<()> {
+    #[cfg(test)]
+    {
+        let fire = FAIL_AFTER.with(|remaining| match remaining.get() {
+            Some(0) => true,
+            Some(n) => {
+                remaining.set(Some(n - 1));
+                false
+            }
+            None => false,
+        });
+        if fire {
+            return Err(MemvidError::Io {
+                source: std::io::Error::other("injected vacuum failure"),
+                path: None,
+            });
+        }
+    }
+    Ok(())
+}
+
+impl Memvid {
+    /// Compact the memory by dropping payloads of superseded and deleted frames.
+    ///
+    /// Pending WAL records are committed first. See [`Memvid::vacuum_with_progress`].
+    pub fn vacuum(&mut self) -> Result<VacuumReport> {
+        self.vacuum_with_progress(|_| {})
+    }
+
+    /// Compact the memory, reporting progress after every frame and phase change.
+    ///
+    /// The compacted file is written next to the original and atomically renamed over it
+    /// once every segment has been rebuilt; on error the original is left untouched.
+    pub fn vacuum_with_progress<F>(&mut self, mut progress: F) -> Result<VacuumReport>
+    where
+        F: FnMut(VacuumProgress),
+    {
+        self.commit()?;
+
+        // Everything the rebuild carries over must be resident before the file is swapped.
+        self.toc.frames.load_all()?;
+        self.ensure_vec_index()?;
+        if self.clip_enabled {
+            self.ensure_clip_index()?;
+        }
+
+        let referenced = self.referenced_blob_chunks()?;
+        let bytes_before = self.file.metadata()?.len();
+        let payload_start = self.header.wal_offset + self.header.wal_size;
+        let source = self.file.try_clone()?;
+        let mut report = VacuumReport {
+            frames_retained: 0,
+            frames_purged: 0,
+            payload_bytes: 0,
+            bytes_before,
+            bytes_after: bytes_before,
+            bytes_reclaimed: 0,
+            blob_chunks_retained: 0,
+            blob_chunks_purged: 0,
+        };
+
+        self.with_staging(Some(payload_start), |mem| {
+            mem.vacuum_into_staging(
+                &source,
+                payload_start,
+                &referenced,
+                &mut progress,
+                &mut report,
+            )
+        })?;
+
+        report.bytes_after = self.file.metadata()?.len();
+        report.bytes_reclaimed = bytes_before.saturating_sub(report.bytes_after);
+        tracing::info!(
+            frames_retained = report.frames_retained,
+            frames_purged = report.frames_purged,
+            bytes_reclaimed = report.bytes_reclaimed,
+            blob_chunks_purged = report.blob_chunks_purged,
+            "vacuum completed"
+        );
+        Ok(report)
+    }
+
+    /// Fill the staging file (already seeded with the header and WAL region) from `source`.
+    fn vacuum_into_staging(
+        &mut self,
+        source: &File,
+        payload_start: u64,
+        referenced: &HashSet<ChunkHash>,
+        progress: &mut dyn FnMut(VacuumProgress),
+        report: &mut VacuumReport,
+    ) -> Result<()> {
+        checkpoint()?;
+        let mut reader = source;
+        let source_len = source.metadata()?.len();
+        let frames_total = self.toc.frames.len() as u64;
+        let mut frames_done = 0u64;
+        let mut cursor = payload_start;
+        // Frames sharing a stored payload keep sharing it in the compacted copy.
+        let mut relocated: HashMap<(u64, u64), u64> = HashMap::new();
+
+        self.file.set_len(payload_start)?;
+        self.file.seek(SeekFrom::Start(payload_start))?;
+        for frame in self.toc.frames.try_iter_mut()? {
+            if frame.status != FrameStatus::Active {
+                if frame.payload_length != 0 {
+                    report.frames_purged += 1;
+                }
+                frame.payload_offset = 0;
+                frame.payload_length = 0;
+            } else if frame.payload_length == 0 {
+                frame.payload_offset = cursor;
+            } else {
+                let key = (frame.payload_offset, frame.payload_length);
+                if let Some(&offset) = relocated.get(&key) {
+                    frame.payload_offset = offset;
+                } else {
+                    let end = frame.payload_offset.checked_add(frame.payload_length);
+                    if frame.payload_offset < payload_start
+                        || end.is_none_or(|end| end > source_len)
+                    {
+                        return Err(MemvidError::InvalidFrame {
+                            frame_id: frame.id,
+                            reason: "payload outside data region",
+                        });
+                    }
+                    reader.seek(SeekFrom::Start(frame.payload_offset))?;
+                    let copied = std::io::copy(
+                        &mut (&mut reader).take(frame.payload_length),
+                        &mut self.file,
+                    )?;
+                    if copied != frame.payload_length {
+                        return Err(MemvidError::InvalidFrame {
+                            frame_id: frame.id,
+                            reason: "payload truncated",
+                        });
+                    }
+                    relocated.insert(key, cursor);
+                    frame.payload_offset = cursor;
+                    report.payload_bytes += copied;
+                    cursor += copied;
+                }
+                report.frames_retained += 1;
+            }
+
+            frames_done += 1;
+            checkpoint()?;
+            progress(VacuumProgress {
+                phase: VacuumPhase::CopyPayloads,
+                frames_done,
+                frames_total,
+                bytes_copied: report.payload_bytes,
+            });
+        }
+
+        // Chunks referenced by the recipes above follow them; the rest are dropped.
+        let mut blob_store = BlobStore::default();
+        for chunk in std::mem::take(&mut self.blob_store).by_offset() {
+            if !referenced.contains(&chunk.hash) {
+                report.blob_chunks_purged += 1;
+                continue;
+            }
+            reader.seek(SeekFrom::Start(chunk.offset))?;
+            let copied =
+                std::io::copy(&mut (&mut reader).take(chunk.stored_length), &mut self.file)?;
+            if copied != chunk.stored_length {
+                return Err(MemvidError::InvalidBlobStore {
+                    reason: "chunk truncated".into(),
+                });
+            }
+            blob_store.insert(BlobChunk {
+                offset: cursor,
+                ..chunk
+            });
+            report.payload_bytes += copied;
+            cursor += copied;
+        }
+        report.blob_chunks_retained = blob_store.len() as u64;
+        blob_store.mark_dirty();
+        self.blob_store = blob_store;
+        // The change log segment was cut off with the rest of the old tail.
+        self.change_log.mark_dirty();
+
+        // Dictionaries decode the payloads above; keep them right after them.
+        for dictionary in &mut self.toc.compression.dictionaries {
+            reader.seek(SeekFrom::Start(dictionary.bytes_offset))?;
+            let copied = std::io::copy(
+                &mut (&mut reader).take(dictionary.bytes_length),
+                &mut self.file,
+            )?;
+            if copied != dictionary.bytes_length {
+                return Err(MemvidError::InvalidCompressionDictionary {
+                    reason: "dictionary segment truncated".into(),
+                });
+            }
+            dictionary.bytes_offset = cursor;
+            cursor += copied;
+        }
+
+        self.data_end = cursor;
+        self.header.footer_offset = cursor;
+
+        self.discard_index_segments()?;
+
+        progress(VacuumProgress {
+            phase: VacuumPhase::RebuildIndexes,
+            frames_done,
+            frames_total,
+            bytes_copied: report.payload_bytes,
+        });
+        self.rebuild_indexes(&[])?;
+        checkpoint()?;
+
+        // Replay sessions live after the indexes; carry the segment over verbatim.
+        if let Some(manifest) = self.toc.replay_manifest.as_mut() {
+            let offset = self.header.footer_offset;
+            reader.seek(SeekFrom::Start(manifest.segment_offset))?;
+            self.file.seek(SeekFrom::Start(offset))?;
+            let copied = std::io::copy(
+                &mut (&mut reader).take(manifest.segment_size),
+                &mut self.file,
+            )?;
+            if copied != manifest.segment_size {
+                return Err(MemvidError::InvalidToc {
+                    reason: "replay segment truncated".into(),
+                });
+            }
+            manifest.segment_offset = offset;
+            self.header.footer_offset = offset + copied;
+        }
+        self.persist_sketch_track()?;
+
+        self.rewrite_toc_footer()?;
+        self.header.toc_checksum = self.toc.toc_checksum;
+        crate::persist_header(&mut self.file, &self.header)?;
+        checkpoint()?;
+
+        progress(VacuumProgress {
+            phase: VacuumPhase::Publish,
+            frames_done,
+            frames_total,
+            bytes_copied: report.payload_bytes,
+        });
+        Ok(())
+    }
+
+    /// Forget every index segment, the in-memory Tantivy engine and deleted vectors so
+    /// the next `rebuild_indexes` writes them all afresh from the frame table.
+    pub(crate) fn discard_index_segments(&mut self) -> Result<()> {
+        // Look up removed frames first so a page that fails to load changes nothing.
+        let stale_clip = match self.clip_index.as_ref() {
+            Some(index) => {
+                self.inactive_frames(index.entries().map(|(frame_id, _, _)| frame_id))?
+            }
+            None => Vec::new(),
+        };
+        let stale_vec = match self.vec_index.as_ref() {
+            Some(index) => self.inactive_frames(index.entries().map(|(frame_id, _)| frame_id))?,
+            None => Vec::new(),
+        };
+        self.toc.time_index = None;
+        self.toc.segments.clear();
+        self.toc.indexes.lex_segments.clear();
+        self.toc.segment_catalog.lex_segments.clear();
+        self.toc.segment_catalog.vec_segments.clear();
+        self.toc.segment_catalog.time_segments.clear();
+        #[cfg(feature = "temporal_track")]
+        {
+            self.toc.temporal_track = None;
+            self.toc.segment_catalog.temporal_segments.clear();
+        }
+        #[cfg(feature = "lex")]
+        {
+            self.toc.segment_catalog.tantivy_segments.clear();
+        }
+        #[cfg(feature = "parallel_segments")]
+        {
+            self.toc.segment_catalog.index_segments.clear();
+        }
+
+        // Clear in-memory Tantivy state so it is rebuilt from the frame payloads.
+        #[cfg(feature = "lex")]
+        {
+            self.tantivy = None;
+            self.tantivy_dirty = false;
+        }
+
+        if let Some(mut clip_index) = self.clip_index.take() {
+            for frame_id in stale_clip {
+                Arc::make_mut(&mut clip_index).remove(frame_id);
+            }
+            self.clip_index = Some(clip_index);
+        }
+
+        // Deleted vectors only keep their slot for the HNSW graph; drop them and
+        // let the rebuild start a fresh graph.
+        if let Some(mut vec_index) = self.vec_index.take() {
+            let index = Arc::make_mut(&mut vec_index);
+            for frame_id in stale_vec {
+                index.remove(frame_id);
+            }
+            index.purge_deleted();
+            self.vec_index = Some(vec_index);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fast_options, run_serial_test};
+    use tempfile::tempdir;
+
+    fn seed(path: &std::path::Path) -> Vec<(u64, String)> {
+        let mut mem = Memvid::create(path).expect("create");
+        let mut kept = Vec::new();
+        for idx in 0..24u64 {
+            let text = format!("vacuum payload {idx} {}", "lorem ipsum ".repeat(40));
+            let seq = mem
+                .put_bytes_with_options(text.as_bytes(), fast_options())
+                .expect("put");
+            if idx % 3 != 0 {
+                kept.push((seq, text));
+            }
+        }
+        mem.commit().expect("commit");
+        for idx in (0..24u64).step_by(3) {
+            mem.delete_frame(idx).expect("delete");
+        }
+        mem.commit().expect("commit");
+        kept
+    }
+
+    fn assert_contents(path: &std::path::Path, kept: &[(u64, String)]) {
+        let mut mem = Memvid::open(path).expect("reopen");
+        assert_eq!(mem.frame_count(), 24);
+        for (idx, (_, text)) in (0..24u64).filter(|idx| idx % 3 != 0).zip(kept) {
+            let payload = mem.frame_canonical_payload(idx).expect("payload");
+            assert_eq!(payload, text.as_bytes());
+        }
+    }
+
+    #[test]
+    fn vacuum_reclaims_deleted_payloads() {
+        run_serial_test(|| {
+            let dir = tempdir().expect("tmp");
+            let path = dir.path().join("vacuum.mv2");
+            let kept = seed(&path);
+
+            let mut phases = Vec::new();
+            let report = {
+                let mut mem = Memvid::open(&path).expect("open");
+                mem.vacuum_with_progress(|update| {
+                    if phases.last() != Some(&update.phase) {
+                        phases.push(update.phase);
+                    }
+                })
+                .expect("vacuum")
+            };
+
+            assert_eq!(
+                phases,
+                [
+                    VacuumPhase::CopyPayloads,
+                    VacuumPhase::RebuildIndexes,
+                    VacuumPhase::Publish
+                ]
+            );
+            assert_eq!(report.frames_retained, 16);
+            assert_eq!(report.frames_purged, 8);
+            assert!(report.bytes_reclaimed > 0);
+            assert_eq!(
+                report.bytes_after,
+                std::fs::metadata(&path).expect("metadata").len()
+            );
+            assert_contents(&path, &kept);
+        });
+    }
+
+    #[test]
+    fn vacuum_interruption_preserves_original() {
+        run_serial_test(|| {
+            let dir = tempdir().expect("tmp");
+            let path = dir.path().join("vacuum.mv2");
+            let kept = seed(&path);
+            // Opening for write commits nothing new, so this is the state every failed run
+            // must leave behind.
+            drop(Memvid::open(&path).expect("open"));
+            let original = std::fs::read(&path).expect("read original");
+
+            let mut failures = 0;
+            loop {
+                FAIL_AFTER.with(|remaining| remaining.set(Some(failures)));
+                let result = Memvid::open(&path).expect("open").vacuum();
+                FAIL_AFTER.with(|remaining| remaining.set(None));
+                if result.is_ok() {
+                    break;
+                }
+
+                assert_eq!(
+                    std::fs::read(&path).expect("read"),
+                    original,
+                    "interruption at checkpoint {failures} modified the original"
+                );
+                let siblings = std::fs::read_dir(dir.path()).expect("list").count();
+                assert_eq!(siblings, 1, "staging file left behind");
+                assert_contents(&path, &kept);
+                failures += 1;
+            }
+
+            assert!(failures > 24, "every frame copy should be interruptible");
+            assert!(std::fs::metadata(&path).expect("metadata").len() < original.len() as u64);
+            assert_contents(&path, &kept);
+        });
+    }
+}
@@ -0,0 +1,223 @@
+//! Frame versions: conditional updates and deletes, history and reverts.
+//!
+//! Updating a frame appends a successor that `supersedes` it and marks the frame
+//! `superseded_by` the successor, so the versions of a document form a chain whose newest
+//! frame, the head, is the only active one. [`Memvid::update_frame_if`] and
+//! [`Memvid::delete_frame_if`] apply only while the head is still the version the caller
+//! read, counting updates and deletes that are not committed yet.
+
+use crate::error::{MemvidError, Result};
+use crate::memvid::lifecycle::Memvid;
+use crate::types::{Frame, FrameId, FrameVersion, PutOptions};
+
+impl Memvid {
+    /// [`Memvid::update_frame`] on the newest version of `frame_id`, provided it is still
+    /// `expected`; otherwise fails with [`MemvidError::Conflict`] naming the newest version.
+    pub fn update_frame_if(
+        &mut self,
+        frame_id: FrameId,
+        expected: FrameVersion,
+        payload: Option<Vec<u8>>,
+        options: PutOptions,
+        embedding: Option<Vec<f32>>,
+    ) -> Result<u64> {
+        let head = self.expect_version(frame_id, expected)?;
+        self.update_frame(head, payload, options, embedding)
+    }
+
+    /// [`Memvid::delete_frame`] on the newest version of `frame_id`, provided it is still
+    /// `expected`; otherwise fails with [`MemvidError::Conflict`] naming the newest version.
+    pub fn delete_frame_if(&mut self, frame_id: FrameId, expected: FrameVersion) -> Result<u64> {
+        let head = self.expect_version(frame_id, expected)?;
+        self.delete_frame(head)
+    }
+
+    /// Every committed version of the document `frame_id` belongs to, oldest first.
+    pub fn frame_history(&self, frame_id: FrameId) -> Result<Vec<Frame>> {
+        let limit = self.toc.frames.len();
+        let mut oldest = self.frame_by_id(frame_id)?;
+        for _ in 0..limit {
+            let Some(previous) = oldest.supersedes else {
+                break;
+            };
+            oldest = self.frame_by_id(previous)?;
+        }
+        let mut history = vec![oldest];
+        while let Some(next) = history.last().and_then(|frame| frame.superseded_by) {
+            if history.len() >= limit {
+                return Err(MemvidError::InvalidFrame {
+                    frame_id,
+                    reason: "supersede chain loops",
+                });
+            }
+            history.push(self.frame_by_id(next)?);
+        }
+        Ok(history)
+    }
+
+    /// Make version `to` current again by appending a copy of it that supersedes the
+    /// newest version. `frame_id` may be any version of the same document.
+    pub fn revert_frame(&mut self, frame_id: FrameId, to: FrameId) -> Result<u64> {
+        let history = self.frame_history(frame_id)?;
+        let Some(target) = history.iter().find(|frame| frame.id == to).cloned() else {
+            return Err(MemvidError::InvalidFrame {
+                frame_id: to,
+                reason: "frame is not a version of the reverted frame",
+            });
+        };
+        let head = history.last().map_or(frame_id, |frame| frame.id);
+        let head = self.expect_version(frame_id, FrameVersion::Head(head))?;
+        if head == to {
+            return Err(MemvidError::InvalidFrame {
+                frame_id: to,
+                reason: "frame is already the newest version",
+            });
+        }
+        self.replace_frame(head, &target, None, PutOptions::default(), None)
+    }
+
+    /// The newest version of `frame_id`, if it is `expected` and has no uncommitted change.
+    fn expect_version(&self, frame_id: FrameId, expected: FrameVersion) -> Result<FrameId> {
+        let (head, frame) = self.version_head(frame_id)?;
+        let matches = frame.is_some_and(|frame| match expected {
+            FrameVersion::Head(id) => frame.id == id,
+            FrameVersion::Checksum(checksum) => frame.checksum == checksum,
+        });
+        if !matches {
+            return Err(MemvidError::Conflict { frame_id, head });
+        }
+        Ok(head)
+    }
+
+    /// The newest version of `frame_id` including uncommitted updates and deletes, with
+    /// its frame unless it was changed since the last commit.
+    fn version_head(&self, frame_id: FrameId) -> Result<(FrameId, Option<Frame>)> {
+        let mut frame = self.frame_by_id(frame_id)?;
+        for _ in 0..=self.toc.frames.len() {
+            match self.pending_versions.get(&frame.id) {
+                Some(Some(successor)) => return Ok((*successor, None)),
+                Some(None) => return Ok((frame.id, None)),
+                None => {}
+            }
+            match frame.superseded_by {
+                Some(next) => frame = self.frame_by_id(next)?,
+                None => return Ok((frame.id, Some(frame))),
+            }
+        }
+        Err(MemvidError::InvalidFrame {
+            frame_id,
+            reason: "supersede chain loops",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::run_serial_test;
+    use crate::types::FrameStatus;
+    use tempfile::tempdir;
+
+    fn options() -> PutOptions {
+        PutOptions::builder()
+            .uri("mv2://notes/plan")
+            .auto_tag(false)
+            .extract_dates(false)
+            .extract_triplets(false)
+            .build()
+    }
+
+    #[test]
+    fn stale_versions_conflict_with_the_newest_one() {
+        run_serial_test(|| {
+            let dir = tempdir().expect("tmp");
+            let path = dir.path().join("versions.mv2");
+            let mut mem = Memvid::create(&path).expect("create");
+            mem.put_bytes_with_options(b"plan: ship on friday", options())
+                .expect("put");
+            mem.commit().expect("commit");
+            let read = mem.frame_by_uri("mv2://notes/plan").expect("frame");
+
+            // Two writers start from the same read; the second one loses.
+            mem.update_frame_if(
+                read.id,
+                FrameVersion::Checksum(read.checksum),
+                Some(b"plan: ship on monday".to_vec()),
+                options(),
+                None,
+            )
+            .expect("first update");
+            let err = mem
+                .update_frame_if(
+                    read.id,
+                    FrameVersion::Checksum(read.checksum),
+                    Some(b"plan: cancel".to_vec()),
+                    options(),
+                    None,
+                )
+                .expect_err("second update");
+            assert!(matches!(err, MemvidError::Conflict { head: 1, .. }));
+            mem.commit().expect("commit");
+
+            let err = mem
+                .delete_frame_if(read.id, FrameVersion::Head(read.id))
+                .expect_err("stale delete");
+            assert!(matches!(err, MemvidError::Conflict { head: 1, .. }));
+            mem.delete_frame_if(read.id, FrameVersion::Head(1))
+                .expect("delete head");
+            let err = mem
+                .delete_frame_if(1, FrameVersion::Head(1))
+                .expect_err("pending delete");
+            assert!(matches!(err, MemvidError::Conflict { head: 1, .. }));
+            mem.commit().expect("commit");
+            assert_eq!(
+                mem.frame_by_id(1).expect("frame").status,
+                FrameStatus::Deleted
+            );
+        });
+    }
+
+    #[test]
+    fn reverts_append_an_older_version_to_the_history() {
+        run_serial_test(|| {
+            let dir = tempdir().expect("tmp");
+            let path = dir.path().join("versions.mv2");
+            let mut mem = Memvid::create(&path).expect("create");
+            mem.put_bytes_with_options(b"draft one", options())
+                .expect("put");
+            mem.commit().expect("commit");
+            for text in ["draft two", "draft three"] {
+                let head = mem.frame_by_uri("mv2://notes/plan").expect("head").id;
+                mem.update_frame(head, Some(text.as_bytes().to_vec()), options(), None)
+                    .expect("update");
+                mem.commit().expect("commit");
+            }
+
+            let ids: Vec<_> = mem
+                .frame_history(1)
+                .expect("history")
+                .iter()
+                .map(|frame| frame.id)
+                .collect();
+            assert_eq!(ids, [0, 1, 2]);
+
+            mem.revert_frame(1, 0).expect("revert");
+            mem.commit().expect("commit");
+            let history = mem.frame_history(0).expect("history");
+            assert_eq!(history.len(), 4);
+            let head = history.last().expect("head");
+            assert_eq!(head.status, FrameStatus::Active);
+            assert_eq!(head.supersedes, Some(2));
+            assert_eq!(head.checksum, history[0].checksum);
+            assert_eq!(
+                mem.frame_canonical_payload(head.id).expect("payload"),
+                b"draft one"
+            );
+            assert_eq!(
+                mem.frame_by_uri("mv2://notes/plan").expect("current").id,
+                head.id
+            );
+            assert!(mem.revert_frame(0, head.id).is_err());
+        });
+    }
+}
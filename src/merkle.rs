@@ -0,0 +1,173 @@
+//! Merkle tree over frame checksums, committed to by [`Toc::merkle_root`](crate::types::Toc).
+//!
+//! Leaves are `BLAKE3(0x00 || frame_id_le || checksum)` in frame id order, covering every
+//! frame in the table whatever its status. Interior nodes are `BLAKE3(0x01 || left || right)`;
+//! an unpaired node at the end of a level is promoted unchanged. An empty table has an
+//! all-zero root.
+
+use blake3::{Hasher, hash};
+
+use crate::error::Result;
+use crate::frame_table::FrameTable;
+use crate::memvid::mutation::prepare_canonical_payload;
+use crate::types::{CanonicalEncoding, Frame, FrameId, FrameProof};
+
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+
+/// Leaf hash of `frame`.
+pub(crate) fn frame_leaf(frame: &Frame) -> [u8; 32] {
+    leaf_hash(frame.id, &frame.checksum)
+}
+
+fn leaf_hash(frame_id: FrameId, checksum: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Hasher::new();
+    hasher.update(&[LEAF_PREFIX]);
+    hasher.update(&frame_id.to_le_bytes());
+    hasher.update(checksum);
+    *hasher.finalize().as_bytes()
+}
+
+fn node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Hasher::new();
+    hasher.update(&[NODE_PREFIX]);
+    hasher.update(left);
+    hasher.update(right);
+    *hasher.finalize().as_bytes()
+}
+
+/// Leaf hashes of every frame in `frames`, loading pages of a paged table as needed.
+pub(crate) fn frame_leaves(frames: &FrameTable) -> Result<Vec<[u8; 32]>> {
+    let mut leaves = Vec::with_capacity(frames.len());
+    for index in 0..frames.len() {
+        if let Some(frame) = frames.try_get(index)? {
+            leaves.push(frame_leaf(frame));
+        }
+    }
+    Ok(leaves)
+}
+
+fn next_level(level: &[[u8; 32]]) -> Vec<[u8; 32]> {
+    level
+        .chunks(2)
+        .map(|pair| match pair {
+            [left, right] => node_hash(left, right),
+            [single] => *single,
+            _ => unreachable!("chunks(2) yields one or two nodes"),
+        })
+        .collect()
+}
+
+/// Root of the tree over `leaves`.
+///
+/// Splitting the leaves into runs of a power-of-two length and taking this root over
+/// the roots of the runs gives the same result, which is how frame table pages record
+/// their own subtree roots and a commit only hashes the pages it rewrites.
+pub(crate) fn merkle_root(leaves: &[[u8; 32]]) -> [u8; 32] {
+    if leaves.is_empty() {
+        return [0u8; 32];
+    }
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        level = next_level(&level);
+    }
+    level[0]
+}
+
+/// Sibling hashes from leaf `index` up to the root, skipping levels where the node is
+/// promoted without a sibling.
+pub(crate) fn sibling_path(leaves: &[[u8; 32]], mut index: usize) -> Vec<[u8; 32]> {
+    let mut siblings = Vec::new();
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        let sibling = index ^ 1;
+        if sibling < level.len() {
+            siblings.push(level[sibling]);
+        }
+        level = next_level(&level);
+        index /= 2;
+    }
+    siblings
+}
+
+/// Check `proof` against a trusted `root` without access to the memory file.
+///
+/// `payload` is either the frame's stored payload or, for text frames, its canonical text;
+/// canonical text is re-encoded the way `put` stores it before being compared with the
+/// checksum in the proof. `Chunked` frames are checked against their raw bytes. Frames encoded with a trained dictionary can only be checked
+/// against their stored payload.
+#[must_use]
+pub fn verify_frame_proof(root: &[u8; 32], proof: &FrameProof, payload: &[u8]) -> bool {
+    let matches_checksum = *hash(payload).as_bytes() == proof.checksum
+        || (proof.encoding == CanonicalEncoding::Zstd
+            && prepare_canonical_payload(payload).is_ok_and(|(stored, encoding, _)| {
+                encoding == CanonicalEncoding::Zstd && *hash(&stored).as_bytes() == proof.checksum
+            }))
+        || (proof.encoding == CanonicalEncoding::Lz4
+            && *hash(&lz4_flex::compress_prepend_size(payload)).as_bytes() == proof.checksum);
+    if !matches_checksum || proof.frame_id >= proof.leaf_count {
+        return false;
+    }
+
+    let mut node = leaf_hash(proof.frame_id, &proof.checksum);
+    let mut index = proof.frame_id;
+    let mut width = proof.leaf_count;
+    let mut siblings = proof.siblings.iter();
+    while width > 1 {
+        let sibling = index ^ 1;
+        if sibling < width {
+            let Some(hash) = siblings.next() else {
+                return false;
+            };
+            node = if index % 2 == 0 {
+                node_hash(&node, hash)
+            } else {
+                node_hash(hash, &node)
+            };
+        }
+        index /= 2;
+        width = width.div_ceil(2);
+    }
+    siblings.next().is_none() && node == *root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaves(count: u64) -> Vec<[u8; 32]> {
+        (0..count)
+            .map(|id| leaf_hash(id, hash(&id.to_le_bytes()).as_bytes()))
+            .collect()
+    }
+
+    #[test]
+    fn subtree_roots_combine_into_the_full_root() {
+        for count in [1u64, 4, 5, 8, 13] {
+            let leaves = leaves(count);
+            let roots: Vec<_> = leaves.chunks(4).map(merkle_root).collect();
+            assert_eq!(merkle_root(&roots), merkle_root(&leaves), "{count}");
+        }
+    }
+
+    #[test]
+    fn proofs_verify_for_every_leaf_of_uneven_trees() {
+        for count in 1..=9u64 {
+            let leaves = leaves(count);
+            let root = merkle_root(&leaves);
+            for id in 0..count {
+                let payload = id.to_le_bytes();
+                let proof = FrameProof {
+                    frame_id: id,
+                    leaf_count: count,
+                    checksum: *hash(&payload).as_bytes(),
+                    encoding: CanonicalEncoding::Plain,
+                    siblings: sibling_path(&leaves, id as usize),
+                };
+                assert!(verify_frame_proof(&root, &proof, &payload), "{id}/{count}");
+                assert!(!verify_frame_proof(&root, &proof, b"forged"));
+                assert!(!verify_frame_proof(&[7u8; 32], &proof, &payload));
+            }
+        }
+    }
+}
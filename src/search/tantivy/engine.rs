@@ -5,15 +5,19 @@ use crate::search::parser::ParsedQuery;
 use crate::types::{Frame, FrameId};
 use crate::{MemvidError, Result};
 use blake3::{Hasher, hash};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use tantivy::collector::TopDocs;
+use tantivy::directory::{Directory, RamDirectory};
 use tantivy::indexer::IndexWriter;
 use tantivy::schema::{Field, OwnedValue, Schema, TantivyDocument};
-use tantivy::{Index, IndexReader, Term, doc};
+use tantivy::{Index, IndexReader, ReloadPolicy, Term, doc};
 use tempfile::TempDir;
 
 /// Tantivy-backed search index used when the `lex` feature is enabled.
 pub struct TantivyEngine {
-    pub(super) work_dir: TempDir,
+    /// Index directory on disk; `None` for in-memory indexes of encrypted memories.
+    pub(super) work_dir: Option<Arc<TempDir>>,
     pub(super) index: Index,
     pub(super) _schema: Schema,
     pub(super) content: Field,
@@ -61,7 +65,7 @@ impl TantivyEngine {
             }
         })?;
         initialise_tokenizer(&index);
-        Self::from_parts(dir, index, schema)
+        Self::from_parts(Some(dir), index, schema)
     }
 
     pub fn open_from_dir(dir: TempDir) -> Result<Self> {
@@ -70,10 +74,36 @@ impl TantivyEngine {
         })?;
         initialise_tokenizer(&index);
         let schema = index.schema();
-        Self::from_parts(dir, index, schema)
+        Self::from_parts(Some(dir), index, schema)
     }
 
-    fn from_parts(dir: TempDir, index: Index, schema: Schema) -> Result<Self> {
+    /// Create an empty index that never touches disk.
+    pub fn create_in_memory() -> Result<Self> {
+        let schema = build_schema();
+        let index = Index::create_in_ram(schema.clone());
+        initialise_tokenizer(&index);
+        Self::from_parts(None, index, schema)
+    }
+
+    /// Open an in-memory index from the `(path, bytes)` files of a snapshot.
+    pub fn open_in_memory(files: Vec<(String, Vec<u8>)>) -> Result<Self> {
+        let directory = RamDirectory::create();
+        for (path, bytes) in files {
+            directory
+                .atomic_write(Path::new(&path), &bytes)
+                .map_err(|err| MemvidError::Tantivy {
+                    reason: format!("failed to load Tantivy segment {path}: {err}"),
+                })?;
+        }
+        let index = Index::open(directory).map_err(|err| MemvidError::Tantivy {
+            reason: err.to_string(),
+        })?;
+        initialise_tokenizer(&index);
+        let schema = index.schema();
+        Self::from_parts(None, index, schema)
+    }
+
+    fn from_parts(dir: Option<TempDir>, index: Index, schema: Schema) -> Result<Self> {
         let content = schema
             .get_field("content")
             .map_err(|err| MemvidError::Tantivy {
@@ -120,7 +150,7 @@ impl TantivyEngine {
         })?;
 
         Ok(Self {
-            work_dir: dir,
+            work_dir: dir.map(Arc::new),
             index,
             _schema: schema,
             content,
@@ -136,6 +166,38 @@ impl TantivyEngine {
         })
     }
 
+    /// A search-only engine pinned to the documents committed so far. It shares the index
+    /// files, which stay on disk until every engine using them is dropped. Engines without
+    /// a writer are already pinned and hand out their own reader.
+    pub fn share(&self) -> Result<Self> {
+        let reader = if self.index_writer.is_some() {
+            self.index
+                .reader_builder()
+                .reload_policy(ReloadPolicy::Manual)
+                .try_into()
+                .map_err(|err| MemvidError::Tantivy {
+                    reason: err.to_string(),
+                })?
+        } else {
+            self.reader.clone()
+        };
+        Ok(Self {
+            work_dir: self.work_dir.clone(),
+            index: self.index.clone(),
+            _schema: self._schema.clone(),
+            content: self.content,
+            tags: self.tags,
+            labels: self.labels,
+            track: self.track,
+            timestamp: self.timestamp,
+            uri: self.uri,
+            frame_id: self.frame_id,
+            index_writer: None,
+            reader,
+            tokenizer: self.tokenizer.clone(),
+        })
+    }
+
     fn take_writer(&mut self) -> Result<IndexWriter> {
         self.index_writer.take().ok_or(MemvidError::Tantivy {
             reason: "tantivy index writer unavailable".into(),
@@ -322,11 +384,14 @@ impl TantivyEngine {
     }
 
     pub fn snapshot_segments(&self) -> Result<TantivySnapshot> {
+        let Some(work_dir) = self.work_dir.as_ref() else {
+            return self.snapshot_in_memory();
+        };
         let mut entries =
-            std::fs::read_dir(self.work_dir.path()).map_err(|err| MemvidError::Tantivy {
+            std::fs::read_dir(work_dir.path()).map_err(|err| MemvidError::Tantivy {
                 reason: format!(
                     "failed to read Tantivy index directory {}: {}",
-                    self.work_dir.path().display(),
+                    work_dir.path().display(),
                     err
                 ),
             })?;
@@ -335,7 +400,7 @@ impl TantivyEngine {
             let entry = entry.map_err(|err| MemvidError::Tantivy {
                 reason: format!(
                     "failed to iterate Tantivy index directory {}: {}",
-                    self.work_dir.path().display(),
+                    work_dir.path().display(),
                     err
                 ),
             })?;
@@ -357,14 +422,49 @@ impl TantivyEngine {
         }
         file_names.sort();
 
-        let mut segments = Vec::with_capacity(file_names.len());
-        let mut index_hasher = Hasher::new();
-
+        let mut files = Vec::with_capacity(file_names.len());
         for name in file_names {
-            let path = self.work_dir.path().join(&name);
+            let path = work_dir.path().join(&name);
             let bytes = std::fs::read(&path).map_err(|err| MemvidError::Tantivy {
                 reason: format!("failed to read Tantivy segment {}: {}", path.display(), err),
             })?;
+            files.push((name, bytes));
+        }
+        Ok(self.snapshot_from_files(files))
+    }
+
+    /// Snapshot an in-memory index: the managed segment files plus the index metadata.
+    fn snapshot_in_memory(&self) -> Result<TantivySnapshot> {
+        let directory = self.index.directory();
+        let mut file_names: Vec<String> = directory
+            .list_managed_files()
+            .into_iter()
+            .chain([PathBuf::from("meta.json"), PathBuf::from(".managed.json")])
+            .filter(|path| directory.exists(path).unwrap_or(false))
+            .map(|path| path.to_string_lossy().into_owned())
+            .filter(|name| !name.starts_with(".tantivy-"))
+            .collect();
+        file_names.sort();
+        file_names.dedup();
+
+        let mut files = Vec::with_capacity(file_names.len());
+        for name in file_names {
+            let bytes =
+                directory
+                    .atomic_read(Path::new(&name))
+                    .map_err(|err| MemvidError::Tantivy {
+                        reason: format!("failed to read Tantivy segment {name}: {err}"),
+                    })?;
+            files.push((name, bytes));
+        }
+        Ok(self.snapshot_from_files(files))
+    }
+
+    fn snapshot_from_files(&self, files: Vec<(String, Vec<u8>)>) -> TantivySnapshot {
+        let mut segments = Vec::with_capacity(files.len());
+        let mut index_hasher = Hasher::new();
+
+        for (name, bytes) in files {
             let checksum = *hash(&bytes).as_bytes();
             index_hasher.update(&checksum);
             index_hasher.update(name.as_bytes());
@@ -376,11 +476,11 @@ impl TantivyEngine {
         }
 
         let checksum = *index_hasher.finalize().as_bytes();
-        Ok(TantivySnapshot {
+        TantivySnapshot {
             doc_count: self.reader.searcher().num_docs(),
             checksum,
             segments,
-        })
+        }
     }
 
     pub(crate) fn analyse_text(&self, text: &str) -> Vec<String> {
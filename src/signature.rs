@@ -1,6 +1,6 @@
 use base64::Engine;
 use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
-use ed25519_dalek::{Signature, VerifyingKey};
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
 use serde::Serialize;
 use std::convert::TryInto;
 use uuid::Uuid;
@@ -28,6 +28,15 @@ struct ModelSignaturePayload<'a> {
     size_bytes: u64,
 }
 
+#[derive(Serialize)]
+struct CommitSignaturePayload<'a> {
+    version: u8,
+    previous_signature: Option<&'a [u8]>,
+    merkle_root: &'a [u8; 32],
+    toc_checksum: &'a [u8; 32],
+    committed_at: Option<i64>,
+}
+
 fn ticket_message_bytes(
     memory_id: &Uuid,
     issuer: &str,
@@ -66,6 +75,36 @@ fn model_message_bytes(
     })
 }
 
+fn commit_message_bytes(
+    previous_signature: Option<&[u8]>,
+    merkle_root: &[u8; 32],
+    toc_checksum: &[u8; 32],
+    committed_at: Option<i64>,
+) -> Result<Vec<u8>> {
+    let payload = CommitSignaturePayload {
+        version: SIGNING_SCHEMA_VERSION,
+        previous_signature,
+        merkle_root,
+        toc_checksum,
+        committed_at,
+    };
+    serde_json::to_vec(&payload).map_err(|err| MemvidError::CommitSignatureInvalid {
+        reason: format!("failed to serialize commit payload: {err}").into_boxed_str(),
+    })
+}
+
+pub(crate) fn sign_commit(
+    signing_key: &SigningKey,
+    previous_signature: Option<&[u8]>,
+    merkle_root: &[u8; 32],
+    toc_checksum: &[u8; 32],
+    committed_at: Option<i64>,
+) -> Result<Vec<u8>> {
+    let message =
+        commit_message_bytes(previous_signature, merkle_root, toc_checksum, committed_at)?;
+    Ok(signing_key.sign(&message).to_bytes().to_vec())
+}
+
 pub fn verify_ticket_signature(
     verifying_key: &VerifyingKey,
     memory_id: &Uuid,
@@ -103,6 +142,27 @@ pub fn verify_model_manifest(
         })
 }
 
+/// Verify a commit signature over `(previous_signature, merkle_root, toc_checksum,
+/// committed_at)`, where `toc_checksum` covers the TOC encoded without its signature.
+pub fn verify_commit_signature(
+    verifying_key: &VerifyingKey,
+    previous_signature: Option<&[u8]>,
+    merkle_root: &[u8; 32],
+    toc_checksum: &[u8; 32],
+    committed_at: Option<i64>,
+    signature_bytes: &[u8],
+) -> Result<()> {
+    let message =
+        commit_message_bytes(previous_signature, merkle_root, toc_checksum, committed_at)?;
+    let signature = to_signature(signature_bytes)
+        .map_err(|reason| MemvidError::CommitSignatureInvalid { reason })?;
+    verifying_key
+        .verify_strict(&message, &signature)
+        .map_err(|_| MemvidError::CommitSignatureInvalid {
+            reason: "commit signature mismatch".into(),
+        })
+}
+
 fn to_signature(bytes: &[u8]) -> std::result::Result<Signature, Box<str>> {
     let array: [u8; 64] = bytes
         .try_into()
@@ -133,7 +193,6 @@ pub fn parse_ed25519_public_key_base64(encoded: &str) -> Result<VerifyingKey> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use ed25519_dalek::{Signer, SigningKey};
 
     fn test_signing_key() -> SigningKey {
         let seed = [7u8; 32];
@@ -176,6 +235,41 @@ mod tests {
         .unwrap();
     }
 
+    #[test]
+    fn commit_roundtrip() {
+        let signing = test_signing_key();
+        let verifying = signing.verifying_key();
+        let previous = [9u8; 64];
+        let signature = sign_commit(
+            &signing,
+            Some(&previous),
+            &[1u8; 32],
+            &[2u8; 32],
+            Some(1_700_000_000),
+        )
+        .unwrap();
+        verify_commit_signature(
+            &verifying,
+            Some(&previous),
+            &[1u8; 32],
+            &[2u8; 32],
+            Some(1_700_000_000),
+            &signature,
+        )
+        .unwrap();
+        assert!(
+            verify_commit_signature(
+                &verifying,
+                None,
+                &[1u8; 32],
+                &[2u8; 32],
+                Some(1_700_000_000),
+                &signature,
+            )
+            .is_err()
+        );
+    }
+
     #[test]
     fn parse_public_key() {
         let signing = test_signing_key();
@@ -137,6 +137,7 @@ fn store_table_impl(
         dedup: false,
         instant_index: false,    // Tables are batch operations, commit at end
         extraction_budget_ms: 0, // No budget for table metadata
+        encoding: None,
     };
 
     let meta_frame_id = mem.next_frame_id();
@@ -209,6 +210,7 @@ fn store_table_impl(
             dedup: false,
             instant_index: false, // Tables are batch operations, commit at end
             extraction_budget_ms: 0, // No budget for table rows
+            encoding: None,
         };
 
         let should_embed = embed_rows && embedder.is_some();
@@ -275,14 +277,13 @@ fn store_table_impl(
 /// Vector of table summaries
 pub fn list_tables(mem: &mut Memvid) -> Result<Vec<TableSummary>> {
     // First, collect the frame IDs that are table_meta frames
-    let meta_frame_ids: Vec<FrameId> = mem
-        .toc
-        .frames
-        .iter()
-        .enumerate()
-        .filter(|(_, frame)| frame.kind.as_deref() == Some(TABLE_META_KIND))
-        .map(|(id, _)| id as FrameId)
-        .collect();
+    let mut meta_frame_ids: Vec<FrameId> = Vec::new();
+    for frame in mem.toc.frames.try_iter() {
+        let frame = frame?;
+        if frame.kind.as_deref() == Some(TABLE_META_KIND) {
+            meta_frame_ids.push(frame.id);
+        }
+    }
 
     let mut summaries = Vec::new();
 
@@ -342,19 +343,19 @@ pub fn list_tables(mem: &mut Memvid) -> Result<Vec<TableSummary>> {
 /// The reconstructed ExtractedTable if found
 pub fn get_table(mem: &mut Memvid, table_id: &str) -> Result<Option<ExtractedTable>> {
     // First, find the meta frame ID by scanning frames
-    let meta_frame_id: Option<FrameId> = mem
-        .toc
-        .frames
-        .iter()
-        .enumerate()
-        .find(|(_, f)| {
-            f.kind.as_deref() == Some(TABLE_META_KIND)
-                && f.extra_metadata
-                    .get("table_id")
-                    .map(|id| id == table_id)
-                    .unwrap_or(false)
-        })
-        .map(|(id, _)| id as FrameId);
+    let mut meta_frame_id: Option<FrameId> = None;
+    for f in mem.toc.frames.try_iter() {
+        let f = f?;
+        if f.kind.as_deref() == Some(TABLE_META_KIND)
+            && f.extra_metadata
+                .get("table_id")
+                .map(|id| id == table_id)
+                .unwrap_or(false)
+        {
+            meta_frame_id = Some(f.id);
+            break;
+        }
+    }
 
     let meta_frame_id = match meta_frame_id {
         Some(id) => id,
@@ -409,27 +410,23 @@ pub fn get_table(mem: &mut Memvid, table_id: &str) -> Result<Option<ExtractedTab
         .unwrap_or(TableQuality::Medium);
 
     // Find row frame IDs and their row indices (collect both to avoid borrow issues)
-    let mut row_frame_ids: Vec<(FrameId, usize)> = mem
-        .toc
-        .frames
-        .iter()
-        .enumerate()
-        .filter(|(_, f)| {
-            f.kind.as_deref() == Some(TABLE_ROW_KIND)
-                && f.extra_metadata
-                    .get("table_id")
-                    .map(|id| id == table_id)
-                    .unwrap_or(false)
-        })
-        .map(|(id, f)| {
+    let mut row_frame_ids: Vec<(FrameId, usize)> = Vec::new();
+    for f in mem.toc.frames.try_iter() {
+        let f = f?;
+        if f.kind.as_deref() == Some(TABLE_ROW_KIND)
+            && f.extra_metadata
+                .get("table_id")
+                .map(|id| id == table_id)
+                .unwrap_or(false)
+        {
             let row_index = f
                 .extra_metadata
                 .get("row_index")
                 .and_then(|s| s.parse::<usize>().ok())
                 .unwrap_or(0);
-            (id as FrameId, row_index)
-        })
-        .collect();
+            row_frame_ids.push((f.id, row_index));
+        }
+    }
 
     // Sort by row_index
     row_frame_ids.sort_by_key(|(_, row_index)| *row_index);
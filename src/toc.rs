@@ -1,12 +1,16 @@
+use std::fs::File;
+
 use bincode::serde::{decode_from_slice, encode_to_vec};
 use blake3::Hasher;
 use serde::{Deserialize, Serialize};
 
 use crate::{
+    clip::ClipIndexManifest,
     error::{MemvidError, Result},
     types::{
-        Frame, IndexManifests, MemoryBinding, SegmentCatalog, SegmentMeta, TemporalTrackManifest,
-        TicketRef, TimeIndexManifest, Toc,
+        DistanceMetric, Frame, IndexManifests, LexIndexManifest, LexSegmentManifest, MemoryBinding,
+        SegmentCatalog, SegmentMeta, TemporalTrackManifest, TicketRef, TimeIndexManifest, Toc,
+        VecIndexManifest, VectorCompression,
     },
 };
 
@@ -17,6 +21,105 @@ fn canonical_config() -> impl bincode::config::Config {
         .with_limit::<{ crate::MAX_INDEX_BYTES as usize }>()
 }
 
+/// Legacy vec manifest without the persisted HNSW graph or distance metric
+/// (pre-v2.0.132). These indexes were always ranked by L2 distance.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct LegacyVecIndexManifestV1 {
+    pub vector_count: u64,
+    pub dimension: u32,
+    pub bytes_offset: u64,
+    pub bytes_length: u64,
+    pub checksum: [u8; 32],
+    pub compression_mode: VectorCompression,
+}
+
+/// Legacy CLIP manifest without the distance metric (pre-v2.0.132).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct LegacyClipIndexManifestV1 {
+    pub bytes_offset: u64,
+    pub bytes_length: u64,
+    pub vector_count: u64,
+    pub dimension: u32,
+    pub checksum: [u8; 32],
+    pub model_name: String,
+}
+
+/// Legacy index manifests referencing [`LegacyVecIndexManifestV1`] and
+/// [`LegacyClipIndexManifestV1`] (pre-v2.0.132).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct LegacyIndexManifestsV1 {
+    pub lex: Option<LexIndexManifest>,
+    pub lex_segments: Vec<LexSegmentManifest>,
+    pub vec: Option<LegacyVecIndexManifestV1>,
+    pub clip: Option<LegacyClipIndexManifestV1>,
+}
+
+impl From<LegacyIndexManifestsV1> for IndexManifests {
+    fn from(legacy: LegacyIndexManifestsV1) -> Self {
+        IndexManifests {
+            lex: legacy.lex,
+            lex_segments: legacy.lex_segments,
+            vec: legacy.vec.map(|vec| VecIndexManifest {
+                vector_count: vec.vector_count,
+                dimension: vec.dimension,
+                bytes_offset: vec.bytes_offset,
+                bytes_length: vec.bytes_length,
+                checksum: vec.checksum,
+                compression_mode: vec.compression_mode,
+                metric: DistanceMetric::L2,
+                hnsw: None, // Default for pre-HNSW files
+            }),
+            clip: legacy.clip.map(|clip| ClipIndexManifest {
+                bytes_offset: clip.bytes_offset,
+                bytes_length: clip.bytes_length,
+                vector_count: clip.vector_count,
+                dimension: clip.dimension,
+                checksum: clip.checksum,
+                model_name: clip.model_name,
+                metric: DistanceMetric::L2,
+            }),
+        }
+    }
+}
+
+impl LegacyIndexManifestsV1 {
+    /// Projects current manifests onto the legacy layout for checksum
+    /// verification. Returns `None` if they carry data the legacy layout
+    /// cannot express.
+    fn project(indexes: &IndexManifests) -> Option<Self> {
+        let vec = match indexes.vec.as_ref() {
+            Some(vec) if vec.hnsw.is_some() || vec.metric != DistanceMetric::L2 => return None,
+            Some(vec) => Some(LegacyVecIndexManifestV1 {
+                vector_count: vec.vector_count,
+                dimension: vec.dimension,
+                bytes_offset: vec.bytes_offset,
+                bytes_length: vec.bytes_length,
+                checksum: vec.checksum,
+                compression_mode: vec.compression_mode.clone(),
+            }),
+            None => None,
+        };
+        let clip = match indexes.clip.as_ref() {
+            Some(clip) if clip.metric != DistanceMetric::L2 => return None,
+            Some(clip) => Some(LegacyClipIndexManifestV1 {
+                bytes_offset: clip.bytes_offset,
+                bytes_length: clip.bytes_length,
+                vector_count: clip.vector_count,
+                dimension: clip.dimension,
+                checksum: clip.checksum,
+                model_name: clip.model_name.clone(),
+            }),
+            None => None,
+        };
+        Some(Self {
+            lex: indexes.lex.clone(),
+            lex_segments: indexes.lex_segments.clone(),
+            vec,
+            clip,
+        })
+    }
+}
+
 /// Legacy TOC format without memories_track field (pre-v2.0.105).
 /// Used for backwards compatibility with older .mv2 files.
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -24,7 +127,7 @@ struct LegacyTocV1 {
     pub toc_version: u64,
     pub segments: Vec<SegmentMeta>,
     pub frames: Vec<Frame>,
-    pub indexes: IndexManifests,
+    pub indexes: LegacyIndexManifestsV1,
     pub time_index: Option<TimeIndexManifest>,
     pub temporal_track: Option<TemporalTrackManifest>,
     // Note: memories_track, logic_mesh, replay_manifest NOT present
@@ -42,7 +145,7 @@ struct LegacyTocV2 {
     pub toc_version: u64,
     pub segments: Vec<SegmentMeta>,
     pub frames: Vec<Frame>,
-    pub indexes: IndexManifests,
+    pub indexes: LegacyIndexManifestsV1,
     pub time_index: Option<TimeIndexManifest>,
     pub temporal_track: Option<TemporalTrackManifest>,
     pub memories_track: Option<crate::types::MemoriesTrackManifest>,
@@ -55,13 +158,34 @@ struct LegacyTocV2 {
     pub toc_checksum: [u8; 32],
 }
 
+/// Legacy TOC format whose vec manifest predates the HNSW graph (pre-v2.0.132).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct LegacyTocV3 {
+    pub toc_version: u64,
+    pub segments: Vec<SegmentMeta>,
+    pub frames: Vec<Frame>,
+    pub indexes: LegacyIndexManifestsV1,
+    pub time_index: Option<TimeIndexManifest>,
+    pub temporal_track: Option<TemporalTrackManifest>,
+    pub memories_track: Option<crate::types::MemoriesTrackManifest>,
+    pub logic_mesh: Option<crate::types::LogicMeshManifest>,
+    pub sketch_track: Option<crate::types::SketchTrackManifest>,
+    pub segment_catalog: SegmentCatalog,
+    pub ticket_ref: TicketRef,
+    pub memory_binding: Option<MemoryBinding>,
+    pub replay_manifest: Option<crate::replay::ReplayManifest>,
+    pub enrichment_queue: crate::types::EnrichmentQueueManifest,
+    pub merkle_root: [u8; 32],
+    pub toc_checksum: [u8; 32],
+}
+
 impl From<LegacyTocV1> for Toc {
     fn from(legacy: LegacyTocV1) -> Self {
         Toc {
             toc_version: legacy.toc_version,
             segments: legacy.segments,
-            frames: legacy.frames,
-            indexes: legacy.indexes,
+            frames: legacy.frames.into(),
+            indexes: legacy.indexes.into(),
             time_index: legacy.time_index,
             temporal_track: legacy.temporal_track,
             memories_track: None, // Default for legacy files
@@ -72,6 +196,15 @@ impl From<LegacyTocV1> for Toc {
             memory_binding: legacy.memory_binding,
             replay_manifest: None,                // Default for legacy files
             enrichment_queue: Default::default(), // Default for legacy files
+            frame_table: None,                    // Default for legacy files
+            committed_at: None,                   // Default for legacy files
+            commit_signature: None,               // Default for legacy files
+            retention_rules: Vec::new(),          // Default for legacy files
+            quotas: Default::default(),           // Default for legacy files
+            compression: Default::default(),      // Default for legacy files
+            blob_store: None,                     // Default for legacy files
+            change_log: None,                     // Default for legacy files
+            wal_size: 0,                          // Default for legacy files
             merkle_root: legacy.merkle_root,
             toc_checksum: legacy.toc_checksum,
         }
@@ -83,8 +216,8 @@ impl From<LegacyTocV2> for Toc {
         Toc {
             toc_version: legacy.toc_version,
             segments: legacy.segments,
-            frames: legacy.frames,
-            indexes: legacy.indexes,
+            frames: legacy.frames.into(),
+            indexes: legacy.indexes.into(),
             time_index: legacy.time_index,
             temporal_track: legacy.temporal_track,
             memories_track: legacy.memories_track,
@@ -95,6 +228,47 @@ impl From<LegacyTocV2> for Toc {
             memory_binding: legacy.memory_binding,
             replay_manifest: None, // Default for pre-replay files
             enrichment_queue: Default::default(), // Default for legacy files
+            frame_table: None,     // Default for legacy files
+            committed_at: None,    // Default for legacy files
+            commit_signature: None, // Default for legacy files
+            retention_rules: Vec::new(), // Default for legacy files
+            quotas: Default::default(), // Default for legacy files
+            compression: Default::default(), // Default for legacy files
+            blob_store: None,      // Default for legacy files
+            change_log: None,      // Default for legacy files
+            wal_size: 0,           // Default for legacy files
+            merkle_root: legacy.merkle_root,
+            toc_checksum: legacy.toc_checksum,
+        }
+    }
+}
+
+impl From<LegacyTocV3> for Toc {
+    fn from(legacy: LegacyTocV3) -> Self {
+        Toc {
+            toc_version: legacy.toc_version,
+            segments: legacy.segments,
+            frames: legacy.frames.into(),
+            indexes: legacy.indexes.into(),
+            time_index: legacy.time_index,
+            temporal_track: legacy.temporal_track,
+            memories_track: legacy.memories_track,
+            logic_mesh: legacy.logic_mesh,
+            sketch_track: legacy.sketch_track,
+            segment_catalog: legacy.segment_catalog,
+            ticket_ref: legacy.ticket_ref,
+            memory_binding: legacy.memory_binding,
+            replay_manifest: legacy.replay_manifest,
+            enrichment_queue: legacy.enrichment_queue,
+            frame_table: None, // Frames were always stored inline
+            committed_at: None,
+            commit_signature: None,
+            retention_rules: Vec::new(),
+            quotas: Default::default(),
+            compression: Default::default(),
+            blob_store: None,
+            change_log: None,
+            wal_size: 0,
             merkle_root: legacy.merkle_root,
             toc_checksum: legacy.toc_checksum,
         }
@@ -123,6 +297,20 @@ impl Toc {
             Err(_) => {}
         }
 
+        // Try V3 format (vec manifest without HNSW graph)
+        match decode_from_slice::<LegacyTocV3, _>(bytes, canonical_config()) {
+            Ok((legacy, bytes_read)) => {
+                if bytes_read != bytes.len() {
+                    return Err(MemvidError::InvalidToc {
+                        reason: "unexpected trailing bytes in V3 format".into(),
+                    });
+                }
+                tracing::debug!("Decoded TOC V3 format (pre-hnsw)");
+                return Ok(legacy.into());
+            }
+            Err(_) => {}
+        }
+
         // Try V2 format (with memories_track/logic_mesh, without replay_manifest)
         match decode_from_slice::<LegacyTocV2, _>(bytes, canonical_config()) {
             Ok((legacy, bytes_read)) => {
@@ -159,6 +347,11 @@ impl Toc {
         if let Ok((toc, _)) = decode_from_slice::<Toc, _>(bytes, canonical_config()) {
             return Ok(toc);
         }
+        // Try V3 format (vec manifest without HNSW graph)
+        if let Ok((legacy, _)) = decode_from_slice::<LegacyTocV3, _>(bytes, canonical_config()) {
+            tracing::debug!("Decoded TOC V3 format (pre-hnsw) in lenient mode");
+            return Ok(legacy.into());
+        }
         // Try V2 format (with memories_track/logic_mesh, without replay_manifest)
         if let Ok((legacy, _)) = decode_from_slice::<LegacyTocV2, _>(bytes, canonical_config()) {
             tracing::debug!("Decoded TOC V2 format (pre-replay_manifest) in lenient mode");
@@ -175,6 +368,22 @@ impl Toc {
     }
 }
 
+impl Toc {
+    /// Binds `frames` to the frame table pages referenced by this TOC so that
+    /// they are decoded on demand from `file`. Legacy inline TOCs are left as is.
+    pub(crate) fn bind_frame_table(&mut self, file: &File) -> Result<()> {
+        let Some(manifest) = self.frame_table.as_ref() else {
+            return Ok(());
+        };
+        if !self.frames.is_empty() && !self.frames.is_paged() {
+            return Err(MemvidError::InvalidToc {
+                reason: "toc stores frames both inline and in a frame table".into(),
+            });
+        }
+        self.frames.bind(file, manifest)
+    }
+}
+
 impl LegacyTocV1 {
     /// Encode legacy TOC format for checksum verification.
     fn encode(&self) -> Result<Vec<u8>> {
@@ -189,6 +398,13 @@ impl LegacyTocV2 {
     }
 }
 
+impl LegacyTocV3 {
+    /// Encode V3 TOC format for checksum verification.
+    fn encode(&self) -> Result<Vec<u8>> {
+        Ok(encode_to_vec(self, canonical_config())?)
+    }
+}
+
 impl Toc {
     /// Computes the BLAKE3 checksum used for the TOC integrity field.
     pub fn calculate_checksum(bytes: &[u8]) -> [u8; 32] {
@@ -197,6 +413,17 @@ impl Toc {
         *hasher.finalize().as_bytes()
     }
 
+    /// Checksum of the TOC encoded without its commit signature, as covered by that
+    /// signature.
+    pub(crate) fn unsigned_checksum(&mut self) -> Result<[u8; 32]> {
+        let signature = self.commit_signature.take();
+        let checksum = std::mem::take(&mut self.toc_checksum);
+        let encoded = self.encode();
+        self.commit_signature = signature;
+        self.toc_checksum = checksum;
+        Ok(Self::calculate_checksum(&encoded?))
+    }
+
     /// Verifies that the stored TOC checksum matches the deterministic encoding.
     /// Supports current format and legacy format checksums for backwards compatibility.
     pub fn verify_checksum(&self) -> Result<()> {
@@ -209,14 +436,46 @@ impl Toc {
             return Ok(());
         }
 
+        // Every legacy layout shares the pre-HNSW index manifests and stores
+        // frames inline; if the TOC cannot be expressed that way, it must be current.
+        let legacy_indexes = match LegacyIndexManifestsV1::project(&self.indexes) {
+            Some(indexes) if self.frame_table.is_none() => indexes,
+            _ => return Err(MemvidError::ChecksumMismatch { context: "toc" }),
+        };
+
+        // Try V3 format (vec manifest without HNSW graph)
+        let legacy_v3 = LegacyTocV3 {
+            toc_version: self.toc_version,
+            segments: self.segments.clone(),
+            frames: self.frames.to_vec()?,
+            indexes: legacy_indexes.clone(),
+            time_index: self.time_index.clone(),
+            temporal_track: self.temporal_track.clone(),
+            memories_track: self.memories_track.clone(),
+            logic_mesh: self.logic_mesh.clone(),
+            sketch_track: self.sketch_track.clone(),
+            segment_catalog: self.segment_catalog.clone(),
+            ticket_ref: self.ticket_ref.clone(),
+            memory_binding: self.memory_binding.clone(),
+            replay_manifest: self.replay_manifest.clone(),
+            enrichment_queue: self.enrichment_queue.clone(),
+            merkle_root: self.merkle_root,
+            toc_checksum: [0u8; 32],
+        };
+        let v3_bytes = legacy_v3.encode()?;
+        if Self::calculate_checksum(&v3_bytes) == self.toc_checksum {
+            tracing::debug!("TOC checksum verified using V3 format (pre-hnsw)");
+            return Ok(());
+        }
+
         // Try V2 format (with memories_track/logic_mesh, without replay_manifest)
         // Only try if replay_manifest is None (indicates pre-replay origin)
         if self.replay_manifest.is_none() {
             let legacy_v2 = LegacyTocV2 {
                 toc_version: self.toc_version,
                 segments: self.segments.clone(),
-                frames: self.frames.clone(),
-                indexes: self.indexes.clone(),
+                frames: self.frames.to_vec()?,
+                indexes: legacy_indexes.clone(),
                 time_index: self.time_index.clone(),
                 temporal_track: self.temporal_track.clone(),
                 memories_track: self.memories_track.clone(),
@@ -241,8 +500,8 @@ impl Toc {
             let legacy_v1 = LegacyTocV1 {
                 toc_version: self.toc_version,
                 segments: self.segments.clone(),
-                frames: self.frames.clone(),
-                indexes: self.indexes.clone(),
+                frames: self.frames.to_vec()?,
+                indexes: legacy_indexes,
                 time_index: self.time_index.clone(),
                 temporal_track: self.temporal_track.clone(),
                 segment_catalog: self.segment_catalog.clone(),
@@ -348,7 +607,8 @@ mod tests {
                     source_path: None,
                     enrichment_state: crate::types::EnrichmentState::default(),
                 },
-            ],
+            ]
+            .into(),
             indexes: IndexManifests::default(),
             time_index: Some(TimeIndexManifest {
                 bytes_offset: 8192,
@@ -370,6 +630,15 @@ mod tests {
             memory_binding: None,
             replay_manifest: None,
             enrichment_queue: Default::default(),
+            frame_table: None,
+            committed_at: None,
+            commit_signature: None,
+            retention_rules: Vec::new(),
+            quotas: Default::default(),
+            compression: Default::default(),
+            blob_store: None,
+            change_log: None,
+            wal_size: 0,
             merkle_root: [0x55; 32],
             toc_checksum: [0u8; 32],
         }
@@ -400,6 +669,48 @@ mod tests {
         matches!(err, MemvidError::ChecksumMismatch { .. });
     }
 
+    #[test]
+    fn decode_pre_hnsw_layout() {
+        let mut toc = sample_toc();
+        toc.indexes.vec = Some(VecIndexManifest {
+            vector_count: 2,
+            dimension: 4,
+            bytes_offset: 9000,
+            bytes_length: 64,
+            checksum: [0x66; 32],
+            compression_mode: VectorCompression::None,
+            metric: DistanceMetric::L2,
+            hnsw: None,
+        });
+        let mut legacy = LegacyTocV3 {
+            toc_version: toc.toc_version,
+            segments: toc.segments.clone(),
+            frames: toc.frames.to_vec().expect("frames"),
+            indexes: LegacyIndexManifestsV1::project(&toc.indexes).expect("legacy indexes"),
+            time_index: toc.time_index.clone(),
+            temporal_track: None,
+            memories_track: None,
+            logic_mesh: None,
+            sketch_track: None,
+            segment_catalog: toc.segment_catalog.clone(),
+            ticket_ref: toc.ticket_ref.clone(),
+            memory_binding: None,
+            replay_manifest: None,
+            enrichment_queue: Default::default(),
+            merkle_root: toc.merkle_root,
+            toc_checksum: [0u8; 32],
+        };
+        legacy.toc_checksum = Toc::calculate_checksum(&legacy.encode().expect("encode legacy"));
+        let bytes = legacy.encode().expect("encode legacy");
+
+        let decoded = Toc::decode(&bytes).expect("decode legacy toc");
+        decoded.verify_checksum().expect("legacy checksum matches");
+        let vec = decoded.indexes.vec.expect("vec manifest");
+        assert_eq!(vec.bytes_offset, 9000);
+        assert_eq!(vec.metric, DistanceMetric::L2);
+        assert!(vec.hnsw.is_none());
+    }
+
     #[test]
     fn reject_trailing_bytes() {
         let toc = stamp_checksum(sample_toc());
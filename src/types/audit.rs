@@ -7,7 +7,7 @@
 use serde::{Deserialize, Serialize};
 
 use super::ask::{AskMode, AskRetriever, AskStats};
-use super::common::FrameId;
+use super::common::{CanonicalEncoding, FrameId};
 
 /// A source span representing a specific piece of evidence used in an answer.
 ///
@@ -55,6 +55,27 @@ pub struct SourceSpan {
     /// The actual text snippet used as context.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub snippet: Option<String>,
+
+    /// Inclusion proof of the frame against the report's `merkle_root`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proof: Option<FrameProof>,
+}
+
+/// Merkle inclusion proof that a frame was part of a committed TOC.
+///
+/// Checked against the TOC's `merkle_root` with [`verify_frame_proof`](crate::verify_frame_proof).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FrameProof {
+    /// Frame the proof is for; also its leaf index.
+    pub frame_id: FrameId,
+    /// Number of leaves (frames) in the tree.
+    pub leaf_count: u64,
+    /// BLAKE3 checksum of the frame's stored payload.
+    pub checksum: [u8; 32],
+    /// Encoding of the stored payload.
+    pub encoding: CanonicalEncoding,
+    /// Sibling hashes from the leaf up to the root.
+    pub siblings: Vec<[u8; 32]>,
 }
 
 /// Options for generating an audit report.
@@ -87,6 +108,10 @@ pub struct AuditOptions {
     /// Include full text snippets in sources.
     #[serde(default)]
     pub include_snippets: bool,
+
+    /// Embed a Merkle inclusion proof for each source.
+    #[serde(default)]
+    pub include_proofs: bool,
 }
 
 /// A structured audit report for a question-answering session.
@@ -117,6 +142,10 @@ pub struct AuditReport {
     /// All source spans used to generate the answer.
     pub sources: Vec<SourceSpan>,
 
+    /// Merkle root the source proofs verify against, when proofs were requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merkle_root: Option<[u8; 32]>,
+
     /// Total number of documents searched.
     pub total_hits: usize,
 
@@ -176,6 +205,9 @@ impl AuditReport {
             format_retriever(self.retriever)
         ));
         output.push_str(&format!("  Sources Found:   {}\n", self.total_hits));
+        if let Some(root) = &self.merkle_root {
+            output.push_str(&format!("  Merkle Root:     {}\n", hex::encode(root)));
+        }
         output.push_str(&format!(
             "  Total Latency:   {} ms\n",
             self.stats.latency_ms
@@ -201,6 +233,13 @@ impl AuditReport {
                 output.push_str(&format!("    Title:       {}\n", title));
             }
             output.push_str(&format!("    Frame ID:    {}\n", source.frame_id));
+            if let Some(proof) = &source.proof {
+                output.push_str(&format!(
+                    "    Proof:       {} sibling hashes over {} frames\n",
+                    proof.siblings.len(),
+                    proof.leaf_count
+                ));
+            }
             if let Some(score) = source.score {
                 output.push_str(&format!("    Score:       {:.4}\n", score));
             }
@@ -302,6 +341,9 @@ impl AuditReport {
             format_retriever(self.retriever)
         ));
         output.push_str(&format!("| **Sources Found** | {} |\n", self.total_hits));
+        if let Some(root) = &self.merkle_root {
+            output.push_str(&format!("| **Merkle Root** | `{}` |\n", hex::encode(root)));
+        }
         output.push_str(&format!(
             "| **Total Latency** | {} ms |\n",
             self.stats.latency_ms
@@ -329,6 +371,13 @@ impl AuditReport {
             output.push_str("| Property | Value |\n");
             output.push_str("|:---------|:------|\n");
             output.push_str(&format!("| Frame ID | {} |\n", source.frame_id));
+            if let Some(proof) = &source.proof {
+                output.push_str(&format!(
+                    "| Proof | {} sibling hashes over {} frames |\n",
+                    proof.siblings.len(),
+                    proof.leaf_count
+                ));
+            }
             if let Some(score) = source.score {
                 output.push_str(&format!("| Relevance Score | {:.4} |\n", score));
             }
@@ -532,6 +581,7 @@ mod tests {
             frame_timestamp: Some(1700000000),
             content_dates: vec![],
             snippet: Some("This is a test snippet.".to_string()),
+            proof: None,
         };
 
         let json = serde_json::to_string_pretty(&source).expect("serialize");
@@ -560,7 +610,9 @@ mod tests {
                 frame_timestamp: None,
                 content_dates: vec![],
                 snippet: Some("Memvid is...".to_string()),
+                proof: None,
             }],
+            merkle_root: None,
             total_hits: 5,
             stats: AskStats {
                 retrieval_ms: 10,
@@ -0,0 +1,226 @@
+//! Content-addressed store of raw payload chunks shared between frames.
+//!
+//! Frames stored with [`CanonicalEncoding::Chunked`] hold a recipe instead of their bytes:
+//! the BLAKE3 hashes of their content-defined chunks, in order. Each distinct chunk is
+//! written once to the data region, and the chunk index maps its hash to its location.
+//!
+//! **Segment layout** (little-endian, see `MV2_SPEC` "Blob Store"):
+//! - Header: `[magic:4][version:u16][reserved:u16][chunk_count:u64]`
+//! - `chunk_count` records:
+//!   `[hash:32][offset:u64][stored_length:u64][raw_length:u64][encoding:u8]`
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::common::CanonicalEncoding;
+use crate::constants::{BLOB_STORE_MAGIC, BLOB_STORE_VERSION};
+use crate::error::{MemvidError, Result};
+
+/// BLAKE3 hash of a chunk's raw bytes.
+pub type ChunkHash = [u8; 32];
+
+const HEADER_SIZE: usize = 16;
+const RECORD_SIZE: usize = 57;
+
+/// Location of one stored chunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlobChunk {
+    pub hash: ChunkHash,
+    pub offset: u64,
+    /// Bytes occupied in the file.
+    pub stored_length: u64,
+    /// Bytes after decoding.
+    pub raw_length: u64,
+    /// `Plain` or `Zstd`.
+    pub encoding: CanonicalEncoding,
+}
+
+/// TOC entry locating the chunk index segment.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlobStoreManifest {
+    pub bytes_offset: u64,
+    pub bytes_length: u64,
+    pub chunk_count: u64,
+    /// Bytes the chunks occupy in the file.
+    pub stored_bytes: u64,
+    /// Decoded bytes of the distinct chunks.
+    pub raw_bytes: u64,
+    pub checksum: [u8; 32],
+}
+
+/// In-memory chunk index.
+#[derive(Debug, Clone, Default)]
+pub struct BlobStore {
+    chunks: HashMap<ChunkHash, BlobChunk>,
+    dirty: bool,
+}
+
+impl BlobStore {
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.chunks.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.chunks.is_empty()
+    }
+
+    /// Whether the index changed since it was loaded or last persisted.
+    #[must_use]
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    pub fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+
+    pub fn mark_clean(&mut self) {
+        self.dirty = false;
+    }
+
+    #[must_use]
+    pub fn get(&self, hash: &ChunkHash) -> Option<&BlobChunk> {
+        self.chunks.get(hash)
+    }
+
+    #[must_use]
+    pub fn contains(&self, hash: &ChunkHash) -> bool {
+        self.chunks.contains_key(hash)
+    }
+
+    pub fn insert(&mut self, chunk: BlobChunk) {
+        self.chunks.insert(chunk.hash, chunk);
+        self.dirty = true;
+    }
+
+    pub fn remove(&mut self, hash: &ChunkHash) -> Option<BlobChunk> {
+        let removed = self.chunks.remove(hash);
+        if removed.is_some() {
+            self.dirty = true;
+        }
+        removed
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &BlobChunk> {
+        self.chunks.values()
+    }
+
+    /// Chunks ordered by their offset in the file.
+    #[must_use]
+    pub fn by_offset(&self) -> Vec<BlobChunk> {
+        let mut chunks: Vec<BlobChunk> = self.chunks.values().copied().collect();
+        chunks.sort_by_key(|chunk| chunk.offset);
+        chunks
+    }
+
+    /// Move every chunk `delta` bytes further into the file.
+    pub fn shift(&mut self, delta: u64) {
+        if delta == 0 || self.chunks.is_empty() {
+            return;
+        }
+        for chunk in self.chunks.values_mut() {
+            chunk.offset += delta;
+        }
+        self.dirty = true;
+    }
+
+    #[must_use]
+    pub fn stored_bytes(&self) -> u64 {
+        self.chunks.values().map(|chunk| chunk.stored_length).sum()
+    }
+
+    #[must_use]
+    pub fn raw_bytes(&self) -> u64 {
+        self.chunks.values().map(|chunk| chunk.raw_length).sum()
+    }
+
+    /// Encode the index as a segment, chunks in offset order.
+    #[must_use]
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(HEADER_SIZE + self.chunks.len() * RECORD_SIZE);
+        out.extend_from_slice(&BLOB_STORE_MAGIC);
+        out.extend_from_slice(&BLOB_STORE_VERSION.to_le_bytes());
+        out.extend_from_slice(&0u16.to_le_bytes());
+        out.extend_from_slice(&(self.chunks.len() as u64).to_le_bytes());
+        for chunk in self.by_offset() {
+            out.extend_from_slice(&chunk.hash);
+            out.extend_from_slice(&chunk.offset.to_le_bytes());
+            out.extend_from_slice(&chunk.stored_length.to_le_bytes());
+            out.extend_from_slice(&chunk.raw_length.to_le_bytes());
+            out.push(chunk.encoding.as_byte());
+        }
+        out
+    }
+
+    /// Decode a segment written by [`BlobStore::encode`].
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        let invalid = |reason: &'static str| MemvidError::InvalidBlobStore {
+            reason: reason.into(),
+        };
+        if bytes.len() < HEADER_SIZE || bytes[..4] != BLOB_STORE_MAGIC {
+            return Err(invalid("bad chunk index header"));
+        }
+        let version = u16::from_le_bytes([bytes[4], bytes[5]]);
+        if version != BLOB_STORE_VERSION {
+            return Err(invalid("unsupported chunk index version"));
+        }
+        let u64_at = |start: usize| {
+            let mut buf = [0u8; 8];
+            buf.copy_from_slice(&bytes[start..start + 8]);
+            u64::from_le_bytes(buf)
+        };
+        let count = u64_at(8) as usize;
+        if count
+            .checked_mul(RECORD_SIZE)
+            .and_then(|records| records.checked_add(HEADER_SIZE))
+            != Some(bytes.len())
+        {
+            return Err(invalid("chunk index length mismatch"));
+        }
+
+        let mut chunks = HashMap::with_capacity(count);
+        for index in 0..count {
+            let start = HEADER_SIZE + index * RECORD_SIZE;
+            let mut hash = [0u8; 32];
+            hash.copy_from_slice(&bytes[start..start + 32]);
+            let chunk = BlobChunk {
+                hash,
+                offset: u64_at(start + 32),
+                stored_length: u64_at(start + 40),
+                raw_length: u64_at(start + 48),
+                encoding: CanonicalEncoding::from_byte(bytes[start + 56]),
+            };
+            chunks.insert(hash, chunk);
+        }
+        Ok(Self {
+            chunks,
+            dirty: false,
+        })
+    }
+}
+
+/// Encode the recipe of a chunked payload.
+#[must_use]
+pub fn encode_chunk_recipe(hashes: &[ChunkHash]) -> Vec<u8> {
+    hashes.concat()
+}
+
+/// Decode the recipe of a chunked payload.
+pub fn decode_chunk_recipe(bytes: &[u8]) -> Result<Vec<ChunkHash>> {
+    if bytes.len() % 32 != 0 {
+        return Err(MemvidError::InvalidBlobStore {
+            reason: "chunk recipe length is not a multiple of 32".into(),
+        });
+    }
+    Ok(bytes
+        .chunks_exact(32)
+        .map(|hash| {
+            let mut out = [0u8; 32];
+            out.copy_from_slice(hash);
+            out
+        })
+        .collect())
+}
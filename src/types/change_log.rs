@@ -0,0 +1,193 @@
+//! Change log kept in a segment of its own so downstream systems can mirror a memory
+//! incrementally.
+
+use std::collections::BTreeSet;
+
+use bincode::serde::{decode_from_slice, encode_to_vec};
+use serde::{Deserialize, Serialize};
+
+use super::common::FrameId;
+use super::memory_card::MemoryCardId;
+use crate::error::{MemvidError, Result};
+
+/// Events the change log keeps when no horizon is configured.
+pub const DEFAULT_CHANGE_LOG_HORIZON: u64 = 100_000;
+
+/// A committed mutation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChangeEvent {
+    /// Position in the log, starting at 1. Sequences increase by one per event and are
+    /// never reused, trimmed events included.
+    pub sequence: u64,
+    /// Unix timestamp (seconds) at which the commit recorded the event.
+    pub timestamp: i64,
+    pub kind: ChangeKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChangeKind {
+    /// A frame was added.
+    Put {
+        frame_id: FrameId,
+        uri: Option<String>,
+    },
+    /// A frame was added as the new version of `supersedes`.
+    Update {
+        frame_id: FrameId,
+        supersedes: FrameId,
+        uri: Option<String>,
+    },
+    /// A frame was deleted, by a tombstone, retention or erasure.
+    Delete {
+        frame_id: FrameId,
+        uri: Option<String>,
+    },
+    /// A memory card was added.
+    MemoryCard {
+        card_id: MemoryCardId,
+        entity: String,
+        slot: String,
+    },
+    /// The Logic-Mesh gained or lost nodes or edges.
+    Mesh {
+        added_nodes: Vec<u64>,
+        removed_nodes: Vec<u64>,
+        added_edges: Vec<MeshEdgeId>,
+        removed_edges: Vec<MeshEdgeId>,
+    },
+    /// A memory card was removed with the frame it was extracted from, by retention or
+    /// erasure.
+    MemoryCardRemoved { card_id: MemoryCardId },
+}
+
+/// Identifies a Logic-Mesh edge, which the mesh keeps unique by its endpoints and link.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct MeshEdgeId {
+    pub from_node: u64,
+    pub to_node: u64,
+    pub link: String,
+}
+
+/// TOC entry locating the change log segment.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChangeLogManifest {
+    pub bytes_offset: u64,
+    pub bytes_length: u64,
+    pub event_count: u64,
+    pub last_sequence: u64,
+    pub checksum: [u8; 32],
+}
+
+/// Events returned by [`Memvid::changes_since`](crate::Memvid::changes_since).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChangeBatch {
+    /// Events in sequence order.
+    pub events: Vec<ChangeEvent>,
+    /// Sequence to pass to the next call to continue after `events`.
+    pub resume_token: u64,
+}
+
+/// The change log and what it has seen of the memories track and Logic-Mesh.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChangeLog {
+    /// Retained events, oldest first.
+    pub events: Vec<ChangeEvent>,
+    /// Sequence of the newest event ever recorded, retained or not.
+    pub last_sequence: u64,
+    /// Memory cards with a lower id have been logged.
+    pub next_card_id: MemoryCardId,
+    /// Logic-Mesh nodes and edges as of the last Mesh event.
+    pub mesh_nodes: BTreeSet<u64>,
+    pub mesh_edges: BTreeSet<MeshEdgeId>,
+    /// Events kept in the log; `None` means [`DEFAULT_CHANGE_LOG_HORIZON`].
+    pub horizon: Option<u64>,
+    #[serde(skip)]
+    dirty: bool,
+}
+
+impl ChangeLog {
+    pub fn record(&mut self, timestamp: i64, kind: ChangeKind) {
+        self.dirty = true;
+        self.last_sequence += 1;
+        self.events.push(ChangeEvent {
+            sequence: self.last_sequence,
+            timestamp,
+            kind,
+        });
+    }
+
+    /// Sequence of the oldest event still retained, or of the next one when none is.
+    #[must_use]
+    pub fn first_retained(&self) -> u64 {
+        self.last_sequence + 1 - self.events.len() as u64
+    }
+
+    /// Up to `limit` events after `sequence`, or `None` when some of them were dropped.
+    #[must_use]
+    pub fn since(&self, sequence: u64, limit: usize) -> Option<ChangeBatch> {
+        if sequence.saturating_add(1) < self.first_retained() {
+            return None;
+        }
+        let start = self
+            .events
+            .partition_point(|event| event.sequence <= sequence);
+        let events: Vec<_> = self.events[start..].iter().take(limit).cloned().collect();
+        let resume_token = events.last().map_or(sequence, |event| event.sequence);
+        Some(ChangeBatch {
+            events,
+            resume_token,
+        })
+    }
+
+    /// Drop all but the newest `horizon` events.
+    pub fn trim(&mut self) {
+        let horizon = self.horizon.unwrap_or(DEFAULT_CHANGE_LOG_HORIZON);
+        let excess = self.events.len().saturating_sub(horizon as usize);
+        if excess > 0 {
+            self.events.drain(..excess);
+            self.dirty = true;
+        }
+    }
+
+    /// Whether the log changed since it was loaded or last persisted.
+    #[must_use]
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    pub fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+
+    pub fn mark_clean(&mut self) {
+        self.dirty = false;
+    }
+
+    /// Encode the log as a segment.
+    pub fn encode(&self) -> Result<Vec<u8>> {
+        let mut bytes = CHANGE_LOG_MAGIC.to_vec();
+        bytes.extend_from_slice(&CHANGE_LOG_VERSION.to_le_bytes());
+        bytes.extend(encode_to_vec(self, bincode::config::standard())?);
+        Ok(bytes)
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        let invalid = |reason: &'static str| MemvidError::InvalidToc {
+            reason: reason.into(),
+        };
+        let body = bytes
+            .strip_prefix(CHANGE_LOG_MAGIC)
+            .ok_or_else(|| invalid("invalid change log magic"))?;
+        let (version, body) = body
+            .split_first_chunk::<2>()
+            .ok_or_else(|| invalid("change log too short"))?;
+        if u16::from_le_bytes(*version) != CHANGE_LOG_VERSION {
+            return Err(invalid("unsupported change log version"));
+        }
+        let (log, _) = decode_from_slice(body, bincode::config::standard())?;
+        Ok(log)
+    }
+}
+
+const CHANGE_LOG_MAGIC: &[u8; 4] = b"MVCL";
+const CHANGE_LOG_VERSION: u16 = 1;
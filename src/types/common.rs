@@ -15,6 +15,12 @@ pub type SegmentId = u64;
 pub enum CanonicalEncoding {
     Plain,
     Zstd,
+    /// LZ4 block with the decoded length prepended; faster to decode than zstd.
+    Lz4,
+    /// Zstd frame compressed against a trained dictionary named by the frame's dictionary id.
+    ZstdDict,
+    /// Recipe of content-defined chunk hashes; the chunks live in the blob store.
+    Chunked,
 }
 
 impl CanonicalEncoding {
@@ -22,6 +28,9 @@ impl CanonicalEncoding {
         match value {
             0 => CanonicalEncoding::Plain,
             1 => CanonicalEncoding::Zstd,
+            2 => CanonicalEncoding::Lz4,
+            3 => CanonicalEncoding::ZstdDict,
+            4 => CanonicalEncoding::Chunked,
             _ => CanonicalEncoding::Plain,
         }
     }
@@ -30,6 +39,9 @@ impl CanonicalEncoding {
         match self {
             CanonicalEncoding::Plain => 0,
             CanonicalEncoding::Zstd => 1,
+            CanonicalEncoding::Lz4 => 2,
+            CanonicalEncoding::ZstdDict => 3,
+            CanonicalEncoding::Chunked => 4,
         }
     }
 }
@@ -0,0 +1,92 @@
+//! Canonical encoder selection and trained zstd dictionaries stored in the TOC.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::common::CanonicalEncoding;
+
+/// Default number of frames sampled when training a dictionary.
+pub const DEFAULT_DICTIONARY_SAMPLES: usize = 1000;
+/// Default upper bound on a trained dictionary's size.
+pub const DEFAULT_DICTIONARY_SIZE: usize = 16 * 1024;
+
+/// Encoder choices and dictionaries persisted with the memory.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompressionSettings {
+    /// Encoding used for puts to a track that don't request one explicitly.
+    #[serde(default)]
+    pub track_encodings: BTreeMap<String, CanonicalEncoding>,
+    /// Trained dictionaries, oldest first.
+    #[serde(default)]
+    pub dictionaries: Vec<CompressionDictionaryManifest>,
+}
+
+/// Location of a trained zstd dictionary segment.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompressionDictionaryManifest {
+    /// Zstd dictionary id, also recorded in the header of every frame encoded with it.
+    pub dictionary_id: u32,
+    /// Track the samples were drawn from; `None` for a dictionary trained on every track.
+    #[serde(default)]
+    pub track: Option<String>,
+    pub sample_count: u64,
+    pub bytes_offset: u64,
+    pub bytes_length: u64,
+    pub checksum: [u8; 32],
+}
+
+/// Parameters for [`Memvid::train_dictionary`](crate::Memvid::train_dictionary).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DictionaryOptions {
+    /// Only sample frames from this track, and use the dictionary for puts to it.
+    pub track: Option<String>,
+    /// Maximum number of frames sampled, newest first.
+    pub max_samples: usize,
+    /// Maximum dictionary size in bytes.
+    pub max_size: usize,
+}
+
+impl Default for DictionaryOptions {
+    fn default() -> Self {
+        Self {
+            track: None,
+            max_samples: DEFAULT_DICTIONARY_SAMPLES,
+            max_size: DEFAULT_DICTIONARY_SIZE,
+        }
+    }
+}
+
+impl DictionaryOptions {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn track<T: Into<String>>(mut self, track: T) -> Self {
+        self.track = Some(track.into());
+        self
+    }
+
+    #[must_use]
+    pub fn max_samples(mut self, value: usize) -> Self {
+        self.max_samples = value;
+        self
+    }
+
+    #[must_use]
+    pub fn max_size(mut self, value: usize) -> Self {
+        self.max_size = value;
+        self
+    }
+}
+
+/// Stored and decoded bytes of active frames using one canonical encoding.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EncodingStats {
+    pub encoding: CanonicalEncoding,
+    pub frames: u64,
+    pub payload_bytes: u64,
+    pub logical_bytes: u64,
+}
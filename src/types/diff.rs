@@ -0,0 +1,146 @@
+//! Types describing a structural diff between two memories.
+
+use std::fmt::Write as _;
+
+use serde::{Deserialize, Serialize};
+
+use super::{EntityKind, FrameId, LinkType, MemoryCard};
+
+/// Identifies a frame in a diff without reading its payload.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiffFrame {
+    pub frame_id: FrameId,
+    #[serde(default)]
+    pub uri: Option<String>,
+    #[serde(default)]
+    pub title: Option<String>,
+    /// BLAKE3 checksum of the payload, hex encoded.
+    pub checksum: String,
+}
+
+/// A frame that became superseded between the two sides.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SupersededFrame {
+    #[serde(flatten)]
+    pub frame: DiffFrame,
+    #[serde(default)]
+    pub superseded_by: Option<FrameId>,
+}
+
+/// A single scalar field that differs between the two sides of a frame.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldChange {
+    /// Field name; extra metadata keys are reported as `extra_metadata.<key>`.
+    pub field: String,
+    #[serde(default)]
+    pub before: Option<String>,
+    #[serde(default)]
+    pub after: Option<String>,
+}
+
+/// Tag, label and metadata changes on a frame present on both sides.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FrameMetadataChange {
+    pub frame_id: FrameId,
+    #[serde(default)]
+    pub tags_added: Vec<String>,
+    #[serde(default)]
+    pub tags_removed: Vec<String>,
+    #[serde(default)]
+    pub labels_added: Vec<String>,
+    #[serde(default)]
+    pub labels_removed: Vec<String>,
+    #[serde(default)]
+    pub fields: Vec<FieldChange>,
+}
+
+/// A Logic-Mesh entity identified by its canonical name and kind.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiffMeshNode {
+    pub canonical_name: String,
+    pub display_name: String,
+    pub kind: EntityKind,
+}
+
+/// A Logic-Mesh relationship identified by its endpoints' canonical names.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiffMeshEdge {
+    pub from: String,
+    pub to: String,
+    pub link: LinkType,
+}
+
+/// Structural differences between two memories, or two generations of one memory.
+///
+/// Frames are matched by id and compared through their TOC entries and checksums, so
+/// building a diff never reads or decompresses payloads.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MemoryDiff {
+    /// Frames on the new side whose id does not exist on the old side.
+    pub frames_added: Vec<DiffFrame>,
+    /// Frames that were deleted (or are missing) on the new side.
+    pub frames_deleted: Vec<DiffFrame>,
+    /// Frames that were active on the old side and superseded on the new side.
+    pub frames_superseded: Vec<SupersededFrame>,
+    /// Frames whose payload checksum differs between the two sides.
+    pub frames_modified: Vec<DiffFrame>,
+    /// Frames whose tags, labels or metadata differ between the two sides.
+    pub metadata_changed: Vec<FrameMetadataChange>,
+    /// Memory cards that only exist on the new side.
+    pub cards_added: Vec<MemoryCard>,
+    /// New cards that retract an earlier value.
+    pub cards_retracted: Vec<MemoryCard>,
+    /// Cards that existed on the old side but are gone on the new side.
+    pub cards_removed: Vec<MemoryCard>,
+    pub mesh_nodes_added: Vec<DiffMeshNode>,
+    pub mesh_nodes_removed: Vec<DiffMeshNode>,
+    pub mesh_edges_added: Vec<DiffMeshEdge>,
+    pub mesh_edges_removed: Vec<DiffMeshEdge>,
+}
+
+impl MemoryDiff {
+    /// Returns true when both sides are structurally identical.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.frames_added.is_empty()
+            && self.frames_deleted.is_empty()
+            && self.frames_superseded.is_empty()
+            && self.frames_modified.is_empty()
+            && self.metadata_changed.is_empty()
+            && self.cards_added.is_empty()
+            && self.cards_retracted.is_empty()
+            && self.cards_removed.is_empty()
+            && self.mesh_nodes_added.is_empty()
+            && self.mesh_nodes_removed.is_empty()
+            && self.mesh_edges_added.is_empty()
+            && self.mesh_edges_removed.is_empty()
+    }
+
+    /// Human-readable summary, one line per kind of change.
+    #[must_use]
+    pub fn summary(&self) -> String {
+        if self.is_empty() {
+            return "no changes".to_string();
+        }
+        let mut out = String::new();
+        let mut line = |label: &str, count: usize| {
+            if count > 0 {
+                let _ = writeln!(out, "{label}: {count}");
+            }
+        };
+        line("frames added", self.frames_added.len());
+        line("frames deleted", self.frames_deleted.len());
+        line("frames superseded", self.frames_superseded.len());
+        line("frames modified", self.frames_modified.len());
+        line("frames with metadata changes", self.metadata_changed.len());
+        line("memory cards added", self.cards_added.len());
+        line("memory cards retracted", self.cards_retracted.len());
+        line("memory cards removed", self.cards_removed.len());
+        line("mesh nodes added", self.mesh_nodes_added.len());
+        line("mesh nodes removed", self.mesh_nodes_removed.len());
+        line("mesh edges added", self.mesh_edges_added.len());
+        line("mesh edges removed", self.mesh_edges_removed.len());
+        out.pop();
+        out
+    }
+}
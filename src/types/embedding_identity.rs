@@ -1,5 +1,7 @@
 use std::collections::BTreeMap;
 
+use serde::{Deserialize, Serialize};
+
 /// Frame-level embedding metadata keys (stored in `Frame.extra_metadata`).
 ///
 /// These are intentionally persisted per-frame (instead of in the TOC schema) to avoid
@@ -14,7 +16,7 @@ pub const MEMVID_EMBEDDING_NORMALIZED_KEY: &str = "memvid.embedding.normalized";
 ///
 /// Dimensions alone are not sufficient to guarantee compatibility (multiple models can share a
 /// dimension), so production-safe auto-detection should prefer `provider` + `model` when present.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct EmbeddingIdentity {
     pub provider: Option<Box<str>>,
     pub model: Option<Box<str>>,
@@ -18,6 +18,15 @@ use super::{
 
 // Note: AnchorSource is always defined (not feature-gated) to maintain binary compatibility
 
+/// The version of a frame a conditional update or delete expects to replace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameVersion {
+    /// The newest version is this frame.
+    Head(FrameId),
+    /// The newest version's payload has this checksum.
+    Checksum([u8; 32]),
+}
+
 /// Timeline query parameters for scanning frames chronologically or in reverse.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TimelineQuery {
@@ -142,6 +151,23 @@ pub struct Stats {
     /// Number of CLIP visual embeddings (images/PDF pages)
     #[serde(default)]
     pub clip_image_count: u64,
+    /// Active frames grouped by canonical encoding.
+    #[serde(default)]
+    pub encodings: Vec<super::EncodingStats>,
+    /// Bytes held by trained compression dictionaries.
+    #[serde(default)]
+    pub dictionary_bytes: u64,
+    /// Distinct chunks in the blob store; their stored bytes are part of `payload_bytes`.
+    #[serde(default)]
+    pub blob_chunk_count: u64,
+    #[serde(default)]
+    pub blob_stored_bytes: u64,
+    /// Decoded bytes of the distinct chunks.
+    #[serde(default)]
+    pub blob_raw_bytes: u64,
+    /// Logical bytes of active `Chunked` frames per distinct chunk byte; 1.0 without sharing.
+    #[serde(default)]
+    pub dedup_ratio: f64,
 }
 
 /// Entry returned by `timeline` queries, carrying a lightweight preview.
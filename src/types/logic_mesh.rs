@@ -537,6 +537,28 @@ impl LogicMesh {
         }
     }
 
+    /// Forget everything learned from a frame: its mentions, the edges detected in it, and
+    /// nodes that no other frame mentions.
+    pub fn remove_frame(&mut self, frame_id: FrameId) {
+        for node in &mut self.nodes {
+            node.frame_ids.retain(|id| *id != frame_id);
+            node.mentions.retain(|(id, _, _)| *id != frame_id);
+        }
+        let orphaned: HashSet<u64> = self
+            .nodes
+            .iter()
+            .filter(|node| node.frame_ids.is_empty())
+            .map(|node| node.id)
+            .collect();
+        self.nodes.retain(|node| !orphaned.contains(&node.id));
+        self.edges.retain(|edge| {
+            edge.frame_id != frame_id
+                && !orphaned.contains(&edge.from_node)
+                && !orphaned.contains(&edge.to_node)
+        });
+        self.build_adjacency();
+    }
+
     /// Prepare the mesh for serialization (sort and rebuild adjacency).
     pub fn finalize(&mut self) {
         self.nodes.sort_by_key(|n| n.id);
@@ -7,6 +7,7 @@ use serde::{
 };
 
 use super::{common::FrameId, frame::Frame, ticket::TicketRef};
+use crate::frame_table::FrameTable;
 
 use std::{fmt, marker::PhantomData};
 
@@ -78,11 +79,11 @@ where
     deserialize_vec_bounded::<D, SegmentMeta, MAX_TOC_SEGMENTS>(deserializer)
 }
 
-fn deserialize_toc_frames<'de, D>(deserializer: D) -> Result<Vec<Frame>, D::Error>
+fn deserialize_toc_frames<'de, D>(deserializer: D) -> Result<FrameTable, D::Error>
 where
     D: serde::Deserializer<'de>,
 {
-    deserialize_vec_bounded::<D, Frame, MAX_TOC_FRAMES>(deserializer)
+    deserialize_vec_bounded::<D, Frame, MAX_TOC_FRAMES>(deserializer).map(FrameTable::from)
 }
 
 fn deserialize_catalog_lex<'de, D>(deserializer: D) -> Result<Vec<LexSegmentDescriptor>, D::Error>
@@ -730,6 +731,95 @@ impl Default for VectorCompression {
     }
 }
 
+/// Distance function used to rank vector and CLIP hits.
+///
+/// Indexes written before the metric was recorded always used L2.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DistanceMetric {
+    /// `1 - cos(a, b)`; matches normalized embedding models (MV2 spec default).
+    #[default]
+    Cosine,
+    /// Negated inner product, for models trained for maximum inner product search.
+    Dot,
+    /// Euclidean distance.
+    L2,
+}
+
+impl DistanceMetric {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Cosine => "cosine",
+            Self::Dot => "dot",
+            Self::L2 => "l2",
+        }
+    }
+
+    /// Distance between two vectors (lower is more similar).
+    pub fn distance(self, a: &[f32], b: &[f32]) -> f32 {
+        match self {
+            Self::Cosine => {
+                let (mut dot, mut norm_a, mut norm_b) = (0.0f32, 0.0f32, 0.0f32);
+                for (x, y) in a.iter().zip(b.iter()) {
+                    dot += x * y;
+                    norm_a += x * x;
+                    norm_b += y * y;
+                }
+                cosine_distance_from_parts(dot, norm_a, norm_b)
+            }
+            Self::Dot => -a.iter().zip(b.iter()).map(|(x, y)| x * y).sum::<f32>(),
+            Self::L2 => a
+                .iter()
+                .zip(b.iter())
+                .map(|(x, y)| (x - y).powi(2))
+                .sum::<f32>()
+                .sqrt(),
+        }
+    }
+
+    /// Converts a distance produced by [`Self::distance`] into a similarity
+    /// score (higher is more similar) as reported in `SearchHit::score`.
+    ///
+    /// Cosine yields the cosine similarity, dot the raw inner product and L2
+    /// `1 / (1 + distance)`.
+    pub fn similarity(self, distance: f32) -> f32 {
+        match self {
+            Self::Cosine => 1.0 - distance,
+            Self::Dot => -distance,
+            Self::L2 => 1.0 / (1.0 + distance.max(0.0)),
+        }
+    }
+}
+
+/// Cosine distance from a dot product and squared norms; zero vectors are
+/// treated as orthogonal to everything.
+pub(crate) fn cosine_distance_from_parts(dot: f32, norm_a_sq: f32, norm_b_sq: f32) -> f32 {
+    let denom = (norm_a_sq * norm_b_sq).sqrt();
+    if denom <= f32::EPSILON {
+        return 1.0;
+    }
+    1.0 - dot / denom
+}
+
+impl std::fmt::Display for DistanceMetric {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl std::str::FromStr for DistanceMetric {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "cosine" | "cos" => Ok(Self::Cosine),
+            "dot" | "ip" | "inner-product" | "inner_product" => Ok(Self::Dot),
+            "l2" | "euclidean" => Ok(Self::L2),
+            _ => Err(format!("Unknown distance metric: {}", s)),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VecIndexManifest {
     pub vector_count: u64,
@@ -740,6 +830,33 @@ pub struct VecIndexManifest {
     /// Compression mode for vector storage (default: None for backward compatibility)
     #[serde(default)]
     pub compression_mode: VectorCompression,
+    /// Distance metric the index was built for.
+    #[serde(default)]
+    pub metric: DistanceMetric,
+    /// HNSW graph persisted right after the vectors (absent for small or PQ indexes).
+    #[serde(default)]
+    pub hnsw: Option<HnswGraphManifest>,
+}
+
+impl VecIndexManifest {
+    /// End offset of the vector bytes and, if present, the trailing HNSW graph.
+    pub fn end_offset(&self) -> u64 {
+        let vectors_end = self.bytes_offset.saturating_add(self.bytes_length);
+        self.hnsw.as_ref().map_or(vectors_end, |graph| {
+            vectors_end.max(graph.bytes_offset.saturating_add(graph.bytes_length))
+        })
+    }
+}
+
+/// Location and build parameters of a persisted HNSW graph.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HnswGraphManifest {
+    pub bytes_offset: u64,
+    pub bytes_length: u64,
+    pub checksum: [u8; 32],
+    pub node_count: u64,
+    pub m: u32,
+    pub ef_construction: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -775,6 +892,18 @@ pub struct Header {
     pub wal_checkpoint_pos: u64,
     pub wal_sequence: u64,
     pub toc_checksum: [u8; 32],
+    /// Present when payloads, WAL entries, indexes and the TOC are encrypted.
+    #[serde(default)]
+    pub encryption: Option<HeaderEncryption>,
+}
+
+/// Key-derivation parameters of an encrypted-mode memory, stored in the header padding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HeaderEncryption {
+    /// Random salt mixed into every region key.
+    pub salt: [u8; 16],
+    /// Derived from the master key and salt; rejects a wrong key before any region is read.
+    pub key_check: [u8; 32],
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -782,8 +911,10 @@ pub struct Toc {
     pub toc_version: u64,
     #[serde(deserialize_with = "deserialize_toc_segments")]
     pub segments: Vec<SegmentMeta>,
+    /// Frames indexed by id. Inline for legacy TOCs; otherwise stored in the
+    /// segment described by `frame_table` and loaded on demand.
     #[serde(deserialize_with = "deserialize_toc_frames")]
-    pub frames: Vec<Frame>,
+    pub frames: FrameTable,
     pub indexes: IndexManifests,
     pub time_index: Option<TimeIndexManifest>,
     /// Always present for backwards compatibility, even if feature is disabled.
@@ -812,7 +943,134 @@ pub struct Toc {
     /// Tracks frames needing background Phase 2 work (full extraction + embeddings).
     #[serde(default)]
     pub enrichment_queue: EnrichmentQueueManifest,
+    /// Paged frame table segment holding `frames` (absent for legacy inline TOCs).
+    #[serde(default)]
+    pub frame_table: Option<FrameTableManifest>,
+    /// Unix timestamp (seconds) of the commit that produced this TOC.
+    #[serde(default)]
+    pub committed_at: Option<i64>,
+    /// Ed25519 signature of the commit, when it was made with a signing key.
+    #[serde(default)]
+    pub commit_signature: Option<CommitSignature>,
+    /// Retention rules evaluated on every commit.
+    #[serde(default)]
+    pub retention_rules: Vec<super::RetentionRule>,
+    /// Capacity quotas enforced on every put.
+    #[serde(default)]
+    pub quotas: super::QuotaSettings,
+    /// Per-track canonical encodings and trained zstd dictionaries.
+    #[serde(default)]
+    pub compression: super::CompressionSettings,
+    /// Chunk index of the content-addressed blob store.
+    #[serde(default)]
+    pub blob_store: Option<super::BlobStoreManifest>,
+    /// Segment holding the committed mutations for change-data-capture consumers.
+    #[serde(default)]
+    pub change_log: Option<super::ChangeLogManifest>,
+    /// Size of the WAL region when this TOC was written, so older generations can be
+    /// relocated after the WAL grows. Zero for TOCs written before it was recorded.
+    #[serde(default)]
+    pub wal_size: u64,
+    pub merkle_root: [u8; 32],
+    pub toc_checksum: [u8; 32],
+}
+
+/// Signature recorded by a signed commit.
+///
+/// Signs the previous generation's signature, `merkle_root`, the checksum of the TOC
+/// encoded without this signature, and `committed_at`, chaining generations together.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CommitSignature {
+    /// Ed25519 public key of the signer.
+    pub public_key: [u8; 32],
+    /// 64-byte Ed25519 signature.
+    pub signature: Vec<u8>,
+    /// Signature of the previous generation, absent when the chain starts here.
+    pub previous_signature: Option<Vec<u8>>,
+}
+
+/// Location of the paged frame table.
+///
+/// Every page of [`FRAMES_PER_PAGE`](crate::frame_table::FRAMES_PER_PAGE) frames is
+/// its own segment. A commit writes the pages it changed as one run immediately
+/// before the TOC and keeps pointing at unchanged pages of earlier generations.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct FrameTableManifest {
+    /// Start of the pages written by this commit.
+    pub bytes_offset: u64,
+    /// Length of the pages written by this commit; zero when none changed.
+    pub bytes_length: u64,
+    pub frame_count: u64,
+    /// End of the furthest frame payload, so `data_end` can be computed without
+    /// loading every page.
+    pub payload_end: u64,
+    pub pages: Vec<FrameTablePage>,
+}
+
+impl FrameTableManifest {
+    /// End of the furthest page, wherever it was written.
+    #[must_use]
+    pub fn pages_end(&self) -> u64 {
+        self.pages
+            .iter()
+            .map(FrameTablePage::end)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Moves the table along with the data after the WAL grew by `delta` bytes.
+    pub(crate) fn shift(&mut self, delta: u64) {
+        self.bytes_offset += delta;
+        if self.payload_end != 0 {
+            self.payload_end += delta;
+        }
+        for page in &mut self.pages {
+            page.shift(delta);
+        }
+    }
+}
+
+/// One page segment of the frame table.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameTablePage {
+    pub bytes_offset: u64,
+    pub bytes_length: u64,
+    /// Added to every nonzero payload offset decoded from the page, so growing the
+    /// WAL moves payloads without rewriting the pages that point at them.
+    pub payload_shift: u64,
+    /// End of the furthest payload in the page, zero if there is none.
+    pub payload_end: u64,
+    pub checksum: [u8; 32],
+    /// Root of the Merkle subtree over the page's frames, so a commit only hashes
+    /// the pages it rewrites.
     pub merkle_root: [u8; 32],
+}
+
+impl FrameTablePage {
+    #[must_use]
+    pub fn end(&self) -> u64 {
+        self.bytes_offset + self.bytes_length
+    }
+
+    pub(crate) fn shift(&mut self, delta: u64) {
+        self.bytes_offset += delta;
+        self.payload_shift += delta;
+        if self.payload_end != 0 {
+            self.payload_end += delta;
+        }
+    }
+}
+
+/// A committed generation still present in the file, as listed by `Memvid::list_generations`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct GenerationInfo {
+    pub generation: u64,
+    /// Unix timestamp of the commit, when recorded by the writer.
+    pub committed_at: Option<i64>,
+    /// Frames in the generation's frame table, including deleted ones.
+    pub frame_count: u64,
+    /// Absolute offset of the TOC bytes.
+    pub toc_offset: u64,
     pub toc_checksum: [u8; 32],
 }
 
@@ -4,7 +4,7 @@
 //! extracted memory cards along with indices for fast lookup and enrichment
 //! tracking metadata.
 
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
 use serde::{Deserialize, Serialize};
 
@@ -288,6 +288,23 @@ impl MemoriesTrack {
         id
     }
 
+    /// Rebuild a track from cards that already carry their ids, e.g. from an archive.
+    ///
+    /// Card ids are kept as-is and new cards continue after the highest one.
+    #[must_use]
+    pub fn from_cards(cards: Vec<MemoryCard>, enrichment_manifest: EnrichmentManifest) -> Self {
+        let mut slot_index = SlotIndex::new();
+        for card in &cards {
+            slot_index.insert(card);
+        }
+        Self {
+            next_id: cards.iter().map(|card| card.id + 1).max().unwrap_or(0),
+            cards,
+            slot_index,
+            enrichment_manifest,
+        }
+    }
+
     /// Add multiple cards at once.
     pub fn add_cards(&mut self, cards: Vec<MemoryCard>) -> Vec<MemoryCardId> {
         cards.into_iter().map(|c| self.add_card(c)).collect()
@@ -299,6 +316,25 @@ impl MemoriesTrack {
         self.cards.iter().find(|c| c.id == id)
     }
 
+    /// Remove every card extracted from one of `frame_ids`, returning the removed ids.
+    pub fn remove_cards_from_frames(&mut self, frame_ids: &BTreeSet<FrameId>) -> Vec<MemoryCardId> {
+        let mut removed = Vec::new();
+        self.cards.retain(|card| {
+            let keep = !frame_ids.contains(&card.source_frame_id);
+            if !keep {
+                removed.push(card.id);
+            }
+            keep
+        });
+        if !removed.is_empty() {
+            self.slot_index.clear();
+            for card in &self.cards {
+                self.slot_index.insert(card);
+            }
+        }
+        removed
+    }
+
     /// Get all cards.
     #[must_use]
     pub fn cards(&self) -> &[MemoryCard] {
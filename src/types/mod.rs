@@ -4,7 +4,11 @@ pub mod adaptive;
 pub mod ask;
 pub mod audit;
 pub mod binding;
+pub mod blob_store;
+pub mod change_log;
 pub mod common;
+pub mod compression;
+pub mod diff;
 pub mod embedding;
 pub mod embedding_identity;
 pub mod frame;
@@ -15,7 +19,9 @@ pub mod memories_track;
 pub mod memory_card;
 pub mod metadata;
 pub mod options;
+pub mod quota;
 pub mod reranker;
+pub mod retention;
 pub mod schema;
 pub mod search;
 pub mod sketch_track;
@@ -29,24 +35,38 @@ pub use ask::{
     AskCitation, AskContextFragment, AskContextFragmentKind, AskMode, AskRequest, AskResponse,
     AskRetriever, AskStats, VecEmbedder,
 };
-pub use audit::{AuditOptions, AuditReport, SourceSpan};
+pub use audit::{AuditOptions, AuditReport, FrameProof, SourceSpan};
 pub use binding::{FileInfo, MemoryBinding};
+pub use blob_store::{BlobChunk, BlobStore, BlobStoreManifest, ChunkHash};
+pub use change_log::{
+    ChangeBatch, ChangeEvent, ChangeKind, ChangeLog, ChangeLogManifest, DEFAULT_CHANGE_LOG_HORIZON,
+    MeshEdgeId,
+};
 pub use common::{
     CanonicalEncoding, EnrichmentState, EnrichmentTask, FrameId, FrameRole, FrameStatus,
     MemvidHandle, Open, Sealed, Tier,
 };
 // AnchorSource always exported - not feature-gated to maintain binary compatibility
+pub use compression::{
+    CompressionDictionaryManifest, CompressionSettings, DictionaryOptions, EncodingStats,
+};
+pub use diff::{
+    DiffFrame, DiffMeshEdge, DiffMeshNode, FieldChange, FrameMetadataChange, MemoryDiff,
+    SupersededFrame,
+};
 pub use frame::AnchorSource;
-pub use frame::{Frame, Stats, TimelineEntry, TimelineQuery, TimelineQueryBuilder};
+pub use frame::{Frame, FrameVersion, Stats, TimelineEntry, TimelineQuery, TimelineQueryBuilder};
 // Serialized manifest types - always exported for binary compatibility
 pub use manifest::TemporalSegmentDescriptor;
 pub use manifest::TemporalTrackManifest;
 pub use manifest::{
-    EnrichmentQueueManifest, Header, IndexManifests, IndexSegmentRef, LexIndexManifest,
-    LexSegmentDescriptor, LexSegmentManifest, LogicMeshManifest, MemoriesTrackManifest,
-    SegmentCatalog, SegmentCommon, SegmentCompression, SegmentKind, SegmentMeta, SegmentSpan,
-    SegmentStats, SketchTrackManifest, TantivySegmentDescriptor, TimeIndexManifest,
-    TimeSegmentDescriptor, Toc, VecIndexManifest, VecSegmentDescriptor, VectorCompression,
+    CommitSignature, DistanceMetric, EnrichmentQueueManifest, FrameTableManifest, FrameTablePage,
+    GenerationInfo, Header, HeaderEncryption, HnswGraphManifest, IndexManifests, IndexSegmentRef,
+    LexIndexManifest, LexSegmentDescriptor, LexSegmentManifest, LogicMeshManifest,
+    MemoriesTrackManifest, SegmentCatalog, SegmentCommon, SegmentCompression, SegmentKind,
+    SegmentMeta, SegmentSpan, SegmentStats, SketchTrackManifest, TantivySegmentDescriptor,
+    TimeIndexManifest, TimeSegmentDescriptor, Toc, VecIndexManifest, VecSegmentDescriptor,
+    VectorCompression,
 };
 // Logic-Mesh types for entity-relationship graph traversal
 pub use logic_mesh::{
@@ -57,7 +77,12 @@ pub use metadata::{
     AudioSegmentMetadata, DocAudioMetadata, DocExifMetadata, DocGpsMetadata, DocMetadata,
     MediaManifest, TextChunkManifest, TextChunkRange,
 };
-pub use options::{PutManyOpts, PutOptions, PutOptionsBuilder, PutRequest};
+pub use options::{MergeOptions, PutManyOpts, PutOptions, PutOptionsBuilder, PutRequest};
+pub use quota::{PutPreflight, QuotaSettings};
+pub use retention::{
+    MEMVID_TOMBSTONE_REASON_KEY, RetentionCandidate, RetentionReason, RetentionReport,
+    RetentionRule, RetentionSelector,
+};
 pub use search::{
     SearchEngineKind, SearchHit, SearchHitEntity, SearchHitMetadata, SearchParams, SearchRequest,
     SearchResponse,
@@ -72,11 +97,13 @@ pub use temporal::{
 };
 pub use ticket::{Ticket, TicketRef};
 pub use verification::{
+    ArchiveReport, CommitSignatureFailure, CommitSignatureIssue, CommitSignatureReport,
     DOCTOR_PLAN_VERSION, DoctorActionDetail, DoctorActionKind, DoctorActionPlan,
     DoctorActionReport, DoctorActionStatus, DoctorFinding, DoctorFindingCode, DoctorMetrics,
     DoctorOptions, DoctorPhaseDuration, DoctorPhaseKind, DoctorPhasePlan, DoctorPhaseReport,
-    DoctorPhaseStatus, DoctorPlan, DoctorReport, DoctorSeverity, DoctorStatus, VerificationCheck,
-    VerificationReport, VerificationStatus,
+    DoctorPhaseStatus, DoctorPlan, DoctorReport, DoctorSeverity, DoctorStatus, ErasureReceipt,
+    MergeReport, VacuumPhase, VacuumProgress, VacuumReport, VerificationCheck, VerificationReport,
+    VerificationStatus, VerifyOptions,
 };
 // Memory card types for structured memory extraction
 pub use memories_track::{
@@ -5,7 +5,7 @@ use std::collections::BTreeMap;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use super::common::{FrameId, FrameRole};
+use super::common::{CanonicalEncoding, FrameId, FrameRole};
 use super::metadata::DocMetadata;
 
 fn default_true() -> bool {
@@ -71,6 +71,10 @@ pub struct PutOptions {
     /// Default: 350ms (optimized for sub-second total ingestion).
     #[serde(default = "default_extraction_budget_ms")]
     pub extraction_budget_ms: u64,
+    /// Canonical encoding for the payload, overriding the track's encoding.
+    /// `None` uses the track's encoding, or zstd for UTF-8 and plain bytes otherwise.
+    #[serde(default)]
+    pub encoding: Option<CanonicalEncoding>,
 }
 
 fn default_extraction_budget_ms() -> u64 {
@@ -101,6 +105,7 @@ impl Default for PutOptions {
             dedup: false,
             instant_index: true, // Instant searchability by default
             extraction_budget_ms: default_extraction_budget_ms(),
+            encoding: None,
         }
     }
 }
@@ -248,6 +253,12 @@ impl PutOptionsBuilder {
         self
     }
 
+    /// Encode the payload with `encoding` instead of the track's encoding.
+    pub fn encoding(mut self, encoding: CanonicalEncoding) -> Self {
+        self.inner.encoding = Some(encoding);
+        self
+    }
+
     pub fn build(self) -> PutOptions {
         self.inner
     }
@@ -354,3 +365,12 @@ impl Default for PutManyOpts {
         }
     }
 }
+
+/// Options for [`Memvid::merge_from`](crate::Memvid::merge_from).
+#[derive(Debug, Clone, Default)]
+pub struct MergeOptions {
+    /// Skip frames whose BLAKE3 checksum matches an active frame already in the target,
+    /// as [`PutOptions::dedup`] does. References to a skipped frame are redirected to the
+    /// existing one.
+    pub dedup: bool,
+}
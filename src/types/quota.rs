@@ -0,0 +1,63 @@
+//! Capacity quotas stored in the TOC, and the estimate returned before a put.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Capacity limits enforced on every put.
+///
+/// A ticket's capacity takes precedence over `capacity_bytes`; without either, the tier
+/// capacity applies.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuotaSettings {
+    /// Total capacity in bytes, measured up to the end of the payload region.
+    pub capacity_bytes: Option<u64>,
+    /// Stored payload bytes allowed for active frames of each track.
+    pub track_bytes: BTreeMap<String, u64>,
+    /// Maximum number of active frames, document chunks included.
+    pub max_frames: Option<u64>,
+}
+
+impl QuotaSettings {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn capacity_bytes(mut self, value: u64) -> Self {
+        self.capacity_bytes = Some(value);
+        self
+    }
+
+    #[must_use]
+    pub fn track_bytes<T: Into<String>>(mut self, track: T, value: u64) -> Self {
+        self.track_bytes.insert(track.into(), value);
+        self
+    }
+
+    #[must_use]
+    pub fn max_frames(mut self, value: u64) -> Self {
+        self.max_frames = Some(value);
+        self
+    }
+}
+
+/// Estimate returned by [`Memvid::preflight_put`](crate::Memvid::preflight_put).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PutPreflight {
+    /// Payload size the estimate was made for.
+    pub size: u64,
+    /// Bytes counted against the capacity.
+    pub used_bytes: u64,
+    /// Effective capacity: the ticket's, the quota's, or the tier's.
+    pub capacity_bytes: u64,
+    /// Bytes left after the put, or zero if it would not fit.
+    pub remaining_bytes: u64,
+    /// Active frames counted against `max_frames`, pending inserts included.
+    pub frame_count: u64,
+    /// Frame limit from the quota settings.
+    pub max_frames: Option<u64>,
+    /// Whether a put of `size` bytes would be accepted.
+    pub fits: bool,
+}
@@ -0,0 +1,231 @@
+//! Declarative retention rules and the reports produced when evaluating them.
+//!
+//! Rules are stored in the TOC and written as `selector=value` followed by one or more
+//! limits, e.g. `track=chat max_age=30d` or `tag=scratch max_count=1000`.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use super::{FrameId, MemoryCardId};
+
+/// Extra-metadata key recording why a frame was tombstoned.
+pub const MEMVID_TOMBSTONE_REASON_KEY: &str = "memvid.tombstone.reason";
+
+/// Frames a retention rule applies to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RetentionSelector {
+    /// Frames whose track equals the value.
+    Track(String),
+    /// Frames carrying the tag.
+    Tag(String),
+    /// Frames whose kind equals the value.
+    Kind(String),
+}
+
+impl RetentionSelector {
+    fn matches(&self, track: Option<&str>, kind: Option<&str>, tags: &[String]) -> bool {
+        match self {
+            Self::Track(value) => track == Some(value.as_str()),
+            Self::Kind(value) => kind == Some(value.as_str()),
+            Self::Tag(value) => tags.iter().any(|tag| tag == value),
+        }
+    }
+}
+
+/// A retention rule: frames matching `selector` expire once they are older than `max_age`
+/// seconds, or once more than `max_count` newer matching frames exist.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetentionRule {
+    pub selector: RetentionSelector,
+    /// Maximum age in seconds, measured from the frame timestamp.
+    pub max_age: Option<u64>,
+    /// Maximum number of matching frames to keep, newest first.
+    pub max_count: Option<u64>,
+}
+
+impl RetentionRule {
+    /// Whether a frame with these attributes falls under the rule.
+    #[must_use]
+    pub fn matches(&self, track: Option<&str>, kind: Option<&str>, tags: &[String]) -> bool {
+        self.selector.matches(track, kind, tags)
+    }
+}
+
+fn parse_duration(value: &str) -> std::result::Result<u64, String> {
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (digits, unit) = value.split_at(split);
+    let amount: u64 = digits
+        .parse()
+        .map_err(|_| format!("invalid retention duration: {value}"))?;
+    let scale = match unit {
+        "" | "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => return Err(format!("unknown retention duration unit: {value}")),
+    };
+    amount
+        .checked_mul(scale)
+        .ok_or_else(|| format!("retention duration overflows: {value}"))
+}
+
+fn format_duration(seconds: u64) -> String {
+    let units = [
+        ("w", 7 * 24 * 60 * 60),
+        ("d", 24 * 60 * 60),
+        ("h", 60 * 60),
+        ("m", 60),
+    ];
+    for (unit, scale) in units {
+        if seconds > 0 && seconds % scale == 0 {
+            return format!("{}{unit}", seconds / scale);
+        }
+    }
+    format!("{seconds}s")
+}
+
+impl FromStr for RetentionRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let mut selector = None;
+        let mut max_age = None;
+        let mut max_count = None;
+        for part in s.split_whitespace() {
+            let (key, value) = part
+                .split_once('=')
+                .filter(|(_, value)| !value.is_empty())
+                .ok_or_else(|| format!("expected key=value in retention rule: {part}"))?;
+            let parsed = match key {
+                "track" => Some(RetentionSelector::Track(value.to_string())),
+                "tag" => Some(RetentionSelector::Tag(value.to_string())),
+                "kind" => Some(RetentionSelector::Kind(value.to_string())),
+                "max_age" => {
+                    max_age = Some(parse_duration(value)?);
+                    None
+                }
+                "max_count" => {
+                    max_count = Some(
+                        value
+                            .parse()
+                            .map_err(|_| format!("invalid retention count: {value}"))?,
+                    );
+                    None
+                }
+                _ => return Err(format!("unknown retention rule key: {key}")),
+            };
+            if let Some(parsed) = parsed {
+                if selector.replace(parsed).is_some() {
+                    return Err(format!("retention rule has more than one selector: {s}"));
+                }
+            }
+        }
+        let selector =
+            selector.ok_or_else(|| format!("retention rule needs track, tag or kind: {s}"))?;
+        if max_age.is_none() && max_count.is_none() {
+            return Err(format!("retention rule needs max_age or max_count: {s}"));
+        }
+        Ok(Self {
+            selector,
+            max_age,
+            max_count,
+        })
+    }
+}
+
+impl fmt::Display for RetentionRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.selector {
+            RetentionSelector::Track(value) => write!(f, "track={value}")?,
+            RetentionSelector::Tag(value) => write!(f, "tag={value}")?,
+            RetentionSelector::Kind(value) => write!(f, "kind={value}")?,
+        }
+        if let Some(max_age) = self.max_age {
+            write!(f, " max_age={}", format_duration(max_age))?;
+        }
+        if let Some(max_count) = self.max_count {
+            write!(f, " max_count={max_count}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Why retention removed a frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RetentionReason {
+    MaxAge,
+    MaxCount,
+}
+
+impl RetentionReason {
+    /// Reason code stored under [`MEMVID_TOMBSTONE_REASON_KEY`].
+    #[must_use]
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::MaxAge => "retention.max_age",
+            Self::MaxCount => "retention.max_count",
+        }
+    }
+}
+
+/// A frame removed, or due to be removed, by a retention rule.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetentionCandidate {
+    pub frame_id: FrameId,
+    #[serde(default)]
+    pub uri: Option<String>,
+    pub timestamp: i64,
+    /// The rule that matched, in its textual form.
+    pub rule: String,
+    pub reason: RetentionReason,
+}
+
+/// Outcome of evaluating the retention rules of a memory.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetentionReport {
+    /// True when nothing was removed and the report only lists what would be.
+    pub dry_run: bool,
+    /// Unix timestamp the rules were evaluated against.
+    pub evaluated_at: i64,
+    pub frames: Vec<RetentionCandidate>,
+    /// Memory cards derived from the listed frames.
+    pub memory_cards: Vec<MemoryCardId>,
+}
+
+impl RetentionReport {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rules_parse_and_roundtrip() {
+        let rule: RetentionRule = "track=chat max_age=30d".parse().expect("parse");
+        assert_eq!(rule.selector, RetentionSelector::Track("chat".into()));
+        assert_eq!(rule.max_age, Some(30 * 24 * 60 * 60));
+        assert_eq!(rule.to_string(), "track=chat max_age=30d");
+
+        let rule: RetentionRule = "tag=scratch max_count=1000 max_age=90m"
+            .parse()
+            .expect("parse");
+        assert_eq!(rule.max_count, Some(1000));
+        assert_eq!(rule.to_string(), "tag=scratch max_age=90m max_count=1000");
+
+        assert!("track=chat".parse::<RetentionRule>().is_err());
+        assert!("max_age=1d".parse::<RetentionRule>().is_err());
+        assert!("track=a tag=b max_age=1d".parse::<RetentionRule>().is_err());
+        assert!("track=chat max_age=1y".parse::<RetentionRule>().is_err());
+    }
+}
@@ -67,6 +67,27 @@ pub struct SearchRequest {
     pub no_sketch: bool,
 }
 
+impl SearchRequest {
+    /// Ten hits for `query` with 80-character snippets and no filters, for tests.
+    #[doc(hidden)]
+    #[must_use]
+    pub fn test_query(query: &str) -> Self {
+        Self {
+            query: query.to_string(),
+            top_k: 10,
+            snippet_chars: 80,
+            uri: None,
+            scope: None,
+            cursor: None,
+            #[cfg(feature = "temporal_track")]
+            temporal: None,
+            as_of_frame: None,
+            as_of_ts: None,
+            no_sketch: false,
+        }
+    }
+}
+
 /// A single ranked hit with snippet metadata.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchHit {
@@ -129,7 +129,7 @@ impl SketchVariant {
 }
 
 /// Feature flags for sketch entries.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub struct SketchFlags(u16);
 
 impl SketchFlags {
@@ -352,7 +352,7 @@ impl SketchEntryMedium {
 // ============================================================================
 
 /// Unified sketch entry that can represent any variant.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SketchEntry {
     /// Frame ID this sketch belongs to.
     pub frame_id: FrameId,
@@ -862,6 +862,21 @@ impl SketchTrack {
         self.entries.insert(frame_id, entry);
     }
 
+    /// Replace the sketch of a frame with an empty one.
+    ///
+    /// Entries are stored by position, so the slot is kept to leave later frames' sketches
+    /// where they are. Returns whether the frame had a sketch.
+    pub fn clear(&mut self, frame_id: FrameId) -> bool {
+        let variant = self.variant;
+        match self.entries.get_mut(&frame_id) {
+            Some(entry) => {
+                *entry = SketchEntry::new(frame_id, variant);
+                true
+            }
+            None => false,
+        }
+    }
+
     /// Get a sketch entry by frame ID.
     #[must_use]
     pub fn get(&self, frame_id: FrameId) -> Option<&SketchEntry> {
@@ -2,6 +2,9 @@
 
 use serde::{Deserialize, Serialize};
 
+/// Issuer of the placeholder ticket every memory starts with.
+pub(crate) const FREE_TIER_ISSUER: &str = "free-tier";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TicketRef {
     pub issuer: String,
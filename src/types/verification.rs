@@ -1,9 +1,12 @@
 //! Types describing verification and doctor workflows.
 
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 use serde::{Deserialize, Serialize};
 
+use super::{FrameId, MemoryCardId};
+
 /// User-provided preferences that influence how the doctor plans repair work.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct DoctorOptions {
@@ -19,6 +22,35 @@ pub struct DoctorOptions {
     pub dry_run: bool,
 }
 
+/// Options for [`Memvid::verify_with_options`](crate::Memvid::verify_with_options).
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct VerifyOptions {
+    /// Re-read every segment and compare it with its checksum.
+    #[serde(default)]
+    pub deep: bool,
+    /// Ed25519 public keys allowed to sign commits. When empty, the key of the oldest
+    /// signed generation is taken on trust and every later generation must use it.
+    #[serde(default)]
+    pub trusted_keys: Vec<[u8; 32]>,
+}
+
+impl VerifyOptions {
+    #[must_use]
+    pub fn new(deep: bool) -> Self {
+        Self {
+            deep,
+            trusted_keys: Vec::new(),
+        }
+    }
+
+    /// Accept commits signed by `key`.
+    #[must_use]
+    pub fn trusted_key(mut self, key: [u8; 32]) -> Self {
+        self.trusted_keys.push(key);
+        self
+    }
+}
+
 /// Version identifier embedded in `DoctorPlan` for compatibility checks.
 pub const DOCTOR_PLAN_VERSION: u32 = 1;
 
@@ -327,6 +359,55 @@ pub struct VerificationReport {
     pub checks: Vec<VerificationCheck>,
     /// Aggregate status across all checks.
     pub overall_status: VerificationStatus,
+    /// Signature chain over the generations still present in the file.
+    #[serde(default)]
+    pub commit_signatures: Option<CommitSignatureReport>,
+}
+
+/// Outcome of validating the commit signature chain.
+///
+/// The chain starts at the oldest signed generation still present in the file; every
+/// later generation must be signed and chain to its predecessor.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommitSignatureReport {
+    /// Generations inspected.
+    pub generations: u64,
+    /// Generations carrying a valid signature.
+    pub signed_generations: u64,
+    /// Oldest signed generation, where the chain starts.
+    pub first_signed_generation: Option<u64>,
+    /// Distinct public keys that signed the chain, in order of first use.
+    pub signers: Vec<[u8; 32]>,
+    /// Whether the signers were checked against caller-supplied trusted keys. When
+    /// false the chain only proves that one key signed it, not whose key it is.
+    #[serde(default)]
+    pub signers_trusted: bool,
+    /// First generation that breaks the chain, if any.
+    pub first_failure: Option<CommitSignatureFailure>,
+}
+
+/// Generation that breaks the commit signature chain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommitSignatureFailure {
+    pub generation: u64,
+    pub issue: CommitSignatureIssue,
+}
+
+/// Why a generation breaks the commit signature chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CommitSignatureIssue {
+    /// The generation follows a signed one but carries no signature.
+    Unsigned,
+    /// The signature does not match the generation's TOC.
+    Tampered,
+    /// The signature is valid but does not chain to the previous generation.
+    BrokenChain,
+    /// The signature is valid but its key is not one of the trusted keys.
+    UntrustedKey,
+    /// No trusted keys were given and the generation is signed by a different key than
+    /// the one before it.
+    SignerChanged,
 }
 
 /// Individual verification check outcome.
@@ -348,3 +429,115 @@ pub enum VerificationStatus {
     Failed,
     Skipped,
 }
+
+/// Stage reached by a running vacuum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VacuumPhase {
+    CopyPayloads,
+    RebuildIndexes,
+    Publish,
+}
+
+/// Progress snapshot passed to the callback of `vacuum_with_progress`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VacuumProgress {
+    pub phase: VacuumPhase,
+    /// Frames whose payloads have been visited so far.
+    pub frames_done: u64,
+    /// Frames in the table being compacted.
+    pub frames_total: u64,
+    /// Payload bytes written to the compacted copy so far.
+    pub bytes_copied: u64,
+}
+
+/// Summary returned once a vacuum has replaced the memory file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VacuumReport {
+    /// Frames whose payloads were carried over.
+    pub frames_retained: u64,
+    /// Superseded or deleted frames whose payloads were dropped.
+    pub frames_purged: u64,
+    /// Payload bytes written to the compacted file.
+    pub payload_bytes: u64,
+    /// File size before the vacuum.
+    pub bytes_before: u64,
+    /// File size after the vacuum.
+    pub bytes_after: u64,
+    /// `bytes_before - bytes_after`, saturating at zero.
+    pub bytes_reclaimed: u64,
+    /// Blob store chunks still referenced by an active frame.
+    #[serde(default)]
+    pub blob_chunks_retained: u64,
+    /// Blob store chunks no active frame referenced any more.
+    #[serde(default)]
+    pub blob_chunks_purged: u64,
+}
+
+/// Receipt returned by [`Memvid::erase_frame`](crate::Memvid::erase_frame).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErasureReceipt {
+    /// The frame whose erasure was requested.
+    pub frame_id: FrameId,
+    /// Every frame erased with it: its chunks and frames sharing its stored payload.
+    pub erased_frames: Vec<FrameId>,
+    /// BLAKE3 checksum of the erased payload, kept in the frame table as its Merkle leaf.
+    pub checksum: [u8; 32],
+    /// Unix timestamp of the erasure commit.
+    pub erased_at: i64,
+    /// Generation written by the erasure; the oldest one that can mention the frame.
+    pub generation: u64,
+    /// Earlier generations that mentioned the frame and were overwritten.
+    pub generations_overwritten: Vec<u64>,
+    /// Payload bytes overwritten with zeros.
+    pub payload_bytes_scrubbed: u64,
+    /// Bytes of earlier TOCs, frame tables, index segments and the WAL overwritten with zeros.
+    pub history_bytes_scrubbed: u64,
+    /// Memory cards extracted from the erased frames.
+    pub memory_cards_removed: Vec<MemoryCardId>,
+    /// Merkle root of the frame table after the erasure.
+    pub merkle_root: [u8; 32],
+}
+
+/// Summary returned by [`Memvid::merge_from`](crate::Memvid::merge_from).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MergeReport {
+    /// Frames copied into the target under new ids.
+    pub frames_merged: u64,
+    /// Frames skipped because the target already held identical content.
+    pub frames_deduplicated: u64,
+    /// Payload bytes appended to the target.
+    pub payload_bytes: u64,
+    /// Vector embeddings carried over.
+    pub embeddings_merged: u64,
+    /// CLIP entries carried over.
+    pub clip_entries_merged: u64,
+    /// Memory cards carried over.
+    pub cards_merged: u64,
+    /// Logic-Mesh nodes merged into the target mesh.
+    pub mesh_nodes_merged: u64,
+    /// Frame id in the source file mapped to its id in the target.
+    pub frame_ids: BTreeMap<FrameId, FrameId>,
+}
+
+/// Summary of an archive written by [`Memvid::export_archive`](crate::Memvid::export_archive)
+/// or read by [`Memvid::import_archive`](crate::Memvid::import_archive).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchiveReport {
+    /// Frames listed in `frames.jsonl`, in every status.
+    pub frames: u64,
+    /// Distinct payload blobs.
+    pub blobs: u64,
+    /// Total size of the payload blobs.
+    pub blob_bytes: u64,
+    /// Vector embeddings.
+    pub embeddings: u64,
+    /// CLIP entries.
+    pub clip_entries: u64,
+    /// Memory cards.
+    pub cards: u64,
+    /// Logic-Mesh nodes.
+    pub mesh_nodes: u64,
+    /// Replay sessions.
+    pub replay_sessions: u64,
+}
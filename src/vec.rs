@@ -1,7 +1,11 @@
 use blake3::hash;
 use serde::{Deserialize, Serialize};
 
-use crate::{MemvidError, Result, types::FrameId};
+use crate::vec_hnsw::{DEFAULT_EF_SEARCH, HnswGraph, MIN_VECTORS_FOR_HNSW};
+use crate::{
+    MemvidError, Result,
+    types::{DistanceMetric, FrameId},
+};
 
 fn vec_config() -> impl bincode::config::Config {
     bincode::config::standard()
@@ -75,7 +79,13 @@ pub struct VecIndexArtifact {
 
 #[derive(Debug, Clone)]
 pub enum VecIndex {
-    Uncompressed { documents: Vec<VecDocument> },
+    Uncompressed {
+        documents: Vec<VecDocument>,
+        /// Optional HNSW graph over a prefix of `documents`; documents past the
+        /// graph's length are scanned exhaustively. Documents whose node the
+        /// graph marks deleted keep their slot but are no longer searchable.
+        graph: Option<HnswGraph>,
+    },
     Compressed(crate::vec_pq::QuantizedVecIndex),
 }
 
@@ -112,7 +122,10 @@ impl VecIndex {
                     docs_count = documents.len(),
                     "decoded as uncompressed"
                 );
-                return Ok(Self::Uncompressed { documents });
+                return Ok(Self::Uncompressed {
+                    documents,
+                    graph: None,
+                });
             }
             Ok((_, read)) => {
                 tracing::debug!(
@@ -149,40 +162,148 @@ impl VecIndex {
         }
     }
 
+    /// Searches with L2 distance, the metric used by indexes that predate
+    /// [`DistanceMetric`].
     pub fn search(&self, query: &[f32], limit: usize) -> Vec<VecSearchHit> {
+        self.search_with_metric(query, limit, DistanceMetric::L2)
+    }
+
+    pub fn search_with_metric(
+        &self,
+        query: &[f32],
+        limit: usize,
+        metric: DistanceMetric,
+    ) -> Vec<VecSearchHit> {
+        self.search_with_ef(query, limit, metric, DEFAULT_EF_SEARCH)
+    }
+
+    /// Searches the index, using the HNSW graph when one built for `metric` is
+    /// attached.
+    ///
+    /// `ef_search` is the graph candidate pool size (clamped to at least
+    /// `limit`); it is ignored for exhaustive scans.
+    pub fn search_with_ef(
+        &self,
+        query: &[f32],
+        limit: usize,
+        metric: DistanceMetric,
+        ef_search: usize,
+    ) -> Vec<VecSearchHit> {
         if query.is_empty() {
             return Vec::new();
         }
         match self {
-            VecIndex::Uncompressed { documents } => {
+            VecIndex::Uncompressed {
+                documents,
+                graph: Some(graph),
+            } if graph.metric() == metric => {
+                let mut hits: Vec<VecSearchHit> = graph
+                    .search(
+                        |position| documents[position].embedding.as_slice(),
+                        query,
+                        limit,
+                        ef_search,
+                    )
+                    .into_iter()
+                    .map(|position| &documents[position])
+                    .chain(documents.iter().skip(graph.len()))
+                    .map(|doc| VecSearchHit {
+                        frame_id: doc.frame_id,
+                        distance: metric.distance(query, &doc.embedding),
+                    })
+                    .collect();
+                sort_hits(&mut hits);
+                hits.truncate(limit);
+                hits
+            }
+            VecIndex::Uncompressed { documents, graph } => {
                 let mut hits: Vec<VecSearchHit> = documents
                     .iter()
-                    .map(|doc| {
-                        let distance = l2_distance(query, &doc.embedding);
+                    .enumerate()
+                    .filter(|(position, _)| {
+                        !graph.as_ref().is_some_and(|g| g.is_deleted(*position))
+                    })
+                    .map(|(_, doc)| {
+                        let distance = metric.distance(query, &doc.embedding);
                         VecSearchHit {
                             frame_id: doc.frame_id,
                             distance,
                         }
                     })
                     .collect();
-                hits.sort_by(|a, b| {
-                    a.distance
-                        .partial_cmp(&b.distance)
-                        .unwrap_or(std::cmp::Ordering::Equal)
-                });
+                sort_hits(&mut hits);
                 hits.truncate(limit);
                 hits
             }
-            VecIndex::Compressed(quantized) => quantized.search(query, limit),
+            VecIndex::Compressed(quantized) => quantized.search_with_metric(query, limit, metric),
+        }
+    }
+
+    /// Returns the attached HNSW graph, if any.
+    pub fn graph(&self) -> Option<&HnswGraph> {
+        match self {
+            VecIndex::Uncompressed { graph, .. } => graph.as_ref(),
+            VecIndex::Compressed(_) => None,
+        }
+    }
+
+    /// Attaches a previously persisted graph. Graphs covering more nodes than
+    /// there are documents are rejected and leave the index unchanged.
+    pub fn attach_graph(&mut self, new_graph: HnswGraph) -> bool {
+        match self {
+            VecIndex::Uncompressed { documents, graph } if new_graph.len() <= documents.len() => {
+                *graph = Some(new_graph);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Detaches the graph so it can be carried over to a rebuilt index.
+    pub fn take_graph(&mut self) -> Option<HnswGraph> {
+        match self {
+            VecIndex::Uncompressed { graph, .. } => graph.take(),
+            VecIndex::Compressed(_) => None,
+        }
+    }
+
+    /// Brings the graph up to date with the documents, building one from
+    /// scratch once the index is large enough or when the existing graph was
+    /// built for another metric. Returns `true` if the graph changed.
+    pub fn ensure_graph(&mut self, metric: DistanceMetric) -> bool {
+        let VecIndex::Uncompressed { documents, graph } = self else {
+            return false;
+        };
+        let embedding = |position: usize| documents[position].embedding.as_slice();
+        match graph {
+            Some(existing) if existing.metric() != metric => {
+                *graph = (documents.len() >= MIN_VECTORS_FOR_HNSW)
+                    .then(|| HnswGraph::build(metric, documents.len(), embedding));
+                true
+            }
+            Some(existing) if existing.len() == documents.len() => false,
+            Some(existing) => {
+                existing.extend(documents.len(), embedding);
+                true
+            }
+            None if documents.len() >= MIN_VECTORS_FOR_HNSW => {
+                *graph = Some(HnswGraph::build(metric, documents.len(), embedding));
+                true
+            }
+            None => false,
         }
     }
 
     pub fn entries(&self) -> Box<dyn Iterator<Item = (FrameId, &[f32])> + '_> {
         match self {
-            VecIndex::Uncompressed { documents } => Box::new(
+            VecIndex::Uncompressed { documents, graph } => Box::new(
                 documents
                     .iter()
-                    .map(|doc| (doc.frame_id, doc.embedding.as_slice())),
+                    .enumerate()
+                    .filter(move |(position, _)| {
+                        !graph.as_ref().is_some_and(|g| g.is_deleted(*position))
+                    })
+                    .map(|(_, doc)| (doc.frame_id, doc.embedding.as_slice())),
             ),
             VecIndex::Compressed(_) => {
                 // Compressed vectors don't have direct f32 access
@@ -192,28 +313,59 @@ impl VecIndex {
     }
 
     pub fn embedding_for(&self, frame_id: FrameId) -> Option<&[f32]> {
+        self.entries()
+            .find(|(id, _)| *id == frame_id)
+            .map(|(_, embedding)| embedding)
+    }
+
+    /// Every document slot, including those deleted but still held by the graph.
+    pub(crate) fn slots(&self) -> &[VecDocument] {
         match self {
-            VecIndex::Uncompressed { documents } => documents
-                .iter()
-                .find(|doc| doc.frame_id == frame_id)
-                .map(|doc| doc.embedding.as_slice()),
-            VecIndex::Compressed(_) => {
-                // Compressed vectors don't have direct f32 access
-                None
-            }
+            VecIndex::Uncompressed { documents, .. } => documents,
+            VecIndex::Compressed(_) => &[],
         }
     }
 
     pub fn remove(&mut self, frame_id: FrameId) {
         match self {
-            VecIndex::Uncompressed { documents } => {
-                documents.retain(|doc| doc.frame_id != frame_id);
+            VecIndex::Uncompressed { documents, graph } => {
+                let Some(position) = documents.iter().position(|doc| doc.frame_id == frame_id)
+                else {
+                    return;
+                };
+                match graph {
+                    // Graph nodes are document positions, so the slot stays until the
+                    // graph is rebuilt.
+                    Some(graph) if position < graph.len() => graph.mark_deleted(position),
+                    _ => {
+                        documents.remove(position);
+                    }
+                }
             }
             VecIndex::Compressed(_quantized) => {
                 // Compressed indices are immutable
             }
         }
     }
+
+    /// Drops the slots of deleted documents along with the graph holding them, so
+    /// the next commit rebuilds it. Returns `true` if anything was dropped.
+    pub fn purge_deleted(&mut self) -> bool {
+        let VecIndex::Uncompressed { documents, graph } = self else {
+            return false;
+        };
+        let Some(existing) = graph.as_ref().filter(|g| g.deleted_count() > 0) else {
+            return false;
+        };
+        let mut position = 0;
+        documents.retain(|_| {
+            let keep = !existing.is_deleted(position);
+            position += 1;
+            keep
+        });
+        *graph = None;
+        true
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -222,12 +374,12 @@ pub struct VecSearchHit {
     pub distance: f32,
 }
 
-fn l2_distance(a: &[f32], b: &[f32]) -> f32 {
-    a.iter()
-        .zip(b.iter())
-        .map(|(x, y)| (x - y).powi(2))
-        .sum::<f32>()
-        .sqrt()
+fn sort_hits(hits: &mut [VecSearchHit]) {
+    hits.sort_by(|a, b| {
+        a.distance
+            .partial_cmp(&b.distance)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
 }
 
 #[cfg(test)]
@@ -250,7 +402,30 @@ mod tests {
 
     #[test]
     fn l2_distance_behaves() {
-        let d = l2_distance(&[0.0, 0.0], &[3.0, 4.0]);
+        let d = DistanceMetric::L2.distance(&[0.0, 0.0], &[3.0, 4.0]);
         assert!((d - 5.0).abs() < 1e-6);
     }
+
+    #[test]
+    fn metric_changes_ranking_and_similarity() {
+        let mut builder = VecIndexBuilder::new();
+        builder.add_document(1, vec![4.0, 0.0]);
+        builder.add_document(2, vec![0.6, 0.8]);
+        let artifact = builder.finish().expect("finish");
+        let index = VecIndex::decode(&artifact.bytes).expect("decode");
+        let query = [0.6, 0.8];
+
+        let hits = index.search_with_metric(&query, 2, DistanceMetric::Cosine);
+        assert_eq!(hits[0].frame_id, 2);
+        assert!((DistanceMetric::Cosine.similarity(hits[0].distance) - 1.0).abs() < 1e-6);
+        assert!((DistanceMetric::Cosine.similarity(hits[1].distance) - 0.6).abs() < 1e-6);
+
+        let hits = index.search_with_metric(&query, 2, DistanceMetric::Dot);
+        assert_eq!(hits[0].frame_id, 1);
+        assert!((DistanceMetric::Dot.similarity(hits[0].distance) - 2.4).abs() < 1e-6);
+
+        let hits = index.search_with_metric(&query, 2, DistanceMetric::L2);
+        assert_eq!(hits[0].frame_id, 2);
+        assert!((DistanceMetric::L2.similarity(hits[0].distance) - 1.0).abs() < 1e-6);
+    }
 }
@@ -0,0 +1,610 @@
+//! Hierarchical Navigable Small World (HNSW) graph for approximate vector search
+//!
+//! The graph is built at commit time over the uncompressed vector index and is
+//! persisted next to the vectors, so opening a memory never rebuilds it.
+//!
+//! **Parameters** (see `MV2_SPEC` "Vec Index"):
+//! - `M = 16` neighbours per node on upper layers, `2 * M` on layer 0
+//! - `ef_construction = 200`
+//! - Graph navigation uses the index's [`DistanceMetric`] (cosine by default)
+//!
+//! Nodes are positions into the owning index's document list; the graph does
+//! not store vectors of its own. Callers supply a lookup closure instead.
+//! Deleting a vector only marks its node: the node keeps routing queries but is
+//! never returned, until enough nodes are deleted that the graph is rebuilt.
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashSet};
+
+use blake3::hash;
+use serde::{Deserialize, Serialize};
+
+use crate::{MemvidError, Result, types::DistanceMetric};
+
+fn graph_config() -> impl bincode::config::Config {
+    bincode::config::standard()
+        .with_fixed_int_encoding()
+        .with_little_endian()
+}
+
+// 512 MiB fits in `usize` on every target the crate builds for.
+#[allow(clippy::cast_possible_truncation)]
+const GRAPH_DECODE_LIMIT: usize = crate::MAX_INDEX_BYTES as usize;
+
+/// Maximum neighbours per node on layers above zero.
+pub const HNSW_M: u32 = 16;
+/// Candidate pool size used while inserting nodes.
+pub const HNSW_EF_CONSTRUCTION: u32 = 200;
+/// Candidate pool size used by queries unless the caller overrides it.
+pub const DEFAULT_EF_SEARCH: usize = 64;
+/// Below this many vectors a brute-force scan is both exact and fast enough,
+/// so no graph is built.
+pub const MIN_VECTORS_FOR_HNSW: usize = 512;
+/// Once more than one node in this many is deleted, a commit rebuilds the graph
+/// instead of carrying it over.
+pub const MAX_DELETED_ONE_IN: usize = 4;
+
+/// Fixed seed so that identical inputs always produce identical graph bytes.
+const LEVEL_SEED: u64 = 0x6d76_325f_686e_7377;
+
+/// Serialized HNSW graph plus its checksum, ready to be written to the file.
+#[derive(Debug, Clone)]
+pub struct HnswGraphArtifact {
+    pub bytes: Vec<u8>,
+    pub node_count: u64,
+    pub checksum: [u8; 32],
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HnswGraph {
+    metric: DistanceMetric,
+    m: u32,
+    ef_construction: u32,
+    entry_point: u32,
+    max_level: u32,
+    /// `links[node][level]` holds the neighbours of `node` on `level`.
+    links: Vec<Vec<Vec<u32>>>,
+    /// Cached vector norms used by the cosine metric.
+    norms: Vec<f32>,
+    rng_state: u64,
+    /// `deleted[node]` is set once the node's vector was deleted.
+    deleted: Vec<bool>,
+}
+
+impl Default for HnswGraph {
+    fn default() -> Self {
+        Self {
+            metric: DistanceMetric::default(),
+            m: HNSW_M,
+            ef_construction: HNSW_EF_CONSTRUCTION,
+            entry_point: 0,
+            max_level: 0,
+            links: Vec::new(),
+            norms: Vec::new(),
+            rng_state: LEVEL_SEED,
+            deleted: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Candidate {
+    distance: f32,
+    node: u32,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.distance
+            .total_cmp(&other.distance)
+            .then_with(|| self.node.cmp(&other.node))
+    }
+}
+
+impl HnswGraph {
+    /// Builds a graph over `count` vectors returned by `vector`.
+    pub fn build<'a, F>(metric: DistanceMetric, count: usize, vector: F) -> Self
+    where
+        F: Fn(usize) -> &'a [f32],
+    {
+        let mut graph = Self {
+            metric,
+            ..Self::default()
+        };
+        graph.extend(count, vector);
+        graph
+    }
+
+    /// Inserts every vector at a position `>= self.len()` and `< count`.
+    ///
+    /// Positions already present in the graph are left untouched, which lets a
+    /// commit reuse the previous graph when vectors were only appended.
+    pub fn extend<'a, F>(&mut self, count: usize, vector: F)
+    where
+        F: Fn(usize) -> &'a [f32],
+    {
+        for position in self.len()..count {
+            // Nodes are `u32`; an index within `MAX_INDEX_BYTES` never holds that many.
+            let Ok(node) = u32::try_from(position) else {
+                break;
+            };
+            self.insert(node, &vector);
+        }
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.links.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.links.is_empty()
+    }
+
+    #[must_use]
+    pub fn metric(&self) -> DistanceMetric {
+        self.metric
+    }
+
+    #[must_use]
+    pub fn m(&self) -> u32 {
+        self.m
+    }
+
+    #[must_use]
+    pub fn ef_construction(&self) -> u32 {
+        self.ef_construction
+    }
+
+    /// Stops returning `position` from searches; the node keeps routing them.
+    pub fn mark_deleted(&mut self, position: usize) {
+        if let Some(deleted) = self.deleted.get_mut(position) {
+            *deleted = true;
+        }
+    }
+
+    #[must_use]
+    pub fn is_deleted(&self, position: usize) -> bool {
+        self.deleted.get(position).copied().unwrap_or(false)
+    }
+
+    #[must_use]
+    pub fn deleted_count(&self) -> usize {
+        self.deleted.iter().filter(|deleted| **deleted).count()
+    }
+
+    /// Whether enough nodes were deleted that the graph should be rebuilt.
+    #[must_use]
+    pub fn needs_rebuild(&self) -> bool {
+        !self.is_empty() && self.deleted_count() * MAX_DELETED_ONE_IN > self.len()
+    }
+
+    pub fn encode(&self) -> Result<HnswGraphArtifact> {
+        let bytes = bincode::serde::encode_to_vec(self, graph_config())?;
+        let checksum = *hash(&bytes).as_bytes();
+        Ok(HnswGraphArtifact {
+            bytes,
+            node_count: self.len() as u64,
+            checksum,
+        })
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        let (graph, read): (Self, usize) = bincode::serde::decode_from_slice(
+            bytes,
+            bincode::config::standard()
+                .with_fixed_int_encoding()
+                .with_little_endian()
+                .with_limit::<GRAPH_DECODE_LIMIT>(),
+        )?;
+        if read != bytes.len() {
+            return Err(MemvidError::InvalidToc {
+                reason: "unexpected trailing bytes in hnsw graph".into(),
+            });
+        }
+        if graph.norms.len() != graph.links.len()
+            || graph.deleted.len() != graph.links.len()
+            || (!graph.links.is_empty() && graph.entry_point as usize >= graph.links.len())
+        {
+            return Err(MemvidError::InvalidToc {
+                reason: "hnsw graph is inconsistent".into(),
+            });
+        }
+        Ok(graph)
+    }
+
+    /// Returns up to `limit` live node positions ordered by ascending distance to `query`.
+    ///
+    /// `ef` is the candidate pool size; larger values trade speed for recall.
+    pub fn search<'a, F>(&self, vector: F, query: &[f32], limit: usize, ef: usize) -> Vec<usize>
+    where
+        F: Fn(usize) -> &'a [f32],
+    {
+        if self.is_empty() || limit == 0 || query.is_empty() {
+            return Vec::new();
+        }
+        let query_norm = norm(query);
+        let distance = |node: u32| {
+            let node = node as usize;
+            self.metric
+                .graph_distance(query, query_norm, vector(node), self.norms[node])
+        };
+
+        let mut entry = Candidate {
+            distance: distance(self.entry_point),
+            node: self.entry_point,
+        };
+        for level in (1..=self.max_level as usize).rev() {
+            entry = self.greedy_closest(entry, level, &distance);
+        }
+
+        let mut nearest = self.search_layer(entry, ef.max(limit), 0, &distance, false);
+        nearest.truncate(limit);
+        nearest
+            .into_iter()
+            .map(|candidate| candidate.node as usize)
+            .collect()
+    }
+
+    fn insert<'a, F>(&mut self, node: u32, vector: &F)
+    where
+        F: Fn(usize) -> &'a [f32],
+    {
+        let position = node as usize;
+        let top_level = self.random_level();
+        let level = top_level as usize;
+        let embedding = vector(position);
+        let embedding_norm = norm(embedding);
+        self.links.push(vec![Vec::new(); level + 1]);
+        self.norms.push(embedding_norm);
+        self.deleted.push(false);
+
+        if position == 0 {
+            self.entry_point = node;
+            self.max_level = top_level;
+            return;
+        }
+
+        let (metric, norms) = (self.metric, &self.norms);
+        let distance = |other: u32| {
+            let other = other as usize;
+            metric.graph_distance(embedding, embedding_norm, vector(other), norms[other])
+        };
+
+        let mut entry = Candidate {
+            distance: distance(self.entry_point),
+            node: self.entry_point,
+        };
+        let top = self.max_level as usize;
+        for layer in (level + 1..=top).rev() {
+            entry = self.greedy_closest(entry, layer, &distance);
+        }
+
+        let mut selected_per_layer = Vec::with_capacity(level.min(top) + 1);
+        for layer in (0..=level.min(top)).rev() {
+            let candidates =
+                self.search_layer(entry, self.ef_construction as usize, layer, &distance, true);
+            if let Some(closest) = candidates.first() {
+                entry = *closest;
+            }
+            let selected: Vec<u32> = candidates
+                .iter()
+                .take(self.max_links(layer))
+                .map(|candidate| candidate.node)
+                .collect();
+            selected_per_layer.push((layer, selected));
+        }
+
+        for (layer, selected) in selected_per_layer {
+            for &neighbour in &selected {
+                self.connect(neighbour, node, layer, vector);
+            }
+            self.links[position][layer] = selected;
+        }
+
+        if level > top {
+            self.entry_point = node;
+            self.max_level = top_level;
+        }
+    }
+
+    /// Adds `node` to the neighbour list of `neighbour`, pruning the list back
+    /// to the layer's capacity by keeping the closest links.
+    fn connect<'a, F>(&mut self, neighbour: u32, node: u32, layer: usize, vector: &F)
+    where
+        F: Fn(usize) -> &'a [f32],
+    {
+        let capacity = self.max_links(layer);
+        let base = neighbour as usize;
+        let links = &mut self.links[base][layer];
+        links.push(node);
+        if links.len() <= capacity {
+            return;
+        }
+        let base_vector = vector(base);
+        let base_norm = self.norms[base];
+        let (metric, norms) = (self.metric, &self.norms);
+        let mut scored: Vec<Candidate> = links
+            .iter()
+            .map(|&other| Candidate {
+                distance: metric.graph_distance(
+                    base_vector,
+                    base_norm,
+                    vector(other as usize),
+                    norms[other as usize],
+                ),
+                node: other,
+            })
+            .collect();
+        scored.sort_unstable();
+        scored.truncate(capacity);
+        *links = scored.into_iter().map(|candidate| candidate.node).collect();
+    }
+
+    fn greedy_closest<D>(&self, mut current: Candidate, layer: usize, distance: &D) -> Candidate
+    where
+        D: Fn(u32) -> f32,
+    {
+        loop {
+            let mut improved = false;
+            for &neighbour in self.neighbours(current.node, layer) {
+                let candidate_distance = distance(neighbour);
+                if candidate_distance < current.distance {
+                    current = Candidate {
+                        distance: candidate_distance,
+                        node: neighbour,
+                    };
+                    improved = true;
+                }
+            }
+            if !improved {
+                return current;
+            }
+        }
+    }
+
+    /// Best-first search on one layer; returns candidates sorted by distance.
+    ///
+    /// Deleted nodes are expanded like any other but only returned when
+    /// `include_deleted` is set, as while linking a new node.
+    fn search_layer<D>(
+        &self,
+        entry: Candidate,
+        ef: usize,
+        layer: usize,
+        distance: &D,
+        include_deleted: bool,
+    ) -> Vec<Candidate>
+    where
+        D: Fn(u32) -> f32,
+    {
+        let returnable = |node: u32| include_deleted || !self.is_deleted(node as usize);
+        let mut visited: HashSet<u32> = HashSet::new();
+        visited.insert(entry.node);
+        // Min-heap of nodes still to expand.
+        let mut frontier = BinaryHeap::new();
+        frontier.push(std::cmp::Reverse(entry));
+        // Max-heap of the best `ef` returnable nodes found so far.
+        let mut results = BinaryHeap::new();
+        if returnable(entry.node) {
+            results.push(entry);
+        }
+
+        while let Some(std::cmp::Reverse(current)) = frontier.pop() {
+            let worst = results
+                .peek()
+                .map_or(f32::INFINITY, |c: &Candidate| c.distance);
+            if current.distance > worst && results.len() >= ef {
+                break;
+            }
+            for &neighbour in self.neighbours(current.node, layer) {
+                if !visited.insert(neighbour) {
+                    continue;
+                }
+                let candidate = Candidate {
+                    distance: distance(neighbour),
+                    node: neighbour,
+                };
+                let worst = results.peek().map_or(f32::INFINITY, |c| c.distance);
+                if results.len() < ef || candidate.distance < worst {
+                    frontier.push(std::cmp::Reverse(candidate));
+                    if returnable(neighbour) {
+                        results.push(candidate);
+                        if results.len() > ef {
+                            results.pop();
+                        }
+                    }
+                }
+            }
+        }
+
+        results.into_sorted_vec()
+    }
+
+    fn neighbours(&self, node: u32, layer: usize) -> &[u32] {
+        self.links
+            .get(node as usize)
+            .and_then(|levels| levels.get(layer))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    fn max_links(&self, layer: usize) -> usize {
+        if layer == 0 {
+            self.m as usize * 2
+        } else {
+            self.m as usize
+        }
+    }
+
+    /// Draws a level from the usual `floor(-ln(U) / ln(M))` distribution, capped at 16,
+    /// using a `SplitMix64` stream, keeping builds deterministic.
+    fn random_level(&mut self) -> u32 {
+        self.rng_state = self.rng_state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.rng_state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+        // 52 random bits as a mantissa in [1, 2), mapped into (0, 1].
+        let uniform = 2.0 - f64::from_bits(0x3ff0_0000_0000_0000 | (z >> 12));
+        // The level is the largest `k` with `U <= M^-k`.
+        let scale = f64::from(self.m).recip();
+        let mut level = 0;
+        let mut threshold = scale;
+        while level < 16 && uniform <= threshold {
+            level += 1;
+            threshold *= scale;
+        }
+        level
+    }
+}
+
+fn norm(vector: &[f32]) -> f32 {
+    vector.iter().map(|value| value * value).sum::<f32>().sqrt()
+}
+
+impl DistanceMetric {
+    /// Same as [`DistanceMetric::distance`], reusing precomputed norms.
+    fn graph_distance(self, a: &[f32], a_norm: f32, b: &[f32], b_norm: f32) -> f32 {
+        match self {
+            DistanceMetric::Cosine => cosine_distance(a, a_norm, b, b_norm),
+            DistanceMetric::Dot | DistanceMetric::L2 => self.distance(a, b),
+        }
+    }
+}
+
+fn cosine_distance(a: &[f32], a_norm: f32, b: &[f32], b_norm: f32) -> f32 {
+    if a_norm == 0.0 || b_norm == 0.0 {
+        return 1.0;
+    }
+    let dot: f32 = a.iter().zip(b.iter()).map(|(x, y)| x * y).sum();
+    1.0 - dot / (a_norm * b_norm)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn random_unit_vectors(count: usize, dimension: usize, seed: u64) -> Vec<Vec<f32>> {
+        let mut rng = fastrand::Rng::with_seed(seed);
+        (0..count)
+            .map(|_| {
+                let raw: Vec<f32> = (0..dimension).map(|_| rng.f32() * 2.0 - 1.0).collect();
+                let length = norm(&raw);
+                raw.into_iter().map(|value| value / length).collect()
+            })
+            .collect()
+    }
+
+    fn brute_force(vectors: &[Vec<f32>], query: &[f32], limit: usize) -> Vec<usize> {
+        let query_norm = norm(query);
+        let mut scored: Vec<(f32, usize)> = vectors
+            .iter()
+            .enumerate()
+            .map(|(idx, v)| (cosine_distance(query, query_norm, v, norm(v)), idx))
+            .collect();
+        scored.sort_by(|a, b| a.0.total_cmp(&b.0));
+        scored.into_iter().take(limit).map(|(_, idx)| idx).collect()
+    }
+
+    #[test]
+    fn recall_matches_brute_force() {
+        let vectors = random_unit_vectors(1_000, 24, 7);
+        let graph = HnswGraph::build(DistanceMetric::Cosine, vectors.len(), |idx| {
+            vectors[idx].as_slice()
+        });
+        let queries = random_unit_vectors(50, 24, 99);
+
+        let limit = 10;
+        let mut found = 0usize;
+        for query in &queries {
+            let expected: HashSet<usize> =
+                brute_force(&vectors, query, limit).into_iter().collect();
+            let actual = graph.search(|idx| vectors[idx].as_slice(), query, limit, 100);
+            found += actual.iter().filter(|idx| expected.contains(idx)).count();
+        }
+        let recall = found as f32 / (queries.len() * limit) as f32;
+        assert!(recall >= 0.95, "recall too low: {recall}");
+    }
+
+    #[test]
+    fn encode_roundtrip_and_extend() {
+        let vectors = random_unit_vectors(64, 8, 3);
+        let mut graph = HnswGraph::build(DistanceMetric::Cosine, 40, |idx| vectors[idx].as_slice());
+        let artifact = graph.encode().expect("encode");
+        let decoded = HnswGraph::decode(&artifact.bytes).expect("decode");
+        assert_eq!(decoded.len(), 40);
+        assert_eq!(
+            decoded.encode().expect("re-encode").checksum,
+            artifact.checksum
+        );
+
+        graph.extend(vectors.len(), |idx| vectors[idx].as_slice());
+        assert_eq!(graph.len(), 64);
+        let hits = graph.search(|idx| vectors[idx].as_slice(), &vectors[50], 1, 32);
+        assert_eq!(hits, vec![50]);
+    }
+
+    #[test]
+    fn deleted_nodes_route_but_are_not_returned() {
+        let vectors = random_unit_vectors(600, 16, 5);
+        let mut graph = HnswGraph::build(DistanceMetric::Cosine, vectors.len(), |idx| {
+            vectors[idx].as_slice()
+        });
+        let search =
+            |graph: &HnswGraph| graph.search(|idx| vectors[idx].as_slice(), &vectors[42], 5, 64);
+        assert_eq!(search(&graph)[0], 42);
+
+        graph.mark_deleted(42);
+        let hits = search(&graph);
+        assert_eq!(hits.len(), 5);
+        assert!(!hits.contains(&42));
+        let decoded = HnswGraph::decode(&graph.encode().expect("encode").bytes).expect("decode");
+        assert!(decoded.is_deleted(42));
+        assert_eq!(decoded.deleted_count(), 1);
+
+        assert!(!graph.needs_rebuild());
+        for position in 0..vectors.len() / 3 {
+            graph.mark_deleted(position);
+        }
+        assert!(graph.needs_rebuild());
+    }
+
+    #[test]
+    fn graph_follows_metric() {
+        let vectors = random_unit_vectors(600, 16, 11);
+        let scaled: Vec<Vec<f32>> = vectors
+            .iter()
+            .enumerate()
+            .map(|(idx, v)| v.iter().map(|x| x * (1.0 + (idx % 7) as f32)).collect())
+            .collect();
+        let query = &vectors[123];
+        for metric in [DistanceMetric::Dot, DistanceMetric::L2] {
+            let graph = HnswGraph::build(metric, scaled.len(), |idx| scaled[idx].as_slice());
+            assert_eq!(graph.metric(), metric);
+            let expected = (0..scaled.len())
+                .min_by(|&a, &b| {
+                    metric
+                        .distance(query, &scaled[a])
+                        .total_cmp(&metric.distance(query, &scaled[b]))
+                })
+                .expect("non-empty");
+            let hits = graph.search(|idx| scaled[idx].as_slice(), query, 1, 128);
+            assert_eq!(hits, vec![expected], "metric {metric}");
+        }
+    }
+}
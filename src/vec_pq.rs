@@ -12,8 +12,12 @@
 use blake3::hash;
 use serde::{Deserialize, Serialize};
 
+use crate::types::manifest::cosine_distance_from_parts;
 use crate::vec::VecSearchHit;
-use crate::{MemvidError, Result, types::FrameId};
+use crate::{
+    MemvidError, Result,
+    types::{DistanceMetric, FrameId},
+};
 
 fn vec_config() -> impl bincode::config::Config {
     bincode::config::standard()
@@ -195,11 +199,28 @@ impl ProductQuantizer {
     /// Compute asymmetric distance between query vector and PQ-encoded vector
     /// Uses precomputed lookup tables for efficiency
     pub fn asymmetric_distance(&self, query: &[f32], codes: &[u8]) -> f32 {
+        self.asymmetric_distance_with_metric(query, codes, DistanceMetric::L2)
+    }
+
+    /// Asymmetric distance under `metric`.
+    ///
+    /// Dot products and squared norms decompose over subspaces just like the
+    /// squared L2 distance, so every metric is computed from the centroids
+    /// without reconstructing the vector.
+    pub fn asymmetric_distance_with_metric(
+        &self,
+        query: &[f32],
+        codes: &[u8],
+        metric: DistanceMetric,
+    ) -> f32 {
         if query.len() != TOTAL_DIM || codes.len() != NUM_SUBSPACES {
             return f32::INFINITY;
         }
 
         let mut total_dist_sq = 0.0f32;
+        let mut dot = 0.0f32;
+        let mut query_norm_sq = 0.0f32;
+        let mut code_norm_sq = 0.0f32;
 
         for subspace_idx in 0..NUM_SUBSPACES {
             let start_dim = subspace_idx * SUBSPACE_DIM;
@@ -209,10 +230,25 @@ impl ProductQuantizer {
             let code = codes[subspace_idx];
             let centroid = self.codebooks[subspace_idx].get_centroid(code);
 
-            total_dist_sq += l2_distance_squared(query_subspace, centroid);
+            match metric {
+                DistanceMetric::L2 => {
+                    total_dist_sq += l2_distance_squared(query_subspace, centroid);
+                }
+                DistanceMetric::Dot | DistanceMetric::Cosine => {
+                    for (q, c) in query_subspace.iter().zip(centroid.iter()) {
+                        dot += q * c;
+                        query_norm_sq += q * q;
+                        code_norm_sq += c * c;
+                    }
+                }
+            }
         }
 
-        total_dist_sq.sqrt()
+        match metric {
+            DistanceMetric::L2 => total_dist_sq.sqrt(),
+            DistanceMetric::Dot => -dot,
+            DistanceMetric::Cosine => cosine_distance_from_parts(dot, query_norm_sq, code_norm_sq),
+        }
     }
 }
 
@@ -349,6 +385,16 @@ impl QuantizedVecIndex {
 
     /// Search using asymmetric distance computation
     pub fn search(&self, query: &[f32], limit: usize) -> Vec<VecSearchHit> {
+        self.search_with_metric(query, limit, DistanceMetric::L2)
+    }
+
+    /// Search using asymmetric distance computation under `metric`
+    pub fn search_with_metric(
+        &self,
+        query: &[f32],
+        limit: usize,
+        metric: DistanceMetric,
+    ) -> Vec<VecSearchHit> {
         if query.is_empty() {
             return Vec::new();
         }
@@ -357,7 +403,9 @@ impl QuantizedVecIndex {
             .documents
             .iter()
             .map(|doc| {
-                let distance = self.quantizer.asymmetric_distance(query, &doc.codes);
+                let distance = self
+                    .quantizer
+                    .asymmetric_distance_with_metric(query, &doc.codes, metric);
                 VecSearchHit {
                     frame_id: doc.frame_id,
                     distance,
@@ -569,6 +617,24 @@ mod tests {
         // Distance between original and decoded should be small
         let dist = l2_distance_squared(test_vec, &decoded).sqrt();
         assert!(dist < 10.0, "Reconstruction error too large: {}", dist);
+
+        // Asymmetric distances equal the exact metric against the reconstruction
+        let query = &training_vecs[7];
+        for metric in [
+            DistanceMetric::L2,
+            DistanceMetric::Dot,
+            DistanceMetric::Cosine,
+        ] {
+            let asymmetric = pq.asymmetric_distance_with_metric(query, &codes, metric);
+            let exact = metric.distance(query, &decoded);
+            assert!(
+                (asymmetric - exact).abs() < 1e-2,
+                "{} mismatch: {} vs {}",
+                metric,
+                asymmetric,
+                exact
+            );
+        }
     }
 
     #[test]
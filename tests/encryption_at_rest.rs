@@ -0,0 +1,199 @@
+//! Encrypted-mode memory tests (regions sealed inside the .mv2 itself).
+
+#![cfg(feature = "encryption")]
+
+use memvid_core::{Memvid, MemvidError, PutOptions, SearchRequest};
+use std::env;
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Command, Stdio};
+use tempfile::TempDir;
+
+const KEY: [u8; 32] = [42u8; 32];
+const WRITER_ENV: &str = "MEMVID_ENCRYPTED_WRITER";
+const SECRET: &str = "the launch codes are hidden under the lighthouse";
+
+fn search_request(query: &str) -> SearchRequest {
+    SearchRequest {
+        query: query.to_string(),
+        top_k: 10,
+        snippet_chars: 200,
+        uri: None,
+        scope: None,
+        cursor: None,
+        #[cfg(feature = "temporal_track")]
+        temporal: None,
+        as_of_frame: None,
+        as_of_ts: None,
+        no_sketch: false,
+    }
+}
+
+fn create_encrypted_memory(path: &std::path::Path) -> Memvid {
+    let mut mem = Memvid::create_encrypted(path, &KEY).expect("create");
+    mem.enable_lex().expect("lex");
+    mem.put_bytes_with_options(
+        SECRET.as_bytes(),
+        PutOptions {
+            uri: Some("mv2://secrets/lighthouse".to_string()),
+            title: Some("Lighthouse".to_string()),
+            ..Default::default()
+        },
+    )
+    .expect("put");
+    mem.commit().expect("commit");
+    mem
+}
+
+#[test]
+fn encrypted_memory_roundtrips_and_searches() {
+    let dir = TempDir::new().expect("tmp");
+    let path = dir.path().join("secret.mv2");
+    create_encrypted_memory(&path);
+
+    let mut mem = Memvid::open_encrypted(&path, &KEY).expect("open");
+    assert_eq!(mem.frame_count(), 1);
+    let id = mem
+        .frame_by_uri("mv2://secrets/lighthouse")
+        .expect("frame")
+        .id;
+    assert_eq!(
+        mem.frame_canonical_payload(id).expect("payload"),
+        SECRET.as_bytes()
+    );
+
+    let results = mem.search(search_request("lighthouse")).expect("search");
+    assert_eq!(results.hits.len(), 1);
+    assert_eq!(results.hits[0].frame_id, id);
+}
+
+#[test]
+fn encrypted_memory_keeps_plaintext_off_disk() {
+    let dir = TempDir::new().expect("tmp");
+    let path = dir.path().join("secret.mv2");
+    create_encrypted_memory(&path);
+
+    let bytes = std::fs::read(&path).expect("read");
+    for needle in ["lighthouse", "launch codes", "mv2://secrets"] {
+        assert!(
+            !bytes
+                .windows(needle.len())
+                .any(|window| window == needle.as_bytes()),
+            "plaintext {needle:?} found in encrypted memory"
+        );
+    }
+}
+
+#[test]
+fn encrypted_memory_rejects_wrong_key_and_plain_open() {
+    let dir = TempDir::new().expect("tmp");
+    let path = dir.path().join("secret.mv2");
+    create_encrypted_memory(&path);
+
+    let Err(err) = Memvid::open_encrypted(&path, &[7u8; 32]) else {
+        panic!("wrong key must be rejected");
+    };
+    assert!(matches!(err, MemvidError::InvalidEncryptionKey));
+
+    let Err(err) = Memvid::open(&path) else {
+        panic!("encrypted memory must not open without a key");
+    };
+    assert!(matches!(err, MemvidError::EncryptedFile { .. }));
+
+    let Err(err) = Memvid::open_read_only(&path) else {
+        panic!("read-only open must not bypass the key");
+    };
+    assert!(matches!(err, MemvidError::EncryptedFile { .. }));
+}
+
+#[test]
+fn encrypted_memory_survives_reopen_and_append() {
+    let dir = TempDir::new().expect("tmp");
+    let path = dir.path().join("secret.mv2");
+    create_encrypted_memory(&path);
+
+    {
+        let mut mem = Memvid::open_encrypted(&path, &KEY).expect("open");
+        mem.put_bytes(b"a second note about the harbour")
+            .expect("put");
+        mem.commit().expect("commit");
+    }
+
+    let mut mem = Memvid::open_encrypted(&path, &KEY).expect("reopen");
+    assert_eq!(mem.frame_count(), 2);
+    let results = mem.search(search_request("harbour")).expect("search");
+    assert_eq!(results.hits.len(), 1);
+}
+
+/// Puts and commits the line read from stdin, acknowledging it on stdout.
+#[test]
+#[ignore = "run as a child process by encrypted_memory_opens_read_only_and_at_generation"]
+fn writer_process() {
+    let Ok(path) = env::var(WRITER_ENV) else {
+        return;
+    };
+    let mut mem = create_encrypted_memory(path.as_ref());
+    println!("writer: ready");
+    let line = std::io::stdin()
+        .lock()
+        .lines()
+        .next()
+        .expect("line")
+        .expect("read");
+    mem.put_bytes(line.as_bytes()).expect("put");
+    mem.commit().expect("commit");
+    println!("writer: committed");
+}
+
+#[test]
+fn encrypted_memory_opens_read_only_and_at_generation() {
+    let dir = TempDir::new().expect("tmp");
+    let path = dir.path().join("secret.mv2");
+
+    let mut writer = Command::new(env::current_exe().expect("test binary"))
+        .args(["writer_process", "--exact", "--ignored", "--nocapture"])
+        .env(WRITER_ENV, &path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("spawn writer");
+    let mut commands = writer.stdin.take().expect("stdin");
+    let mut acks = BufReader::new(writer.stdout.take().expect("stdout")).lines();
+    // libtest prints the test name on the same line as the writer's first message.
+    let mut wait_for = |message: &str| {
+        assert!(
+            acks.by_ref()
+                .any(|line| line.expect("read").ends_with(message)),
+            "writer exited before printing {message:?}"
+        );
+    };
+    wait_for("writer: ready");
+
+    let mut reader = Memvid::open_read_only_encrypted(&path, &KEY).expect("open read-only");
+    assert_eq!(reader.frame_count(), 1);
+    let first = reader.list_generations().expect("generations");
+    let first = first.last().expect("generation").generation;
+
+    writeln!(commands, "a second note about the harbour").expect("write");
+    wait_for("writer: committed");
+    drop(commands);
+    assert!(writer.wait().expect("writer").success());
+    assert!(reader.refresh().expect("refresh"));
+    assert_eq!(reader.frame_count(), 2);
+    let results = reader.search(search_request("harbour")).expect("search");
+    assert_eq!(results.hits.len(), 1);
+
+    let mut earlier =
+        Memvid::open_at_generation_encrypted(&path, first, &KEY).expect("open generation");
+    assert_eq!(earlier.frame_count(), 1);
+    let results = earlier.search(search_request("harbour")).expect("search");
+    assert!(results.hits.is_empty());
+
+    let Err(err) = Memvid::open_at_generation(&path, first) else {
+        panic!("generation open must not bypass the key");
+    };
+    assert!(matches!(err, MemvidError::EncryptedFile { .. }));
+    let Err(err) = Memvid::open_read_only_encrypted(&path, &[7u8; 32]) else {
+        panic!("wrong key must be rejected");
+    };
+    assert!(matches!(err, MemvidError::InvalidEncryptionKey));
+}
@@ -1,12 +1,17 @@
 //! Encryption capsule tests (.mv2e).
 
 #[cfg(feature = "encryption")]
-use memvid_core::encryption::{EncryptionError, Mv2eHeader, lock_file, unlock_file};
+use memvid_core::encryption::{
+    EncryptionError, Identity, MV2E_VERSION, Mv2eHeader, Recipient, RecipientKind,
+    STREAM_CHUNK_SIZE, TAG_SIZE, add_recipient, generate_key_file, generate_x25519_keypair,
+    list_recipients, lock_file, lock_file_for, read_key_file, remove_recipient, rotate_password,
+    unlock_file, unlock_file_with,
+};
 #[cfg(feature = "encryption")]
 use memvid_core::{Memvid, PutOptions};
 
 #[cfg(feature = "encryption")]
-use std::fs::read;
+use std::fs::{read, write};
 #[cfg(feature = "encryption")]
 use tempfile::TempDir;
 
@@ -86,3 +91,241 @@ fn wrong_password_fails() {
     let err = unlock_file(&mv2e_path, None, b"password-b").expect_err("should fail");
     assert!(matches!(err, EncryptionError::Decryption { .. }));
 }
+
+/// Offset of the encrypted body: the fixed header plus the recipient block.
+#[cfg(feature = "encryption")]
+fn body_offset(capsule: &[u8]) -> usize {
+    let header = Mv2eHeader::decode(capsule[..Mv2eHeader::SIZE].try_into().expect("header"))
+        .expect("decode");
+    Mv2eHeader::SIZE + u32::from_le_bytes(header.reserved) as usize
+}
+
+#[cfg(feature = "encryption")]
+fn lock_multi_chunk_capsule(dir: &TempDir) -> (std::path::PathBuf, std::path::PathBuf) {
+    let mv2_path = dir.path().join("large.mv2");
+    let mv2e_path = dir.path().join("large.mv2e");
+    {
+        let mut mem = Memvid::create(&mv2_path).expect("create");
+        // Incompressible, so the stored payload alone spans several chunks.
+        let mut state = 0x9E37_79B9_7F4A_7C15_u64;
+        let payload: Vec<u8> = (0..3 * STREAM_CHUNK_SIZE)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state as u8
+            })
+            .collect();
+        mem.put_bytes(&payload).expect("put");
+        mem.commit().expect("commit");
+    }
+    lock_file(&mv2_path, Some(mv2e_path.as_path()), b"chunked").expect("lock");
+    (mv2_path, mv2e_path)
+}
+
+#[test]
+#[cfg(feature = "encryption")]
+fn chunked_capsule_roundtrip_spans_many_chunks() {
+    let dir = TempDir::new().expect("tmp");
+    let (mv2_path, mv2e_path) = lock_multi_chunk_capsule(&dir);
+    let restored_path = dir.path().join("restored.mv2");
+
+    let capsule = read(&mv2e_path).expect("read capsule");
+    let header = Mv2eHeader::decode(capsule[..Mv2eHeader::SIZE].try_into().expect("header"))
+        .expect("decode");
+    assert_eq!(header.version, MV2E_VERSION);
+    let original = read(&mv2_path).expect("read original");
+    let chunks = original.len().div_ceil(STREAM_CHUNK_SIZE);
+    assert!(chunks > 3);
+    assert_eq!(
+        capsule.len(),
+        body_offset(&capsule) + original.len() + chunks * TAG_SIZE
+    );
+
+    unlock_file(&mv2e_path, Some(restored_path.as_path()), b"chunked").expect("unlock");
+    assert_eq!(read(&restored_path).expect("read restored"), original);
+}
+
+#[test]
+#[cfg(feature = "encryption")]
+fn truncated_or_reordered_capsules_are_rejected() {
+    let dir = TempDir::new().expect("tmp");
+    let (_, mv2e_path) = lock_multi_chunk_capsule(&dir);
+    let capsule = read(&mv2e_path).expect("read capsule");
+    let sealed = STREAM_CHUNK_SIZE + TAG_SIZE;
+    let body = body_offset(&capsule);
+
+    let mut reordered = capsule.clone();
+    reordered[body..body + 2 * sealed].rotate_left(sealed);
+
+    let cases: Vec<(&str, Vec<u8>)> = vec![
+        ("header only", capsule[..body].to_vec()),
+        ("recipients truncated", capsule[..body - 1].to_vec()),
+        ("chunk boundary", capsule[..body + 2 * sealed].to_vec()),
+        ("mid chunk", capsule[..body + sealed + 100].to_vec()),
+        (
+            "last chunk dropped tail",
+            capsule[..capsule.len() - 1].to_vec(),
+        ),
+        ("reordered", reordered),
+    ];
+    for (name, bytes) in cases {
+        let tampered = dir.path().join("tampered.mv2e");
+        let output = dir.path().join("tampered.mv2");
+        write(&tampered, bytes).expect("write");
+        let err = unlock_file(&tampered, Some(output.as_path()), b"chunked").expect_err(name);
+        assert!(
+            matches!(
+                err,
+                EncryptionError::Decryption { .. }
+                    | EncryptionError::Truncated { .. }
+                    | EncryptionError::InvalidRecipients { .. }
+            ),
+            "{name}: {err:?}"
+        );
+        assert!(!output.exists(), "{name}: partial output left behind");
+    }
+}
+
+#[cfg(feature = "encryption")]
+fn create_small_memory(dir: &TempDir) -> std::path::PathBuf {
+    let mv2_path = dir.path().join("shared.mv2");
+    let mut mem = Memvid::create(&mv2_path).expect("create");
+    mem.put_bytes(b"shared with several recipients")
+        .expect("put");
+    mem.commit().expect("commit");
+    mv2_path
+}
+
+#[test]
+#[cfg(feature = "encryption")]
+fn every_recipient_can_unlock_a_multi_recipient_capsule() {
+    let dir = TempDir::new().expect("tmp");
+    let mv2_path = create_small_memory(&dir);
+    let mv2e_path = dir.path().join("shared.mv2e");
+    let key_path = dir.path().join("team.key");
+    let key = generate_key_file(&key_path).expect("key file");
+    assert_eq!(read_key_file(&key_path).expect("read key"), key);
+    let (secret, public) = generate_x25519_keypair();
+
+    lock_file_for(
+        &mv2_path,
+        Some(mv2e_path.as_path()),
+        &[
+            Recipient::Password(b"alpha".to_vec()),
+            Recipient::KeyFile(key),
+            Recipient::X25519(public),
+        ],
+    )
+    .expect("lock");
+
+    let kinds: Vec<RecipientKind> = list_recipients(&mv2e_path)
+        .expect("list")
+        .into_iter()
+        .map(|info| info.kind)
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            RecipientKind::Password,
+            RecipientKind::KeyFile,
+            RecipientKind::X25519
+        ]
+    );
+
+    let original = read(&mv2_path).expect("read original");
+    for (name, identity) in [
+        ("password", Identity::Password(b"alpha".to_vec())),
+        ("key file", Identity::KeyFile(key)),
+        ("x25519", Identity::X25519(secret)),
+    ] {
+        let restored = dir.path().join(format!("{name}.mv2"));
+        unlock_file_with(&mv2e_path, Some(restored.as_path()), &identity).expect(name);
+        assert_eq!(read(&restored).expect("read restored"), original, "{name}");
+    }
+
+    let (stranger, _) = generate_x25519_keypair();
+    let err = unlock_file_with(&mv2e_path, None, &Identity::X25519(stranger))
+        .expect_err("unknown identity");
+    assert!(matches!(err, EncryptionError::Decryption { .. }));
+}
+
+#[test]
+#[cfg(feature = "encryption")]
+fn recipients_can_be_added_removed_and_rotated_without_reencrypting() {
+    let dir = TempDir::new().expect("tmp");
+    let mv2_path = create_small_memory(&dir);
+    let mv2e_path = dir.path().join("shared.mv2e");
+    let restored = dir.path().join("restored.mv2");
+    lock_file(&mv2_path, Some(mv2e_path.as_path()), b"old").expect("lock");
+    let body = |path: &std::path::Path| {
+        let capsule = read(path).expect("read capsule");
+        capsule[body_offset(&capsule)..].to_vec()
+    };
+    let sealed_body = body(&mv2e_path);
+
+    let (secret, public) = generate_x25519_keypair();
+    let owner = Identity::Password(b"old".to_vec());
+    let added = add_recipient(&mv2e_path, &owner, &Recipient::X25519(public)).expect("add");
+    assert_eq!(added.kind, RecipientKind::X25519);
+    assert_eq!(list_recipients(&mv2e_path).expect("list").len(), 2);
+    assert_eq!(body(&mv2e_path), sealed_body);
+    unlock_file_with(
+        &mv2e_path,
+        Some(restored.as_path()),
+        &Identity::X25519(secret),
+    )
+    .expect("unlock with added recipient");
+
+    rotate_password(&mv2e_path, b"old", b"new").expect("rotate");
+    assert_eq!(body(&mv2e_path), sealed_body);
+    assert!(matches!(
+        unlock_file(&mv2e_path, None, b"old").expect_err("old password"),
+        EncryptionError::Decryption { .. }
+    ));
+    unlock_file(&mv2e_path, Some(restored.as_path()), b"new").expect("new password");
+
+    let x25519 = Identity::X25519(secret);
+    remove_recipient(&mv2e_path, &x25519, &added.fingerprint).expect("remove");
+    assert!(matches!(
+        unlock_file_with(&mv2e_path, None, &x25519).expect_err("removed recipient"),
+        EncryptionError::Decryption { .. }
+    ));
+    let remaining = list_recipients(&mv2e_path).expect("list");
+    assert_eq!(remaining.len(), 1);
+    let err = remove_recipient(
+        &mv2e_path,
+        &Identity::Password(b"new".to_vec()),
+        &remaining[0].fingerprint,
+    )
+    .expect_err("last recipient");
+    assert!(matches!(err, EncryptionError::LastRecipient));
+    assert_eq!(body(&mv2e_path), sealed_body);
+}
+
+#[test]
+#[cfg(feature = "encryption")]
+fn tampered_recipient_block_is_rejected() {
+    let dir = TempDir::new().expect("tmp");
+    let mv2_path = create_small_memory(&dir);
+    let mv2e_path = dir.path().join("shared.mv2e");
+    let (_, public) = generate_x25519_keypair();
+    lock_file_for(
+        &mv2_path,
+        Some(mv2e_path.as_path()),
+        &[
+            Recipient::Password(b"alpha".to_vec()),
+            Recipient::X25519(public),
+        ],
+    )
+    .expect("lock");
+
+    // Flip a byte of the X25519 public key in the second stanza.
+    let mut capsule = read(&mv2e_path).expect("read capsule");
+    let offset = body_offset(&capsule) - 40;
+    capsule[offset] ^= 0x01;
+    write(&mv2e_path, capsule).expect("write");
+
+    let err = unlock_file(&mv2e_path, None, b"alpha").expect_err("tampered");
+    assert!(matches!(err, EncryptionError::Decryption { .. }), "{err:?}");
+}
@@ -267,7 +267,7 @@ fn embedding_identity_summary_unknown_when_missing() {
 
     let mem = Memvid::open_read_only(&path).unwrap();
     assert_eq!(
-        mem.embedding_identity_summary(1_000),
+        mem.embedding_identity_summary(1_000).expect("summary"),
         EmbeddingIdentitySummary::Unknown
     );
 }
@@ -291,7 +291,7 @@ fn embedding_identity_summary_single() {
     mem.commit().unwrap();
 
     let mem = Memvid::open_read_only(&path).unwrap();
-    match mem.embedding_identity_summary(1_000) {
+    match mem.embedding_identity_summary(1_000).expect("summary") {
         EmbeddingIdentitySummary::Single(identity) => {
             assert_eq!(identity.provider.as_deref(), Some("openai"));
             assert_eq!(identity.model.as_deref(), Some("text-embedding-3-small"));
@@ -332,7 +332,7 @@ fn embedding_identity_summary_mixed() {
     mem.commit().unwrap();
 
     let mem = Memvid::open_read_only(&path).unwrap();
-    match mem.embedding_identity_summary(1_000) {
+    match mem.embedding_identity_summary(1_000).expect("summary") {
         EmbeddingIdentitySummary::Mixed(identities) => {
             assert_eq!(identities.len(), 2);
             let models: Vec<_> = identities
@@ -0,0 +1,103 @@
+//! Integration tests for read-only handles following a writer in another process.
+//! The writer is this test binary, re-run with `WRITER_ENV` set.
+
+use memvid_core::{Memvid, OpenReadOptions, PutOptions, SearchRequest};
+use std::env;
+use std::io::{BufRead, BufReader, Lines, Write};
+use std::process::{ChildStdout, Command, Stdio};
+use tempfile::TempDir;
+
+const WRITER_ENV: &str = "MEMVID_REFRESH_WRITER";
+
+fn hits(mem: &mut Memvid, query: &str) -> usize {
+    mem.search(SearchRequest::test_query(query))
+        .unwrap()
+        .hits
+        .len()
+}
+
+/// Puts and commits every line read from stdin, acknowledging each on stdout.
+#[test]
+#[ignore = "run as a child process by read_only_handles_follow_a_writer_process"]
+fn writer_process() {
+    let Ok(path) = env::var(WRITER_ENV) else {
+        return;
+    };
+    let mut mem = Memvid::create(&path).unwrap();
+    mem.enable_lex().unwrap();
+    mem.put_bytes_with_options(
+        b"seed note about the coastline",
+        PutOptions::builder().uri("mv2://notes/seed").build(),
+    )
+    .unwrap();
+    mem.commit().unwrap();
+    println!("writer: ready");
+
+    for (index, line) in std::io::stdin().lock().lines().enumerate() {
+        let options = PutOptions::builder()
+            .uri(format!("mv2://notes/{index}"))
+            .build();
+        mem.put_bytes_with_options(line.unwrap().as_bytes(), options)
+            .unwrap();
+        mem.commit().unwrap();
+        println!("writer: committed");
+    }
+}
+
+fn wait_for(lines: &mut Lines<BufReader<ChildStdout>>, message: &str) {
+    // libtest prints the test name on the same line as the writer's first message.
+    for line in lines.by_ref() {
+        if line.unwrap().ends_with(message) {
+            return;
+        }
+    }
+    panic!("writer exited before printing {message:?}");
+}
+
+/// Test that read-only handles pick up commits made by another process.
+#[test]
+fn read_only_handles_follow_a_writer_process() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("shared.mv2");
+    let mut writer = Command::new(env::current_exe().unwrap())
+        .args(["writer_process", "--exact", "--ignored", "--nocapture"])
+        .env(WRITER_ENV, &path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut commands = writer.stdin.take().unwrap();
+    let mut acks = BufReader::new(writer.stdout.take().unwrap()).lines();
+    wait_for(&mut acks, "writer: ready");
+
+    let mut manual = Memvid::open_read_only(&path).unwrap();
+    let mut auto = Memvid::open_read_only_with_options(
+        &path,
+        OpenReadOptions {
+            auto_refresh: true,
+            ..OpenReadOptions::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(hits(&mut manual, "coastline"), 1);
+    assert!(!manual.refresh().unwrap());
+
+    writeln!(commands, "storm warning for the harbour").unwrap();
+    wait_for(&mut acks, "writer: committed");
+    assert_eq!(hits(&mut manual, "harbour"), 0);
+    assert!(manual.refresh().unwrap());
+    assert_eq!(hits(&mut manual, "harbour"), 1);
+    assert_eq!(manual.frame_count(), 2);
+    assert_eq!(hits(&mut auto, "harbour"), 1);
+
+    writeln!(commands, "the lighthouse beam is out").unwrap();
+    wait_for(&mut acks, "writer: committed");
+    assert_eq!(hits(&mut auto, "lighthouse"), 1);
+    assert_eq!(hits(&mut manual, "lighthouse"), 0);
+
+    drop(commands);
+    assert!(writer.wait().unwrap().success());
+    assert!(manual.refresh().unwrap());
+    assert_eq!(hits(&mut manual, "lighthouse"), 1);
+    assert!(!manual.refresh().unwrap());
+}